@@ -10,7 +10,7 @@ use crate::{
     agent::agent_config::AgentConfig,
     services::{
         connection::ServiceConnections, credential_definition::ServiceCredentialDefinitions,
-        did_exchange::ServiceDidExchange, holder::ServiceCredentialsHolder,
+        did_exchange::ServiceDidExchange, did_web::ServiceDidWeb, holder::ServiceCredentialsHolder,
         issuer::ServiceCredentialsIssuer, out_of_band::ServiceOutOfBand, prover::ServiceProver,
         revocation_registry::ServiceRevocationRegistries, schema::ServiceSchemas,
         verifier::ServiceVerifier,
@@ -34,6 +34,7 @@ pub struct Agent {
     pub(super) prover: Arc<ServiceProver>,
     pub(super) out_of_band: Arc<ServiceOutOfBand>,
     pub(super) did_exchange: Arc<ServiceDidExchange>,
+    pub(super) did_web: Arc<ServiceDidWeb>,
 }
 
 impl Agent {
@@ -73,6 +74,10 @@ impl Agent {
         self.did_exchange.clone()
     }
 
+    pub fn did_web(&self) -> Arc<ServiceDidWeb> {
+        self.did_web.clone()
+    }
+
     pub fn schemas(&self) -> Arc<ServiceSchemas> {
         self.schemas.clone()
     }
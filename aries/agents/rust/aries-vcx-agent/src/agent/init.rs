@@ -16,6 +16,7 @@ use aries_vcx_core::{
         IndySdkWallet, WalletConfig,
     },
 };
+use did_key::resolver::DidKeyResolver;
 use did_peer::resolver::PeerDidResolver;
 use did_resolver_registry::ResolverRegistry;
 use did_resolver_sov::resolution::DidSovResolver;
@@ -27,6 +28,7 @@ use crate::{
         connection::{ServiceConnections, ServiceEndpoint},
         credential_definition::ServiceCredentialDefinitions,
         did_exchange::ServiceDidExchange,
+        did_web::ServiceDidWeb,
         holder::ServiceCredentialsHolder,
         issuer::ServiceCredentialsIssuer,
         out_of_band::ServiceOutOfBand,
@@ -115,11 +117,13 @@ impl Agent {
         )
         .await?;
 
+        let did_key_resolver = DidKeyResolver::new();
         let did_peer_resolver = PeerDidResolver::new();
         let did_sov_resolver: DidSovResolver<Arc<DefaultIndyLedgerRead>, DefaultIndyLedgerRead> =
             DidSovResolver::new(ledger_read.clone());
         let did_resolver_registry = Arc::new(
             ResolverRegistry::new()
+                .register_resolver("key".into(), did_key_resolver)
                 .register_resolver("peer".into(), did_peer_resolver)
                 .register_resolver("sov".into(), did_sov_resolver),
         );
@@ -130,12 +134,15 @@ impl Agent {
             init_config.service_endpoint.clone(),
         ));
         let did_exchange = Arc::new(ServiceDidExchange::new(
-            ledger_read.clone(),
             wallet.clone(),
             did_resolver_registry,
             init_config.service_endpoint.clone(),
             public_did,
         ));
+        let did_web = Arc::new(ServiceDidWeb::new(
+            wallet.clone(),
+            init_config.service_endpoint.clone(),
+        ));
         let out_of_band = Arc::new(ServiceOutOfBand::new(
             wallet.clone(),
             init_config.service_endpoint,
@@ -163,6 +170,7 @@ impl Agent {
             anoncreds,
             wallet.clone(),
             connections.clone(),
+            rev_regs.clone(),
         ));
         let holder = Arc::new(ServiceCredentialsHolder::new(
             ledger_read.clone(),
@@ -190,6 +198,7 @@ impl Agent {
             wallet,
             connections,
             did_exchange,
+            did_web,
             out_of_band,
             schemas,
             cred_defs,
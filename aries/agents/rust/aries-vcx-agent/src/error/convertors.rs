@@ -86,3 +86,11 @@ impl From<(GenericDidExchange, AriesVcxError)> for AgentError {
         AgentError { message, kind }
     }
 }
+
+impl From<did_resolver_web::error::DidWebError> for AgentError {
+    fn from(err: did_resolver_web::error::DidWebError) -> Self {
+        let kind = AgentErrorKind::GenericAriesVcxError;
+        let message = format!("DidWebError; err: {:?}", err.to_string());
+        AgentError { message, kind }
+    }
+}
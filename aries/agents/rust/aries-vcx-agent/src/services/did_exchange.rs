@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
 use aries_vcx::{
-    messages::msg_fields::protocols::{
-        did_exchange::{
-            complete::Complete, problem_report::ProblemReport, request::Request, response::Response,
+    messages::{
+        msg_fields::protocols::{
+            did_exchange::{
+                complete::Complete, problem_report::ProblemReport, request::Request,
+                response::Response, DidExchange,
+            },
+            out_of_band::invitation::Invitation as OobInvitation,
         },
-        out_of_band::invitation::Invitation as OobInvitation,
+        msg_types::protocols::did_exchange::DidExchangeTypeV1,
     },
     protocols::did_exchange::{
         resolve_key_from_invitation,
@@ -13,7 +17,7 @@ use aries_vcx::{
     },
     transport::Transport,
 };
-use aries_vcx_core::{ledger::indy_vdr_ledger::DefaultIndyLedgerRead, wallet::indy::IndySdkWallet};
+use aries_vcx_core::wallet::indy::IndySdkWallet;
 use did_resolver_registry::ResolverRegistry;
 
 use super::connection::ServiceEndpoint;
@@ -25,7 +29,6 @@ use crate::{
 };
 
 pub struct ServiceDidExchange {
-    ledger_read: Arc<DefaultIndyLedgerRead>,
     wallet: Arc<IndySdkWallet>,
     resolver_registry: Arc<ResolverRegistry>,
     service_endpoint: ServiceEndpoint,
@@ -35,14 +38,12 @@ pub struct ServiceDidExchange {
 
 impl ServiceDidExchange {
     pub fn new(
-        ledger_read: Arc<DefaultIndyLedgerRead>,
         wallet: Arc<IndySdkWallet>,
         resolver_registry: Arc<ResolverRegistry>,
         service_endpoint: ServiceEndpoint,
         public_did: String,
     ) -> Self {
         Self {
-            ledger_read,
             wallet,
             service_endpoint,
             resolver_registry,
@@ -53,7 +54,7 @@ impl ServiceDidExchange {
 
     pub async fn send_request_public(&self, their_did: String) -> AgentResult<String> {
         let (requester, request) = GenericDidExchange::construct_request_public(
-            self.ledger_read.as_ref(),
+            self.resolver_registry.clone(),
             format!("did:sov:{}", their_did).parse()?,
             format!("did:sov:{}", self.public_did).parse()?,
         )
@@ -71,17 +72,21 @@ impl ServiceDidExchange {
             .thid;
         let ddo_their = requester.their_did_doc();
         let ddo_our = requester.our_did_document();
+        let request = DidExchange::new(requester.version(), request).into();
         let encryption_envelope =
-            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &request.into()).await?;
+            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &request).await?;
         VcxHttpClient
             .send_message(encryption_envelope.0, get_their_endpoint(ddo_their)?)
             .await?;
         self.did_exchange.insert(&request_id, requester.clone())
     }
 
+    /// Answers `request` in the protocol `version` of its `@type`, which may be lower than the
+    /// highest version offered by the invitation
     pub async fn send_response(
         &self,
         request: Request,
+        version: DidExchangeTypeV1,
         invitation: OobInvitation,
     ) -> AgentResult<String> {
         // TODO: We should fetch the out of band invite associated with the request.
@@ -108,12 +113,14 @@ impl ServiceDidExchange {
             vec![],
             invitation.id.clone(),
             invitation_key,
+            version,
         )
         .await?;
         let ddo_their = responder.their_did_doc();
         let ddo_our = responder.our_did_document();
+        let response = DidExchange::new(responder.version(), response).into();
         let encryption_envelope =
-            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &response.into()).await?;
+            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &response).await?;
         VcxHttpClient
             .send_message(encryption_envelope.0, get_their_endpoint(ddo_their)?)
             .await?;
@@ -125,12 +132,17 @@ impl ServiceDidExchange {
         let (requester, complete) = self
             .did_exchange
             .get(&thread_id)?
-            .handle_response(response)
+            .handle_response(
+                self.wallet.as_ref(),
+                self.resolver_registry.clone(),
+                response,
+            )
             .await?;
         let ddo_their = requester.their_did_doc();
         let ddo_our = requester.our_did_document();
+        let complete = DidExchange::new(requester.version(), complete).into();
         let encryption_envelope =
-            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &complete.into()).await?;
+            pairwise_encrypt(ddo_our, ddo_their, self.wallet.as_ref(), &complete).await?;
         VcxHttpClient
             .send_message(encryption_envelope.0, get_their_endpoint(ddo_their)?)
             .await?;
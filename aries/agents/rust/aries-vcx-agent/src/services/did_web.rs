@@ -0,0 +1,72 @@
+use std::sync::{Arc, RwLock};
+
+use aries_vcx::did_parser::Did;
+use aries_vcx_core::wallet::{base_wallet::BaseWallet, indy::IndySdkWallet};
+use axum::Router;
+use did_resolver_web::hosting::{
+    router::{did_web_router, SharedDidWebHost},
+    DidWebHost,
+};
+use public_key::{Key, KeyType};
+
+use super::connection::ServiceEndpoint;
+use crate::{
+    storage::{object_cache::ObjectCache, Storage},
+    AgentError, AgentErrorKind, AgentResult,
+};
+
+/// did:web DIDs hosted by the agent. Their documents list a wallet key and the agent's DIDComm
+/// endpoint and are served by the router returned from [`ServiceDidWeb::router`], to be mounted
+/// by the server the DID's domain points to.
+pub struct ServiceDidWeb {
+    wallet: Arc<IndySdkWallet>,
+    service_endpoint: ServiceEndpoint,
+    hosts: ObjectCache<SharedDidWebHost>,
+}
+
+impl ServiceDidWeb {
+    pub fn new(wallet: Arc<IndySdkWallet>, service_endpoint: ServiceEndpoint) -> Self {
+        Self {
+            wallet,
+            service_endpoint,
+            hosts: ObjectCache::new("did-web"),
+        }
+    }
+
+    /// Starts hosting `did` with a new wallet key, returning its verkey
+    pub async fn host_did(&self, did: &str) -> AgentResult<String> {
+        if self.hosts.contains_key(did) {
+            return Err(AgentError::from_msg(
+                AgentErrorKind::InvalidArguments,
+                &format!("DID {} is already hosted", did),
+            ));
+        }
+        let (_, verkey) = self.wallet.create_and_store_my_did(None, None).await?;
+        let host = DidWebHost::from_verkey(Did::parse(did.to_string())?, &verkey)?
+            .set_didcomm_service(self.service_endpoint.clone().into(), vec![]);
+        self.hosts
+            .insert(did, Arc::new(RwLock::new(host)))
+            .map(|_| verkey)
+    }
+
+    /// Rotates the key of the hosted `did` to a new wallet key, returning its verkey. The
+    /// previous key stays in the DID document for a grace period.
+    pub async fn rotate_key(&self, did: &str) -> AgentResult<String> {
+        let host = self.hosts.get(did)?;
+        let (_, verkey) = self.wallet.create_and_store_my_did(None, None).await?;
+        host.write()
+            .map_err(|_| {
+                AgentError::from_msg(AgentErrorKind::LockError, "Unable to lock did:web host")
+            })?
+            .rotate_key(Key::from_base58(&verkey, KeyType::Ed25519)?)?;
+        Ok(verkey)
+    }
+
+    /// Router serving the document of the hosted `did`, reflecting key rotations right away
+    pub fn router<S>(&self, did: &str) -> AgentResult<Router<S>>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Ok(did_web_router(self.hosts.get(did)?)?)
+    }
+}
@@ -16,7 +16,7 @@ use aries_vcx_core::{anoncreds::credx_anoncreds::IndyCredxAnonCreds, wallet::ind
 use crate::{
     error::*,
     http::VcxHttpClient,
-    services::connection::ServiceConnections,
+    services::{connection::ServiceConnections, revocation_registry::ServiceRevocationRegistries},
     storage::{object_cache::ObjectCache, Storage},
 };
 
@@ -40,6 +40,7 @@ pub struct ServiceCredentialsIssuer {
     wallet: Arc<IndySdkWallet>,
     creds_issuer: ObjectCache<IssuerWrapper>,
     service_connections: Arc<ServiceConnections>,
+    service_rev_regs: Arc<ServiceRevocationRegistries>,
 }
 
 impl ServiceCredentialsIssuer {
@@ -47,9 +48,11 @@ impl ServiceCredentialsIssuer {
         anoncreds: IndyCredxAnonCreds,
         wallet: Arc<IndySdkWallet>,
         service_connections: Arc<ServiceConnections>,
+        service_rev_regs: Arc<ServiceRevocationRegistries>,
     ) -> Self {
         Self {
             service_connections,
+            service_rev_regs,
             creds_issuer: ObjectCache::new("creds-issuer"),
             anoncreds,
             wallet,
@@ -90,11 +93,29 @@ impl ServiceCredentialsIssuer {
             (None, Some(connection_id)) => (Issuer::create("")?, connection_id.to_string()),
             (None, None) => return Err(AgentError::from_kind(AgentErrorKind::InvalidArguments)),
         };
-        let connection = self.service_connections.get_by_id(&connection_id)?;
         issuer
             .build_credential_offer_msg(self.wallet.as_ref(), &self.anoncreds, offer_info, None)
             .await?;
+        self.send_offer(issuer, &connection_id).await
+    }
+
+    /// Offers a revocable credential issued into the registries managed by
+    /// [`ServiceRevocationRegistries::init_rev_reg_manager`] for the credential definition
+    pub async fn send_managed_credential_offer(
+        &self,
+        connection_id: &str,
+        cred_def_id: &str,
+        credential_json: String,
+    ) -> AgentResult<String> {
+        let mut issuer = Issuer::create("")?;
+        self.service_rev_regs
+            .build_managed_credential_offer(&mut issuer, cred_def_id, credential_json)
+            .await?;
+        self.send_offer(issuer, connection_id).await
+    }
 
+    async fn send_offer(&self, issuer: Issuer, connection_id: &str) -> AgentResult<String> {
+        let connection = self.service_connections.get_by_id(connection_id)?;
         let wallet = self.wallet.as_ref();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
@@ -105,7 +126,7 @@ impl ServiceCredentialsIssuer {
         send_closure(credential_offer).await?;
         self.creds_issuer.insert(
             &issuer.get_thread_id()?,
-            IssuerWrapper::new(issuer, &connection_id),
+            IssuerWrapper::new(issuer, connection_id),
         )
     }
 
@@ -159,6 +180,9 @@ impl ServiceCredentialsIssuer {
             IssuerState::Failed => {
                 let problem_report = issuer.get_problem_report()?;
                 send_closure(problem_report.into()).await?;
+                self.service_rev_regs
+                    .release_managed_issuance(&issuer)
+                    .await?;
             }
             _ => {
                 let msg_issue_credential = issuer.get_msg_issue_credential()?;
@@ -1,6 +1,7 @@
 pub(crate) mod connection;
 pub(crate) mod credential_definition;
 pub(crate) mod did_exchange;
+pub(crate) mod did_web;
 pub(crate) mod holder;
 pub(crate) mod issuer;
 pub(crate) mod out_of_band;
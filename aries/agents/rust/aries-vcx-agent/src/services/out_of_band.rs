@@ -59,6 +59,9 @@ impl ServiceOutOfBand {
             .append_service(&OobService::SovService(service))
             .append_handshake_protocol(Protocol::DidExchangeType(DidExchangeType::V1(
                 DidExchangeTypeV1::new_v1_0(),
+            )))?
+            .append_handshake_protocol(Protocol::DidExchangeType(DidExchangeType::V1(
+                DidExchangeTypeV1::new_v1_1(),
             )))?;
 
         self.out_of_band.insert(
@@ -1,9 +1,18 @@
 use std::{
     path::Path,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use aries_vcx::common::primitives::revocation_registry::RevocationRegistry;
+use aries_vcx::{
+    common::primitives::{
+        revocation_registry::RevocationRegistry,
+        revocation_registry_manager::{
+            RevocationRegistryManager, RevocationRegistryManagerConfigBuilder, StandbyPreparation,
+        },
+    },
+    handlers::issuance::issuer::Issuer,
+};
 use aries_vcx_core::{
     anoncreds::credx_anoncreds::IndyCredxAnonCreds,
     ledger::indy_vdr_ledger::DefaultIndyLedgerWrite, wallet::indy::IndySdkWallet,
@@ -14,12 +23,17 @@ use crate::{
     storage::{object_cache::ObjectCache, Storage},
 };
 
+// Managers are shared by concurrent issuances, each of which holds the lock for the whole
+// reservation of its registry slot
+type SharedRevRegManager = Arc<tokio::sync::Mutex<RevocationRegistryManager>>;
+
 pub struct ServiceRevocationRegistries {
     ledger_write: Arc<DefaultIndyLedgerWrite>,
     anoncreds: IndyCredxAnonCreds,
     wallet: Arc<IndySdkWallet>,
     issuer_did: String,
     rev_regs: ObjectCache<RevocationRegistry>,
+    rev_reg_managers: ObjectCache<SharedRevRegManager>,
 }
 
 impl ServiceRevocationRegistries {
@@ -32,6 +46,7 @@ impl ServiceRevocationRegistries {
         Self {
             issuer_did,
             rev_regs: ObjectCache::new("rev-regs"),
+            rev_reg_managers: ObjectCache::new("rev-reg-managers"),
             ledger_write,
             anoncreds,
             wallet,
@@ -121,4 +136,197 @@ impl ServiceRevocationRegistries {
         };
         self.rev_regs.find_by(f)
     }
+
+    /// Starts managing the revocation registries of the credential definition, so that
+    /// credentials can be offered with [`Self::build_managed_credential_offer`] without
+    /// creating and publishing registries by hand. Tails files are uploaded to the tails server
+    /// at `tails_base_url` if `tails_upload_token` is set.
+    pub async fn init_rev_reg_manager(
+        &self,
+        cred_def_id: &str,
+        max_creds: u32,
+        tails_base_url: &str,
+        tails_upload_token: Option<&str>,
+    ) -> AgentResult<String> {
+        if self.rev_reg_managers.contains_key(cred_def_id) {
+            return Ok(cred_def_id.to_string());
+        }
+        let mut config = RevocationRegistryManagerConfigBuilder::default();
+        config
+            .issuer_did(self.issuer_did.as_str())
+            .cred_def_id(cred_def_id)
+            .tails_dir("/tmp")
+            .tails_base_url(tails_base_url)
+            .max_creds(max_creds);
+        if let Some(tails_upload_token) = tails_upload_token {
+            config.tails_upload_token(tails_upload_token);
+        }
+        let config = config.build().map_err(|err| {
+            AgentError::from_msg(AgentErrorKind::InvalidArguments, &err.to_string())
+        })?;
+        let manager = RevocationRegistryManager::init(
+            self.wallet.as_ref(),
+            &self.anoncreds,
+            self.ledger_write.as_ref(),
+            config,
+        )
+        .await?;
+        self.rev_reg_managers
+            .insert(cred_def_id, Arc::new(tokio::sync::Mutex::new(manager)))
+    }
+
+    /// Builds the offer into the active registry of the credential definition. The next
+    /// registry is prepared in the background once the active registry gets close to full.
+    pub async fn build_managed_credential_offer(
+        &self,
+        issuer: &mut Issuer,
+        cred_def_id: &str,
+        credential_json: String,
+    ) -> AgentResult<()> {
+        let manager = self.rev_reg_managers.get(cred_def_id)?;
+        let preparation = {
+            let mut manager = manager.lock().await;
+            issuer
+                .build_managed_credential_offer_msg(
+                    self.wallet.as_ref(),
+                    &self.anoncreds,
+                    self.ledger_write.as_ref(),
+                    &mut manager,
+                    credential_json,
+                    None,
+                )
+                .await?;
+            manager.start_standby_preparation()
+        };
+        if let Some(preparation) = preparation {
+            self.prepare_standby_in_background(manager, preparation);
+        }
+        Ok(())
+    }
+
+    fn prepare_standby_in_background(
+        &self,
+        manager: SharedRevRegManager,
+        preparation: StandbyPreparation,
+    ) {
+        let wallet = self.wallet.clone();
+        let anoncreds = self.anoncreds;
+        let ledger_write = self.ledger_write.clone();
+        tokio::spawn(async move {
+            let prepared = preparation
+                .prepare(wallet.as_ref(), &anoncreds, ledger_write.as_ref())
+                .await;
+            match manager.lock().await.finish_standby_preparation(prepared) {
+                Ok(rev_reg_id) => info!("Prepared standby revocation registry {}", rev_reg_id),
+                Err(err) => error!("Failed to prepare standby revocation registry: {}", err),
+            }
+        });
+    }
+
+    /// Releases the registry slot of a failed issuance if its registry is managed
+    pub async fn release_managed_issuance(&self, issuer: &Issuer) -> AgentResult<()> {
+        // Credentials offered without revocation have no registry slot to release
+        let rev_reg_id = match issuer.get_rev_reg_id() {
+            Ok(rev_reg_id) => rev_reg_id,
+            Err(_) => return Ok(()),
+        };
+        if let Some(manager) = self.find_rev_reg_manager(&rev_reg_id).await? {
+            issuer
+                .release_managed_issuance(self.wallet.as_ref(), &mut *manager.lock().await)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Revokes a credential issued into a managed registry. The revocation is published by
+    /// [`Self::publish_managed_revocations`] once the publish interval of the registry has
+    /// elapsed.
+    pub async fn revoke_managed_credential(
+        &self,
+        rev_reg_id: &str,
+        cred_rev_id: &str,
+    ) -> AgentResult<()> {
+        let manager = self
+            .find_rev_reg_manager(rev_reg_id)
+            .await?
+            .ok_or_else(|| {
+                AgentError::from_msg(
+                    AgentErrorKind::NotFound,
+                    &format!("Revocation registry {} is not managed", rev_reg_id),
+                )
+            })?;
+        manager
+            .lock()
+            .await
+            .revoke_credential(
+                self.wallet.as_ref(),
+                &self.anoncreds,
+                rev_reg_id,
+                cred_rev_id,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Publishes the due revocations of all managed registries, returning the IDs of the
+    /// registries whose revocations were published
+    pub async fn publish_managed_revocations(&self) -> AgentResult<Vec<String>> {
+        let mut published = Vec::new();
+        for manager in self.rev_reg_managers()? {
+            let mut manager = manager.lock().await;
+            if manager.is_publish_due() {
+                published.extend(
+                    manager
+                        .publish_pending_revocations(
+                            self.wallet.as_ref(),
+                            &self.anoncreds,
+                            self.ledger_write.as_ref(),
+                        )
+                        .await?,
+                );
+            }
+        }
+        Ok(published)
+    }
+
+    /// Runs [`Self::publish_managed_revocations`] every `period` until the returned task is
+    /// aborted
+    pub fn spawn_revocation_publisher(
+        self: Arc<Self>,
+        period: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.publish_managed_revocations().await {
+                    Ok(published) if !published.is_empty() => {
+                        info!("Published revocations of registries {:?}", published)
+                    }
+                    Ok(_) => {}
+                    Err(err) => error!("Failed to publish revocations: {}", err),
+                }
+            }
+        })
+    }
+
+    fn rev_reg_managers(&self) -> AgentResult<Vec<SharedRevRegManager>> {
+        self.rev_reg_managers
+            .find_by(|(cred_def_id, _)| Some(cred_def_id.clone()))?
+            .iter()
+            .map(|cred_def_id| self.rev_reg_managers.get(cred_def_id))
+            .collect()
+    }
+
+    async fn find_rev_reg_manager(
+        &self,
+        rev_reg_id: &str,
+    ) -> AgentResult<Option<SharedRevRegManager>> {
+        for manager in self.rev_reg_managers()? {
+            if manager.lock().await.get_rev_reg(rev_reg_id).is_ok() {
+                return Ok(Some(manager));
+            }
+        }
+        Ok(None)
+    }
 }
@@ -0,0 +1,4 @@
+-- Time of arrival, used to expire messages older than the configured retention period
+ALTER TABLE messages
+    ADD COLUMN received_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD INDEX (received_at);
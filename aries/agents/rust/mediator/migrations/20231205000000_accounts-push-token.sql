@@ -0,0 +1,2 @@
+-- Push notification token registered by the account's device, notified when messages are queued
+ALTER TABLE accounts ADD COLUMN push_token VARCHAR(512) NULL;
@@ -0,0 +1,44 @@
+-- SQLite port of the MySQL 3-part schema.
+-- SQLite has no UUID functions, so account_id / message_id uuids are generated by the mediator.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    -- metadata (total accounts registered until this account's creation)
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- uuid bytes
+    account_id BLOB NOT NULL UNIQUE,
+    -- for display purpose (uuid string of account_id)
+    account_name TEXT NOT NULL,
+    -- mediator facing pubkey
+    auth_pubkey TEXT NOT NULL UNIQUE,
+    -- key used to sign messages to aries peer
+    our_signing_key TEXT NOT NULL,
+    -- their complete did_doc, as json text
+    did_doc TEXT NOT NULL
+);
+
+
+CREATE TABLE IF NOT EXISTS recipients (
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id BLOB NOT NULL,
+    recipient_key TEXT NOT NULL UNIQUE,
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE
+);
+
+
+CREATE TABLE IF NOT EXISTS messages (
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id BLOB NOT NULL,
+    -- Allow nullable so that recipient_key remove key-updates are possible
+    -- without also removing associated messages
+    recipient_key TEXT NULL,
+    -- Readable message id (for identifying message over wire)
+    message_id TEXT NOT NULL UNIQUE,
+    message_data BLOB NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (recipient_key) REFERENCES recipients(recipient_key)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS messages_account_recipient ON messages(account_id, recipient_key);
@@ -0,0 +1,8 @@
+-- Time of arrival (unix seconds), used to expire messages older than the configured retention period.
+-- SQLite can't add a column defaulting to current time, so it is set by the mediator on insert;
+-- already queued messages are treated as received now.
+ALTER TABLE messages ADD COLUMN received_at INTEGER NOT NULL DEFAULT 0;
+
+UPDATE messages SET received_at = CAST(strftime('%s', 'now') AS INTEGER);
+
+CREATE INDEX IF NOT EXISTS messages_received_at ON messages(received_at);
@@ -23,7 +23,7 @@ use messages::{
 };
 use test_utils::mockdata::mock_ledger::MockLedger;
 
-use crate::persistence::MediatorPersistence;
+use crate::{error::MediatorResult, persistence::MediatorPersistence};
 pub mod transports;
 
 use self::transports::AriesTransport;
@@ -94,11 +94,12 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
         let their_vk = state.remote_vk().map_err(|e| e.to_string())?;
         let our_vk = &state.pairwise_info().pw_vk;
         self.create_account(&their_vk, our_vk, state.their_did_doc())
-            .await?;
+            .await
+            .map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub async fn list_contacts(&self) -> Result<Vec<(String, String)>, String> {
+    pub async fn list_contacts(&self) -> MediatorResult<Vec<(String, String)>> {
         self.persistence.list_accounts().await
     }
     /// Workflow method to establish DidComm connection with Aries peer, given OOB invite.
@@ -126,10 +127,7 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
             serde_json::to_string_pretty(&response_envelope).unwrap()
         );
         let response_envelope_bytes = serde_json::to_vec(&response_envelope)?;
-        let response_unpacked = self
-            .unpack_didcomm(&response_envelope_bytes)
-            .await
-            .map_err(|err| GenericStringError { msg: err })?;
+        let response_unpacked = self.unpack_didcomm(&response_envelope_bytes).await?;
         let response_message: AriesMessage = serde_json::from_str(&response_unpacked.message)?;
         let AriesMessage::Connection(Connection::Response(connection_response)) = response_message
         else {
@@ -1,9 +1,17 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Arc};
 
 use async_trait::async_trait;
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use futures::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
 use log::debug;
 use serde_json::Value;
+use tokio::{net::TcpStream, sync::Mutex};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 #[derive(thiserror::Error, Debug)]
 #[error("{msg}")]
@@ -65,3 +73,87 @@ impl AriesTransport for AriesReqwest {
         Ok(res_json)
     }
 }
+
+/// Transport over a single WebSocket connection, e.g. to the mediator's `/ws` endpoint.
+/// The connection is held open, so with `return_route: all` the peer may also push messages
+/// over it; those are read with [`AriesWebSocket::receive_aries_envelope`].
+///
+/// Clones share the connection. Sends are serialized, each envelope being written and its
+/// response read before the next envelope is sent.
+#[derive(Clone)]
+pub struct AriesWebSocket {
+    sink: Arc<Mutex<SplitSink<WebSocket, Message>>>,
+    stream: Arc<Mutex<SplitStream<WebSocket>>>,
+}
+
+impl AriesWebSocket {
+    pub async fn connect(url: &str) -> Result<Self, AriesTransportError> {
+        let (socket, _response) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(AriesTransportError::from_std_error)?;
+        let (sink, stream) = socket.split();
+        Ok(AriesWebSocket {
+            sink: Arc::new(Mutex::new(sink)),
+            stream: Arc::new(Mutex::new(stream)),
+        })
+    }
+
+    /// Waits for the next envelope sent by the peer
+    pub async fn receive_aries_envelope(&mut self) -> Result<Value, AriesTransportError> {
+        let mut stream = self.stream.lock().await;
+        Self::read_envelope(&mut stream).await
+    }
+
+    async fn read_envelope(
+        stream: &mut SplitStream<WebSocket>,
+    ) -> Result<Value, AriesTransportError> {
+        loop {
+            let message = stream
+                .next()
+                .await
+                .ok_or_else(|| AriesTransportError {
+                    msg: "WebSocket closed by peer".to_owned(),
+                })?
+                .map_err(AriesTransportError::from_std_error)?;
+            let envelope_bytes = match message {
+                Message::Text(text) => text.into_bytes(),
+                Message::Binary(bytes) => bytes,
+                Message::Close(_) => {
+                    return Err(AriesTransportError {
+                        msg: "WebSocket closed by peer".to_owned(),
+                    })
+                }
+                _ => continue,
+            };
+            let envelope_json: Value = serde_json::from_slice(&envelope_bytes)
+                .map_err(AriesTransportError::from_std_error)?;
+            debug!("Received envelope {:?}", envelope_json);
+            return Ok(envelope_json);
+        }
+    }
+}
+
+#[async_trait]
+impl AriesTransport for AriesWebSocket {
+    /// Destination is ignored, envelope goes to the peer at the other end of the socket
+    async fn send_aries_envelope(
+        &mut self,
+        envelope_json: Value,
+        _destination: &AriesDidDoc,
+    ) -> Result<Value, AriesTransportError> {
+        debug!(
+            "Packed: {:?}, sending over WebSocket",
+            serde_json::to_string(&envelope_json).unwrap()
+        );
+        // holding the read half until the response arrives keeps concurrent senders from
+        // taking each other's responses
+        let mut stream = self.stream.lock().await;
+        self.sink
+            .lock()
+            .await
+            .send(Message::Text(envelope_json.to_string()))
+            .await
+            .map_err(AriesTransportError::from_std_error)?;
+        Self::read_envelope(&mut stream).await
+    }
+}
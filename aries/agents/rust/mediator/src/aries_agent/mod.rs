@@ -25,7 +25,15 @@ use messages::{
 use serde_json::json;
 
 use crate::{
-    persistence::{get_persistence, MediatorPersistence},
+    error::{MediatorError, MediatorResult},
+    mediation::{
+        admission::{MediationAdmissionPolicy, OpenAdmission},
+        live_delivery::LiveDeliverySessions,
+        notifier::{NoopNotifier, PushNotifier, PushRateLimit},
+        retention::RetentionPolicy,
+    },
+    metrics::MediatorMetrics,
+    persistence::{get_persistence, InMemoryPersistence, MediatorPersistence},
     utils::{prelude::*, structs::VerKey},
 };
 
@@ -38,6 +46,12 @@ pub struct Agent<T: BaseWallet, P: MediatorPersistence> {
     wallet: Arc<T>,
     persistence: Arc<P>,
     service: Option<AriesService>,
+    live_sessions: Arc<LiveDeliverySessions>,
+    admission_policy: Arc<dyn MediationAdmissionPolicy>,
+    retention_policy: RetentionPolicy,
+    metrics: Arc<MediatorMetrics>,
+    push_notifier: Arc<dyn PushNotifier>,
+    push_rate_limit: Arc<PushRateLimit>,
 }
 
 pub type ArcAgent<T, P> = Arc<Agent<T, P>>;
@@ -47,21 +61,28 @@ pub struct AgentBuilder<T: BaseWallet> {
 }
 /// Constructors
 impl AgentBuilder<IndySdkWallet> {
-    pub async fn new_from_wallet_config(
+    pub async fn new_from_wallet_config<P: MediatorPersistence>(
         config: WalletConfig,
-    ) -> Result<Agent<IndySdkWallet, sqlx::MySqlPool>, AriesVcxCoreError> {
+        persistence: Arc<P>,
+    ) -> Result<Agent<IndySdkWallet, P>, AriesVcxCoreError> {
         let wallet_handle: WalletHandle = create_and_open_wallet(&config).await?;
         let wallet = Arc::new(IndySdkWallet::new(wallet_handle));
-        info!("Connecting to persistence layer");
-        let persistence = Arc::new(get_persistence().await);
         Ok(Agent {
             wallet,
             persistence,
             service: None,
+            live_sessions: Arc::new(LiveDeliverySessions::default()),
+            admission_policy: Arc::new(OpenAdmission::default()),
+            retention_policy: RetentionPolicy::default(),
+            metrics: Arc::new(MediatorMetrics::default()),
+            push_notifier: Arc::new(NoopNotifier),
+            push_rate_limit: Arc::new(PushRateLimit::default()),
         })
     }
-    pub async fn new_demo_agent() -> Result<Agent<IndySdkWallet, sqlx::MySqlPool>, AriesVcxCoreError>
-    {
+    /// Demo agent backed by given persistence
+    pub async fn new_demo_agent_with_persistence<P: MediatorPersistence>(
+        persistence: Arc<P>,
+    ) -> Result<Agent<IndySdkWallet, P>, AriesVcxCoreError> {
         let config = WalletConfig {
             wallet_name: uuid::Uuid::new_v4().to_string(),
             wallet_key: "8dvfYSt5d1taSd6yJdpjq4emkwsPDDLYxkNFysFD2cZY".into(),
@@ -72,7 +93,19 @@ impl AgentBuilder<IndySdkWallet> {
             rekey: None,
             rekey_derivation_method: None,
         };
-        Self::new_from_wallet_config(config).await
+        Self::new_from_wallet_config(config, persistence).await
+    }
+    /// Demo agent backed by MySQL persistence
+    pub async fn new_demo_agent() -> Result<Agent<IndySdkWallet, sqlx::MySqlPool>, AriesVcxCoreError>
+    {
+        info!("Connecting to persistence layer");
+        let persistence = Arc::new(get_persistence().await);
+        Self::new_demo_agent_with_persistence(persistence).await
+    }
+    /// Demo agent backed by in-memory persistence
+    pub async fn new_in_memory_demo_agent(
+    ) -> Result<Agent<IndySdkWallet, InMemoryPersistence>, AriesVcxCoreError> {
+        Self::new_demo_agent_with_persistence(Arc::new(InMemoryPersistence::default())).await
     }
 }
 
@@ -84,6 +117,36 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
     pub fn get_persistence_ref(&self) -> Arc<impl MediatorPersistence> {
         self.persistence.clone()
     }
+    pub fn get_live_sessions_ref(&self) -> Arc<LiveDeliverySessions> {
+        self.live_sessions.clone()
+    }
+    pub fn get_admission_policy_ref(&self) -> &dyn MediationAdmissionPolicy {
+        self.admission_policy.as_ref()
+    }
+    pub fn set_admission_policy(&mut self, policy: Arc<dyn MediationAdmissionPolicy>) {
+        self.admission_policy = policy;
+    }
+    pub fn get_retention_policy_ref(&self) -> &RetentionPolicy {
+        &self.retention_policy
+    }
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.retention_policy = policy;
+    }
+    pub fn get_metrics_ref(&self) -> &MediatorMetrics {
+        self.metrics.as_ref()
+    }
+    pub fn get_push_notifier_ref(&self) -> Arc<dyn PushNotifier> {
+        self.push_notifier.clone()
+    }
+    pub fn set_push_notifier(&mut self, notifier: Arc<dyn PushNotifier>) {
+        self.push_notifier = notifier;
+    }
+    pub fn get_push_rate_limit_ref(&self) -> Arc<PushRateLimit> {
+        self.push_rate_limit.clone()
+    }
+    pub fn set_push_rate_limit(&mut self, rate_limit: PushRateLimit) {
+        self.push_rate_limit = Arc::new(rate_limit);
+    }
     pub fn get_service_ref(&self) -> Option<&AriesService> {
         self.service.as_ref()
     }
@@ -112,22 +175,24 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
     ) -> Result<(), AriesVcxCoreError> {
         self.reset_service(routing_keys, service_endpoint).await
     }
-    pub fn get_oob_invite(&self) -> Result<OOBInvitation, String> {
+    pub fn get_oob_invite(&self) -> MediatorResult<OOBInvitation> {
         if let Some(service) = &self.service {
             let invitation = OutOfBandSender::create()
                 .append_service(&OobService::AriesService(service.clone()))
                 .oob;
             Ok(invitation)
         } else {
-            Err("No service to create invite for".to_owned())
+            Err(MediatorError::Internal(
+                "No service to create invite for".to_owned(),
+            ))
         }
     }
-    pub async fn unpack_didcomm(&self, didcomm_msg: &[u8]) -> Result<UnpackMessageOutput, String> {
+    pub async fn unpack_didcomm(&self, didcomm_msg: &[u8]) -> MediatorResult<UnpackMessageOutput> {
         let unpacked = self
             .wallet
             .unpack_message(didcomm_msg)
             .await
-            .expect("Valid didcomm?");
+            .map_err(|err| MediatorError::MalformedMessage(err.to_string()))?;
         info!("{:#?}", unpacked);
         Ok(unpacked)
     }
@@ -137,33 +202,73 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
         message: &[u8],
         our_vk: &VerKey,
         their_diddoc: &AriesDidDoc,
-    ) -> Result<EncryptionEnvelope, String> {
+    ) -> MediatorResult<EncryptionEnvelope> {
         EncryptionEnvelope::create(self.wallet.as_ref(), message, Some(our_vk), their_diddoc)
             .await
-            .map_err(string_from_std_error)
+            .map_err(|err| MediatorError::Internal(err.to_string()))
     }
 
     pub async fn auth_and_get_details(
         &self,
         sender_verkey: &Option<VerKey>,
-    ) -> Result<(String, VerKey, VerKey, AriesDidDoc), String> {
+    ) -> MediatorResult<(String, VerKey, VerKey, AriesDidDoc)> {
         let auth_pubkey = sender_verkey
             .as_deref()
-            .ok_or("Anonymous sender can't be authenticated")?
+            .ok_or_else(|| {
+                MediatorError::AuthFailure("Anonymous sender can't be authenticated".to_owned())
+            })?
             .to_owned();
-        let (_sr_no, account_name, our_signing_key, did_doc_json) =
-            self.persistence.get_account_details(&auth_pubkey).await?;
-        let diddoc =
-            serde_json::from_value::<AriesDidDoc>(did_doc_json).map_err(string_from_std_error)?;
+        let (_sr_no, account_name, our_signing_key, did_doc_json) = self
+            .persistence
+            .get_account_details(&auth_pubkey)
+            .await
+            .map_err(|err| match err {
+                MediatorError::UnknownAccount(account) => {
+                    MediatorError::AuthFailure(format!("No account for sender {account}"))
+                }
+                err => err,
+            })?;
+        let diddoc = serde_json::from_value::<AriesDidDoc>(did_doc_json)
+            .map_err(|err| MediatorError::Storage(err.to_string()))?;
         Ok((account_name, auth_pubkey, our_signing_key, diddoc))
     }
+    /// Creates an account for the sender of the connection request, if the admission policy
+    /// admits it. `sender_verkey` is the authenticated sender of the request envelope, it has to
+    /// be the recipient key of the DidDoc in the request.
     pub async fn handle_connection_req(
         &self,
         request: Request,
-    ) -> Result<EncryptionEnvelope, String> {
+        sender_verkey: &Option<VerKey>,
+    ) -> MediatorResult<EncryptionEnvelope> {
         if let Err(err) = request.content.connection.did_doc.validate() {
-            return Err(format!("Request DidDoc validation failed! {:?}", err));
+            return Err(MediatorError::MalformedMessage(format!(
+                "Request DidDoc validation failed! {:?}",
+                err
+            )));
         }
+        let their_keys = request
+            .content
+            .connection
+            .did_doc
+            .recipient_keys()
+            .map_err(|e| MediatorError::MalformedMessage(e.to_string()))?;
+        let auth_pubkey = their_keys
+            .first()
+            .ok_or_else(|| {
+                MediatorError::MalformedMessage("No recipient key for client :/ ?".to_owned())
+            })?
+            .to_owned();
+        if sender_verkey.as_deref() != Some(auth_pubkey.as_str()) {
+            return Err(MediatorError::AuthFailure(
+                "Connection request must be sent by the recipient key of its DidDoc".to_owned(),
+            ));
+        }
+        self.admission_policy
+            .admit(&auth_pubkey)
+            .map_err(MediatorError::AuthFailure)?;
+        let service = self.service.as_ref().ok_or_else(|| {
+            MediatorError::Internal("Mediator must have service defined".to_owned())
+        })?;
 
         let thread_id = request
             .decorators
@@ -174,14 +279,11 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
             .wallet
             .create_and_store_my_did(None, None)
             .await
-            .map_err(|e| e.to_string())?;
-        let old_vk = self
-            .service
-            .as_ref()
-            .unwrap()
+            .map_err(|e| MediatorError::Internal(e.to_string()))?;
+        let old_vk = service
             .recipient_keys
             .first()
-            .unwrap()
+            .ok_or_else(|| MediatorError::Internal("Service must have recipient key".to_owned()))?
             .to_owned();
 
         let response: Response = utils::build_response_content(
@@ -190,11 +292,11 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
             old_vk.clone(),
             did,
             vk.clone(),
-            self.service.as_ref().unwrap().service_endpoint.clone(),
-            self.service.as_ref().unwrap().routing_keys.clone(),
+            service.service_endpoint.clone(),
+            service.routing_keys.clone(),
         )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| MediatorError::Internal(e.to_string()))?;
         let aries_response = AriesMessage::Connection(Connection::Response(response));
         let their_diddoc = request.content.connection.did_doc;
         let packed_response_envelope = EncryptionEnvelope::create(
@@ -204,12 +306,9 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
             &their_diddoc,
         )
         .await
-        .map_err(|e| e.to_string())?;
-        let their_keys = their_diddoc.recipient_keys().map_err(|e| e.to_string())?;
-        let auth_pubkey = their_keys
-            .first()
-            .ok_or("No recipient key for client :/ ?".to_owned())?;
-        self.create_account(auth_pubkey, &vk, &their_diddoc).await?;
+        .map_err(|e| MediatorError::Internal(e.to_string()))?;
+        self.create_account(&auth_pubkey, &vk, &their_diddoc)
+            .await?;
         Ok(packed_response_envelope)
     }
 
@@ -218,7 +317,7 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
         their_vk: &VerKey,
         our_vk: &VerKey,
         did_doc: &AriesDidDoc,
-    ) -> Result<(), String> {
+    ) -> MediatorResult<()> {
         self.persistence
             .create_account(their_vk, our_vk, &json!(did_doc).to_string())
             .await?;
@@ -228,6 +327,8 @@ impl<T: BaseWallet + 'static, P: MediatorPersistence> Agent<T, P> {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use aries_vcx::{
         protocols::oob::oob_invitation_to_legacy_did_doc,
         utils::encryption_envelope::EncryptionEnvelope,
@@ -237,12 +338,16 @@ mod test {
     use test_utils::mockdata::mock_ledger::MockLedger;
 
     use super::AgentBuilder;
+    use crate::{
+        didcomm_handlers::process_aries, error::MediatorError,
+        mediation::admission::AllowListAdmission, persistence::MediatorPersistence,
+    };
 
     #[tokio::test]
     pub async fn test_pack_unpack() {
         let message: Value = serde_json::from_str("{}").unwrap();
         let message_bytes = serde_json::to_vec(&message).unwrap();
-        let mut agent = AgentBuilder::new_demo_agent().await.unwrap();
+        let mut agent = AgentBuilder::new_in_memory_demo_agent().await.unwrap();
         agent
             .init_service(
                 vec![],
@@ -265,4 +370,33 @@ mod test {
         info!("{:?}", unpacked);
         print!("{:?}", unpacked);
     }
+
+    #[tokio::test]
+    pub async fn test_connection_denied_by_admission_policy() {
+        let mut mediator = AgentBuilder::new_in_memory_demo_agent().await.unwrap();
+        mediator
+            .init_service(
+                vec![],
+                "http://127.0.0.1:8005/didcomm".to_string().parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        mediator.set_admission_policy(Arc::new(AllowListAdmission::new(Vec::new(), None)));
+        let client = AgentBuilder::new_in_memory_demo_agent().await.unwrap();
+        let (_state, EncryptionEnvelope(request)) = client
+            .gen_connection_request(mediator.get_oob_invite().unwrap())
+            .await
+            .unwrap();
+        let mediator = Arc::new(mediator);
+        let Err(err) = process_aries(&mediator, &request).await else {
+            panic!("Connection request should be refused");
+        };
+        assert!(matches!(err, MediatorError::AuthFailure(_)));
+        assert!(mediator
+            .get_persistence_ref()
+            .list_accounts()
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -1,31 +1,106 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use aries_vcx_core::wallet::indy::IndySdkWallet;
 use log::info;
-use mediator::aries_agent::AgentBuilder;
+use mediator::{
+    aries_agent::{Agent, AgentBuilder},
+    error::ConfigError,
+    http_routes::{
+        admin::admin_token_from_env,
+        did_web::{did_web_host, DidWebHosting},
+    },
+    mediation::{
+        admission::admission_policy_from_env,
+        notifier::{push_notifier_from_env, push_rate_limit_from_env},
+        retention::{retention_policy_from_env, spawn_purge_task},
+    },
+    persistence::{get_persistence, get_sqlite_db_pool, InMemoryPersistence, MediatorPersistence},
+};
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     load_dot_env();
     setup_logging();
     info!("Starting up mediator! ⚙️⚙️");
     let endpoint_root = std::env::var("ENDPOINT_ROOT").unwrap_or("127.0.0.1:8005".into());
     info!("Mediator endpoint root address {}", endpoint_root);
-    let mut agent = AgentBuilder::new_demo_agent().await.unwrap();
-    agent
-        .init_service(
-            vec![],
-            format!("http://{endpoint_root}/didcomm").parse().unwrap(),
-        )
+    let persistence_backend = std::env::var("MEDIATOR_PERSISTENCE").unwrap_or("mysql".into());
+    info!("Connecting to {} persistence layer", persistence_backend);
+    match persistence_backend.as_str() {
+        "mysql" => {
+            let persistence = Arc::new(get_persistence().await);
+            serve(new_agent(persistence).await?, &endpoint_root).await
+        }
+        "sqlite" => {
+            let persistence = Arc::new(get_sqlite_db_pool().await);
+            serve(new_agent(persistence).await?, &endpoint_root).await
+        }
+        "in_memory" => {
+            let persistence = Arc::new(InMemoryPersistence::default());
+            serve(new_agent(persistence).await?, &endpoint_root).await
+        }
+        other => Err(ConfigError(format!(
+            "Unknown MEDIATOR_PERSISTENCE {other:?}, expected one of mysql, sqlite, in_memory"
+        ))
+        .into()),
+    }
+}
+
+async fn new_agent<P: MediatorPersistence>(
+    persistence: Arc<P>,
+) -> Result<Agent<IndySdkWallet, P>, ConfigError> {
+    let mut agent = AgentBuilder::new_demo_agent_with_persistence(persistence)
         .await
         .unwrap();
-    let app_router = mediator::http_routes::build_router(agent).await;
+    agent.set_admission_policy(admission_policy_from_env()?.into());
+    agent.set_push_notifier(push_notifier_from_env()?.into());
+    agent.set_push_rate_limit(push_rate_limit_from_env()?);
+    let retention_policy = retention_policy_from_env()?;
+    spawn_purge_task(agent.get_persistence_ref(), retention_policy.clone());
+    agent.set_retention_policy(retention_policy);
+    Ok(agent)
+}
+
+async fn serve(
+    mut agent: Agent<IndySdkWallet, impl MediatorPersistence>,
+    endpoint_root: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listen_address = endpoint_root.parse::<SocketAddr>().map_err(|err| {
+        ConfigError(format!(
+            "ENDPOINT_ROOT={endpoint_root:?}: {err}, pass an address to listen on like IP:PORT"
+        ))
+    })?;
+    let service_endpoint = format!("http://{endpoint_root}/didcomm")
+        .parse::<url::Url>()
+        .map_err(|err| ConfigError(format!("ENDPOINT_ROOT={endpoint_root:?}: {err}")))?;
+    agent.init_service(vec![], service_endpoint).await?;
+    let did_web = match std::env::var("MEDIATOR_DID_WEB").ok() {
+        Some(did) => {
+            info!("Serving DID document of {}", did);
+            let host = did_web_host(&agent, &did).map_err(|err| {
+                ConfigError(format!(
+                    "MEDIATOR_DID_WEB={did:?}: {err}, pass a did:web DID like did:web:example.com"
+                ))
+            })?;
+            let state_file = std::env::var("MEDIATOR_DID_WEB_STATE_FILE")
+                .ok()
+                .map(PathBuf::from);
+            Some(DidWebHosting::new(host, state_file)?)
+        }
+        None => None,
+    };
+    let admin_token = admin_token_from_env()?;
+    if admin_token.is_none() {
+        info!("MEDIATOR_ADMIN_TOKEN not set, admin API disabled");
+    }
+    let app_router =
+        mediator::http_routes::build_router_with_admin(agent, admin_token.as_deref(), did_web)
+            .await;
     info!("Starting server");
-    axum::Server::bind(
-        &endpoint_root
-            .parse()
-            .expect("Pass an address to listen on like IP:PORT"),
-    )
-    .serve(app_router.into_make_service())
-    .await
-    .unwrap();
+    axum::Server::bind(&listen_address)
+        .serve(app_router.into_make_service())
+        .await?;
+    Ok(())
 }
 
 fn setup_logging() {
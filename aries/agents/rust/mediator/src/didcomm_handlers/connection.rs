@@ -1,17 +1,21 @@
 use messages::msg_fields::protocols::connection::Connection;
 
 use super::{unhandled_aries_message, utils::prelude::*, ArcAgent};
+use crate::utils::structs::VerKey;
 
 pub async fn handle_aries_connection<T: BaseWallet + 'static, P: MediatorPersistence>(
     agent: ArcAgent<T, P>,
     connection: Connection,
-) -> Result<EncryptionEnvelope, String> {
+    sender_verkey: &Option<VerKey>,
+) -> MediatorResult<EncryptionEnvelope> {
     match connection {
-        Connection::Invitation(_invite) => {
-            Err("Mediator does not handle random invites. Sorry.".to_owned())
-        }
+        Connection::Invitation(_invite) => Err(MediatorError::UnsupportedMessage(
+            "Mediator does not handle random invites. Sorry.".to_owned(),
+        )),
         Connection::Request(register_request) => {
-            agent.handle_connection_req(register_request).await
+            agent
+                .handle_connection_req(register_request, sender_verkey)
+                .await
         }
         _ => Err(unhandled_aries_message(connection)),
     }
@@ -1,4 +1,4 @@
-use messages::msg_fields::protocols::{notification::ack::Ack, routing::Forward};
+use messages::msg_fields::protocols::routing::Forward;
 
 use super::{utils::prelude::*, ArcAgent};
 use crate::mediation::forward::handle_forward;
@@ -6,9 +6,17 @@ use crate::mediation::forward::handle_forward;
 pub async fn handle_routing_forward(
     agent: ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
     forward: Forward,
-) -> Result<Ack, String> {
+) -> MediatorResult<()> {
     info!("{:?}", forward);
-    let ack = handle_forward(agent.get_persistence_ref(), forward).await;
-
-    Ok(ack)
+    handle_forward(
+        agent.get_persistence_ref(),
+        agent.get_live_sessions_ref(),
+        agent.get_retention_policy_ref(),
+        agent.get_metrics_ref(),
+        agent.get_push_notifier_ref(),
+        agent.get_push_rate_limit_ref(),
+        forward,
+    )
+    .await;
+    Ok(())
 }
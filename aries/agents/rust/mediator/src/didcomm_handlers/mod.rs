@@ -1,78 +1,141 @@
 use std::fmt::Debug;
 
 use axum::{body::Bytes, extract::State, Json};
-use messages::AriesMessage;
+use messages::{
+    decorators::transport::{ReturnRoute, Transport},
+    AriesMessage,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use utils::prelude::*;
 
+use crate::utils::structs::VerKey;
+
 mod connection;
 mod forward;
 mod mediator_coord;
 mod pickup;
+mod push_notifications;
 mod utils;
 
 use connection::handle_aries_connection;
 use forward::handle_routing_forward;
 use mediator_coord::handle_mediation_coord;
 use pickup::handle_pickup_protocol;
+use push_notifications::{handle_set_device_info, SetDeviceInfo};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 enum GeneralAriesMessage {
     AriesVCXSupported(AriesMessage),
+    PushNotifications(SetDeviceInfo),
 }
-pub fn unhandled_aries_message(message: impl Debug) -> String {
-    format!("Don't know how to handle this message type {:#?}", message)
+pub fn unhandled_aries_message(message: impl Debug) -> MediatorError {
+    MediatorError::UnsupportedMessage(format!(
+        "Don't know how to handle this message type {:#?}",
+        message
+    ))
 }
 
 pub async fn handle_aries<T: BaseWallet + 'static, P: MediatorPersistence>(
     State(agent): State<ArcAgent<T, P>>,
     didcomm_msg: Bytes,
-) -> Result<Json<Value>, String> {
+) -> MediatorResult<Json<Value>> {
+    let processed = process_aries(&agent, &didcomm_msg).await?;
+    Ok(Json(processed.response.unwrap_or_else(|| json!({}))))
+}
+
+/// Result of processing an inbound envelope, independent of the transport it came over
+pub struct ProcessedAriesMessage {
+    /// Response for the sender, None if there is nothing to respond with. Packed, except for
+    /// the empty response to a forward, as the forwarding sender is anonymous.
+    pub response: Option<Value>,
+    /// auth_pubkey of the authenticated sender, if it asked (RFC 0092 `~transport`
+    /// `return_route: all`) for all messages to be returned over the inbound transport
+    pub return_route_auth_pubkey: Option<VerKey>,
+}
+
+pub async fn process_aries<T: BaseWallet + 'static, P: MediatorPersistence>(
+    agent: &ArcAgent<T, P>,
+    didcomm_msg: &[u8],
+) -> MediatorResult<ProcessedAriesMessage> {
     log::info!("processing message {:?}", &didcomm_msg);
-    let unpacked = agent.unpack_didcomm(&didcomm_msg).await.unwrap();
-    let aries_message: GeneralAriesMessage =
-        serde_json::from_str(&unpacked.message).map_err(|e| e.to_string())?;
-    let packed_response =
+    let unpacked = agent.unpack_didcomm(didcomm_msg).await?;
+    let message_json: Value = serde_json::from_str(&unpacked.message)
+        .map_err(|e| MediatorError::MalformedMessage(e.to_string()))?;
+    let message_id = message_json["@id"].as_str().unwrap_or_default().to_owned();
+    let return_route_all = message_json
+        .get("~transport")
+        .and_then(|transport| serde_json::from_value::<Transport>(transport.clone()).ok())
+        .is_some_and(|transport| transport.return_route == ReturnRoute::All);
+    let aries_message: GeneralAriesMessage = serde_json::from_value(message_json)
+        .map_err(|e| MediatorError::MalformedMessage(e.to_string()))?;
+    let (packed_response, return_route_auth_pubkey) =
         if let GeneralAriesMessage::AriesVCXSupported(AriesMessage::Connection(conn)) =
             aries_message
         {
-            handle_aries_connection(agent.clone(), conn).await?
+            (
+                handle_aries_connection(agent.clone(), conn, &unpacked.sender_verkey).await?,
+                None,
+            )
         } else if let GeneralAriesMessage::AriesVCXSupported(AriesMessage::Routing(forward)) =
             aries_message
         {
             handle_routing_forward(agent.clone(), forward).await?;
-            return Ok(Json(json!({})));
+            // Same response whatever happened to the message, forwarder is anonymous
+            return Ok(ProcessedAriesMessage {
+                response: Some(json!({})),
+                return_route_auth_pubkey: None,
+            });
         } else {
             // Authenticated flow: Auth known VerKey then process account related messages
             let (account_name, auth_pubkey, our_signing_key, their_diddoc) =
                 agent.auth_and_get_details(&unpacked.sender_verkey).await?;
             log::info!("Processing message for {:?}", account_name);
-            let aries_response = match aries_message {
-                GeneralAriesMessage::AriesVCXSupported(AriesMessage::Pickup(pickup_message)) => {
-                    let pickup_response =
-                        handle_pickup_protocol(&agent, pickup_message, &auth_pubkey).await?;
-                    AriesMessage::Pickup(pickup_response)
-                }
-                GeneralAriesMessage::AriesVCXSupported(AriesMessage::CoordinateMediation(
-                    coord_message,
-                )) => {
-                    let coord_response =
-                        handle_mediation_coord(&agent, coord_message, &auth_pubkey).await?;
-                    AriesMessage::CoordinateMediation(coord_response)
-                }
-                GeneralAriesMessage::AriesVCXSupported(aries_message) => {
-                    Err(unhandled_aries_message(aries_message))?
-                }
-            };
-            let aries_response_bytes =
-                serde_json::to_vec(&aries_response).map_err(string_from_std_error)?;
-            agent
+            let aries_response =
+                match handle_authenticated(agent, aries_message, &auth_pubkey).await {
+                    Ok(aries_response) => aries_response,
+                    // Sender is known, so errors are reported back over DIDComm
+                    Err(err) => {
+                        log::info!("Responding with problem report: {}", err);
+                        AriesMessage::ReportProblem(err.to_problem_report(&message_id))
+                    }
+                };
+            let aries_response_bytes = serde_json::to_vec(&aries_response)
+                .map_err(|e| MediatorError::Internal(e.to_string()))?;
+            let packed_response = agent
                 .pack_didcomm(&aries_response_bytes, &our_signing_key, &their_diddoc)
-                .await?
+                .await?;
+            (packed_response, return_route_all.then_some(auth_pubkey))
         };
     let EncryptionEnvelope(packed_message_bytes) = packed_response;
-    let packed_json = serde_json::from_slice(&packed_message_bytes[..]).unwrap();
-    Ok(Json(packed_json))
+    let packed_json = serde_json::from_slice(&packed_message_bytes[..])
+        .map_err(|e| MediatorError::Internal(e.to_string()))?;
+    Ok(ProcessedAriesMessage {
+        response: Some(packed_json),
+        return_route_auth_pubkey,
+    })
+}
+
+async fn handle_authenticated<T: BaseWallet + 'static, P: MediatorPersistence>(
+    agent: &ArcAgent<T, P>,
+    aries_message: GeneralAriesMessage,
+    auth_pubkey: &str,
+) -> MediatorResult<AriesMessage> {
+    match aries_message {
+        GeneralAriesMessage::AriesVCXSupported(AriesMessage::Pickup(pickup_message)) => {
+            let pickup_response =
+                handle_pickup_protocol(agent, pickup_message, auth_pubkey).await?;
+            Ok(AriesMessage::Pickup(pickup_response))
+        }
+        GeneralAriesMessage::AriesVCXSupported(AriesMessage::CoordinateMediation(
+            coord_message,
+        )) => handle_mediation_coord(agent, coord_message, auth_pubkey).await,
+        GeneralAriesMessage::PushNotifications(set_device_info) => {
+            handle_set_device_info(agent, set_device_info, auth_pubkey).await
+        }
+        GeneralAriesMessage::AriesVCXSupported(aries_message) => {
+            Err(unhandled_aries_message(aries_message))
+        }
+    }
 }
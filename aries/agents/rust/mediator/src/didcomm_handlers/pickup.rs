@@ -6,12 +6,18 @@ pub async fn handle_pickup_protocol(
     agent: &ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Result<Pickup, String> {
+) -> MediatorResult<Pickup> {
     let pickup_response = crate::mediation::pickup::handle_pickup_authenticated(
         agent.get_persistence_ref(),
+        agent.get_live_sessions_ref(),
         pickup_message,
         auth_pubkey,
     )
-    .await;
+    .await?;
+    if let Pickup::Delivery(delivery) = &pickup_response {
+        agent
+            .get_metrics_ref()
+            .record_messages_delivered(delivery.content.attach.len() as u64);
+    }
     Ok(pickup_response)
 }
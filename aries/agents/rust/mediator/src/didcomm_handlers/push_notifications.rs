@@ -0,0 +1,91 @@
+use messages::{
+    decorators::thread::Thread,
+    msg_fields::protocols::notification::{
+        ack::{Ack, AckContent, AckDecorators, AckStatus},
+        Notification,
+    },
+    AriesMessage,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::utils::prelude::*;
+
+/// `set-device-info` of RFC 0734 (Push Notifications FCM 1.0), by which a client registers the
+/// push token of its device, or removes it with null `device_token`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetDeviceInfo {
+    #[serde(rename = "@type")]
+    pub msg_type: SetDeviceInfoType,
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub device_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_platform: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetDeviceInfoType {
+    #[serde(
+        rename = "https://didcomm.org/push-notifications-fcm/1.0/set-device-info",
+        alias = "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/push-notifications-fcm/1.0/set-device-info"
+    )]
+    PushNotificationsFcmV1_0,
+}
+
+pub async fn handle_set_device_info(
+    agent: &ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
+    set_device_info: SetDeviceInfo,
+    auth_pubkey: &str,
+) -> MediatorResult<AriesMessage> {
+    info!(
+        "Setting push token of {} (platform {:?})",
+        auth_pubkey, set_device_info.device_platform
+    );
+    agent
+        .get_persistence_ref()
+        .set_push_token(auth_pubkey, set_device_info.device_token.as_deref())
+        .await?;
+    let ack = Ack::builder()
+        .content(AckContent::builder().status(AckStatus::Ok).build())
+        .decorators(
+            AckDecorators::builder()
+                .thread(Thread::builder().thid(set_device_info.id).build())
+                .build(),
+        )
+        .id(Uuid::new_v4().to_string())
+        .build();
+    Ok(AriesMessage::Notification(Notification::Ack(ack)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_set_device_info_deserializes() {
+        let set_device_info: SetDeviceInfo = serde_json::from_value(json!({
+            "@type": "https://didcomm.org/push-notifications-fcm/1.0/set-device-info",
+            "@id": "set-id",
+            "device_token": "token",
+            "device_platform": "android"
+        }))
+        .unwrap();
+        assert_eq!(set_device_info.device_token.as_deref(), Some("token"));
+        let cleared: SetDeviceInfo = serde_json::from_value(json!({
+            "@type": "https://didcomm.org/push-notifications-fcm/1.0/set-device-info",
+            "@id": "set-id",
+            "device_token": null
+        }))
+        .unwrap();
+        assert_eq!(cleared.device_token, None);
+        assert!(serde_json::from_value::<SetDeviceInfo>(json!({
+            "@type": "https://didcomm.org/basicmessage/1.0/message",
+            "@id": "msg-id",
+            "device_token": "token"
+        }))
+        .is_err());
+    }
+}
@@ -4,5 +4,10 @@ pub mod prelude {
     pub use aries_vcx::utils::encryption_envelope::EncryptionEnvelope;
     pub use aries_vcx_core::wallet::base_wallet::BaseWallet;
 
-    pub use crate::{aries_agent::ArcAgent, persistence::MediatorPersistence, utils::prelude::*};
+    pub use crate::{
+        aries_agent::ArcAgent,
+        error::{MediatorError, MediatorResult},
+        persistence::MediatorPersistence,
+        utils::prelude::*,
+    };
 }
@@ -0,0 +1,154 @@
+use aries_vcx::protocols::common::build_problem_report_msg;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+pub type MediatorResult<T> = Result<T, MediatorError>;
+
+/// Mediator configuration read from the environment is invalid
+#[derive(thiserror::Error, Debug)]
+#[error("Invalid configuration: {0}")]
+pub struct ConfigError(pub String);
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// Parses environment variable `name`, None if it is unset
+pub fn parse_env_var<T>(name: &str) -> ConfigResult<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    std::env::var(name)
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|err| ConfigError(format!("{name}={value:?}: {err}")))
+        })
+        .transpose()
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MediatorError {
+    /// Sender could not be authenticated (anonymous, or no account for its verkey)
+    #[error("Authentication failed: {0}")]
+    AuthFailure(String),
+    /// No account has registered the recipient key
+    #[error("Unknown recipient: {0}")]
+    UnknownRecipient(String),
+    /// No account exists for the auth_pubkey
+    #[error("Unknown account: {0}")]
+    UnknownAccount(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    /// Envelope could not be unpacked, or its content is not a valid message
+    #[error("Malformed message: {0}")]
+    MalformedMessage(String),
+    /// Valid message which the mediator does not handle
+    #[error("Unsupported message: {0}")]
+    UnsupportedMessage(String),
+    /// Account would exceed its queued message quota
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+    #[error("Live delivery not supported: {0}")]
+    LiveDeliveryNotSupported(String),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl MediatorError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            MediatorError::AuthFailure(_) | MediatorError::UnknownAccount(_) => {
+                StatusCode::UNAUTHORIZED
+            }
+            MediatorError::UnknownRecipient(_) => StatusCode::NOT_FOUND,
+            MediatorError::MalformedMessage(_) => StatusCode::BAD_REQUEST,
+            MediatorError::UnsupportedMessage(_) | MediatorError::LiveDeliveryNotSupported(_) => {
+                StatusCode::NOT_IMPLEMENTED
+            }
+            MediatorError::QuotaExceeded(_) => StatusCode::INSUFFICIENT_STORAGE,
+            MediatorError::Storage(_) | MediatorError::Internal(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// Problem report code (RFC 0035) describing the error to the sender
+    pub fn problem_code(&self) -> &'static str {
+        match self {
+            MediatorError::AuthFailure(_) => "e.p.req.unauthorized",
+            MediatorError::UnknownRecipient(_) => "e.p.msg.unknown-recipient",
+            MediatorError::UnknownAccount(_) => "e.p.req.unknown-account",
+            MediatorError::Storage(_) => "e.p.me.res.storage",
+            MediatorError::MalformedMessage(_) => "e.p.msg.malformed",
+            MediatorError::UnsupportedMessage(_) => "e.p.msg.unsupported",
+            MediatorError::QuotaExceeded(_) => "e.p.msg.quota-exceeded",
+            MediatorError::LiveDeliveryNotSupported(_) => "e.msg.live-mode-not-supported",
+            MediatorError::Internal(_) => "e.p.me.internal",
+        }
+    }
+
+    /// Problem report for the message with id `thread_id` which caused the error
+    pub fn to_problem_report(&self, thread_id: &str) -> ProblemReport {
+        build_problem_report_msg(Some(self.problem_code().to_owned()), thread_id)
+    }
+
+    /// Problem report for a message which could not be read far enough to learn its id
+    pub fn to_unthreaded_problem_report(&self) -> ProblemReport {
+        let mut problem_report = self.to_problem_report("");
+        problem_report.decorators.thread = None;
+        problem_report
+    }
+}
+
+impl From<sqlx::Error> for MediatorError {
+    fn from(err: sqlx::Error) -> Self {
+        MediatorError::Storage(format!("{:#}", err))
+    }
+}
+
+/// Used for errors which can't be reported back over DIDComm
+impl IntoResponse for MediatorError {
+    fn into_response(self) -> Response {
+        (self.status_code(), self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kinds_map_to_status_and_problem_code() {
+        let err = MediatorError::AuthFailure("anonymous".to_owned());
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+        let err = MediatorError::MalformedMessage("not json".to_owned());
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        let problem_report = err.to_problem_report("msg-id");
+        assert_eq!(problem_report.content.description.code, "e.p.msg.malformed");
+        assert_eq!(
+            problem_report.decorators.thread.unwrap().thid,
+            "msg-id".to_owned()
+        );
+        assert!(err
+            .to_unthreaded_problem_report()
+            .decorators
+            .thread
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_env_var() {
+        std::env::set_var("MEDIATOR_TEST_NUMBER", "12");
+        assert_eq!(
+            parse_env_var::<u64>("MEDIATOR_TEST_NUMBER").unwrap(),
+            Some(12)
+        );
+        std::env::set_var("MEDIATOR_TEST_NOT_A_NUMBER", "twelve");
+        assert!(parse_env_var::<u64>("MEDIATOR_TEST_NOT_A_NUMBER").is_err());
+        assert_eq!(parse_env_var::<u64>("MEDIATOR_TEST_UNSET").unwrap(), None);
+    }
+}
@@ -0,0 +1,339 @@
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_http::validate_request::ValidateRequestHeaderLayer;
+
+use super::did_web::DidWebHosting;
+use crate::{
+    aries_agent::ArcAgent,
+    error::{ConfigError, ConfigResult, MediatorError},
+    persistence::MediatorPersistence,
+    utils::structs::VerKey,
+};
+
+/// Admin API errors. Unlike DIDComm requests, an unknown account here is not found
+/// rather than an authentication failure.
+pub struct AdminError(MediatorError);
+
+impl From<MediatorError> for AdminError {
+    fn from(err: MediatorError) -> Self {
+        AdminError(err)
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        match self.0 {
+            MediatorError::UnknownAccount(_) => {
+                (StatusCode::NOT_FOUND, self.0.to_string()).into_response()
+            }
+            err => err.into_response(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountSummary {
+    pub account_name: String,
+    pub auth_pubkey: VerKey,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDetails {
+    pub seq_num: u64,
+    pub account_name: String,
+    pub auth_pubkey: VerKey,
+    pub our_signing_key: VerKey,
+    pub did_doc: Value,
+    pub recipient_keys: Vec<VerKey>,
+    pub pending_message_count: u64,
+    pub pending_message_bytes: u64,
+    pub push_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PushTokenRegistration {
+    pub push_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DidWebKey {
+    pub verkey: VerKey,
+}
+
+/// Reads the admin token from `MEDIATOR_ADMIN_TOKEN`, None if it is unset (admin API disabled).
+/// The token is sent in a header, so it must be non-empty printable ASCII without spaces.
+pub fn admin_token_from_env() -> ConfigResult<Option<String>> {
+    let Ok(admin_token) = std::env::var("MEDIATOR_ADMIN_TOKEN") else {
+        return Ok(None);
+    };
+    if admin_token.is_empty() || !admin_token.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(ConfigError(
+            "MEDIATOR_ADMIN_TOKEN must be non-empty printable ASCII without spaces".to_owned(),
+        ));
+    }
+    Ok(Some(admin_token))
+}
+
+/// Admin routes, every request must carry `Authorization: Bearer <admin_token>`. The key of the
+/// hosted did:web DID can be rotated if `did_web` is given.
+pub fn build_admin_router<T: BaseWallet + 'static, P: MediatorPersistence>(
+    admin_token: &str,
+    did_web: Option<DidWebHosting>,
+) -> Router<ArcAgent<T, P>> {
+    let router = match did_web {
+        Some(did_web) => Router::default()
+            .route("/did-web/rotate-key", post(rotate_did_web_key))
+            .layer(Extension(did_web)),
+        None => Router::default(),
+    };
+    router
+        .route("/accounts", get(list_accounts))
+        .route(
+            "/accounts/:auth_pubkey",
+            get(get_account).delete(delete_account),
+        )
+        .route(
+            "/accounts/:auth_pubkey/recipients",
+            get(list_recipient_keys),
+        )
+        .route(
+            "/accounts/:auth_pubkey/push-token",
+            put(set_push_token).delete(clear_push_token),
+        )
+        .layer(ValidateRequestHeaderLayer::bearer(admin_token))
+}
+
+pub async fn list_accounts(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+) -> Result<Json<Vec<AccountSummary>>, AdminError> {
+    let accounts = agent
+        .get_persistence_ref()
+        .list_accounts()
+        .await?
+        .into_iter()
+        .map(|(account_name, auth_pubkey)| AccountSummary {
+            account_name,
+            auth_pubkey,
+        })
+        .collect();
+    Ok(Json(accounts))
+}
+
+pub async fn get_account(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<Json<AccountDetails>, AdminError> {
+    let persistence = agent.get_persistence_ref();
+    let (seq_num, account_name, our_signing_key, did_doc) =
+        persistence.get_account_details(&auth_pubkey).await?;
+    let recipient_keys = persistence.list_recipient_keys(&auth_pubkey).await?;
+    let usage = persistence.get_message_usage(Some(&auth_pubkey)).await?;
+    let push_token = persistence.get_push_token(&auth_pubkey).await?;
+    Ok(Json(AccountDetails {
+        seq_num,
+        account_name,
+        auth_pubkey,
+        our_signing_key,
+        did_doc,
+        recipient_keys,
+        pending_message_count: usage.message_count,
+        pending_message_bytes: usage.message_bytes,
+        push_token,
+    }))
+}
+
+pub async fn delete_account(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<StatusCode, AdminError> {
+    agent
+        .get_persistence_ref()
+        .delete_account(&auth_pubkey)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_recipient_keys(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<Json<Vec<VerKey>>, AdminError> {
+    let recipient_keys = agent
+        .get_persistence_ref()
+        .list_recipient_keys(&auth_pubkey)
+        .await?;
+    Ok(Json(recipient_keys))
+}
+
+/// Registers token of the account's device, notified when messages are queued for it
+pub async fn set_push_token(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+    Json(registration): Json<PushTokenRegistration>,
+) -> Result<StatusCode, AdminError> {
+    agent
+        .get_persistence_ref()
+        .set_push_token(&auth_pubkey, Some(&registration.push_token))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn clear_push_token(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<StatusCode, AdminError> {
+    agent
+        .get_persistence_ref()
+        .set_push_token(&auth_pubkey, None)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Rotates the key of the hosted did:web DID to a new key created in the wallet
+pub async fn rotate_did_web_key(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    Extension(did_web): Extension<DidWebHosting>,
+) -> Result<Json<DidWebKey>, AdminError> {
+    let (_, verkey) = agent
+        .get_wallet_ref()
+        .create_and_store_my_did(None, None)
+        .await
+        .map_err(|err| MediatorError::Internal(err.to_string()))?;
+    did_web.rotate_key(&verkey)?;
+    Ok(Json(DidWebKey { verkey }))
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::{header::AUTHORIZATION, Method, StatusCode};
+    use serde_json::json;
+
+    use crate::{
+        aries_agent::AgentBuilder,
+        http_routes::{
+            build_router_with_admin,
+            did_web::{did_web_host, DidWebHosting},
+        },
+        persistence::MediatorPersistence,
+    };
+
+    const ADMIN_TOKEN: &str = "admin_token";
+
+    /// Serves a mediator with the admin API enabled on a free port, returns its base url
+    async fn spawn_mediator_with_admin() -> String {
+        let mut agent = AgentBuilder::new_in_memory_demo_agent().await.unwrap();
+        agent
+            .init_service(
+                vec![],
+                "http://127.0.0.1:8005/didcomm".to_string().parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        let persistence = agent.get_persistence_ref();
+        persistence
+            .create_account("auth_key", "our_key", &json!({}).to_string())
+            .await
+            .unwrap();
+        persistence
+            .add_recipient("auth_key", "recipient_key")
+            .await
+            .unwrap();
+        let did_web =
+            DidWebHosting::new(did_web_host(&agent, "did:web:example.com").unwrap(), None).unwrap();
+        let router = build_router_with_admin(agent, Some(ADMIN_TOKEN), Some(did_web)).await;
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(router.into_make_service());
+        let base_url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+        base_url
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_require_admin_token() {
+        let base_url = spawn_mediator_with_admin().await;
+        let client = reqwest::Client::new();
+        // account deletion last, the other routes act on the account
+        let routes = [
+            (Method::GET, "/admin/accounts", StatusCode::OK),
+            (Method::GET, "/admin/accounts/auth_key", StatusCode::OK),
+            (
+                Method::GET,
+                "/admin/accounts/auth_key/recipients",
+                StatusCode::OK,
+            ),
+            (
+                Method::PUT,
+                "/admin/accounts/auth_key/push-token",
+                StatusCode::NO_CONTENT,
+            ),
+            (
+                Method::DELETE,
+                "/admin/accounts/auth_key/push-token",
+                StatusCode::NO_CONTENT,
+            ),
+            (Method::POST, "/admin/did-web/rotate-key", StatusCode::OK),
+            (
+                Method::DELETE,
+                "/admin/accounts/auth_key",
+                StatusCode::NO_CONTENT,
+            ),
+        ];
+        for (method, path, expected_status) in routes {
+            let request = || {
+                let request = client.request(method.clone(), format!("{base_url}{path}"));
+                match method {
+                    Method::PUT => request.json(&json!({ "push_token": "device_token" })),
+                    _ => request,
+                }
+            };
+            let missing_token = request().send().await.unwrap();
+            assert_eq!(
+                missing_token.status(),
+                StatusCode::UNAUTHORIZED,
+                "{method} {path}"
+            );
+            let wrong_token = request()
+                .header(AUTHORIZATION, "Bearer wrong_token")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(
+                wrong_token.status(),
+                StatusCode::UNAUTHORIZED,
+                "{method} {path}"
+            );
+            let correct_token = request()
+                .header(AUTHORIZATION, format!("Bearer {ADMIN_TOKEN}"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(correct_token.status(), expected_status, "{method} {path}");
+        }
+        let accounts: serde_json::Value = client
+            .get(format!("{base_url}/admin/accounts"))
+            .header(AUTHORIZATION, format!("Bearer {ADMIN_TOKEN}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(accounts, json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_do_not_require_admin_token() {
+        let base_url = spawn_mediator_with_admin().await;
+        let metrics = reqwest::get(format!("{base_url}/metrics")).await.unwrap();
+        assert_eq!(metrics.status(), StatusCode::OK);
+        let metrics = metrics.text().await.unwrap();
+        assert!(metrics.contains("mediator_accounts 1\n"), "{metrics}");
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use axum::Router;
+use did_doc_sov::extra_fields::KeyKind;
+use did_resolver::{did_doc::schema::types::url::Url, did_parser::Did};
+use did_resolver_web::hosting::{
+    router::{did_web_router, SharedDidWebHost},
+    DidWebHost, DidWebHostState,
+};
+use public_key::{Key, KeyType};
+
+use crate::{
+    aries_agent::Agent,
+    error::{MediatorError, MediatorResult},
+    persistence::MediatorPersistence,
+};
+
+/// Host of the did:web document for `did`, controlled by the key of the agent's service and
+/// advertising its DIDComm endpoint. Serve it through [`DidWebHosting`].
+pub fn did_web_host<T: BaseWallet + 'static, P: MediatorPersistence>(
+    agent: &Agent<T, P>,
+    did: &str,
+) -> MediatorResult<DidWebHost> {
+    let service = agent
+        .get_service_ref()
+        .ok_or_else(|| MediatorError::Internal("No service to create did:web for".to_owned()))?;
+    let verkey = service.recipient_keys.first().ok_or_else(|| {
+        MediatorError::Internal("Service has no recipient key to create did:web for".to_owned())
+    })?;
+    let did = Did::parse(did.to_owned()).map_err(|err| MediatorError::Internal(err.to_string()))?;
+    let service_endpoint = Url::new(service.service_endpoint.as_str())
+        .map_err(|err| MediatorError::Internal(err.to_string()))?;
+    let routing_keys = service
+        .routing_keys
+        .iter()
+        .map(|key| KeyKind::Value(key.to_owned()))
+        .collect();
+    Ok(DidWebHost::from_verkey(did, verkey)
+        .map_err(|err| MediatorError::Internal(err.to_string()))?
+        .set_didcomm_service(service_endpoint, routing_keys))
+}
+
+/// did:web DID hosted by the mediator, shared between the router serving its DID document and the
+/// admin API rotating its key. If a state file is given, the keys are saved to it after every
+/// rotation and restored from it on startup, so that retired keys are served for their whole
+/// grace period across restarts.
+#[derive(Clone)]
+pub struct DidWebHosting {
+    host: SharedDidWebHost,
+    state_file: Option<PathBuf>,
+}
+
+impl DidWebHosting {
+    pub fn new(mut host: DidWebHost, state_file: Option<PathBuf>) -> MediatorResult<Self> {
+        if let Some(state_file) = state_file.as_ref().filter(|path| path.exists()) {
+            let state =
+                std::fs::read(state_file).map_err(|err| MediatorError::Storage(err.to_string()))?;
+            let state: DidWebHostState = serde_json::from_slice(&state)
+                .map_err(|err| MediatorError::Storage(err.to_string()))?;
+            host.restore_state(state)
+                .map_err(|err| MediatorError::Internal(err.to_string()))?;
+        }
+        let hosting = Self {
+            host: Arc::new(RwLock::new(host)),
+            state_file,
+        };
+        hosting.save_state()?;
+        Ok(hosting)
+    }
+
+    pub fn host(&self) -> SharedDidWebHost {
+        self.host.clone()
+    }
+
+    /// Router serving the DID document, reflecting key rotations right away
+    pub fn router<S: Clone + Send + Sync + 'static>(&self) -> Router<S> {
+        // the host was created for a did:web DID, so its document path is known
+        did_web_router(self.host()).expect("did:web DID has a document path")
+    }
+
+    /// Makes `verkey` the key of the DID, keeping the replaced key in its document for the grace
+    /// period
+    pub fn rotate_key(&self, verkey: &str) -> MediatorResult<()> {
+        let key = Key::from_base58(verkey, KeyType::Ed25519)
+            .map_err(|err| MediatorError::Internal(err.to_string()))?;
+        self.host
+            .write()
+            .unwrap()
+            .rotate_key(key)
+            .map_err(|err| MediatorError::Internal(err.to_string()))?;
+        self.save_state()
+    }
+
+    fn save_state(&self) -> MediatorResult<()> {
+        let Some(state_file) = &self.state_file else {
+            return Ok(());
+        };
+        let state = serde_json::to_vec(&self.host.read().unwrap().state())
+            .map_err(|err| MediatorError::Internal(err.to_string()))?;
+        std::fs::write(state_file, state).map_err(|err| MediatorError::Storage(err.to_string()))
+    }
+}
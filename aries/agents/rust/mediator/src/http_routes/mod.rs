@@ -4,19 +4,26 @@ use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use axum::{
     body::Bytes,
     extract::State,
-    http::header::{HeaderMap, ACCEPT},
+    http::header::{HeaderMap, ACCEPT, CONTENT_TYPE},
     response::{Html, IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use serde_json::Value;
 
+use self::did_web::DidWebHosting;
 use crate::{
     aries_agent::{Agent, ArcAgent},
     didcomm_handlers,
+    error::MediatorResult,
     persistence::MediatorPersistence,
 };
 
+pub mod admin;
+pub mod did_web;
+mod websocket;
+pub use websocket::handle_didcomm_ws;
+
 pub async fn oob_invite_qr(
     headers: HeaderMap,
     State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
@@ -57,10 +64,23 @@ pub async fn oob_invite_json(
 pub async fn handle_didcomm(
     State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
     didcomm_msg: Bytes,
-) -> Result<Json<Value>, String> {
+) -> MediatorResult<Json<Value>> {
     didcomm_handlers::handle_aries(State(agent), didcomm_msg).await
 }
 
+/// Prometheus text exposition format
+pub async fn metrics(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+) -> MediatorResult<Response> {
+    let persistence = agent.get_persistence_ref();
+    let queue = persistence.get_message_usage(None).await?;
+    let account_count = persistence.count_accounts().await?;
+    let body = agent
+        .get_metrics_ref()
+        .render_prometheus(queue, account_count);
+    Ok(([(CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response())
+}
+
 pub async fn readme() -> Html<String> {
     Html("<p>Please refer to the API section of <a>readme</a> for usage. Thanks. </p>".into())
 }
@@ -68,11 +88,35 @@ pub async fn readme() -> Html<String> {
 pub async fn build_router(
     agent: Agent<impl BaseWallet + 'static, impl MediatorPersistence>,
 ) -> Router {
-    Router::default()
+    build_router_with_admin(agent, None, None).await
+}
+
+/// Same as build_router, with the admin API mounted under `/admin` if admin_token is given and
+/// the DID document of the did:web DID served if did_web is given
+pub async fn build_router_with_admin<T: BaseWallet + 'static, P: MediatorPersistence>(
+    agent: Agent<T, P>,
+    admin_token: Option<&str>,
+    did_web: Option<DidWebHosting>,
+) -> Router {
+    let router = Router::default()
         .route("/", get(readme))
         .route("/register", get(oob_invite_qr))
         .route("/register.json", get(oob_invite_json))
         .route("/didcomm", get(handle_didcomm).post(handle_didcomm))
+        .route("/ws", get(handle_didcomm_ws))
+        .route("/metrics", get(metrics));
+    let router = match &did_web {
+        Some(did_web) => router.merge(did_web.router()),
+        None => router,
+    };
+    let router = match admin_token {
+        Some(admin_token) => router.nest(
+            "/admin",
+            admin::build_admin_router::<T, P>(admin_token, did_web),
+        ),
+        None => router,
+    };
+    router
         .layer(tower_http::catch_panic::CatchPanicLayer::new())
         .with_state(Arc::new(agent))
 }
@@ -0,0 +1,158 @@
+use aries_vcx::utils::encryption_envelope::EncryptionEnvelope;
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+};
+use log::info;
+use messages::AriesMessage;
+use serde_json::json;
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::{
+    aries_agent::ArcAgent,
+    didcomm_handlers::process_aries,
+    error::{MediatorError, MediatorResult},
+    mediation::{live_delivery::LiveMessage, pickup::build_live_delivery},
+    persistence::MediatorPersistence,
+    utils::structs::VerKey,
+};
+
+/// DIDComm over WebSocket. Responses are sent back over the socket. Once the sender asks
+/// for `return_route: all`, the socket is registered as held-open transport of its account,
+/// and messages forwarded to the account are pushed over it as they arrive.
+pub async fn handle_didcomm_ws(
+    ws: WebSocketUpgrade,
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+) -> Response {
+    ws.on_upgrade(|socket| serve_socket(agent, socket))
+}
+
+async fn serve_socket(
+    agent: ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
+    mut socket: WebSocket,
+) {
+    let live_sessions = agent.get_live_sessions_ref();
+    let (live_sender, mut live_receiver) = unbounded_channel::<LiveMessage>();
+    let mut registered_auth_pubkey: Option<VerKey> = None;
+    loop {
+        tokio::select! {
+            inbound = socket.recv() => {
+                let didcomm_msg = match inbound {
+                    Some(Ok(Message::Text(text))) => text.into_bytes(),
+                    Some(Ok(Message::Binary(bytes))) => bytes,
+                    // pings are answered by axum
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(err)) => {
+                        info!("WebSocket receive failed: {}", err);
+                        break;
+                    }
+                };
+                let processed = match process_aries(&agent, &didcomm_msg).await {
+                    Ok(processed) => processed,
+                    // Sender is unknown, so the problem report can't be packed for it
+                    Err(err) => {
+                        info!("Responding with problem report: {}", err);
+                        let problem_report =
+                            AriesMessage::ReportProblem(err.to_unthreaded_problem_report());
+                        let problem_report = Message::Text(json!(problem_report).to_string());
+                        if socket.send(problem_report).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                if let Some(auth_pubkey) = processed.return_route_auth_pubkey {
+                    if registered_auth_pubkey.as_ref() != Some(&auth_pubkey) {
+                        if let Some(previous) = registered_auth_pubkey.take() {
+                            live_sessions.unregister_transport(&previous, &live_sender);
+                        }
+                        live_sessions.register_transport(&auth_pubkey, live_sender.clone());
+                        // Held-open socket with return route is live until client says otherwise
+                        let _ = live_sessions.set_live_delivery(&auth_pubkey, true);
+                        registered_auth_pubkey = Some(auth_pubkey);
+                    }
+                }
+                if let Some(response) = processed.response {
+                    if socket.send(Message::Text(response.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            Some(live_message) = live_receiver.recv() => {
+                let Some(auth_pubkey) = &registered_auth_pubkey else {
+                    requeue_live_message(&agent, live_message).await;
+                    continue;
+                };
+                let packed_delivery =
+                    pack_live_delivery(&agent, auth_pubkey, &live_message.message_data).await;
+                let packed_delivery = match packed_delivery {
+                    Ok(packed_delivery) => packed_delivery,
+                    Err(err) => {
+                        info!("Could not pack live delivery for {}: {}", auth_pubkey, err);
+                        requeue_live_message(&agent, live_message).await;
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(packed_delivery)).await.is_err() {
+                    requeue_live_message(&agent, live_message).await;
+                    break;
+                }
+            }
+        }
+    }
+    if let Some(auth_pubkey) = registered_auth_pubkey {
+        live_sessions.unregister_transport(&auth_pubkey, &live_sender);
+    }
+    // Messages pushed while the socket was closing
+    live_receiver.close();
+    while let Ok(live_message) = live_receiver.try_recv() {
+        requeue_live_message(&agent, live_message).await;
+    }
+}
+
+/// Queues message which could not be pushed over the socket. Its sender was acked already,
+/// so it's queued even if the account is over quota.
+async fn requeue_live_message(
+    agent: &ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
+    live_message: LiveMessage,
+) {
+    info!("Queueing live message for {}", live_message.recipient_key);
+    let requeued = match String::from_utf8(live_message.message_data) {
+        Ok(message_data) => {
+            agent
+                .get_persistence_ref()
+                .persist_forward_message(&live_message.recipient_key, &message_data)
+                .await
+        }
+        Err(err) => Err(MediatorError::MalformedMessage(err.to_string())),
+    };
+    if let Err(err) = requeued {
+        info!(
+            "Could not queue live message for {}: {}",
+            live_message.recipient_key, err
+        );
+    }
+}
+
+/// Wraps forwarded message into pickup delivery, packed for the account owning the socket
+async fn pack_live_delivery(
+    agent: &ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>,
+    auth_pubkey: &VerKey,
+    message_data: &[u8],
+) -> MediatorResult<String> {
+    let (_account_name, _auth_pubkey, our_signing_key, their_diddoc) = agent
+        .auth_and_get_details(&Some(auth_pubkey.to_owned()))
+        .await?;
+    let delivery = AriesMessage::Pickup(build_live_delivery(message_data));
+    let delivery_bytes =
+        serde_json::to_vec(&delivery).map_err(|e| MediatorError::Internal(e.to_string()))?;
+    let EncryptionEnvelope(packed_message_bytes) = agent
+        .pack_didcomm(&delivery_bytes, &our_signing_key, &their_diddoc)
+        .await?;
+    String::from_utf8(packed_message_bytes).map_err(|e| MediatorError::Internal(e.to_string()))
+}
@@ -1,6 +1,8 @@
 pub mod aries_agent;
 pub mod didcomm_handlers;
+pub mod error;
 pub mod http_routes;
 pub mod mediation;
+pub mod metrics;
 pub mod persistence;
 pub mod utils;
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use crate::error::{parse_env_var, ConfigResult};
+
+/// Decides which clients the mediator is willing to mediate for, and how many recipient keys
+/// each mediated account may register. Clients are admitted when they connect (no account is
+/// created for denied ones) and again on RFC 0211 mediate-request.
+pub trait MediationAdmissionPolicy: Send + Sync + 'static {
+    /// Returns Err with reason if mediation should be denied to the client identified by its
+    /// auth_pubkey, the connection verkey the client authenticated with.
+    fn admit(&self, auth_pubkey: &str) -> Result<(), String>;
+    /// Maximum recipient keys per account, None for no limit
+    fn max_recipient_keys(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Grants mediation to everyone
+#[derive(Debug, Clone, Default)]
+pub struct OpenAdmission {
+    pub max_recipient_keys: Option<usize>,
+}
+
+impl MediationAdmissionPolicy for OpenAdmission {
+    fn admit(&self, _auth_pubkey: &str) -> Result<(), String> {
+        Ok(())
+    }
+    fn max_recipient_keys(&self) -> Option<usize> {
+        self.max_recipient_keys
+    }
+}
+
+/// Grants mediation only to clients whose auth_pubkey is allow-listed. DIDs are not matched,
+/// as the DID in a connection request is self-asserted.
+#[derive(Debug, Clone, Default)]
+pub struct AllowListAdmission {
+    pub allowed: HashSet<String>,
+    pub max_recipient_keys: Option<usize>,
+}
+
+impl AllowListAdmission {
+    pub fn new(
+        allowed: impl IntoIterator<Item = String>,
+        max_recipient_keys: Option<usize>,
+    ) -> Self {
+        Self {
+            allowed: allowed.into_iter().collect(),
+            max_recipient_keys,
+        }
+    }
+}
+
+impl MediationAdmissionPolicy for AllowListAdmission {
+    fn admit(&self, auth_pubkey: &str) -> Result<(), String> {
+        if self.allowed.contains(auth_pubkey) {
+            Ok(())
+        } else {
+            Err(format!("{auth_pubkey} is not on the mediation allow-list"))
+        }
+    }
+    fn max_recipient_keys(&self) -> Option<usize> {
+        self.max_recipient_keys
+    }
+}
+
+/// Reads policy from environment.
+/// `MEDIATION_ALLOW_LIST`: comma separated client verkeys, open admission if unset.
+/// `MEDIATION_MAX_RECIPIENT_KEYS`: recipient key limit per account, unlimited if unset.
+pub fn admission_policy_from_env() -> ConfigResult<Box<dyn MediationAdmissionPolicy>> {
+    let max_recipient_keys = parse_env_var("MEDIATION_MAX_RECIPIENT_KEYS")?;
+    Ok(match std::env::var("MEDIATION_ALLOW_LIST") {
+        Ok(allow_list) => Box::new(AllowListAdmission::new(
+            allow_list
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_owned),
+            max_recipient_keys,
+        )),
+        Err(_) => Box::new(OpenAdmission { max_recipient_keys }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_list_admission() {
+        let policy = AllowListAdmission::new(["key1".to_owned()], Some(2));
+        assert!(policy.admit("key1").is_ok());
+        assert!(policy.admit("key0").is_err());
+        assert_eq!(policy.max_recipient_keys(), Some(2));
+    }
+
+    #[test]
+    fn test_open_admission() {
+        let policy = OpenAdmission::default();
+        assert!(policy.admit("key0").is_ok());
+        assert_eq!(policy.max_recipient_keys(), None);
+    }
+}
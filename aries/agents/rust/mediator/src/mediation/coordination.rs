@@ -3,8 +3,9 @@
 
 use std::sync::Arc;
 
+use log::info;
 use messages::msg_fields::protocols::coordinate_mediation::{
-    keylist::KeylistItem,
+    keylist::{KeylistItem, KeylistPagination},
     keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
     keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
     CoordinateMediation, Keylist, KeylistContent, KeylistDecorators, KeylistQueryContent,
@@ -14,44 +15,51 @@ use messages::msg_fields::protocols::coordinate_mediation::{
 };
 use uuid::Uuid;
 
-use crate::persistence::MediatorPersistence;
+use super::admission::MediationAdmissionPolicy;
+use crate::{
+    error::{MediatorError, MediatorResult},
+    persistence::MediatorPersistence,
+};
 
+/// Mediator side of coordinate-mediation 1.0 for clients with an established connection.
 pub async fn handle_coord_authenticated(
     storage: Arc<impl MediatorPersistence>,
+    policy: &dyn MediationAdmissionPolicy,
     message: CoordinateMediation,
     auth_pubkey: &str,
-) -> CoordinateMediation {
+    grant_content: MediateGrantContent,
+) -> MediatorResult<CoordinateMediation> {
     match message {
         CoordinateMediation::MediateRequest(_mediate_request) => {
-            panic!(
-                "Use handle_mediate_request directly. This handler is for preregistered clients."
-            );
+            Ok(handle_mediate_request(policy, auth_pubkey, grant_content))
         }
         CoordinateMediation::KeylistUpdate(keylist_update) => {
-            handle_keylist_update(storage, keylist_update.content, auth_pubkey).await
+            Ok(handle_keylist_update(storage, policy, keylist_update.content, auth_pubkey).await)
         }
         CoordinateMediation::KeylistQuery(keylist_query) => {
             handle_keylist_query(storage, keylist_query.content, auth_pubkey).await
         }
-        _ => handle_unimplemented().await,
+        CoordinateMediation::MediateGrant(MediateGrant { id, .. })
+        | CoordinateMediation::MediateDeny(MediateDeny { id, .. })
+        | CoordinateMediation::KeylistUpdateResponse(KeylistUpdateResponse { id, .. })
+        | CoordinateMediation::Keylist(Keylist { id, .. }) => Err(handle_unimplemented(&id)),
     }
 }
 
-pub async fn handle_unimplemented() -> CoordinateMediation {
-    todo!("This error should ideally be handled on outer layer. Panicking for now.")
+/// Messages only a mediator sends are refused
+pub fn handle_unimplemented(message_id: &str) -> MediatorError {
+    MediatorError::UnsupportedMessage(format!(
+        "Coordinate-mediation message {message_id} is meant for the client"
+    ))
 }
 
-pub async fn handle_mediate_request<T: MediatorPersistence>(
-    storage: Arc<T>,
+/// Clients are admitted when connecting, policy is checked again in case it changed since
+pub fn handle_mediate_request(
+    policy: &dyn MediationAdmissionPolicy,
     auth_pubkey: &str,
-    did_doc: &str,
-    our_signing_key: &str,
     grant_content: MediateGrantContent,
 ) -> CoordinateMediation {
-    match storage
-        .create_account(auth_pubkey, our_signing_key, did_doc)
-        .await
-    {
+    match policy.admit(auth_pubkey) {
         Ok(()) => {
             let mediate_grant_msg = MediateGrant::builder()
                 .content(grant_content)
@@ -60,7 +68,8 @@ pub async fn handle_mediate_request<T: MediatorPersistence>(
                 .build();
             CoordinateMediation::MediateGrant(mediate_grant_msg)
         }
-        Err(_msg) => {
+        Err(reason) => {
+            info!("Denying mediation: {}", reason);
             let mediate_deny_msg = MediateDeny::builder()
                 .content(MediateDenyContent::default())
                 .decorators(MediateDenyDecorators::default())
@@ -73,55 +82,74 @@ pub async fn handle_mediate_request<T: MediatorPersistence>(
 
 pub async fn handle_keylist_query<T: MediatorPersistence>(
     storage: Arc<T>,
-    //todo: use the limits mentioned in the KeylistQueryData to modify response
-    _keylist_query_data: KeylistQueryContent,
+    keylist_query_data: KeylistQueryContent,
     auth_pubkey: &str,
-) -> CoordinateMediation {
-    let keylist_items: Vec<KeylistItem> = match storage.list_recipient_keys(auth_pubkey).await {
-        Ok(recipient_keys) => recipient_keys
-            .into_iter()
-            .map(|recipient_key| KeylistItem { recipient_key })
-            .collect(),
-        Err(err) => todo!(
-            "This error should ideally be handled on outer layer. Panicking for now{}",
-            err
-        ),
+) -> MediatorResult<CoordinateMediation> {
+    let recipient_keys = storage.list_recipient_keys(auth_pubkey).await?;
+    let (keys, pagination) = match keylist_query_data.paginate {
+        Some(paginate) => {
+            let total = recipient_keys.len() as u64;
+            let offset = paginate.offset.unwrap_or(0).min(total);
+            let limit = paginate.limit.unwrap_or(total - offset);
+            let keys: Vec<String> = recipient_keys
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect();
+            let count = keys.len() as u64;
+            let pagination = KeylistPagination::builder()
+                .count(count)
+                .offset(offset)
+                .remaining(total - offset - count)
+                .build();
+            (keys, Some(pagination))
+        }
+        None => (recipient_keys, None),
     };
+    let keylist_items: Vec<KeylistItem> = keys
+        .into_iter()
+        .map(|recipient_key| KeylistItem { recipient_key })
+        .collect();
     let keylist = Keylist::builder()
         .content(KeylistContent {
             keys: keylist_items,
-            pagination: None,
+            pagination,
         })
         .decorators(KeylistDecorators::default())
         .id(Uuid::new_v4().to_string())
         .build();
-    CoordinateMediation::Keylist(keylist)
+    Ok(CoordinateMediation::Keylist(keylist))
 }
 
 pub async fn handle_keylist_update<T: MediatorPersistence>(
     storage: Arc<T>,
+    policy: &dyn MediationAdmissionPolicy,
     keylist_update_data: KeylistUpdateContent,
     auth_pubkey: &str,
 ) -> CoordinateMediation {
     let updates: Vec<KeylistUpdateItem> = keylist_update_data.updates;
     let mut updated: Vec<KeylistUpdateResponseItem> = Vec::new();
     for update_item in updates.into_iter() {
-        let result = match &update_item.action {
+        let update_item_result = match &update_item.action {
             KeylistUpdateItemAction::Add => {
-                storage
-                    .add_recipient(auth_pubkey, &update_item.recipient_key)
-                    .await
+                add_recipient_within_limit(
+                    storage.as_ref(),
+                    policy,
+                    auth_pubkey,
+                    &update_item.recipient_key,
+                )
+                .await
             }
             KeylistUpdateItemAction::Remove => {
-                storage
+                match storage
                     .remove_recipient(auth_pubkey, &update_item.recipient_key)
                     .await
+                {
+                    Ok(()) => KeylistUpdateItemResult::Success,
+                    Err(_msg) => KeylistUpdateItemResult::ServerError,
+                }
             }
         };
-        let update_item_result = match result {
-            Ok(()) => KeylistUpdateItemResult::Success,
-            Err(_msg) => KeylistUpdateItemResult::ServerError,
-        };
         updated.push(KeylistUpdateResponseItem {
             recipient_key: update_item.recipient_key,
             action: update_item.action,
@@ -135,3 +163,156 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
         .build();
     CoordinateMediation::KeylistUpdateResponse(keylist_update_response)
 }
+
+async fn add_recipient_within_limit(
+    storage: &impl MediatorPersistence,
+    policy: &dyn MediationAdmissionPolicy,
+    auth_pubkey: &str,
+    recipient_key: &str,
+) -> KeylistUpdateItemResult {
+    let Ok(recipient_keys) = storage.list_recipient_keys(auth_pubkey).await else {
+        return KeylistUpdateItemResult::ServerError;
+    };
+    if recipient_keys.iter().any(|key| key == recipient_key) {
+        return KeylistUpdateItemResult::NoChange;
+    }
+    if let Some(max_recipient_keys) = policy.max_recipient_keys() {
+        if recipient_keys.len() >= max_recipient_keys {
+            info!(
+                "Account {} reached limit of {} recipient keys",
+                auth_pubkey, max_recipient_keys
+            );
+            return KeylistUpdateItemResult::ClientError;
+        }
+    }
+    match storage.add_recipient(auth_pubkey, recipient_key).await {
+        Ok(()) => KeylistUpdateItemResult::Success,
+        Err(_msg) => KeylistUpdateItemResult::ServerError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use messages::msg_fields::protocols::coordinate_mediation::{
+        keylist_query::KeylistQueryPaginateParams, keylist_update::KeylistUpdateItem, KeylistUpdate,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        mediation::admission::{AllowListAdmission, OpenAdmission},
+        persistence::InMemoryPersistence,
+    };
+
+    async fn storage_with_keys(keys: &[&str]) -> Arc<InMemoryPersistence> {
+        let storage = Arc::new(InMemoryPersistence::default());
+        storage
+            .create_account("auth", "our", &json!({}).to_string())
+            .await
+            .unwrap();
+        for key in keys {
+            storage.add_recipient("auth", key).await.unwrap();
+        }
+        storage
+    }
+
+    fn grant_content() -> MediateGrantContent {
+        MediateGrantContent {
+            endpoint: "http://localhost".to_owned(),
+            routing_keys: vec![],
+        }
+    }
+
+    #[test]
+    fn test_mediate_request_denied_by_policy() {
+        let policy = AllowListAdmission::new(["allowed".to_owned()], None);
+        let response = handle_mediate_request(&policy, "denied", grant_content());
+        assert!(matches!(response, CoordinateMediation::MediateDeny(_)));
+        let response = handle_mediate_request(&policy, "allowed", grant_content());
+        assert!(matches!(response, CoordinateMediation::MediateGrant(_)));
+    }
+
+    #[tokio::test]
+    async fn test_keylist_query_pagination() {
+        let storage = storage_with_keys(&["key1", "key2", "key3"]).await;
+        let query = KeylistQueryContent::builder()
+            .paginate(
+                KeylistQueryPaginateParams::builder()
+                    .limit(2)
+                    .offset(1)
+                    .build(),
+            )
+            .build();
+        let CoordinateMediation::Keylist(keylist) =
+            handle_keylist_query(storage, query, "auth").await.unwrap()
+        else {
+            panic!("Expected keylist");
+        };
+        assert_eq!(keylist.content.keys.len(), 2);
+        let pagination = keylist.content.pagination.unwrap();
+        assert_eq!(
+            (pagination.count, pagination.offset, pagination.remaining),
+            (2, 1, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_respects_max_recipient_keys() {
+        let storage = storage_with_keys(&["key1"]).await;
+        let policy = OpenAdmission {
+            max_recipient_keys: Some(1),
+        };
+        let update = KeylistUpdateContent::builder()
+            .updates(vec![KeylistUpdateItem {
+                recipient_key: "key2".to_owned(),
+                action: KeylistUpdateItemAction::Add,
+            }])
+            .build();
+        let CoordinateMediation::KeylistUpdateResponse(response) =
+            handle_keylist_update(storage.clone(), &policy, update, "auth").await
+        else {
+            panic!("Expected keylist update response");
+        };
+        assert_eq!(
+            response.content.updated[0].result,
+            KeylistUpdateItemResult::ClientError
+        );
+        assert_eq!(storage.list_recipient_keys("auth").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_messages_are_unsupported() {
+        let storage = storage_with_keys(&[]).await;
+        let keylist = Keylist::builder()
+            .content(KeylistContent::default())
+            .decorators(KeylistDecorators::default())
+            .id("keylist-id".to_owned())
+            .build();
+        let response = handle_coord_authenticated(
+            storage.clone(),
+            &OpenAdmission::default(),
+            CoordinateMediation::Keylist(keylist),
+            "auth",
+            grant_content(),
+        )
+        .await;
+        assert!(matches!(
+            response.unwrap_err(),
+            MediatorError::UnsupportedMessage(_)
+        ));
+        // requests handled as usual
+        let update = KeylistUpdate::builder()
+            .content(KeylistUpdateContent::builder().updates(vec![]).build())
+            .id("update-id".to_owned())
+            .build();
+        assert!(handle_coord_authenticated(
+            storage,
+            &OpenAdmission::default(),
+            CoordinateMediation::KeylistUpdate(update),
+            "auth",
+            grant_content(),
+        )
+        .await
+        .is_ok());
+    }
+}
@@ -4,46 +4,188 @@
 use std::sync::Arc;
 
 use log::{debug, info};
-use messages::{
-    decorators::thread::Thread,
-    msg_fields::protocols::{
-        notification::ack::{Ack, AckContent, AckDecorators, AckStatus},
-        routing::Forward,
-    },
-};
-use uuid::Uuid;
+use messages::msg_fields::protocols::routing::Forward;
 
-use crate::persistence::MediatorPersistence;
+use super::{
+    live_delivery::{LiveDeliverySessions, LiveMessage},
+    notifier::{notify_message_queued, PushNotifier, PushRateLimit},
+    retention::{persist_within_quota, RetentionPolicy},
+};
+use crate::{
+    error::{MediatorError, MediatorResult},
+    metrics::{ForwardOutcome, MediatorMetrics},
+    persistence::MediatorPersistence,
+};
 
-pub async fn handle_forward<T>(storage: Arc<T>, forward_msg: Forward) -> Ack
+/// Delivers or queues forwarded message. The outcome is for the mediator only, the forwarder
+/// is anonymous and must not learn whether the recipient exists or what its quota state is.
+pub async fn handle_forward<T>(
+    storage: Arc<T>,
+    live_sessions: Arc<LiveDeliverySessions>,
+    retention_policy: &RetentionPolicy,
+    metrics: &MediatorMetrics,
+    notifier: Arc<dyn PushNotifier>,
+    push_rate_limit: Arc<PushRateLimit>,
+    forward_msg: Forward,
+) -> ForwardOutcome
 where
     T: MediatorPersistence,
 {
-    info!("Persisting forward message");
     debug!("{forward_msg:#?}");
-    let _ack_status = match storage
-        .persist_forward_message(
-            &forward_msg.content.to,
-            &serde_json::to_string(&forward_msg.content.msg).unwrap(),
-        )
-        .await
+    let message_data = serde_json::to_string(&forward_msg.content.msg).unwrap();
+    let message_data = match try_live_delivery(
+        storage.as_ref(),
+        live_sessions.as_ref(),
+        &forward_msg.content.to,
+        message_data,
+    )
+    .await
+    {
+        Ok(None) => {
+            info!("Delivered forward over live transport");
+            metrics.record_forward(ForwardOutcome::DeliveredLive);
+            return ForwardOutcome::DeliveredLive;
+        }
+        Ok(Some(message_data)) => message_data,
+        Err(e) => {
+            info!("Error when delivering forward over live transport: {}", e);
+            metrics.record_forward(ForwardOutcome::Failed);
+            return ForwardOutcome::Failed;
+        }
+    };
+    info!("Persisting forward message");
+    let outcome = match persist_within_quota(
+        storage.as_ref(),
+        retention_policy,
+        &forward_msg.content.to,
+        &message_data,
+    )
+    .await
     {
         Ok(_) => {
             info!("Persisted forward");
-            AckStatus::Ok
+            notify_message_queued(
+                storage.clone(),
+                notifier,
+                push_rate_limit,
+                &forward_msg.content.to,
+            );
+            ForwardOutcome::Queued
+        }
+        Err(MediatorError::QuotaExceeded(reason)) => {
+            info!("Rejecting forward: {}", reason);
+            ForwardOutcome::Rejected
         }
         Err(e) => {
             info!("Error when persisting forward: {}", e);
-            AckStatus::Pending
+            ForwardOutcome::Failed
         }
     };
-    let ack_content = AckContent::builder().status(AckStatus::Ok).build();
-    let ack_deco = AckDecorators::builder()
-        .thread(Thread::builder().thid(forward_msg.id).build())
-        .build();
-    Ack::builder()
-        .content(ack_content)
-        .decorators(ack_deco)
-        .id(Uuid::new_v4().to_string())
-        .build()
+    metrics.record_forward(outcome);
+    outcome
+}
+
+/// Pushes message to the recipient's held-open transport if its account has live delivery on.
+/// Gives the message back if it has to be queued instead.
+async fn try_live_delivery(
+    storage: &impl MediatorPersistence,
+    live_sessions: &LiveDeliverySessions,
+    recipient_key: &str,
+    message_data: String,
+) -> MediatorResult<Option<String>> {
+    let Ok(auth_pubkey) = storage
+        .get_recipient_account_auth_pubkey(recipient_key)
+        .await
+    else {
+        return Ok(Some(message_data));
+    };
+    let live_message = LiveMessage {
+        recipient_key: recipient_key.to_owned(),
+        message_data: message_data.into_bytes(),
+    };
+    match live_sessions.try_deliver(&auth_pubkey, live_message) {
+        Ok(()) => Ok(None),
+        Err(live_message) => String::from_utf8(live_message.message_data)
+            .map(Some)
+            .map_err(|err| MediatorError::MalformedMessage(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use messages::msg_fields::protocols::routing::ForwardContent;
+    use serde_json::json;
+    use tokio::sync::mpsc::unbounded_channel;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{mediation::notifier::NoopNotifier, persistence::InMemoryPersistence};
+
+    async fn forward(
+        storage: Arc<InMemoryPersistence>,
+        live_sessions: Arc<LiveDeliverySessions>,
+        recipient_key: &str,
+    ) -> ForwardOutcome {
+        let content = ForwardContent::builder()
+            .to(recipient_key.to_owned())
+            .msg(json!({ "protected": "test" }))
+            .build();
+        let forward_msg = Forward::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .build();
+        handle_forward(
+            storage,
+            live_sessions,
+            &RetentionPolicy::default(),
+            &MediatorMetrics::default(),
+            Arc::new(NoopNotifier),
+            Arc::new(PushRateLimit::default()),
+            forward_msg,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_forward_delivered_live() {
+        let storage = Arc::new(InMemoryPersistence::default());
+        storage
+            .create_account("auth", "our", &json!({}).to_string())
+            .await
+            .unwrap();
+        storage.add_recipient("auth", "key").await.unwrap();
+        let live_sessions = Arc::new(LiveDeliverySessions::default());
+        let (sender, mut receiver) = unbounded_channel();
+        live_sessions.register_transport("auth", sender);
+
+        assert_eq!(
+            forward(storage.clone(), live_sessions.clone(), "key").await,
+            ForwardOutcome::Queued
+        );
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth", None)
+                .await
+                .unwrap(),
+            1
+        );
+
+        live_sessions.set_live_delivery("auth", true).unwrap();
+        assert_eq!(
+            forward(storage.clone(), live_sessions.clone(), "key").await,
+            ForwardOutcome::DeliveredLive
+        );
+        let delivered = receiver.try_recv().unwrap();
+        assert_eq!(delivered.recipient_key, "key");
+        let delivered: serde_json::Value = serde_json::from_slice(&delivered.message_data).unwrap();
+        assert_eq!(delivered, json!({ "protected": "test" }));
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth", None)
+                .await
+                .unwrap(),
+            1
+        );
+    }
 }
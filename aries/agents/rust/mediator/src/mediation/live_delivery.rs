@@ -0,0 +1,163 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use log::info;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    error::{MediatorError, MediatorResult},
+    utils::structs::VerKey,
+};
+
+/// Forwarded message pushed to a held-open transport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveMessage {
+    pub recipient_key: VerKey,
+    pub message_data: Vec<u8>,
+}
+
+/// Channel over which messages are pushed to a held-open transport
+pub type LiveSender = UnboundedSender<LiveMessage>;
+
+struct LiveSession {
+    sender: LiveSender,
+    live_delivery: bool,
+}
+
+/// Registry of held-open transports, keyed by auth_pubkey of the account owning them.
+/// Messages for accounts which enabled live delivery (Pickup 2.0 `live-delivery-change`)
+/// are pushed over the registered transport instead of being queued in persistence.
+#[derive(Default)]
+pub struct LiveDeliverySessions {
+    sessions: RwLock<HashMap<VerKey, LiveSession>>,
+}
+
+impl LiveDeliverySessions {
+    /// Registers a held-open transport for the account. Live delivery stays disabled until
+    /// the client requests it.
+    pub fn register_transport(&self, auth_pubkey: &str, sender: LiveSender) {
+        info!("Registering held-open transport for {:#?}", auth_pubkey);
+        self.sessions.write().unwrap().insert(
+            auth_pubkey.to_owned(),
+            LiveSession {
+                sender,
+                live_delivery: false,
+            },
+        );
+    }
+
+    /// Unregisters the account's held-open transport, unless it was since replaced by another
+    pub fn unregister_transport(&self, auth_pubkey: &str, sender: &LiveSender) {
+        let mut sessions = self.sessions.write().unwrap();
+        if let Some(session) = sessions.get(auth_pubkey) {
+            if session.sender.same_channel(sender) {
+                info!("Unregistering held-open transport for {:#?}", auth_pubkey);
+                sessions.remove(auth_pubkey);
+            }
+        }
+    }
+
+    pub fn has_transport(&self, auth_pubkey: &str) -> bool {
+        self.sessions.read().unwrap().contains_key(auth_pubkey)
+    }
+
+    /// Toggles live delivery for the account. Fails if the account has no held-open transport.
+    pub fn set_live_delivery(&self, auth_pubkey: &str, live_delivery: bool) -> MediatorResult<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        match sessions.get_mut(auth_pubkey) {
+            Some(session) => {
+                session.live_delivery = live_delivery;
+                Ok(())
+            }
+            None if !live_delivery => Ok(()),
+            None => Err(MediatorError::LiveDeliveryNotSupported(
+                "Live delivery requires a held-open transport".to_owned(),
+            )),
+        }
+    }
+
+    pub fn is_live(&self, auth_pubkey: &str) -> bool {
+        self.sessions
+            .read()
+            .unwrap()
+            .get(auth_pubkey)
+            .map(|session| session.live_delivery)
+            .unwrap_or(false)
+    }
+
+    /// Pushes message over the account's held-open transport if live delivery is enabled.
+    /// Returns the message back if it could not be delivered, so that caller can queue it.
+    pub fn try_deliver(&self, auth_pubkey: &str, message: LiveMessage) -> Result<(), LiveMessage> {
+        let sessions = self.sessions.read().unwrap();
+        let Some(session) = sessions.get(auth_pubkey).filter(|s| s.live_delivery) else {
+            return Err(message);
+        };
+        match session.sender.send(message) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                info!("Held-open transport of {:#?} is gone", auth_pubkey);
+                let sender = session.sender.clone();
+                drop(sessions);
+                self.unregister_transport(auth_pubkey, &sender);
+                Err(err.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    fn live_message(message_data: u8) -> LiveMessage {
+        LiveMessage {
+            recipient_key: "key".to_owned(),
+            message_data: vec![message_data],
+        }
+    }
+
+    #[test]
+    fn test_live_delivery_requires_transport() {
+        let sessions = LiveDeliverySessions::default();
+        assert!(sessions.set_live_delivery("auth", true).is_err());
+        assert!(sessions.set_live_delivery("auth", false).is_ok());
+        assert_eq!(
+            sessions.try_deliver("auth", live_message(1)),
+            Err(live_message(1))
+        );
+    }
+
+    #[test]
+    fn test_live_delivery_pushes_when_enabled() {
+        let sessions = LiveDeliverySessions::default();
+        let (sender, mut receiver) = unbounded_channel();
+        sessions.register_transport("auth", sender);
+        assert_eq!(
+            sessions.try_deliver("auth", live_message(1)),
+            Err(live_message(1))
+        );
+        sessions.set_live_delivery("auth", true).unwrap();
+        assert!(sessions.try_deliver("auth", live_message(2)).is_ok());
+        assert_eq!(receiver.try_recv().unwrap(), live_message(2));
+        drop(receiver);
+        assert_eq!(
+            sessions.try_deliver("auth", live_message(3)),
+            Err(live_message(3))
+        );
+        assert!(!sessions.has_transport("auth"));
+    }
+
+    #[test]
+    fn test_replaced_transport_stays_registered() {
+        let sessions = LiveDeliverySessions::default();
+        let (old_sender, _old_receiver) = unbounded_channel();
+        let (new_sender, _new_receiver) = unbounded_channel();
+        sessions.register_transport("auth", old_sender.clone());
+        sessions.register_transport("auth", new_sender.clone());
+        sessions.unregister_transport("auth", &old_sender);
+        assert!(sessions.has_transport("auth"));
+        sessions.unregister_transport("auth", &new_sender);
+        assert!(!sessions.has_transport("auth"));
+    }
+}
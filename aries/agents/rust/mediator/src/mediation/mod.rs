@@ -1,3 +1,7 @@
+pub mod admission;
 pub mod coordination;
 pub mod forward;
+pub mod live_delivery;
+pub mod notifier;
 pub mod pickup;
+pub mod retention;
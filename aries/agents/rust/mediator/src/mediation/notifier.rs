@@ -0,0 +1,322 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    error::{parse_env_var, ConfigResult, MediatorError, MediatorResult},
+    persistence::MediatorPersistence,
+};
+
+/// Sent to the device of an account when a message is queued for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PushNotification {
+    /// Token the account registered for its device
+    pub push_token: String,
+    /// Recipient key the queued message is addressed to
+    pub recipient_key: String,
+}
+
+/// Notifies offline devices that messages are waiting for pickup
+#[async_trait]
+pub trait PushNotifier: Send + Sync + 'static {
+    async fn notify(&self, notification: &PushNotification) -> MediatorResult<()>;
+}
+
+/// Does not notify anyone
+#[derive(Debug, Clone, Default)]
+pub struct NoopNotifier;
+
+#[async_trait]
+impl PushNotifier for NoopNotifier {
+    async fn notify(&self, _notification: &PushNotification) -> MediatorResult<()> {
+        Ok(())
+    }
+}
+
+/// POSTs notifications as json to a webhook, typically a relay to FCM / APNs
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    pub url: url::Url,
+    pub client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: url::Url) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PushNotifier for WebhookNotifier {
+    async fn notify(&self, notification: &PushNotification) -> MediatorResult<()> {
+        self.client
+            .post(self.url.clone())
+            .json(notification)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| MediatorError::Internal(format!("Push webhook failed: {err}")))?;
+        Ok(())
+    }
+}
+
+/// Reads `MEDIATOR_PUSH_WEBHOOK_URL`, no notifications are sent if unset
+pub fn push_notifier_from_env() -> ConfigResult<Box<dyn PushNotifier>> {
+    Ok(match parse_env_var("MEDIATOR_PUSH_WEBHOOK_URL")? {
+        Some(url) => Box::new(WebhookNotifier::new(url)),
+        None => Box::new(NoopNotifier),
+    })
+}
+
+/// Limits how often push notifications are sent. A recipient key is notified at most once per
+/// `min_interval`, so a burst of forwards to an offline device results in a single notification,
+/// and at most `max_in_flight` notifications are being sent at any time. Only successfully sent
+/// notifications count, a failed one does not hold back the next.
+#[derive(Debug)]
+pub struct PushRateLimit {
+    min_interval: Duration,
+    state: Mutex<RateLimitState>,
+    in_flight: Arc<Semaphore>,
+}
+
+#[derive(Debug, Default)]
+struct RateLimitState {
+    /// When recipient keys were last notified successfully
+    last_notified: HashMap<String, Instant>,
+    /// Recipient keys being notified
+    notifying: HashSet<String>,
+}
+
+/// Permit to notify a recipient key. Until it's dropped, no other notification is sent for the
+/// key.
+struct PushPermit {
+    rate_limit: Arc<PushRateLimit>,
+    recipient_key: String,
+    _in_flight: OwnedSemaphorePermit,
+}
+
+impl PushPermit {
+    /// Records the notification as sent, the key is not notified again for min_interval
+    fn notified(self) {
+        let mut state = self.rate_limit.state.lock().unwrap();
+        state
+            .last_notified
+            .insert(self.recipient_key.clone(), Instant::now());
+    }
+}
+
+impl Drop for PushPermit {
+    fn drop(&mut self) {
+        let mut state = self.rate_limit.state.lock().unwrap();
+        state.notifying.remove(&self.recipient_key);
+    }
+}
+
+impl PushRateLimit {
+    pub fn new(min_interval: Duration, max_in_flight: usize) -> Self {
+        Self {
+            min_interval,
+            state: Mutex::new(RateLimitState::default()),
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    /// Returns permit to notify recipient_key, or None if it's to be skipped
+    fn try_acquire(self: &Arc<Self>, recipient_key: &str) -> Option<PushPermit> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        if state.notifying.contains(recipient_key) {
+            return None;
+        }
+        if let Some(notified_at) = state.last_notified.get(recipient_key) {
+            if now.duration_since(*notified_at) < self.min_interval {
+                return None;
+            }
+        }
+        let in_flight = self.in_flight.clone().try_acquire_owned().ok()?;
+        state
+            .last_notified
+            .retain(|_, notified_at| now.duration_since(*notified_at) < self.min_interval);
+        state.notifying.insert(recipient_key.to_owned());
+        Some(PushPermit {
+            rate_limit: self.clone(),
+            recipient_key: recipient_key.to_owned(),
+            _in_flight: in_flight,
+        })
+    }
+}
+
+impl Default for PushRateLimit {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30), 64)
+    }
+}
+
+/// Reads `MEDIATOR_PUSH_MIN_INTERVAL_SECS`, 30 seconds if unset
+pub fn push_rate_limit_from_env() -> ConfigResult<PushRateLimit> {
+    Ok(match parse_env_var("MEDIATOR_PUSH_MIN_INTERVAL_SECS")? {
+        Some(min_interval_secs) => PushRateLimit {
+            min_interval: Duration::from_secs(min_interval_secs),
+            ..Default::default()
+        },
+        None => PushRateLimit::default(),
+    })
+}
+
+/// Notifies the account owning recipient_key that a message was queued for it, if the account
+/// registered a push token and the rate limit allows. Runs in background so that the forward is
+/// not held up.
+pub fn notify_message_queued(
+    storage: Arc<impl MediatorPersistence>,
+    notifier: Arc<dyn PushNotifier>,
+    rate_limit: Arc<PushRateLimit>,
+    recipient_key: &str,
+) {
+    let Some(permit) = rate_limit.try_acquire(recipient_key) else {
+        info!("Skipping push notification for {}", recipient_key);
+        return;
+    };
+    let recipient_key = recipient_key.to_owned();
+    tokio::spawn(async move {
+        let push_token = match storage
+            .get_recipient_account_auth_pubkey(&recipient_key)
+            .await
+        {
+            Ok(auth_pubkey) => storage.get_push_token(&auth_pubkey).await,
+            Err(err) => Err(err),
+        };
+        let push_token = match push_token {
+            Ok(Some(push_token)) => push_token,
+            Ok(None) => return,
+            Err(err) => {
+                info!(
+                    "Could not look up push token for {}: {}",
+                    recipient_key, err
+                );
+                return;
+            }
+        };
+        let notification = PushNotification {
+            push_token,
+            recipient_key,
+        };
+        match notifier.notify(&notification).await {
+            Ok(()) => permit.notified(),
+            Err(err) => info!("Push notification failed: {}", err),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::json;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+    use crate::persistence::InMemoryPersistence;
+
+    /// Local webhook receiver, returns its url and channel of received notifications
+    async fn spawn_webhook_receiver() -> (
+        url::Url,
+        tokio::sync::mpsc::UnboundedReceiver<PushNotification>,
+    ) {
+        async fn receive(
+            State(sender): State<UnboundedSender<PushNotification>>,
+            Json(notification): Json<PushNotification>,
+        ) {
+            sender.send(notification).unwrap();
+        }
+        let (sender, receiver) = unbounded_channel();
+        let app = Router::new()
+            .route("/push", post(receive))
+            .with_state(sender);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let url = format!("http://{}/push", server.local_addr())
+            .parse()
+            .unwrap();
+        tokio::spawn(server);
+        (url, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_queued_message_notifies_webhook() {
+        let (url, mut receiver) = spawn_webhook_receiver().await;
+        let storage = Arc::new(InMemoryPersistence::default());
+        storage
+            .create_account("auth", "our", &json!({}).to_string())
+            .await
+            .unwrap();
+        storage.add_recipient("auth", "key").await.unwrap();
+        let notifier: Arc<dyn PushNotifier> = Arc::new(WebhookNotifier::new(url));
+        storage
+            .set_push_token("auth", Some("device_token"))
+            .await
+            .unwrap();
+        notify_message_queued(storage, notifier, Arc::new(PushRateLimit::default()), "key");
+        let notification = receiver.recv().await.unwrap();
+        assert_eq!(
+            notification,
+            PushNotification {
+                push_token: "device_token".to_owned(),
+                recipient_key: "key".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_push_rate_limit() {
+        let rate_limit = Arc::new(PushRateLimit::new(Duration::from_secs(60), 2));
+        let first = rate_limit.try_acquire("key1");
+        assert!(first.is_some());
+        // being notified
+        assert!(rate_limit.try_acquire("key1").is_none());
+        let second = rate_limit.try_acquire("key2");
+        assert!(second.is_some());
+        // capped
+        assert!(rate_limit.try_acquire("key3").is_none());
+        first.unwrap().notified();
+        // debounced
+        assert!(rate_limit.try_acquire("key1").is_none());
+        let third = rate_limit.try_acquire("key3");
+        assert!(third.is_some());
+        // failed notification of key2, it can be retried
+        drop(second);
+        assert!(rate_limit.try_acquire("key2").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_notification_is_not_rate_limited() {
+        let storage = Arc::new(InMemoryPersistence::default());
+        storage
+            .create_account("auth", "our", &json!({}).to_string())
+            .await
+            .unwrap();
+        storage.add_recipient("auth", "key").await.unwrap();
+        storage
+            .set_push_token("auth", Some("device_token"))
+            .await
+            .unwrap();
+        // nothing listens on the url
+        let notifier: Arc<dyn PushNotifier> = Arc::new(WebhookNotifier::new(
+            "http://127.0.0.1:1/push".parse().unwrap(),
+        ));
+        let rate_limit = Arc::new(PushRateLimit::default());
+        notify_message_queued(storage, notifier, rate_limit.clone(), "key");
+        while rate_limit.state.lock().unwrap().notifying.contains("key") {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(rate_limit.try_acquire("key").is_some());
+    }
+}
@@ -6,19 +6,22 @@ use log::info;
 use messages::{
     decorators::attachment::{Attachment, AttachmentData, AttachmentType},
     msg_fields::protocols::pickup::{
-        Delivery, DeliveryContent, DeliveryRequestContent, Pickup, Status, StatusContent,
-        StatusDecorators, StatusRequestContent,
+        Delivery, DeliveryContent, DeliveryRequestContent, LiveDeliveryChangeContent,
+        MessagesReceivedContent, Pickup, Status, StatusContent, StatusDecorators,
+        StatusRequestContent,
     },
 };
 use uuid::Uuid;
 
-use crate::persistence::MediatorPersistence;
+use super::live_delivery::LiveDeliverySessions;
+use crate::{error::MediatorResult, persistence::MediatorPersistence};
 
 pub async fn handle_pickup_authenticated<T: MediatorPersistence>(
     storage: Arc<T>,
+    live_sessions: Arc<LiveDeliverySessions>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Pickup {
+) -> MediatorResult<Pickup> {
     match &pickup_message {
         Pickup::StatusRequest(status_request) => {
             handle_pickup_status_req(&status_request.content, storage, auth_pubkey).await
@@ -33,6 +36,18 @@ pub async fn handle_pickup_authenticated<T: MediatorPersistence>(
         Pickup::DeliveryRequest(delivery_request) => {
             handle_pickup_delivery_req(&delivery_request.content, storage, auth_pubkey).await
         }
+        Pickup::MessagesReceived(messages_received) => {
+            handle_pickup_messages_received(&messages_received.content, storage, auth_pubkey).await
+        }
+        Pickup::LiveDeliveryChange(live_delivery_change) => {
+            handle_pickup_live_delivery_change(
+                &live_delivery_change.content,
+                storage,
+                live_sessions,
+                auth_pubkey,
+            )
+            .await
+        }
         _ => {
             info!("Received {:#?}", &pickup_message);
             // StatusCode::NOT_IMPLEMENTED,
@@ -45,12 +60,11 @@ async fn handle_pickup_status_req<T: MediatorPersistence>(
     status_request: &StatusRequestContent,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> MediatorResult<Pickup> {
     info!("Received {:#?}", &status_request);
     let message_count = storage
         .retrieve_pending_message_count(auth_pubkey, status_request.recipient_key.as_ref())
-        .await
-        .unwrap();
+        .await?;
     let status_content = if let Some(recipient_key) = status_request.recipient_key.clone() {
         StatusContent::builder()
             .message_count(message_count)
@@ -68,14 +82,14 @@ async fn handle_pickup_status_req<T: MediatorPersistence>(
         .build();
 
     info!("Sending {:#?}", &status);
-    Pickup::Status(status)
+    Ok(Pickup::Status(status))
 }
 
 async fn handle_pickup_delivery_req<T: MediatorPersistence>(
     delivery_request: &DeliveryRequestContent,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> MediatorResult<Pickup> {
     info!("Received {:#?}", &delivery_request);
     let messages = storage
         .retrieve_pending_messages(
@@ -83,26 +97,16 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
             delivery_request.limit,
             delivery_request.recipient_key.as_ref(),
         )
-        .await
-        .unwrap();
+        .await?;
     // for (message_id, message_content) in messages.into_iter() {
     //     info!("Message {:#?} {:#?}", message_id, String::from_utf8(message_content).unwrap())
     // }
     let attach: Vec<Attachment> = messages
         .into_iter()
-        .map(|(message_id, message_content)| {
-            Attachment::builder()
-                .id(message_id)
-                .data(
-                    AttachmentData::builder()
-                        .content(AttachmentType::Base64(base64_url::encode(&message_content)))
-                        .build(),
-                )
-                .build()
-        })
+        .map(|(message_id, message_content)| build_attachment(message_id, &message_content))
         .collect();
     if !attach.is_empty() {
-        Pickup::Delivery(
+        Ok(Pickup::Delivery(
             Delivery::builder()
                 .content(DeliveryContent {
                     recipient_key: delivery_request.recipient_key.to_owned(),
@@ -110,12 +114,62 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
                 })
                 .id(Uuid::new_v4().to_string())
                 .build(),
-        )
+        ))
     } else {
         // send default status message instead
         handle_pickup_default_status(storage, auth_pubkey).await
     }
 }
+
+/// Delivery of a message pushed over a held-open transport in live mode.
+/// Such messages are never queued, so there is nothing to acknowledge with messages-received.
+pub fn build_live_delivery(message_data: &[u8]) -> Pickup {
+    Pickup::Delivery(
+        Delivery::builder()
+            .content(DeliveryContent {
+                recipient_key: None,
+                attach: vec![build_attachment(Uuid::new_v4().to_string(), message_data)],
+            })
+            .id(Uuid::new_v4().to_string())
+            .build(),
+    )
+}
+
+fn build_attachment(message_id: String, message_content: &[u8]) -> Attachment {
+    Attachment::builder()
+        .id(message_id)
+        .data(
+            AttachmentData::builder()
+                .content(AttachmentType::Base64(base64_url::encode(message_content)))
+                .build(),
+        )
+        .build()
+}
+
+/// Deletes acknowledged messages and responds with updated status
+async fn handle_pickup_messages_received<T: MediatorPersistence>(
+    messages_received: &MessagesReceivedContent,
+    storage: Arc<T>,
+    auth_pubkey: &str,
+) -> MediatorResult<Pickup> {
+    info!("Received {:#?}", &messages_received);
+    storage
+        .delete_messages(auth_pubkey, &messages_received.message_id_list)
+        .await?;
+    handle_pickup_default_status(storage, auth_pubkey).await
+}
+
+/// Toggles live delivery over the held-open transport and responds with status
+async fn handle_pickup_live_delivery_change<T: MediatorPersistence>(
+    live_delivery_change: &LiveDeliveryChangeContent,
+    storage: Arc<T>,
+    live_sessions: Arc<LiveDeliverySessions>,
+    auth_pubkey: &str,
+) -> MediatorResult<Pickup> {
+    info!("Received {:#?}", &live_delivery_change);
+    live_sessions.set_live_delivery(auth_pubkey, live_delivery_change.live_delivery)?;
+    handle_pickup_default_status(storage, auth_pubkey).await
+}
 // Returns global status message for user (not restricted to recipient key)
 // async fn handle_pickup_default<T: MediatorPersistence>(
 //     storage: Arc<T>,
@@ -136,7 +190,7 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
 async fn handle_pickup_default_status(
     storage: Arc<impl MediatorPersistence>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> MediatorResult<Pickup> {
     info!("Default behavior: responding with status");
     let status_request = StatusRequestContent::builder().build();
     handle_pickup_status_req(&status_request, storage, auth_pubkey).await
@@ -0,0 +1,190 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use log::info;
+use tokio::task::JoinHandle;
+
+use crate::{
+    error::{parse_env_var, ConfigResult, MediatorResult},
+    persistence::{MediatorPersistence, MessageQuota},
+};
+
+/// Limits on messages queued by the mediator for its accounts
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Maximum number of queued messages per account, None for no limit
+    pub max_messages_per_account: Option<u64>,
+    /// Maximum total size in bytes of queued messages per account, None for no limit
+    pub max_bytes_per_account: Option<u64>,
+    /// Queued messages older than this are purged, None to keep them until picked up
+    pub max_message_age: Option<Duration>,
+    /// How often expired messages are purged
+    pub purge_interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_messages_per_account: None,
+            max_bytes_per_account: None,
+            max_message_age: None,
+            purge_interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Per account limits on queued messages
+    pub fn quota(&self) -> MessageQuota {
+        MessageQuota {
+            max_messages: self.max_messages_per_account,
+            max_bytes: self.max_bytes_per_account,
+        }
+    }
+
+    fn has_quota(&self) -> bool {
+        self.max_messages_per_account.is_some() || self.max_bytes_per_account.is_some()
+    }
+}
+
+/// Reads policy from environment, every limit is unset by default.
+/// `MEDIATOR_MAX_MESSAGES_PER_ACCOUNT`: queued message count limit per account.
+/// `MEDIATOR_MAX_BYTES_PER_ACCOUNT`: queued message size limit per account.
+/// `MEDIATOR_MAX_MESSAGE_AGE_SECS`: age after which queued messages are purged.
+/// `MEDIATOR_PURGE_INTERVAL_SECS`: how often to purge, 3600 if unset.
+pub fn retention_policy_from_env() -> ConfigResult<RetentionPolicy> {
+    let default = RetentionPolicy::default();
+    Ok(RetentionPolicy {
+        max_messages_per_account: parse_env_var("MEDIATOR_MAX_MESSAGES_PER_ACCOUNT")?,
+        max_bytes_per_account: parse_env_var("MEDIATOR_MAX_BYTES_PER_ACCOUNT")?,
+        max_message_age: parse_env_var("MEDIATOR_MAX_MESSAGE_AGE_SECS")?.map(Duration::from_secs),
+        purge_interval: parse_env_var("MEDIATOR_PURGE_INTERVAL_SECS")?
+            .map(Duration::from_secs)
+            .unwrap_or(default.purge_interval),
+    })
+}
+
+/// Queues message for the account of recipient_key, unless the account is over quota
+pub async fn persist_within_quota(
+    storage: &impl MediatorPersistence,
+    policy: &RetentionPolicy,
+    recipient_key: &str,
+    message_data: &str,
+) -> MediatorResult<()> {
+    if policy.has_quota() {
+        storage
+            .persist_forward_message_within_quota(recipient_key, message_data, policy.quota())
+            .await
+    } else {
+        storage
+            .persist_forward_message(recipient_key, message_data)
+            .await
+    }
+}
+
+/// Deletes messages older than the policy's max_message_age, returns number of purged messages
+pub async fn purge_expired_messages(
+    storage: &impl MediatorPersistence,
+    policy: &RetentionPolicy,
+) -> MediatorResult<u64> {
+    let Some(max_message_age) = policy.max_message_age else {
+        return Ok(0);
+    };
+    let received_before = SystemTime::now()
+        .checked_sub(max_message_age)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    storage
+        .purge_messages_received_before(received_before)
+        .await
+}
+
+/// Spawns task purging expired messages every purge_interval.
+/// Returns None if the policy doesn't expire messages.
+pub fn spawn_purge_task(
+    storage: Arc<impl MediatorPersistence>,
+    policy: RetentionPolicy,
+) -> Option<JoinHandle<()>> {
+    policy.max_message_age?;
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(policy.purge_interval);
+        loop {
+            interval.tick().await;
+            match purge_expired_messages(storage.as_ref(), &policy).await {
+                Ok(purged) => info!("Purged {} expired messages", purged),
+                Err(err) => info!("Failed to purge expired messages: {}", err),
+            }
+            if let Ok(usage) = storage.get_message_usage(None).await {
+                info!(
+                    "Queued messages: {} ({} bytes)",
+                    usage.message_count, usage.message_bytes
+                );
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        error::MediatorError,
+        persistence::{InMemoryPersistence, MessageUsage},
+    };
+
+    #[test]
+    fn test_check_quota() {
+        let policy = RetentionPolicy {
+            max_messages_per_account: Some(2),
+            max_bytes_per_account: Some(10),
+            ..Default::default()
+        };
+        let usage = MessageUsage {
+            message_count: 1,
+            message_bytes: 6,
+        };
+        assert!(policy.quota().check(usage, 4).is_ok());
+        assert!(matches!(
+            policy.quota().check(usage, 5),
+            Err(MediatorError::QuotaExceeded(_))
+        ));
+        let usage = MessageUsage {
+            message_count: 2,
+            message_bytes: 0,
+        };
+        assert!(policy.quota().check(usage, 1).is_err());
+        assert!(RetentionPolicy::default().quota().check(usage, 1).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_persist_within_quota() {
+        let storage = InMemoryPersistence::default();
+        storage
+            .create_account("auth", "our", &json!({}).to_string())
+            .await
+            .unwrap();
+        storage.add_recipient("auth", "key").await.unwrap();
+        let policy = RetentionPolicy {
+            max_messages_per_account: Some(1),
+            ..Default::default()
+        };
+        persist_within_quota(&storage, &policy, "key", "first")
+            .await
+            .unwrap();
+        assert!(matches!(
+            persist_within_quota(&storage, &policy, "key", "second").await,
+            Err(MediatorError::QuotaExceeded(_))
+        ));
+        assert_eq!(
+            storage
+                .get_message_usage(Some("auth"))
+                .await
+                .unwrap()
+                .message_count,
+            1
+        );
+    }
+}
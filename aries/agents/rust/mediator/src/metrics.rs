@@ -0,0 +1,149 @@
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::persistence::MessageUsage;
+
+/// What happened to a received forward message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardOutcome {
+    /// Pushed over the recipient's held-open transport
+    DeliveredLive,
+    /// Queued for pickup
+    Queued,
+    /// Refused, recipient's account is over quota
+    Rejected,
+    /// Could not be queued, e.g. unknown recipient or storage failure
+    Failed,
+}
+
+/// Counters of mediator activity since startup, exposed in Prometheus text format
+#[derive(Debug, Default)]
+pub struct MediatorMetrics {
+    forwards_received: AtomicU64,
+    forwards_delivered_live: AtomicU64,
+    forwards_queued: AtomicU64,
+    forwards_rejected: AtomicU64,
+    forwards_failed: AtomicU64,
+    messages_delivered: AtomicU64,
+}
+
+impl MediatorMetrics {
+    pub fn record_forward(&self, outcome: ForwardOutcome) {
+        self.forwards_received.fetch_add(1, Ordering::Relaxed);
+        let counter = match outcome {
+            ForwardOutcome::DeliveredLive => &self.forwards_delivered_live,
+            ForwardOutcome::Queued => &self.forwards_queued,
+            ForwardOutcome::Rejected => &self.forwards_rejected,
+            ForwardOutcome::Failed => &self.forwards_failed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records queued messages handed out in a pickup delivery
+    pub fn record_messages_delivered(&self, message_count: u64) {
+        self.messages_delivered
+            .fetch_add(message_count, Ordering::Relaxed);
+    }
+
+    /// Renders counters together with current queue depth and account count
+    pub fn render_prometheus(&self, queue: MessageUsage, account_count: u64) -> String {
+        let mut out = String::new();
+        let forwards = [
+            ("delivered_live", &self.forwards_delivered_live),
+            ("queued", &self.forwards_queued),
+            ("rejected", &self.forwards_rejected),
+            ("failed", &self.forwards_failed),
+        ];
+        write_header(
+            &mut out,
+            "mediator_forwards_received_total",
+            "Forward messages received",
+            "counter",
+        );
+        writeln!(
+            out,
+            "mediator_forwards_received_total {}",
+            self.forwards_received.load(Ordering::Relaxed)
+        )
+        .unwrap();
+        write_header(
+            &mut out,
+            "mediator_forwards_total",
+            "Forward messages received, by outcome",
+            "counter",
+        );
+        for (outcome, counter) in forwards {
+            writeln!(
+                out,
+                "mediator_forwards_total{{outcome=\"{}\"}} {}",
+                outcome,
+                counter.load(Ordering::Relaxed)
+            )
+            .unwrap();
+        }
+        let gauges = [
+            (
+                "mediator_messages_delivered_total",
+                "Queued messages handed out in pickup deliveries",
+                "counter",
+                self.messages_delivered.load(Ordering::Relaxed),
+            ),
+            (
+                "mediator_queued_messages",
+                "Messages waiting for pickup",
+                "gauge",
+                queue.message_count,
+            ),
+            (
+                "mediator_queued_message_bytes",
+                "Total size of messages waiting for pickup",
+                "gauge",
+                queue.message_bytes,
+            ),
+            (
+                "mediator_accounts",
+                "Registered accounts",
+                "gauge",
+                account_count,
+            ),
+        ];
+        for (name, help, metric_type, value) in gauges {
+            write_header(&mut out, name, help, metric_type);
+            writeln!(out, "{} {}", name, value).unwrap();
+        }
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, metric_type: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, metric_type).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = MediatorMetrics::default();
+        metrics.record_forward(ForwardOutcome::Queued);
+        metrics.record_forward(ForwardOutcome::Rejected);
+        metrics.record_messages_delivered(3);
+        let rendered = metrics.render_prometheus(
+            MessageUsage {
+                message_count: 1,
+                message_bytes: 10,
+            },
+            2,
+        );
+        assert!(rendered.contains("mediator_forwards_received_total 2\n"));
+        assert!(rendered.contains("mediator_forwards_total{outcome=\"rejected\"} 1\n"));
+        assert!(rendered.contains("mediator_messages_delivered_total 3\n"));
+        assert!(rendered.contains("# TYPE mediator_queued_messages gauge\n"));
+        assert!(rendered.contains("mediator_queued_message_bytes 10\n"));
+        assert!(rendered.contains("mediator_accounts 2\n"));
+    }
+}
@@ -2,4 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod mysql;
+mod sqlite;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 pub use mysql::get_db_pool;
+pub use sqlite::{get_sqlite_db_pool, get_sqlite_db_pool_from_url};
+
+use crate::error::MediatorError;
+
+/// Maps missing row to the given not-found error, anything else to storage error
+fn not_found_or_storage(err: sqlx::Error, not_found: MediatorError) -> MediatorError {
+    match err {
+        sqlx::Error::RowNotFound => not_found,
+        err => err.into(),
+    }
+}
+
+/// Seconds since unix epoch, as stored in received_at columns
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -1,12 +1,18 @@
 // Copyright 2023 Naian G.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::SystemTime;
+
 use async_trait::async_trait;
 use futures::TryStreamExt;
 use log::info;
 use sqlx::{mysql::MySqlPoolOptions, MySqlPool, Row};
 
-use super::super::MediatorPersistence;
+use super::{
+    super::{MediatorPersistence, MessageQuota, MessageUsage},
+    not_found_or_storage, unix_seconds,
+};
+use crate::error::{MediatorError, MediatorResult};
 
 pub async fn get_db_pool() -> MySqlPool {
     let _ = dotenvy::dotenv();
@@ -27,7 +33,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
         auth_pubkey: &str,
         our_signing_key: &str,
         did_doc: &str,
-    ) -> Result<(), String> {
+    ) -> MediatorResult<()> {
         info!(
             "Adding new account to database with auth_pubkey {:#?}",
             &auth_pubkey
@@ -42,7 +48,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
         .await;
         if let Err(err) = insert_result {
             info!("Error during creating new account, {:#?}", err);
-            return Err(format!("{:#}", err));
+            return Err(err.into());
         };
         let account_id = self.get_account_id(auth_pubkey).await?;
         info!(
@@ -52,7 +58,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
         Ok(())
     }
     /// Get account id associated with auth_pubkey
-    async fn get_account_id(&self, auth_pubkey: &str) -> Result<Vec<u8>, String> {
+    async fn get_account_id(&self, auth_pubkey: &str) -> MediatorResult<Vec<u8>> {
         let account_id: Vec<u8> =
             match sqlx::query("SELECT (account_id) FROM accounts WHERE auth_pubkey = ?;")
                 .bind(auth_pubkey)
@@ -62,31 +68,84 @@ impl MediatorPersistence for sqlx::MySqlPool {
                 Ok(account_row) => account_row.get("account_id"),
                 Err(err) => {
                     info!("Error while finding account, {:#?}", err);
-                    return Err(format!("{:#}", err));
+                    return Err(not_found_or_storage(
+                        err,
+                        MediatorError::UnknownAccount(auth_pubkey.to_owned()),
+                    ));
                 }
             };
         Ok(account_id)
     }
-    async fn list_accounts(&self) -> Result<Vec<(String, String)>, String> {
+    async fn delete_account(&self, auth_pubkey: &str) -> MediatorResult<()> {
+        info!("Deleting account with auth_pubkey {:#?}", auth_pubkey);
+        // recipients and messages of the account are removed by ON DELETE CASCADE
+        let result = sqlx::query("DELETE FROM accounts WHERE auth_pubkey = ?;")
+            .bind(auth_pubkey)
+            .execute(self)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(MediatorError::UnknownAccount(auth_pubkey.to_owned()));
+        }
+        Ok(())
+    }
+    async fn set_push_token(
+        &self,
+        auth_pubkey: &str,
+        push_token: Option<&str>,
+    ) -> MediatorResult<()> {
+        info!(
+            "Setting push token of account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let result = sqlx::query("UPDATE accounts SET push_token = ? WHERE auth_pubkey = ?;")
+            .bind(push_token)
+            .bind(auth_pubkey)
+            .execute(self)
+            .await?;
+        if result.rows_affected() == 0 {
+            // MySQL doesn't count rows updated to their current value, so check existence
+            self.get_account_id(auth_pubkey).await?;
+        }
+        Ok(())
+    }
+    async fn get_push_token(&self, auth_pubkey: &str) -> MediatorResult<Option<String>> {
+        let row = sqlx::query("SELECT push_token FROM accounts WHERE auth_pubkey = ?;")
+            .bind(auth_pubkey)
+            .fetch_one(self)
+            .await
+            .map_err(|err| {
+                not_found_or_storage(err, MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+            })?;
+        Ok(row.get("push_token"))
+    }
+    async fn list_accounts(&self) -> MediatorResult<Vec<(String, String)>> {
         let list: Vec<(String, String)> =
             sqlx::query("SELECT account_name, auth_pubkey FROM accounts;")
                 .fetch_all(self)
-                .await
-                .map_err(|e| e.to_string())?
+                .await?
                 .iter()
                 .map(|row| (row.get("account_name"), row.get("auth_pubkey")))
                 .collect();
         Ok(list)
     }
+    async fn count_accounts(&self) -> MediatorResult<u64> {
+        let account_count: i64 = sqlx::query("SELECT COUNT(*) AS account_count FROM accounts;")
+            .fetch_one(self)
+            .await?
+            .get("account_count");
+        Ok(account_count as u64)
+    }
     async fn get_account_details(
         &self,
         auth_pubkey: &str,
-    ) -> Result<(u64, String, String, serde_json::Value), String> {
+    ) -> MediatorResult<(u64, String, String, serde_json::Value)> {
         let row = sqlx::query("SELECT * FROM accounts WHERE auth_pubkey = ?;")
             .bind(auth_pubkey)
             .fetch_one(self)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|err| {
+                not_found_or_storage(err, MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+            })?;
         Ok((
             row.get("seq_num"),
             row.get("account_name"),
@@ -125,18 +184,24 @@ impl MediatorPersistence for sqlx::MySqlPool {
         &self,
         recipient_key: &str,
         message_data: &str,
-    ) -> Result<(), String> {
+    ) -> MediatorResult<()> {
         // Fetch recipient with given recipient_key
         info!("Fetching recipient with recipient_key {:#?}", recipient_key);
         let recipient_row = sqlx::query("SELECT * FROM recipients WHERE recipient_key = ?")
             .bind(recipient_key)
             .fetch_one(self)
             .await;
-        if let Err(err) = recipient_row {
-            info!("Error while finding target recipient, {:#}", err);
-            return Err(format!("{:#}", err));
-        }
-        let account_id: Vec<u8> = recipient_row.unwrap().get("account_id");
+        let recipient_row = match recipient_row {
+            Ok(recipient_row) => recipient_row,
+            Err(err) => {
+                info!("Error while finding target recipient, {:#}", err);
+                return Err(not_found_or_storage(
+                    err,
+                    MediatorError::UnknownRecipient(recipient_key.to_owned()),
+                ));
+            }
+        };
+        let account_id: Vec<u8> = recipient_row.get("account_id");
         // Save message for recipient
         info!("Persisting message for account {:x?}", account_id);
         let insert_result = sqlx::query(
@@ -152,15 +217,68 @@ impl MediatorPersistence for sqlx::MySqlPool {
                 "Error while saving message for recipient {:x?}, {:#}",
                 recipient_key, err
             );
-            return Err(format!("{:#}", err));
+            return Err(err.into());
         }
         Ok(())
     }
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        quota: MessageQuota,
+    ) -> MediatorResult<()> {
+        info!("Fetching recipient with recipient_key {:#?}", recipient_key);
+        let mut transaction = self.begin().await?;
+        // Locking the account row serializes quota checks of concurrent forwards
+        let account_id: Vec<u8> = match sqlx::query(
+            "SELECT accounts.account_id FROM recipients
+            JOIN accounts ON recipients.account_id = accounts.account_id
+            WHERE recipients.recipient_key = ? FOR UPDATE",
+        )
+        .bind(recipient_key)
+        .fetch_one(&mut *transaction)
+        .await
+        {
+            Ok(account_row) => account_row.get("account_id"),
+            Err(err) => {
+                info!("Error while finding target recipient, {:#}", err);
+                return Err(not_found_or_storage(
+                    err,
+                    MediatorError::UnknownRecipient(recipient_key.to_owned()),
+                ));
+            }
+        };
+        let usage_row = sqlx::query(
+            "SELECT COUNT(*) AS message_count, CAST(COALESCE(SUM(LENGTH(message_data)), 0) AS \
+             SIGNED) AS message_bytes FROM messages WHERE (account_id = ?)",
+        )
+        .bind(&account_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+        let message_count: i64 = usage_row.get("message_count");
+        let message_bytes: i64 = usage_row.get("message_bytes");
+        let usage = MessageUsage {
+            message_count: message_count as u64,
+            message_bytes: message_bytes as u64,
+        };
+        quota.check(usage, message_data.len() as u64)?;
+        info!("Persisting message for account {:x?}", account_id);
+        sqlx::query(
+            "INSERT INTO messages (account_id, recipient_key, message_data) VALUES (?, ?, ?)",
+        )
+        .bind(&account_id)
+        .bind(recipient_key)
+        .bind(message_data)
+        .execute(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
     async fn retrieve_pending_message_count(
         &self,
         auth_pubkey: &str,
         recipient_key: Option<&String>,
-    ) -> Result<u32, String> {
+    ) -> MediatorResult<u32> {
         let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
         let message_count_result = if let Some(recipient_key) = recipient_key {
             sqlx::query(
@@ -181,8 +299,10 @@ impl MediatorPersistence for sqlx::MySqlPool {
             .await
         };
         // MySQL BIGINT can be converted to i32 only, not u32
-        let message_count: i32 = message_count_result.unwrap().get::<i32, &str>("COUNT(*)");
-        let message_count: u32 = message_count.try_into().unwrap();
+        let message_count: i32 = message_count_result?.get::<i32, &str>("COUNT(*)");
+        let message_count: u32 = message_count
+            .try_into()
+            .map_err(|_| MediatorError::Storage(format!("Invalid count {message_count}")))?;
         info!(
             "Total message count of all requested recipients: {:#?}",
             &message_count
@@ -194,7 +314,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
         auth_pubkey: &str,
         limit: u32,
         recipient_key: Option<&String>,
-    ) -> Result<Vec<(String, Vec<u8>)>, String> {
+    ) -> MediatorResult<Vec<(String, Vec<u8>)>> {
         info!(
             "Processing retrieve for messages to recipient_key {:#?} of auth_pubkey {:#?}",
             recipient_key, auth_pubkey
@@ -211,7 +331,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
                 .bind(&account_id)
                 .fetch(self)
         };
-        while let Some(message_row) = message_rows.try_next().await.unwrap() {
+        while let Some(message_row) = message_rows.try_next().await? {
             let id: String = message_row.get("message_id");
             let msg: Vec<u8> = message_row.get("message_data");
             // debug!("id {:#?}", id);
@@ -229,7 +349,100 @@ impl MediatorPersistence for sqlx::MySqlPool {
         );
         Ok(messages)
     }
-    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> Result<(), String> {
+    async fn delete_messages(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> MediatorResult<()> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "Deleting {:#?} received messages of auth_pubkey {:#?}",
+            message_ids.len(),
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let query_string = format!(
+            "DELETE FROM messages WHERE (account_id = ?) AND message_id IN ({placeholders});"
+        );
+        let mut query = sqlx::query(&query_string).bind(&account_id);
+        for message_id in message_ids {
+            query = query.bind(message_id);
+        }
+        match query.execute(self).await {
+            Ok(result) => {
+                info!("Deleted {:#?} messages", result.rows_affected());
+                Ok(())
+            }
+            Err(err) => {
+                info!("Error while deleting messages, {:#}", err);
+                Err(err.into())
+            }
+        }
+    }
+    async fn get_message_usage(&self, auth_pubkey: Option<&str>) -> MediatorResult<MessageUsage> {
+        let usage_row = if let Some(auth_pubkey) = auth_pubkey {
+            let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+            sqlx::query(
+                "SELECT COUNT(*) AS message_count, CAST(COALESCE(SUM(LENGTH(message_data)), 0) AS \
+                 SIGNED) AS message_bytes FROM messages WHERE (account_id = ?)",
+            )
+            .bind(account_id)
+            .fetch_one(self)
+            .await?
+        } else {
+            sqlx::query(
+                "SELECT COUNT(*) AS message_count, CAST(COALESCE(SUM(LENGTH(message_data)), 0) AS \
+                 SIGNED) AS message_bytes FROM messages",
+            )
+            .fetch_one(self)
+            .await?
+        };
+        let message_count: i64 = usage_row.get("message_count");
+        let message_bytes: i64 = usage_row.get("message_bytes");
+        Ok(MessageUsage {
+            message_count: message_count as u64,
+            message_bytes: message_bytes as u64,
+        })
+    }
+    async fn purge_messages_received_before(
+        &self,
+        received_before: SystemTime,
+    ) -> MediatorResult<u64> {
+        let result = sqlx::query("DELETE FROM messages WHERE received_at < FROM_UNIXTIME(?)")
+            .bind(unix_seconds(received_before))
+            .execute(self)
+            .await?;
+        info!("Purged {:#?} expired messages", result.rows_affected());
+        Ok(result.rows_affected())
+    }
+    async fn get_recipient_account_auth_pubkey(
+        &self,
+        recipient_key: &str,
+    ) -> MediatorResult<String> {
+        let auth_pubkey: String = match sqlx::query(
+            "SELECT accounts.auth_pubkey FROM recipients
+            JOIN accounts ON recipients.account_id = accounts.account_id
+            WHERE recipients.recipient_key = ?;",
+        )
+        .bind(recipient_key)
+        .fetch_one(self)
+        .await
+        {
+            Ok(row) => row.get("auth_pubkey"),
+            Err(err) => {
+                info!("Error while finding account of recipient, {:#}", err);
+                return Err(not_found_or_storage(
+                    err,
+                    MediatorError::UnknownRecipient(recipient_key.to_owned()),
+                ));
+            }
+        };
+        Ok(auth_pubkey)
+    }
+    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
         info!(
             "Adding recipient_key to account with auth_pubkey {:#?}",
             auth_pubkey
@@ -249,11 +462,11 @@ impl MediatorPersistence for sqlx::MySqlPool {
             Ok(_result) => Ok(()),
             Err(err) => {
                 info!("Error while adding recipient, {:#}", err);
-                Err(format!("{:#}", err))
+                Err(err.into())
             }
         }
     }
-    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> Result<(), String> {
+    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
         info!(
             "Removing recipient_key from account with auth_pubkey {:#?}",
             auth_pubkey
@@ -273,11 +486,11 @@ impl MediatorPersistence for sqlx::MySqlPool {
             Ok(_result) => Ok(()),
             Err(err) => {
                 info!("Error while removing recipient, {:#}", err);
-                Err(format!("{:#}", err))
+                Err(err.into())
             }
         }
     }
-    async fn list_recipient_keys(&self, auth_pubkey: &str) -> Result<Vec<String>, String> {
+    async fn list_recipient_keys(&self, auth_pubkey: &str) -> MediatorResult<Vec<String>> {
         info!(
             "Retrieving recipient_keys for account with auth_pubkey {:#?}",
             auth_pubkey
@@ -295,7 +508,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
                     .collect(),
                 Err(err) => {
                     info!("Error while getting recipient_keys, {:#}", err);
-                    return Err(format!("{:#}", err));
+                    return Err(err.into());
                 }
             };
         Ok(recipient_keys)
@@ -0,0 +1,491 @@
+use std::{str::FromStr, time::SystemTime};
+
+use async_trait::async_trait;
+use log::info;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Row, SqlitePool,
+};
+use uuid::Uuid;
+
+use super::{
+    super::{MediatorPersistence, MessageQuota, MessageUsage},
+    not_found_or_storage, unix_seconds,
+};
+use crate::error::{MediatorError, MediatorResult};
+
+pub async fn get_sqlite_db_pool() -> SqlitePool {
+    let _ = dotenvy::dotenv();
+    let database_url =
+        std::env::var("SQLITE_URL").unwrap_or("sqlite:mediator-persistence.db".into());
+    get_sqlite_db_pool_from_url(&database_url).await
+}
+
+/// Connects to (creating if missing) sqlite database at database_url and runs migrations
+pub async fn get_sqlite_db_pool_from_url(database_url: &str) -> SqlitePool {
+    let connect_options = SqliteConnectOptions::from_str(database_url)
+        .expect("SQLITE_URL should be a valid sqlite url")
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new()
+        .connect_with(connect_options)
+        .await
+        .expect("Failed to connect to database!");
+    sqlx::migrate!("./migrations_sqlite")
+        .run(&pool)
+        .await
+        .expect("Failed to run database migrations!");
+    pool
+}
+
+/// MediatorPersistence trait implementation for Sqlite Database
+#[async_trait]
+impl MediatorPersistence for sqlx::SqlitePool {
+    async fn create_account(
+        &self,
+        auth_pubkey: &str,
+        our_signing_key: &str,
+        did_doc: &str,
+    ) -> MediatorResult<()> {
+        info!(
+            "Adding new account to database with auth_pubkey {:#?}",
+            &auth_pubkey
+        );
+        // sqlite can't check json the way MySQL's JSON column does
+        serde_json::from_str::<serde_json::Value>(did_doc)
+            .map_err(|err| MediatorError::MalformedMessage(err.to_string()))?;
+        let account_id = Uuid::new_v4();
+        let insert_result = sqlx::query(
+            "INSERT INTO accounts (account_id, account_name, auth_pubkey, our_signing_key, \
+             did_doc) VALUES (?, ?, ?, ?, ?);",
+        )
+        .bind(account_id.as_bytes().as_slice())
+        .bind(account_id.to_string())
+        .bind(auth_pubkey)
+        .bind(our_signing_key)
+        .bind(did_doc)
+        .execute(self)
+        .await;
+        if let Err(err) = insert_result {
+            info!("Error during creating new account, {:#?}", err);
+            return Err(err.into());
+        };
+        info!(
+            "Created account {:x?} for auth_pubkey {:#?}",
+            &account_id, &auth_pubkey
+        );
+        Ok(())
+    }
+    /// Get account id associated with auth_pubkey
+    async fn get_account_id(&self, auth_pubkey: &str) -> MediatorResult<Vec<u8>> {
+        let account_id: Vec<u8> =
+            match sqlx::query("SELECT account_id FROM accounts WHERE auth_pubkey = ?;")
+                .bind(auth_pubkey)
+                .fetch_one(self)
+                .await
+            {
+                Ok(account_row) => account_row.get("account_id"),
+                Err(err) => {
+                    info!("Error while finding account, {:#?}", err);
+                    return Err(not_found_or_storage(
+                        err,
+                        MediatorError::UnknownAccount(auth_pubkey.to_owned()),
+                    ));
+                }
+            };
+        Ok(account_id)
+    }
+    async fn delete_account(&self, auth_pubkey: &str) -> MediatorResult<()> {
+        info!("Deleting account with auth_pubkey {:#?}", auth_pubkey);
+        // recipients and messages of the account are removed by ON DELETE CASCADE
+        let result = sqlx::query("DELETE FROM accounts WHERE auth_pubkey = ?;")
+            .bind(auth_pubkey)
+            .execute(self)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(MediatorError::UnknownAccount(auth_pubkey.to_owned()));
+        }
+        Ok(())
+    }
+    async fn set_push_token(
+        &self,
+        auth_pubkey: &str,
+        push_token: Option<&str>,
+    ) -> MediatorResult<()> {
+        info!(
+            "Setting push token of account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let result = sqlx::query("UPDATE accounts SET push_token = ? WHERE auth_pubkey = ?;")
+            .bind(push_token)
+            .bind(auth_pubkey)
+            .execute(self)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(MediatorError::UnknownAccount(auth_pubkey.to_owned()));
+        }
+        Ok(())
+    }
+    async fn get_push_token(&self, auth_pubkey: &str) -> MediatorResult<Option<String>> {
+        let row = sqlx::query("SELECT push_token FROM accounts WHERE auth_pubkey = ?;")
+            .bind(auth_pubkey)
+            .fetch_one(self)
+            .await
+            .map_err(|err| {
+                not_found_or_storage(err, MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+            })?;
+        Ok(row.get("push_token"))
+    }
+    async fn list_accounts(&self) -> MediatorResult<Vec<(String, String)>> {
+        let list: Vec<(String, String)> =
+            sqlx::query("SELECT account_name, auth_pubkey FROM accounts;")
+                .fetch_all(self)
+                .await?
+                .iter()
+                .map(|row| (row.get("account_name"), row.get("auth_pubkey")))
+                .collect();
+        Ok(list)
+    }
+    async fn count_accounts(&self) -> MediatorResult<u64> {
+        let account_count: i64 = sqlx::query("SELECT COUNT(*) AS account_count FROM accounts;")
+            .fetch_one(self)
+            .await?
+            .get("account_count");
+        Ok(account_count as u64)
+    }
+    async fn get_account_details(
+        &self,
+        auth_pubkey: &str,
+    ) -> MediatorResult<(u64, String, String, serde_json::Value)> {
+        let row = sqlx::query("SELECT * FROM accounts WHERE auth_pubkey = ?;")
+            .bind(auth_pubkey)
+            .fetch_one(self)
+            .await
+            .map_err(|err| {
+                not_found_or_storage(err, MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+            })?;
+        let seq_num: i64 = row.get("seq_num");
+        let did_doc: String = row.get("did_doc");
+        Ok((
+            seq_num
+                .try_into()
+                .map_err(|_| MediatorError::Storage(format!("Invalid seq_num {seq_num}")))?,
+            row.get("account_name"),
+            row.get("our_signing_key"),
+            serde_json::from_str(&did_doc)
+                .map_err(|err| MediatorError::Storage(err.to_string()))?,
+        ))
+    }
+    async fn persist_forward_message(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+    ) -> MediatorResult<()> {
+        // Fetch recipient with given recipient_key
+        info!("Fetching recipient with recipient_key {:#?}", recipient_key);
+        let recipient_row = sqlx::query("SELECT * FROM recipients WHERE recipient_key = ?")
+            .bind(recipient_key)
+            .fetch_one(self)
+            .await;
+        let recipient_row = match recipient_row {
+            Ok(recipient_row) => recipient_row,
+            Err(err) => {
+                info!("Error while finding target recipient, {:#}", err);
+                return Err(not_found_or_storage(
+                    err,
+                    MediatorError::UnknownRecipient(recipient_key.to_owned()),
+                ));
+            }
+        };
+        let account_id: Vec<u8> = recipient_row.get("account_id");
+        // Save message for recipient
+        info!("Persisting message for account {:x?}", account_id);
+        let insert_result = sqlx::query(
+            "INSERT INTO messages (account_id, recipient_key, message_id, message_data, \
+             received_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&account_id)
+        .bind(recipient_key)
+        .bind(Uuid::new_v4().to_string())
+        .bind(message_data.as_bytes())
+        .bind(unix_seconds(SystemTime::now()))
+        .execute(self)
+        .await;
+        if let Err(err) = insert_result {
+            info!(
+                "Error while saving message for recipient {:x?}, {:#}",
+                recipient_key, err
+            );
+            return Err(err.into());
+        }
+        Ok(())
+    }
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        quota: MessageQuota,
+    ) -> MediatorResult<()> {
+        // Single statement, so that quota check and insert are atomic
+        info!("Persisting message for recipient_key {:#?}", recipient_key);
+        let insert_result = sqlx::query(
+            "INSERT INTO messages (account_id, recipient_key, message_id, message_data, \
+             received_at)
+            SELECT recipients.account_id, recipients.recipient_key, ?, ?, ? FROM recipients
+            WHERE recipients.recipient_key = ?
+            AND (? IS NULL OR (SELECT COUNT(*) FROM messages
+                WHERE messages.account_id = recipients.account_id) < ?)
+            AND (? IS NULL OR (SELECT COALESCE(SUM(LENGTH(messages.message_data)), 0)
+                FROM messages WHERE messages.account_id = recipients.account_id) + ? <= ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(message_data.as_bytes())
+        .bind(unix_seconds(SystemTime::now()))
+        .bind(recipient_key)
+        .bind(quota.max_messages.map(|max| max as i64))
+        .bind(quota.max_messages.map(|max| max as i64))
+        .bind(quota.max_bytes.map(|max| max as i64))
+        .bind(message_data.len() as i64)
+        .bind(quota.max_bytes.map(|max| max as i64))
+        .execute(self)
+        .await?;
+        if insert_result.rows_affected() == 0 {
+            // Tell apart unknown recipient from exceeded quota
+            self.get_recipient_account_auth_pubkey(recipient_key)
+                .await?;
+            return Err(MediatorError::QuotaExceeded(format!(
+                "Queuing message of {} bytes would exceed account's quota",
+                message_data.len()
+            )));
+        }
+        Ok(())
+    }
+    async fn retrieve_pending_message_count(
+        &self,
+        auth_pubkey: &str,
+        recipient_key: Option<&String>,
+    ) -> MediatorResult<u32> {
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        let message_count_result = if let Some(recipient_key) = recipient_key {
+            sqlx::query(
+                "SELECT COUNT(*) FROM messages
+                WHERE (account_id = ?) AND (recipient_key = ?)",
+            )
+            .bind(&account_id)
+            .bind(recipient_key)
+            .fetch_one(self)
+            .await
+        } else {
+            sqlx::query(
+                "SELECT COUNT(*) FROM messages
+                WHERE (account_id = ?)",
+            )
+            .bind(&account_id)
+            .fetch_one(self)
+            .await
+        };
+        let message_count: i64 = message_count_result?.get::<i64, &str>("COUNT(*)");
+        let message_count: u32 = message_count
+            .try_into()
+            .map_err(|_| MediatorError::Storage(format!("Invalid count {message_count}")))?;
+        info!(
+            "Total message count of all requested recipients: {:#?}",
+            &message_count
+        );
+        Ok(message_count)
+    }
+    async fn retrieve_pending_messages(
+        &self,
+        auth_pubkey: &str,
+        limit: u32,
+        recipient_key: Option<&String>,
+    ) -> MediatorResult<Vec<(String, Vec<u8>)>> {
+        info!(
+            "Processing retrieve for messages to recipient_key {:#?} of auth_pubkey {:#?}",
+            recipient_key, auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        let message_rows = if let Some(recipient_key) = recipient_key {
+            sqlx::query(
+                "SELECT message_id, message_data FROM messages
+                WHERE (account_id = ?) AND (recipient_key = ?) ORDER BY seq_num LIMIT ?",
+            )
+            .bind(&account_id)
+            .bind(recipient_key)
+            .bind(limit)
+            .fetch_all(self)
+            .await
+        } else {
+            sqlx::query(
+                "SELECT message_id, message_data FROM messages
+                WHERE (account_id = ?) ORDER BY seq_num LIMIT ?",
+            )
+            .bind(&account_id)
+            .bind(limit)
+            .fetch_all(self)
+            .await
+        };
+        let messages: Vec<(String, Vec<u8>)> = message_rows?
+            .into_iter()
+            .map(|row| (row.get("message_id"), row.get("message_data")))
+            .collect();
+        info!(
+            "Found total of {:#?} messages, returning them",
+            messages.len()
+        );
+        Ok(messages)
+    }
+    async fn delete_messages(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> MediatorResult<()> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "Deleting {:#?} received messages of auth_pubkey {:#?}",
+            message_ids.len(),
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let query_string = format!(
+            "DELETE FROM messages WHERE (account_id = ?) AND message_id IN ({placeholders});"
+        );
+        let mut query = sqlx::query(&query_string).bind(&account_id);
+        for message_id in message_ids {
+            query = query.bind(message_id);
+        }
+        match query.execute(self).await {
+            Ok(result) => {
+                info!("Deleted {:#?} messages", result.rows_affected());
+                Ok(())
+            }
+            Err(err) => {
+                info!("Error while deleting messages, {:#}", err);
+                Err(err.into())
+            }
+        }
+    }
+    async fn get_message_usage(&self, auth_pubkey: Option<&str>) -> MediatorResult<MessageUsage> {
+        let usage_row = if let Some(auth_pubkey) = auth_pubkey {
+            let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+            sqlx::query(
+                "SELECT COUNT(*) AS message_count, COALESCE(SUM(LENGTH(message_data)), 0) AS \
+                 message_bytes FROM messages WHERE (account_id = ?)",
+            )
+            .bind(account_id)
+            .fetch_one(self)
+            .await?
+        } else {
+            sqlx::query(
+                "SELECT COUNT(*) AS message_count, COALESCE(SUM(LENGTH(message_data)), 0) AS \
+                 message_bytes FROM messages",
+            )
+            .fetch_one(self)
+            .await?
+        };
+        let message_count: i64 = usage_row.get("message_count");
+        let message_bytes: i64 = usage_row.get("message_bytes");
+        Ok(MessageUsage {
+            message_count: message_count as u64,
+            message_bytes: message_bytes as u64,
+        })
+    }
+    async fn purge_messages_received_before(
+        &self,
+        received_before: SystemTime,
+    ) -> MediatorResult<u64> {
+        let result = sqlx::query("DELETE FROM messages WHERE received_at < ?")
+            .bind(unix_seconds(received_before))
+            .execute(self)
+            .await?;
+        info!("Purged {:#?} expired messages", result.rows_affected());
+        Ok(result.rows_affected())
+    }
+    async fn get_recipient_account_auth_pubkey(
+        &self,
+        recipient_key: &str,
+    ) -> MediatorResult<String> {
+        let auth_pubkey: String = match sqlx::query(
+            "SELECT accounts.auth_pubkey FROM recipients
+            JOIN accounts ON recipients.account_id = accounts.account_id
+            WHERE recipients.recipient_key = ?;",
+        )
+        .bind(recipient_key)
+        .fetch_one(self)
+        .await
+        {
+            Ok(row) => row.get("auth_pubkey"),
+            Err(err) => {
+                info!("Error while finding account of recipient, {:#}", err);
+                return Err(not_found_or_storage(
+                    err,
+                    MediatorError::UnknownRecipient(recipient_key.to_owned()),
+                ));
+            }
+        };
+        Ok(auth_pubkey)
+    }
+    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
+        info!(
+            "Adding recipient_key to account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        match sqlx::query("INSERT INTO recipients (account_id, recipient_key) VALUES (?, ?);")
+            .bind(&account_id)
+            .bind(recipient_key)
+            .execute(self)
+            .await
+        {
+            Ok(_result) => Ok(()),
+            Err(err) => {
+                info!("Error while adding recipient, {:#}", err);
+                Err(err.into())
+            }
+        }
+    }
+    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
+        info!(
+            "Removing recipient_key from account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        match sqlx::query("DELETE FROM recipients WHERE (account_id = ?) AND (recipient_key = ?);")
+            .bind(&account_id)
+            .bind(recipient_key)
+            .execute(self)
+            .await
+        {
+            Ok(_result) => Ok(()),
+            Err(err) => {
+                info!("Error while removing recipient, {:#}", err);
+                Err(err.into())
+            }
+        }
+    }
+    async fn list_recipient_keys(&self, auth_pubkey: &str) -> MediatorResult<Vec<String>> {
+        info!(
+            "Retrieving recipient_keys for account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self.get_account_id(auth_pubkey).await?;
+        let recipient_keys: Vec<String> =
+            match sqlx::query("SELECT recipient_key FROM recipients WHERE account_id = ?;")
+                .bind(&account_id)
+                .fetch_all(self)
+                .await
+            {
+                Ok(recipient_key_rows) => recipient_key_rows
+                    .into_iter()
+                    .map(|row| row.get("recipient_key"))
+                    .collect(),
+                Err(err) => {
+                    info!("Error while getting recipient_keys, {:#}", err);
+                    return Err(err.into());
+                }
+            };
+        Ok(recipient_keys)
+    }
+}
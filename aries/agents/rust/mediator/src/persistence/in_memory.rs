@@ -0,0 +1,341 @@
+use std::{sync::Mutex, time::SystemTime};
+
+use async_trait::async_trait;
+use log::info;
+use uuid::Uuid;
+
+use super::{MediatorPersistence, MessageQuota, MessageUsage};
+use crate::error::{MediatorError, MediatorResult};
+
+struct Account {
+    seq_num: u64,
+    account_id: Vec<u8>,
+    account_name: String,
+    auth_pubkey: String,
+    our_signing_key: String,
+    did_doc: serde_json::Value,
+    push_token: Option<String>,
+}
+
+struct Recipient {
+    account_id: Vec<u8>,
+    recipient_key: String,
+}
+
+struct Message {
+    account_id: Vec<u8>,
+    recipient_key: Option<String>,
+    message_id: String,
+    message_data: Vec<u8>,
+    received_at: SystemTime,
+}
+
+#[derive(Default)]
+struct InMemoryState {
+    last_seq_num: u64,
+    accounts: Vec<Account>,
+    recipients: Vec<Recipient>,
+    messages: Vec<Message>,
+}
+
+impl InMemoryState {
+    fn account(&self, auth_pubkey: &str) -> MediatorResult<&Account> {
+        self.accounts
+            .iter()
+            .find(|account| account.auth_pubkey == auth_pubkey)
+            .ok_or_else(|| MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+    }
+    fn account_mut(&mut self, auth_pubkey: &str) -> MediatorResult<&mut Account> {
+        self.accounts
+            .iter_mut()
+            .find(|account| account.auth_pubkey == auth_pubkey)
+            .ok_or_else(|| MediatorError::UnknownAccount(auth_pubkey.to_owned()))
+    }
+}
+
+/// MediatorPersistence implementation keeping everything in process memory.
+/// Data is lost on restart; meant for tests and small, ephemeral deployments.
+#[derive(Default)]
+pub struct InMemoryPersistence {
+    state: Mutex<InMemoryState>,
+}
+
+#[async_trait]
+impl MediatorPersistence for InMemoryPersistence {
+    async fn create_account(
+        &self,
+        auth_pubkey: &str,
+        our_signing_key: &str,
+        did_doc: &str,
+    ) -> MediatorResult<()> {
+        info!("Adding new account with auth_pubkey {:#?}", &auth_pubkey);
+        let did_doc = serde_json::from_str(did_doc)
+            .map_err(|err| MediatorError::MalformedMessage(err.to_string()))?;
+        let mut state = self.state.lock().unwrap();
+        if state.account(auth_pubkey).is_ok() {
+            return Err(MediatorError::Storage(format!(
+                "Account for auth_pubkey {auth_pubkey} already exists"
+            )));
+        }
+        let account_id = Uuid::new_v4();
+        state.last_seq_num += 1;
+        let seq_num = state.last_seq_num;
+        state.accounts.push(Account {
+            seq_num,
+            account_id: account_id.as_bytes().to_vec(),
+            account_name: account_id.to_string(),
+            auth_pubkey: auth_pubkey.to_owned(),
+            our_signing_key: our_signing_key.to_owned(),
+            did_doc,
+            push_token: None,
+        });
+        Ok(())
+    }
+    async fn get_account_id(&self, auth_pubkey: &str) -> MediatorResult<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        Ok(state.account(auth_pubkey)?.account_id.clone())
+    }
+    async fn delete_account(&self, auth_pubkey: &str) -> MediatorResult<()> {
+        info!("Deleting account with auth_pubkey {:#?}", auth_pubkey);
+        let mut state = self.state.lock().unwrap();
+        let account_id = state.account(auth_pubkey)?.account_id.clone();
+        state
+            .accounts
+            .retain(|account| account.account_id != account_id);
+        state
+            .recipients
+            .retain(|recipient| recipient.account_id != account_id);
+        state
+            .messages
+            .retain(|message| message.account_id != account_id);
+        Ok(())
+    }
+    async fn set_push_token(
+        &self,
+        auth_pubkey: &str,
+        push_token: Option<&str>,
+    ) -> MediatorResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.account_mut(auth_pubkey)?.push_token = push_token.map(str::to_owned);
+        Ok(())
+    }
+    async fn get_push_token(&self, auth_pubkey: &str) -> MediatorResult<Option<String>> {
+        let state = self.state.lock().unwrap();
+        Ok(state.account(auth_pubkey)?.push_token.clone())
+    }
+    async fn list_accounts(&self) -> MediatorResult<Vec<(String, String)>> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .accounts
+            .iter()
+            .map(|account| (account.account_name.clone(), account.auth_pubkey.clone()))
+            .collect())
+    }
+    async fn count_accounts(&self) -> MediatorResult<u64> {
+        Ok(self.state.lock().unwrap().accounts.len() as u64)
+    }
+    async fn get_account_details(
+        &self,
+        auth_pubkey: &str,
+    ) -> MediatorResult<(u64, String, String, serde_json::Value)> {
+        let state = self.state.lock().unwrap();
+        let account = state.account(auth_pubkey)?;
+        Ok((
+            account.seq_num,
+            account.account_name.clone(),
+            account.our_signing_key.clone(),
+            account.did_doc.clone(),
+        ))
+    }
+    async fn persist_forward_message(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+    ) -> MediatorResult<()> {
+        info!("Fetching recipient with recipient_key {:#?}", recipient_key);
+        let mut state = self.state.lock().unwrap();
+        let account_id = state
+            .recipients
+            .iter()
+            .find(|recipient| recipient.recipient_key == recipient_key)
+            .map(|recipient| recipient.account_id.clone())
+            .ok_or_else(|| MediatorError::UnknownRecipient(recipient_key.to_owned()))?;
+        state.messages.push(Message {
+            account_id,
+            recipient_key: Some(recipient_key.to_owned()),
+            message_id: Uuid::new_v4().to_string(),
+            message_data: message_data.as_bytes().to_vec(),
+            received_at: SystemTime::now(),
+        });
+        Ok(())
+    }
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        quota: MessageQuota,
+    ) -> MediatorResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let account_id = state
+            .recipients
+            .iter()
+            .find(|recipient| recipient.recipient_key == recipient_key)
+            .map(|recipient| recipient.account_id.clone())
+            .ok_or_else(|| MediatorError::UnknownRecipient(recipient_key.to_owned()))?;
+        let usage = state
+            .messages
+            .iter()
+            .filter(|message| message.account_id == account_id)
+            .fold(MessageUsage::default(), |usage, message| MessageUsage {
+                message_count: usage.message_count + 1,
+                message_bytes: usage.message_bytes + message.message_data.len() as u64,
+            });
+        quota.check(usage, message_data.len() as u64)?;
+        state.messages.push(Message {
+            account_id,
+            recipient_key: Some(recipient_key.to_owned()),
+            message_id: Uuid::new_v4().to_string(),
+            message_data: message_data.as_bytes().to_vec(),
+            received_at: SystemTime::now(),
+        });
+        Ok(())
+    }
+    async fn retrieve_pending_message_count(
+        &self,
+        auth_pubkey: &str,
+        recipient_key: Option<&String>,
+    ) -> MediatorResult<u32> {
+        let state = self.state.lock().unwrap();
+        let account_id = &state.account(auth_pubkey)?.account_id;
+        let message_count = state
+            .messages
+            .iter()
+            .filter(|message| &message.account_id == account_id)
+            .filter(|message| {
+                recipient_key.is_none() || message.recipient_key.as_ref() == recipient_key
+            })
+            .count();
+        message_count
+            .try_into()
+            .map_err(|_| MediatorError::Storage(format!("Invalid count {message_count}")))
+    }
+    async fn retrieve_pending_messages(
+        &self,
+        auth_pubkey: &str,
+        limit: u32,
+        recipient_key: Option<&String>,
+    ) -> MediatorResult<Vec<(String, Vec<u8>)>> {
+        let state = self.state.lock().unwrap();
+        let account_id = &state.account(auth_pubkey)?.account_id;
+        Ok(state
+            .messages
+            .iter()
+            .filter(|message| &message.account_id == account_id)
+            .filter(|message| {
+                recipient_key.is_none() || message.recipient_key.as_ref() == recipient_key
+            })
+            .take(limit as usize)
+            .map(|message| (message.message_id.clone(), message.message_data.clone()))
+            .collect())
+    }
+    async fn delete_messages(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> MediatorResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let account_id = state.account(auth_pubkey)?.account_id.clone();
+        state.messages.retain(|message| {
+            message.account_id != account_id || !message_ids.contains(&message.message_id)
+        });
+        Ok(())
+    }
+    async fn get_message_usage(&self, auth_pubkey: Option<&str>) -> MediatorResult<MessageUsage> {
+        let state = self.state.lock().unwrap();
+        let account_id = match auth_pubkey {
+            Some(auth_pubkey) => Some(&state.account(auth_pubkey)?.account_id),
+            None => None,
+        };
+        Ok(state
+            .messages
+            .iter()
+            .filter(|message| account_id.is_none() || account_id == Some(&message.account_id))
+            .fold(MessageUsage::default(), |usage, message| MessageUsage {
+                message_count: usage.message_count + 1,
+                message_bytes: usage.message_bytes + message.message_data.len() as u64,
+            }))
+    }
+    async fn purge_messages_received_before(
+        &self,
+        received_before: SystemTime,
+    ) -> MediatorResult<u64> {
+        let mut state = self.state.lock().unwrap();
+        let message_count = state.messages.len();
+        state
+            .messages
+            .retain(|message| message.received_at >= received_before);
+        Ok((message_count - state.messages.len()) as u64)
+    }
+    async fn get_recipient_account_auth_pubkey(
+        &self,
+        recipient_key: &str,
+    ) -> MediatorResult<String> {
+        let state = self.state.lock().unwrap();
+        let account_id = &state
+            .recipients
+            .iter()
+            .find(|recipient| recipient.recipient_key == recipient_key)
+            .ok_or_else(|| MediatorError::UnknownRecipient(recipient_key.to_owned()))?
+            .account_id;
+        state
+            .accounts
+            .iter()
+            .find(|account| &account.account_id == account_id)
+            .map(|account| account.auth_pubkey.clone())
+            .ok_or_else(|| MediatorError::UnknownRecipient(recipient_key.to_owned()))
+    }
+    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let account_id = state.account(auth_pubkey)?.account_id.clone();
+        if state
+            .recipients
+            .iter()
+            .any(|recipient| recipient.recipient_key == recipient_key)
+        {
+            return Err(MediatorError::Storage(format!(
+                "Recipient {recipient_key} already exists"
+            )));
+        }
+        state.recipients.push(Recipient {
+            account_id,
+            recipient_key: recipient_key.to_owned(),
+        });
+        Ok(())
+    }
+    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let account_id = state.account(auth_pubkey)?.account_id.clone();
+        state.recipients.retain(|recipient| {
+            recipient.account_id != account_id || recipient.recipient_key != recipient_key
+        });
+        // Same as ON DELETE SET NULL in sql backends: messages are kept
+        for message in state.messages.iter_mut() {
+            if message.account_id == account_id
+                && message.recipient_key.as_deref() == Some(recipient_key)
+            {
+                message.recipient_key = None;
+            }
+        }
+        Ok(())
+    }
+    async fn list_recipient_keys(&self, auth_pubkey: &str) -> MediatorResult<Vec<String>> {
+        let state = self.state.lock().unwrap();
+        let account_id = &state.account(auth_pubkey)?.account_id;
+        Ok(state
+            .recipients
+            .iter()
+            .filter(|recipient| &recipient.account_id == account_id)
+            .map(|recipient| recipient.recipient_key.clone())
+            .collect())
+    }
+}
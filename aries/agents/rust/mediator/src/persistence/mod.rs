@@ -2,9 +2,54 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod database;
+pub mod in_memory;
+use std::time::SystemTime;
+
 use async_trait::async_trait;
 /// Database backend is used for default implementation of MediatorPersistence trait
 pub use database::get_db_pool as get_persistence;
+pub use database::{get_sqlite_db_pool, get_sqlite_db_pool_from_url};
+pub use in_memory::InMemoryPersistence;
+
+use crate::error::{MediatorError, MediatorResult};
+
+/// Number and total size of queued messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageUsage {
+    pub message_count: u64,
+    pub message_bytes: u64,
+}
+
+/// Limits on messages queued for an account, None for no limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageQuota {
+    pub max_messages: Option<u64>,
+    pub max_bytes: Option<u64>,
+}
+
+impl MessageQuota {
+    /// Fails if queuing another message of `message_len` bytes would put the account with
+    /// current `usage` over quota
+    pub fn check(&self, usage: MessageUsage, message_len: u64) -> MediatorResult<()> {
+        if let Some(max_messages) = self.max_messages {
+            if usage.message_count >= max_messages {
+                return Err(MediatorError::QuotaExceeded(format!(
+                    "Account already has {} of {} allowed messages queued",
+                    usage.message_count, max_messages
+                )));
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            if usage.message_bytes + message_len > max_bytes {
+                return Err(MediatorError::QuotaExceeded(format!(
+                    "Message of {} bytes would exceed account's {} of {} allowed bytes",
+                    message_len, usage.message_bytes, max_bytes
+                )));
+            }
+        }
+        Ok(())
+    }
+}
 
 #[async_trait]
 pub trait MediatorPersistence: Send + Sync + 'static {
@@ -13,34 +58,265 @@ pub trait MediatorPersistence: Send + Sync + 'static {
         auth_pubkey: &str,
         our_signing_key: &str,
         did_doc: &str,
-    ) -> Result<(), String>;
-    async fn get_account_id(&self, auth_pubkey: &str) -> Result<Vec<u8>, String>;
-    // async fn vaporize_account(&self, auth_pubkey: String);
-    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> Result<(), String>;
-    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> Result<(), String>;
-    async fn list_recipient_keys(&self, auth_pubkey: &str) -> Result<Vec<String>, String>;
+    ) -> MediatorResult<()>;
+    async fn get_account_id(&self, auth_pubkey: &str) -> MediatorResult<Vec<u8>>;
+    /// Deletes account along with its recipient keys and queued messages
+    async fn delete_account(&self, auth_pubkey: &str) -> MediatorResult<()>;
+    async fn add_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()>;
+    async fn remove_recipient(&self, auth_pubkey: &str, recipient_key: &str) -> MediatorResult<()>;
+    async fn list_recipient_keys(&self, auth_pubkey: &str) -> MediatorResult<Vec<String>>;
     async fn persist_forward_message(
         &self,
         recipient_key: &str,
         message_data: &str,
-    ) -> Result<(), String>;
+    ) -> MediatorResult<()>;
+    /// Like persist_forward_message, but fails with QuotaExceeded if the message would put the
+    /// account of recipient_key over quota. Usage is checked and message queued atomically.
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        quota: MessageQuota,
+    ) -> MediatorResult<()>;
     async fn retrieve_pending_message_count(
         &self,
         auth_pubkey: &str,
         recipient_key: Option<&String>,
-    ) -> Result<u32, String>;
+    ) -> MediatorResult<u32>;
     async fn retrieve_pending_messages(
         &self,
         auth_pubkey: &str,
         limit: u32,
         recipient_key: Option<&String>,
-    ) -> Result<Vec<(String, Vec<u8>)>, String>;
-    // async fn mark_messages_received(&self, message_id: Vec<u32>);
+    ) -> MediatorResult<Vec<(String, Vec<u8>)>>;
+    /// Deletes messages with given message ids, if they belong to account of auth_pubkey
+    async fn delete_messages(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> MediatorResult<()>;
+    /// Returns auth_pubkey of the account which registered the recipient_key
+    async fn get_recipient_account_auth_pubkey(
+        &self,
+        recipient_key: &str,
+    ) -> MediatorResult<String>;
+    /// Returns usage of messages queued for the account of auth_pubkey, or of all accounts if None
+    async fn get_message_usage(&self, auth_pubkey: Option<&str>) -> MediatorResult<MessageUsage>;
+    /// Deletes messages received before given time, returns number of deleted messages
+    async fn purge_messages_received_before(
+        &self,
+        received_before: SystemTime,
+    ) -> MediatorResult<u64>;
+    /// Sets (or clears, with None) the push notification token of the account
+    async fn set_push_token(
+        &self,
+        auth_pubkey: &str,
+        push_token: Option<&str>,
+    ) -> MediatorResult<()>;
+    async fn get_push_token(&self, auth_pubkey: &str) -> MediatorResult<Option<String>>;
     /// Returns vector of (account_name, auth_pubkey)
-    async fn list_accounts(&self) -> Result<Vec<(String, String)>, String>;
+    async fn list_accounts(&self) -> MediatorResult<Vec<(String, String)>>;
+    /// Returns number of accounts
+    async fn count_accounts(&self) -> MediatorResult<u64>;
     /// Returns account details (sr.no, account_name, our_signing_key, did_doc)
     async fn get_account_details(
         &self,
         auth_pubkey: &str,
-    ) -> Result<(u64, String, String, serde_json::Value), String>;
+    ) -> MediatorResult<(u64, String, String, serde_json::Value)>;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    async fn test_persistence_behavior(storage: impl MediatorPersistence) {
+        let did_doc = json!({ "id": "did:sov:123" }).to_string();
+        storage
+            .create_account("auth_key", "our_key", &did_doc)
+            .await
+            .unwrap();
+        assert!(storage
+            .create_account("auth_key", "our_key", &did_doc)
+            .await
+            .is_err());
+        let (_, account_name, our_signing_key, stored_did_doc) =
+            storage.get_account_details("auth_key").await.unwrap();
+        assert_eq!(our_signing_key, "our_key");
+        assert_eq!(stored_did_doc, json!({ "id": "did:sov:123" }));
+        assert_eq!(
+            storage.list_accounts().await.unwrap(),
+            vec![(account_name, "auth_key".to_owned())]
+        );
+        assert_eq!(storage.count_accounts().await.unwrap(), 1);
+
+        storage
+            .add_recipient("auth_key", "recipient_1")
+            .await
+            .unwrap();
+        storage
+            .add_recipient("auth_key", "recipient_2")
+            .await
+            .unwrap();
+        assert!(storage
+            .add_recipient("auth_key", "recipient_1")
+            .await
+            .is_err());
+        assert_eq!(
+            storage.list_recipient_keys("auth_key").await.unwrap().len(),
+            2
+        );
+        assert_eq!(
+            storage
+                .get_recipient_account_auth_pubkey("recipient_2")
+                .await
+                .unwrap(),
+            "auth_key"
+        );
+
+        storage
+            .persist_forward_message("recipient_1", "message_1")
+            .await
+            .unwrap();
+        storage
+            .persist_forward_message("recipient_2", "message_2")
+            .await
+            .unwrap();
+        assert!(storage
+            .persist_forward_message("unknown_recipient", "message_3")
+            .await
+            .is_err());
+        let quota = MessageQuota {
+            max_messages: Some(2),
+            max_bytes: None,
+        };
+        assert!(matches!(
+            storage
+                .persist_forward_message_within_quota("recipient_1", "message_3", quota)
+                .await,
+            Err(MediatorError::QuotaExceeded(_))
+        ));
+        assert!(matches!(
+            storage
+                .persist_forward_message_within_quota("unknown_recipient", "message_3", quota)
+                .await,
+            Err(MediatorError::UnknownRecipient(_))
+        ));
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth_key", None)
+                .await
+                .unwrap(),
+            2
+        );
+        let recipient_1 = "recipient_1".to_owned();
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth_key", Some(&recipient_1))
+                .await
+                .unwrap(),
+            1
+        );
+        let messages = storage
+            .retrieve_pending_messages("auth_key", 1, None)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].1, b"message_1");
+
+        storage
+            .delete_messages("auth_key", &[messages[0].0.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth_key", None)
+                .await
+                .unwrap(),
+            1
+        );
+
+        // removing recipient keeps its messages
+        storage
+            .remove_recipient("auth_key", "recipient_2")
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.list_recipient_keys("auth_key").await.unwrap(),
+            vec!["recipient_1".to_owned()]
+        );
+        assert_eq!(
+            storage
+                .retrieve_pending_message_count("auth_key", None)
+                .await
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(
+            storage.get_message_usage(Some("auth_key")).await.unwrap(),
+            MessageUsage {
+                message_count: 1,
+                message_bytes: 9
+            }
+        );
+        assert_eq!(
+            storage.get_message_usage(None).await.unwrap().message_count,
+            1
+        );
+        let past = SystemTime::now() - std::time::Duration::from_secs(60);
+        assert_eq!(
+            storage.purge_messages_received_before(past).await.unwrap(),
+            0
+        );
+        let future = SystemTime::now() + std::time::Duration::from_secs(60);
+        assert_eq!(
+            storage
+                .purge_messages_received_before(future)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            storage.get_message_usage(None).await.unwrap(),
+            MessageUsage::default()
+        );
+
+        assert_eq!(storage.get_push_token("auth_key").await.unwrap(), None);
+        storage
+            .set_push_token("auth_key", Some("device_token"))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_push_token("auth_key").await.unwrap(),
+            Some("device_token".to_owned())
+        );
+        assert!(storage
+            .set_push_token("unknown_key", Some("device_token"))
+            .await
+            .is_err());
+
+        storage.delete_account("auth_key").await.unwrap();
+        assert!(storage.list_accounts().await.unwrap().is_empty());
+        assert_eq!(storage.count_accounts().await.unwrap(), 0);
+        assert!(storage
+            .get_recipient_account_auth_pubkey("recipient_1")
+            .await
+            .is_err());
+        assert!(storage.delete_account("auth_key").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_persistence() {
+        test_persistence_behavior(InMemoryPersistence::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_persistence() {
+        let db_path = std::env::temp_dir().join(format!("{}.db", uuid::Uuid::new_v4()));
+        let pool = get_sqlite_db_pool_from_url(&format!("sqlite:{}", db_path.display())).await;
+        test_persistence_behavior(pool).await;
+        let _ = std::fs::remove_file(db_path);
+    }
 }
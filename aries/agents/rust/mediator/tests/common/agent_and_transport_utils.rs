@@ -66,7 +66,7 @@ pub async fn gen_mediator_connected_agent() -> Result<(
     VerKey,
     AriesDidDoc,
 )> {
-    let agent = mediator::aries_agent::AgentBuilder::new_demo_agent().await?;
+    let agent = mediator::aries_agent::AgentBuilder::new_in_memory_demo_agent().await?;
     let mut aries_transport = AriesReqwest {
         response_queue: VecDeque::new(),
         client: reqwest::Client::new(),
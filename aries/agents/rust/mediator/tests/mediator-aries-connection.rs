@@ -30,7 +30,7 @@ async fn didcomm_connection_succeeds() -> Result<()> {
         "Got invitation from register endpoint {}",
         serde_json::to_string_pretty(&oobi.clone()).unwrap()
     );
-    let agent = mediator::aries_agent::AgentBuilder::new_demo_agent().await?;
+    let agent = mediator::aries_agent::AgentBuilder::new_in_memory_demo_agent().await?;
     let mut aries_transport = AriesReqwest {
         response_queue: VecDeque::new(),
         client: reqwest::Client::new(),
@@ -9,8 +9,9 @@ use messages::{
     msg_fields::protocols::{
         basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
         pickup::{
-            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, Pickup,
-            StatusRequest, StatusRequestContent, StatusRequestDecorators,
+            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, MessagesReceived,
+            MessagesReceivedContent, MessagesReceivedDecorators, Pickup, StatusRequest,
+            StatusRequestContent, StatusRequestDecorators,
         },
     },
     AriesMessage,
@@ -32,7 +33,7 @@ async fn forward_basic_anoncrypt_message(
     message_text: &str,
 ) -> Result<()> {
     // Prepare forwarding agent
-    let agent_f = mediator::aries_agent::AgentBuilder::new_demo_agent().await?;
+    let agent_f = mediator::aries_agent::AgentBuilder::new_in_memory_demo_agent().await?;
     // Prepare forwarding agent transport
     let mut agent_f_aries_transport = AriesReqwest {
         response_queue: VecDeque::new(),
@@ -169,6 +170,44 @@ async fn test_pickup_flow() -> Result<()> {
         let unpack = agent.unpack_didcomm(&encrypted_message_bytes).await;
         info!("Decoded attachment 1 {:?}", unpack);
     }
+    let message_id_list = delivery
+        .content
+        .attach
+        .iter()
+        .map(|attachment| attachment.id.clone().unwrap())
+        .collect();
+    let pickup_messages_received = Pickup::MessagesReceived(
+        MessagesReceived::builder()
+            .content(
+                MessagesReceivedContent::builder()
+                    .message_id_list(message_id_list)
+                    .build(),
+            )
+            .decorators(MessagesReceivedDecorators::default())
+            .id("messages-received".to_owned())
+            .build(),
+    );
+    let aries_message = AriesMessage::Pickup(pickup_messages_received);
+    let message_bytes = serde_json::to_vec(&aries_message)?;
+    // send message and get response
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    // Verify acknowledged messages are removed from mailbox
+    if let AriesMessage::Pickup(Pickup::Status(status)) = serde_json::from_str(&response_message)? {
+        info!("Received status as expected {:?}", status);
+        assert_eq!(status.content.message_count, 0)
+    } else {
+        panic!(
+            "Expected status with message count = 0, received {:?}",
+            response_message
+        )
+    }
 
     Ok(())
 }
@@ -0,0 +1,137 @@
+mod common;
+use std::collections::VecDeque;
+
+use aries_vcx::utils::encryption_envelope::EncryptionEnvelope;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use mediator::aries_agent::client::transports::{AriesReqwest, AriesTransport, AriesWebSocket};
+use messages::{
+    decorators::{
+        attachment::AttachmentType,
+        transport::{ReturnRoute, Transport},
+    },
+    msg_fields::protocols::{
+        basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
+        pickup::{Pickup, StatusRequest, StatusRequestContent, StatusRequestDecorators},
+    },
+    AriesMessage,
+};
+
+use crate::common::{
+    agent_and_transport_utils::{
+        gen_and_register_recipient_key, gen_mediator_connected_agent, get_mediator_grant_data,
+        send_message_and_pop_response_message,
+    },
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+const WEBSOCKET_ENDPOINT: &str = "ws://localhost:8005/ws";
+
+async fn forward_basic_anoncrypt_message(
+    agent_diddoc: &AriesDidDoc,
+    message_text: &str,
+) -> Result<()> {
+    let agent_f = mediator::aries_agent::AgentBuilder::new_in_memory_demo_agent().await?;
+    let mut agent_f_aries_transport = AriesReqwest {
+        response_queue: VecDeque::new(),
+        client: reqwest::Client::new(),
+    };
+    let message: BasicMessage = BasicMessage::builder()
+        .content(
+            BasicMessageContent::builder()
+                .content(message_text.to_string())
+                .sent_time(chrono::DateTime::default())
+                .build(),
+        )
+        .decorators(BasicMessageDecorators::default())
+        .id("JustHello".to_string())
+        .build();
+    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create(
+        &*agent_f.get_wallet_ref(),
+        &serde_json::to_vec(&message)?,
+        None,
+        agent_diddoc,
+    )
+    .await?;
+    let packed_json = serde_json::from_slice(&packed_message)?;
+    agent_f_aries_transport
+        .send_aries_envelope(packed_json, agent_diddoc)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_live_delivery() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // prepare receiver connection parameters
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        gen_mediator_connected_agent().await?;
+    // setup receiver routing
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    // Hold socket open, asking for messages to be returned over it
+    let mut agent_ws_transport = AriesWebSocket::connect(WEBSOCKET_ENDPOINT).await?;
+    let pickup_status_req = Pickup::StatusRequest(
+        StatusRequest::builder()
+            .content(StatusRequestContent::builder().build())
+            .decorators(
+                StatusRequestDecorators::builder()
+                    .transport(Transport::builder().return_route(ReturnRoute::All).build())
+                    .build(),
+            )
+            .id("request-status".to_owned())
+            .build(),
+    );
+    let message_bytes = serde_json::to_vec(&AriesMessage::Pickup(pickup_status_req))?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut agent_ws_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::Pickup(Pickup::Status(status)) = serde_json::from_str(&response_message)? {
+        assert_eq!(status.content.message_count, 0)
+    } else {
+        panic!("Expected status, received {:?}", response_message)
+    }
+    // Forwarded message is pushed over the socket instead of being queued
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, from AgentF").await?;
+    let pushed_envelope = agent_ws_transport.receive_aries_envelope().await?;
+    let pushed_message = agent
+        .unpack_didcomm(&serde_json::to_vec(&pushed_envelope)?)
+        .await?
+        .message;
+    let AriesMessage::Pickup(Pickup::Delivery(delivery)) = serde_json::from_str(&pushed_message)?
+    else {
+        panic!("Expected live delivery, received {:?}", pushed_message)
+    };
+    assert_eq!(delivery.content.attach.len(), 1);
+    let AttachmentType::Base64(base64message) = &delivery.content.attach[0].data.content else {
+        panic!("Expected base64 attachment")
+    };
+    let forwarded = agent
+        .unpack_didcomm(&base64_url::decode(base64message)?)
+        .await?;
+    info!("Received forwarded message live {:?}", forwarded);
+    assert!(forwarded.message.contains("Hi, from AgentF"));
+    Ok(())
+}
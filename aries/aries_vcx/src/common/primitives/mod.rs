@@ -2,3 +2,5 @@ pub mod credential_definition;
 pub mod credential_schema;
 pub mod revocation_registry;
 pub mod revocation_registry_delta;
+pub mod revocation_registry_manager;
+pub mod revocation_tails;
@@ -0,0 +1,736 @@
+//! Lifecycle management of the revocation registries of a credential definition.
+//!
+//! The manager keeps a single active registry to issue credentials into and prepares (creates and
+//! publishes) the next one before the active registry fills up, so that issuance never stalls on
+//! a full registry. The next registry can be prepared while the manager is in use, see
+//! [`RevocationRegistryManager::start_standby_preparation`]. Revocations are published in
+//! batches, at most once per configured interval.
+//!
+//! The state of each managed registry is kept in a wallet record, so that the manager can be
+//! rebuilt from the wallet after a restart.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aries_vcx_core::{
+    anoncreds::base_anoncreds::BaseAnonCreds,
+    ledger::base_ledger::AnoncredsLedgerWrite,
+    wallet::base_wallet::{AsyncFnIteratorCollect, BaseWallet},
+};
+use serde_json::Value;
+
+use super::{revocation_registry::RevocationRegistry, revocation_tails::TailsServerClient};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+const REV_REG_MANAGER_RECORD_TYPE: &str = "VCX_REV_REG_MANAGER";
+const CRED_DEF_ID_TAG: &str = "cred_def_id";
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct RevocationRegistryManagerConfig {
+    issuer_did: String,
+    cred_def_id: String,
+    tails_dir: String,
+    /// Base URL of the tails server, the tails location of a registry is
+    /// `{tails_base_url}/{rev_reg_id}` with the percent-encoded registry ID
+    tails_base_url: String,
+    /// Token authorizing uploads to the tails server. Tails files are uploaded before their
+    /// registry is published if set, otherwise they must be served from the tails location by
+    /// other means.
+    #[builder(default, setter(into, strip_option))]
+    tails_upload_token: Option<String>,
+    max_creds: u32,
+    /// Number of credentials left in the active registry at which the next registry is prepared
+    #[builder(default = "1")]
+    prepare_threshold: u32,
+    /// Minimal interval between two publications of revocations of a registry
+    #[builder(default)]
+    publish_interval: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ManagedRegistryStatus {
+    /// Registry credentials are currently issued into
+    Active,
+    /// Published registry which becomes active once the active registry is full
+    Standby,
+    /// Registry with no capacity left, which may still have revocations to publish
+    Full,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct ManagedRegistry {
+    rev_reg: RevocationRegistry,
+    status: ManagedRegistryStatus,
+    issued: u32,
+    pending_revocations: Vec<String>,
+    last_published_ms: u64,
+}
+
+impl ManagedRegistry {
+    fn new(rev_reg: RevocationRegistry, status: ManagedRegistryStatus) -> Self {
+        Self {
+            rev_reg,
+            status,
+            issued: 0,
+            pending_revocations: Vec::new(),
+            last_published_ms: 0,
+        }
+    }
+
+    fn remaining(&self) -> u32 {
+        self.rev_reg.max_creds.saturating_sub(self.issued)
+    }
+
+    fn is_publish_due(&self, publish_interval: Duration, now_ms: u64) -> bool {
+        !self.pending_revocations.is_empty()
+            && now_ms.saturating_sub(self.last_published_ms) >= publish_interval.as_millis() as u64
+    }
+
+    fn is_published(&self) -> bool {
+        self.rev_reg.was_rev_reg_def_published() && self.rev_reg.was_rev_reg_delta_published()
+    }
+
+    /// Creates a registry and stores it before its publication, so that an interrupted
+    /// publication is finished by [`RevocationRegistryManager::init`]
+    async fn create(
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        config: &RevocationRegistryManagerConfig,
+        tag: u32,
+        status: ManagedRegistryStatus,
+    ) -> VcxResult<Self> {
+        let rev_reg = RevocationRegistry::create(
+            wallet,
+            anoncreds,
+            &config.issuer_did,
+            &config.cred_def_id,
+            &config.tails_dir,
+            config.max_creds,
+            tag,
+        )
+        .await?;
+        let rev_reg_id = rev_reg.get_rev_reg_id();
+        info!("ManagedRegistry::create >>> created revocation registry {rev_reg_id} as {status:?}");
+
+        let registry = Self::new(rev_reg, status);
+        wallet
+            .add_wallet_record(
+                REV_REG_MANAGER_RECORD_TYPE,
+                &rev_reg_id,
+                &serde_json::to_string(&registry)?,
+                Some(HashMap::from([(
+                    CRED_DEF_ID_TAG.to_owned(),
+                    config.cred_def_id.clone(),
+                )])),
+            )
+            .await?;
+        Ok(registry)
+    }
+
+    /// Uploads the tails file if configured and publishes the registry. Both steps are skipped
+    /// once done, so that an interrupted publication can be retried.
+    async fn publish(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        config: &RevocationRegistryManagerConfig,
+    ) -> VcxResult<()> {
+        let rev_reg_id = self.rev_reg.get_rev_reg_id();
+        let tails_server = TailsServerClient::new(
+            &config.tails_base_url,
+            config.tails_upload_token.clone().unwrap_or_default(),
+        )?;
+        if config.tails_upload_token.is_some() && !self.rev_reg.was_rev_reg_def_published() {
+            tails_server
+                .upload(
+                    &rev_reg_id,
+                    &self.rev_reg.get_tails_dir(),
+                    &self.rev_reg.get_rev_reg_def().value.tails_hash,
+                )
+                .await?;
+        }
+        self.rev_reg
+            .publish_revocation_primitives(
+                wallet,
+                ledger_write,
+                tails_server.tails_location(&rev_reg_id).as_str(),
+            )
+            .await?;
+        self.store(wallet).await
+    }
+
+    async fn store(&self, wallet: &impl BaseWallet) -> VcxResult<()> {
+        wallet
+            .update_wallet_record_value(
+                REV_REG_MANAGER_RECORD_TYPE,
+                &self.rev_reg.get_rev_reg_id(),
+                &serde_json::to_string(self)?,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Slot reserved in a revocation registry for a credential about to be issued
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceSlot {
+    pub rev_reg_id: String,
+    pub tails_dir: String,
+}
+
+/// Standby registry reserved by [`RevocationRegistryManager::start_standby_preparation`], to be
+/// created and published without holding the manager
+#[derive(Clone, Debug)]
+pub struct StandbyPreparation {
+    config: RevocationRegistryManagerConfig,
+    tag: u32,
+}
+
+impl StandbyPreparation {
+    /// Creates and publishes the standby registry, which is then handed to
+    /// [`RevocationRegistryManager::finish_standby_preparation`]
+    pub async fn prepare(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<PreparedRegistry> {
+        let mut registry = ManagedRegistry::create(
+            wallet,
+            anoncreds,
+            &self.config,
+            self.tag,
+            ManagedRegistryStatus::Standby,
+        )
+        .await?;
+        let publication = registry.publish(wallet, ledger_write, &self.config).await;
+        Ok(PreparedRegistry {
+            registry,
+            publication,
+        })
+    }
+}
+
+/// Standby registry created by [`StandbyPreparation::prepare`]. A registry whose publication
+/// failed is still kept, its publication is retried before it becomes active.
+#[derive(Debug)]
+pub struct PreparedRegistry {
+    registry: ManagedRegistry,
+    publication: VcxResult<()>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RevocationRegistryManager {
+    config: RevocationRegistryManagerConfig,
+    registries: HashMap<String, ManagedRegistry>,
+    // tag of the standby registry being prepared, so that no other registry is created with it
+    preparing_tag: Option<u32>,
+}
+
+impl RevocationRegistryManager {
+    /// Rebuilds the manager of the credential definition's registries from the wallet records,
+    /// finishing any interrupted registry publication and creating the first registry if the
+    /// credential definition has none yet.
+    pub async fn init(
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        config: RevocationRegistryManagerConfig,
+    ) -> VcxResult<Self> {
+        trace!(
+            "RevocationRegistryManager::init >>> cred_def_id: {}",
+            config.cred_def_id
+        );
+        let registries = load_registries(wallet, &config.cred_def_id).await?;
+        let mut manager = Self {
+            config,
+            registries,
+            preparing_tag: None,
+        };
+
+        let unpublished: Vec<String> = manager
+            .registries
+            .iter()
+            .filter(|(_, registry)| !registry.is_published())
+            .map(|(rev_reg_id, _)| rev_reg_id.clone())
+            .collect();
+        for rev_reg_id in unpublished {
+            info!("RevocationRegistryManager::init >>> finishing publication of {rev_reg_id}");
+            manager
+                .publish_registry(wallet, ledger_write, &rev_reg_id)
+                .await?;
+        }
+
+        if manager.active_rev_reg_id().is_none() {
+            if let Some(rev_reg_id) = manager.find_by_status(ManagedRegistryStatus::Standby) {
+                manager
+                    .set_status(wallet, &rev_reg_id, ManagedRegistryStatus::Active)
+                    .await?;
+            } else {
+                manager
+                    .prepare_registry(
+                        wallet,
+                        anoncreds,
+                        ledger_write,
+                        ManagedRegistryStatus::Active,
+                    )
+                    .await?;
+            }
+        }
+        Ok(manager)
+    }
+
+    pub fn cred_def_id(&self) -> &str {
+        &self.config.cred_def_id
+    }
+
+    pub fn active_rev_reg_id(&self) -> Option<String> {
+        self.find_by_status(ManagedRegistryStatus::Active)
+    }
+
+    pub fn get_rev_reg(&self, rev_reg_id: &str) -> VcxResult<RevocationRegistry> {
+        Ok(self.get_registry(rev_reg_id)?.rev_reg.clone())
+    }
+
+    pub fn get_status(&self, rev_reg_id: &str) -> VcxResult<ManagedRegistryStatus> {
+        Ok(self.get_registry(rev_reg_id)?.status)
+    }
+
+    /// Number of credentials which can still be issued into the active registry
+    pub fn remaining_in_active(&self) -> u32 {
+        self.active_rev_reg_id()
+            .and_then(|rev_reg_id| self.registries.get(&rev_reg_id))
+            .map_or(0, ManagedRegistry::remaining)
+    }
+
+    /// Reserves a slot for a credential in the active registry. A full active registry is
+    /// replaced by the standby registry, or by a new registry created on the spot if no standby
+    /// registry was prepared in time, see [`Self::start_standby_preparation`].
+    pub async fn reserve_issuance(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<IssuanceSlot> {
+        if self.remaining_in_active() == 0 {
+            self.rotate(wallet, anoncreds, ledger_write).await?;
+        }
+        let rev_reg_id = self.active_rev_reg_id().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "No active revocation registry to issue credentials into",
+            )
+        })?;
+
+        let registry = self.get_registry_mut(&rev_reg_id)?;
+        registry.issued += 1;
+        let slot = IssuanceSlot {
+            rev_reg_id: rev_reg_id.clone(),
+            tails_dir: registry.rev_reg.get_tails_dir(),
+        };
+        self.store_registry(wallet, &rev_reg_id).await?;
+        Ok(slot)
+    }
+
+    /// Reserves the next standby registry once the active registry has at most
+    /// `prepare_threshold` credentials left, unless a standby registry is already available or
+    /// being prepared. Creating and publishing the registry takes long, so the returned
+    /// preparation does not borrow the manager and can run in the background, its result must
+    /// be handed back to [`Self::finish_standby_preparation`].
+    pub fn start_standby_preparation(&mut self) -> Option<StandbyPreparation> {
+        if self.preparing_tag.is_some()
+            || self.remaining_in_active() > self.config.prepare_threshold
+            || self
+                .find_by_status(ManagedRegistryStatus::Standby)
+                .is_some()
+        {
+            return None;
+        }
+        let tag = self.next_tag();
+        self.preparing_tag = Some(tag);
+        Some(StandbyPreparation {
+            config: self.config.clone(),
+            tag,
+        })
+    }
+
+    /// Adds the standby registry prepared after [`Self::start_standby_preparation`], returning
+    /// its ID, or the error of its creation or publication. If the registry could not be
+    /// created, the next call to [`Self::start_standby_preparation`] starts over.
+    pub fn finish_standby_preparation(
+        &mut self,
+        prepared: VcxResult<PreparedRegistry>,
+    ) -> VcxResult<String> {
+        self.preparing_tag = None;
+        let PreparedRegistry {
+            registry,
+            publication,
+        } = prepared?;
+        let rev_reg_id = registry.rev_reg.get_rev_reg_id();
+        self.registries.insert(rev_reg_id.clone(), registry);
+        publication.map(|_| rev_reg_id)
+    }
+
+    /// Prepares the standby registry in place when [`Self::start_standby_preparation`] calls for
+    /// it, for callers which don't share the manager. Returns the ID of the prepared registry.
+    pub async fn prepare_standby(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<Option<String>> {
+        let Some(preparation) = self.start_standby_preparation() else {
+            return Ok(None);
+        };
+        let prepared = preparation.prepare(wallet, anoncreds, ledger_write).await;
+        self.finish_standby_preparation(prepared).map(Some)
+    }
+
+    /// Gives back a slot reserved by [`Self::reserve_issuance`] for a credential which was not
+    /// issued after all. A registry which was already marked full stays so, the slot is then
+    /// left unused.
+    pub async fn release_issuance(
+        &mut self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+    ) -> VcxResult<()> {
+        let registry = self.get_registry_mut(rev_reg_id)?;
+        if registry.issued == 0 {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("No issuance reserved in revocation registry {rev_reg_id} to release"),
+            ));
+        }
+        registry.issued -= 1;
+        self.store_registry(wallet, rev_reg_id).await
+    }
+
+    /// Revokes the credential locally, its revocation is published by the next
+    /// [`Self::publish_pending_revocations`]
+    pub async fn revoke_credential(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        rev_reg_id: &str,
+        cred_rev_id: &str,
+    ) -> VcxResult<()> {
+        let registry = self.get_registry_mut(rev_reg_id)?;
+        registry
+            .rev_reg
+            .revoke_credential_local(wallet, anoncreds, cred_rev_id)
+            .await?;
+        registry.pending_revocations.push(cred_rev_id.to_string());
+        self.store_registry(wallet, rev_reg_id).await
+    }
+
+    /// Whether any registry has revocations whose publication is due
+    pub fn is_publish_due(&self) -> bool {
+        let now = now_ms();
+        self.registries
+            .values()
+            .any(|registry| registry.is_publish_due(self.config.publish_interval, now))
+    }
+
+    /// Publishes the pending revocations of the registries whose publish interval has elapsed,
+    /// with a single revocation registry delta per registry. Returns the IDs of the registries
+    /// whose revocations were published.
+    pub async fn publish_pending_revocations(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<Vec<String>> {
+        let now = now_ms();
+        let due: Vec<String> = self
+            .registries
+            .iter()
+            .filter(|(_, registry)| registry.is_publish_due(self.config.publish_interval, now))
+            .map(|(rev_reg_id, _)| rev_reg_id.clone())
+            .collect();
+
+        let issuer_did = self.config.issuer_did.clone();
+        for rev_reg_id in due.iter() {
+            let registry = self.get_registry_mut(rev_reg_id)?;
+            registry
+                .rev_reg
+                .publish_local_revocations(wallet, anoncreds, ledger_write, &issuer_did)
+                .await?;
+            registry.pending_revocations.clear();
+            registry.last_published_ms = now;
+            self.store_registry(wallet, rev_reg_id).await?;
+        }
+        Ok(due)
+    }
+
+    async fn rotate(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<()> {
+        if let Some(rev_reg_id) = self.active_rev_reg_id() {
+            info!("RevocationRegistryManager::rotate >>> revocation registry {rev_reg_id} is full");
+            self.set_status(wallet, &rev_reg_id, ManagedRegistryStatus::Full)
+                .await?;
+        }
+        match self.find_by_status(ManagedRegistryStatus::Standby) {
+            Some(rev_reg_id) => {
+                if !self.get_registry(&rev_reg_id)?.is_published() {
+                    self.publish_registry(wallet, ledger_write, &rev_reg_id)
+                        .await?;
+                }
+                self.set_status(wallet, &rev_reg_id, ManagedRegistryStatus::Active)
+                    .await
+            }
+            None => {
+                self.prepare_registry(
+                    wallet,
+                    anoncreds,
+                    ledger_write,
+                    ManagedRegistryStatus::Active,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn prepare_registry(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        status: ManagedRegistryStatus,
+    ) -> VcxResult<()> {
+        let tag = self.next_tag();
+        let registry =
+            ManagedRegistry::create(wallet, anoncreds, &self.config, tag, status).await?;
+        let rev_reg_id = registry.rev_reg.get_rev_reg_id();
+        self.registries.insert(rev_reg_id.clone(), registry);
+        self.publish_registry(wallet, ledger_write, &rev_reg_id)
+            .await
+    }
+
+    async fn publish_registry(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        rev_reg_id: &str,
+    ) -> VcxResult<()> {
+        let registry = self
+            .registries
+            .get_mut(rev_reg_id)
+            .ok_or_else(|| unknown_registry_err(rev_reg_id))?;
+        registry.publish(wallet, ledger_write, &self.config).await
+    }
+
+    async fn set_status(
+        &mut self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+        status: ManagedRegistryStatus,
+    ) -> VcxResult<()> {
+        self.get_registry_mut(rev_reg_id)?.status = status;
+        self.store_registry(wallet, rev_reg_id).await
+    }
+
+    async fn store_registry(&self, wallet: &impl BaseWallet, rev_reg_id: &str) -> VcxResult<()> {
+        self.get_registry(rev_reg_id)?.store(wallet).await
+    }
+
+    fn next_tag(&self) -> u32 {
+        self.registries
+            .values()
+            .map(|registry| registry.rev_reg.tag)
+            .chain(self.preparing_tag)
+            .max()
+            .map_or(1, |tag| tag + 1)
+    }
+
+    fn find_by_status(&self, status: ManagedRegistryStatus) -> Option<String> {
+        self.registries
+            .iter()
+            .filter(|(_, registry)| registry.status == status)
+            .max_by_key(|(_, registry)| registry.rev_reg.tag)
+            .map(|(rev_reg_id, _)| rev_reg_id.clone())
+    }
+
+    fn get_registry(&self, rev_reg_id: &str) -> VcxResult<&ManagedRegistry> {
+        self.registries
+            .get(rev_reg_id)
+            .ok_or_else(|| unknown_registry_err(rev_reg_id))
+    }
+
+    fn get_registry_mut(&mut self, rev_reg_id: &str) -> VcxResult<&mut ManagedRegistry> {
+        self.registries
+            .get_mut(rev_reg_id)
+            .ok_or_else(|| unknown_registry_err(rev_reg_id))
+    }
+}
+
+async fn load_registries(
+    wallet: &impl BaseWallet,
+    cred_def_id: &str,
+) -> VcxResult<HashMap<String, ManagedRegistry>> {
+    let query = json!({ CRED_DEF_ID_TAG: cred_def_id }).to_string();
+    let options = json!({ "retrieveValue": true, "retrieveTags": false }).to_string();
+    let records = wallet
+        .iterate_wallet_records(REV_REG_MANAGER_RECORD_TYPE, &query, &options)
+        .await?
+        .collect()
+        .await?;
+
+    records
+        .iter()
+        .map(|record| {
+            let record: Value = serde_json::from_str(record)?;
+            let value = record["value"].as_str().ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Revocation registry record has no value: {record}"),
+                )
+            })?;
+            let registry: ManagedRegistry = serde_json::from_str(value)?;
+            Ok((registry.rev_reg.get_rev_reg_id(), registry))
+        })
+        .collect()
+}
+
+fn unknown_registry_err(rev_reg_id: &str) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidRevocationDetails,
+        format!("Revocation registry {rev_reg_id} is not managed"),
+    )
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use test_utils::constants::{CRED_DEF_ID, REV_REG_ID};
+
+    use super::*;
+
+    fn _managed_registry(max_creds: u32, issued: u32) -> ManagedRegistry {
+        let rev_reg = RevocationRegistry::from_string(
+            &json!({
+                "cred_def_id": CRED_DEF_ID,
+                "issuer_did": "V4SGRU86Z58d6TV7PBUe6f",
+                "rev_reg_id": REV_REG_ID,
+                "rev_reg_def": {
+                    "id": REV_REG_ID,
+                    "revocDefType": "CL_ACCUM",
+                    "tag": "tag1",
+                    "credDefId": CRED_DEF_ID,
+                    "value": {
+                        "issuanceType": "ISSUANCE_BY_DEFAULT",
+                        "maxCredNum": max_creds,
+                        "publicKeys": {},
+                        "tailsHash": "hash",
+                        "tailsLocation": "/tmp"
+                    },
+                    "ver": "1.0"
+                },
+                "rev_reg_entry": "{}",
+                "tails_dir": "/tmp",
+                "max_creds": max_creds,
+                "tag": 1,
+                "rev_reg_def_state": 1,
+                "rev_reg_delta_state": 1
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let mut registry = ManagedRegistry::new(rev_reg, ManagedRegistryStatus::Active);
+        registry.issued = issued;
+        registry
+    }
+
+    #[test]
+    fn test_remaining() {
+        assert_eq!(_managed_registry(10, 0).remaining(), 10);
+        assert_eq!(_managed_registry(10, 9).remaining(), 1);
+        assert_eq!(_managed_registry(10, 10).remaining(), 0);
+        assert_eq!(_managed_registry(10, 11).remaining(), 0);
+    }
+
+    #[test]
+    fn test_publish_not_due_without_pending_revocations() {
+        let registry = _managed_registry(10, 5);
+        assert!(!registry.is_publish_due(Duration::ZERO, now_ms()));
+    }
+
+    #[test]
+    fn test_publish_due_after_interval() {
+        let mut registry = _managed_registry(10, 5);
+        registry.pending_revocations.push("1".to_owned());
+        registry.last_published_ms = 1_000;
+
+        let interval = Duration::from_secs(60);
+        assert!(!registry.is_publish_due(interval, 1_000 + 59_999));
+        assert!(registry.is_publish_due(interval, 1_000 + 60_000));
+    }
+
+    #[test]
+    fn test_managed_registry_serde() {
+        let mut registry = _managed_registry(10, 3);
+        registry.status = ManagedRegistryStatus::Standby;
+        registry.pending_revocations.push("2".to_owned());
+
+        let serialized = serde_json::to_string(&registry).unwrap();
+        let deserialized: ManagedRegistry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(registry, deserialized);
+    }
+
+    fn _manager(registry: ManagedRegistry) -> RevocationRegistryManager {
+        let config = RevocationRegistryManagerConfigBuilder::default()
+            .issuer_did("V4SGRU86Z58d6TV7PBUe6f")
+            .cred_def_id(CRED_DEF_ID)
+            .tails_dir("/tmp")
+            .tails_base_url("http://localhost:8430")
+            .max_creds(10u32)
+            .prepare_threshold(2u32)
+            .build()
+            .unwrap();
+        RevocationRegistryManager {
+            config,
+            registries: HashMap::from([(registry.rev_reg.get_rev_reg_id(), registry)]),
+            preparing_tag: None,
+        }
+    }
+
+    #[test]
+    fn test_standby_preparation_starts_at_threshold() {
+        let mut manager = _manager(_managed_registry(10, 7));
+        assert!(manager.start_standby_preparation().is_none());
+
+        let mut manager = _manager(_managed_registry(10, 8));
+        let preparation = manager.start_standby_preparation().unwrap();
+        assert_eq!(preparation.tag, 2);
+        // a single standby registry is prepared at a time
+        assert!(manager.start_standby_preparation().is_none());
+        // registries created meanwhile don't reuse the reserved tag
+        assert_eq!(manager.next_tag(), 3);
+    }
+
+    #[test]
+    fn test_failed_standby_preparation_starts_over() {
+        let mut manager = _manager(_managed_registry(10, 9));
+        manager.start_standby_preparation().unwrap();
+
+        let err = manager
+            .finish_standby_preparation(Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidLedgerResponse,
+                "rejected",
+            )))
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidLedgerResponse);
+        assert_eq!(manager.start_standby_preparation().unwrap().tag, 2);
+    }
+}
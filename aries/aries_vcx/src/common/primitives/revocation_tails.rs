@@ -0,0 +1,567 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use sha2::{Digest, Sha256};
+use shared::http_client::{get_bytes, put_bytes};
+use url::Url;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Computes the tails hash as published in revocation registry definitions, i.e. base58 encoded
+/// SHA-256 of the tails file content.
+pub fn tails_file_hash(tails_file: &[u8]) -> String {
+    bs58::encode(Sha256::digest(tails_file)).into_string()
+}
+
+/// Checks that `tails_hash` is a base58 encoded SHA-256 digest, which also makes it safe to use as
+/// a file name.
+pub fn validate_tails_hash(tails_hash: &str) -> VcxResult<()> {
+    match bs58::decode(tails_hash).into_vec() {
+        Ok(digest) if digest.len() == 32 => Ok(()),
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidRevocationDetails,
+            format!(
+                "Invalid tails hash {}, expected a base58 encoded SHA-256 digest",
+                tails_hash
+            ),
+        )),
+    }
+}
+
+pub fn verify_tails_file_hash(tails_file: &[u8], expected_hash: &str) -> VcxResult<()> {
+    let actual_hash = tails_file_hash(tails_file);
+    if actual_hash != expected_hash {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidRevocationDetails,
+            format!(
+                "Tails file hash mismatch, expected: {}, actual: {}",
+                expected_hash, actual_hash
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Size and modification time of cached tails files which were verified against their hash, so
+// that proofs don't re-hash the whole file every time it is used
+type FileStamp = (u64, SystemTime);
+
+lazy_static! {
+    static ref VERIFIED_TAILS_FILES: Mutex<HashMap<PathBuf, FileStamp>> =
+        Mutex::new(HashMap::new());
+}
+
+async fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Downloads tails files referenced by revocation registry definitions and keeps them in a local
+/// cache directory. Files are stored under their tails hash, which is the layout expected by
+/// `BaseAnonCreds::create_revocation_state`, so the cache directory can be passed as `tails_dir`.
+#[derive(Clone, Debug)]
+pub struct TailsFetcher {
+    cache_dir: PathBuf,
+}
+
+impl TailsFetcher {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn tails_file_path(&self, tails_hash: &str) -> VcxResult<PathBuf> {
+        validate_tails_hash(tails_hash)?;
+        Ok(self.cache_dir.join(tails_hash))
+    }
+
+    /// Makes sure the tails file with `tails_hash` is present in the cache, downloading it from
+    /// `tails_location` if needed. Returns the tails directory to use for revocation states.
+    pub async fn fetch(&self, tails_location: &str, tails_hash: &str) -> VcxResult<String> {
+        trace!(
+            "TailsFetcher::fetch >>> tails_location: {}, tails_hash: {}",
+            tails_location,
+            tails_hash
+        );
+        if self.is_cached(tails_hash).await? {
+            trace!(
+                "TailsFetcher::fetch <<< tails file {} found in cache",
+                tails_hash
+            );
+            return self.tails_dir();
+        }
+
+        let url = Url::parse(tails_location).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidUrl,
+                format!("Invalid tails location {}, err: {}", tails_location, err),
+            )
+        })?;
+        let tails_file = get_bytes(url).await.map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidHttpResponse,
+                format!("Failed to download tails file, err: {}", err),
+            )
+        })?;
+        verify_tails_file_hash(&tails_file, tails_hash)?;
+        self.store(tails_hash, tails_file).await?;
+
+        info!(
+            "TailsFetcher::fetch <<< tails file {} downloaded from {}",
+            tails_hash, tails_location
+        );
+        self.tails_dir()
+    }
+
+    /// Same as [`TailsFetcher::fetch`], reading `tailsLocation` and `tailsHash` from a revocation
+    /// registry definition.
+    pub async fn fetch_for_rev_reg_def(&self, rev_reg_def_json: &str) -> VcxResult<String> {
+        let rev_reg_def: serde_json::Value = serde_json::from_str(rev_reg_def_json)?;
+        let tails_location = rev_reg_def["value"]["tailsLocation"]
+            .as_str()
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!(
+                        "The field 'tailsLocation' not found on rev_reg_def_json: {}",
+                        rev_reg_def_json
+                    ),
+                )
+            })?;
+        let tails_hash = rev_reg_def["value"]["tailsHash"].as_str().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!(
+                    "The field 'tailsHash' not found on rev_reg_def_json: {}",
+                    rev_reg_def_json
+                ),
+            )
+        })?;
+        self.fetch(tails_location, tails_hash).await
+    }
+
+    /// Returns true if a tails file matching `tails_hash` is cached. A cached file whose content
+    /// does not match its hash is removed. The file is only hashed again if its size or
+    /// modification time changed since it was last verified.
+    pub async fn is_cached(&self, tails_hash: &str) -> VcxResult<bool> {
+        let path = self.tails_file_path(tails_hash)?;
+        let Some(stamp) = file_stamp(&path).await else {
+            return Ok(false);
+        };
+        if VERIFIED_TAILS_FILES.lock()?.get(&path) == Some(&stamp) {
+            return Ok(true);
+        }
+
+        let verify_path = path.clone();
+        let expected_hash = tails_hash.to_owned();
+        let verified = tokio::task::spawn_blocking(move || {
+            fs::read(&verify_path)
+                .map(|tails_file| verify_tails_file_hash(&tails_file, &expected_hash).is_ok())
+                .map_err(|err| io_error(&verify_path, err))
+        })
+        .await
+        .map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::IOError,
+                format!("Failed to verify tails file {:?}, err: {}", path, err),
+            )
+        })??;
+        if verified {
+            VERIFIED_TAILS_FILES.lock()?.insert(path, stamp);
+            return Ok(true);
+        }
+        warn!(
+            "TailsFetcher::is_cached >> cached tails file {:?} is corrupted, removing it",
+            path
+        );
+        VERIFIED_TAILS_FILES.lock()?.remove(&path);
+        tokio::fs::remove_file(&path)
+            .await
+            .map_err(|err| io_error(&path, err))?;
+        Ok(false)
+    }
+
+    // `tails_file` must already be verified against `tails_hash`
+    async fn store(&self, tails_hash: &str, tails_file: Vec<u8>) -> VcxResult<()> {
+        let path = self.tails_file_path(tails_hash)?;
+        tokio::fs::create_dir_all(&self.cache_dir)
+            .await
+            .map_err(|err| io_error(&self.cache_dir, err))?;
+        let tmp_path = self.cache_dir.join(format!("{}.tmp", tails_hash));
+        tokio::fs::write(&tmp_path, tails_file)
+            .await
+            .map_err(|err| io_error(&tmp_path, err))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|err| io_error(&path, err))?;
+        if let Some(stamp) = file_stamp(&path).await {
+            VERIFIED_TAILS_FILES.lock()?.insert(path, stamp);
+        }
+        Ok(())
+    }
+
+    fn tails_dir(&self) -> VcxResult<String> {
+        self.cache_dir
+            .to_str()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidOption,
+                    format!("Tails cache dir {:?} is not a valid string", self.cache_dir),
+                )
+            })
+    }
+}
+
+/// Uploads issuer tails files to a tails server (such as `misc/tails_server`), which then serves
+/// them to holders from the published `tailsLocation`.
+#[derive(Clone, Debug)]
+pub struct TailsServerClient {
+    base_url: Url,
+    upload_token: String,
+}
+
+impl TailsServerClient {
+    pub fn new(base_url: &str, upload_token: impl Into<String>) -> VcxResult<Self> {
+        let base_url = Url::parse(base_url)
+            .ok()
+            .filter(|url| !url.cannot_be_a_base())
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidUrl,
+                    format!("Invalid tails server url {}", base_url),
+                )
+            })?;
+        Ok(Self {
+            base_url,
+            upload_token: upload_token.into(),
+        })
+    }
+
+    /// Returns the URL the tails file of `rev_reg_id` is served from, to be published as
+    /// `tailsLocation`. The id is percent-encoded, as qualified ids contain `/`.
+    pub fn tails_location(&self, rev_reg_id: &str) -> Url {
+        let mut url = self.base_url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push(rev_reg_id);
+        }
+        url
+    }
+
+    /// Uploads the tails file `tails_hash` from `tails_dir` for `rev_reg_id`. Uploading the same
+    /// file again succeeds, so failed publications can be retried. Returns the tails location.
+    pub async fn upload(
+        &self,
+        rev_reg_id: &str,
+        tails_dir: &str,
+        tails_hash: &str,
+    ) -> VcxResult<String> {
+        validate_tails_hash(tails_hash)?;
+        let path = Path::new(tails_dir).join(tails_hash);
+        let tails_file = tokio::fs::read(&path)
+            .await
+            .map_err(|err| io_error(&path, err))?;
+
+        let tails_location = self.tails_location(rev_reg_id);
+        let mut upload_url = tails_location.clone();
+        upload_url
+            .query_pairs_mut()
+            .append_pair("tails_hash", tails_hash);
+        put_bytes(upload_url, tails_file, &self.upload_token)
+            .await
+            .map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidHttpResponse,
+                    format!("Failed to upload tails file {}, err: {}", tails_hash, err),
+                )
+            })?;
+
+        info!(
+            "TailsServerClient::upload <<< tails file {} uploaded to {}",
+            tails_hash, tails_location
+        );
+        Ok(tails_location.into())
+    }
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::IOError,
+        format!("Tails file IO failed for {:?}, err: {}", path, err),
+    )
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::{
+        env,
+        io::{Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+
+    const TAILS_FILE: &[u8] = b"tails file content";
+
+    fn _cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("tails_fetcher_{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Serves `body` to a single GET request, returns the tails location to download it from
+    fn _serve_once(body: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: \
+                 {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+        });
+        format!("http://{}/tails", addr)
+    }
+
+    // Accepts a single upload with `status`, returns the upload url and a receiver of the request
+    fn _accept_upload(status: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !String::from_utf8_lossy(&request).ends_with("tails file content") {
+                let read = stream.read(&mut buf).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            sender
+                .send(String::from_utf8_lossy(&request).into_owned())
+                .unwrap();
+        });
+        (format!("http://{}/tails", addr), receiver)
+    }
+
+    #[test]
+    fn test_validate_tails_hash() {
+        validate_tails_hash(&tails_file_hash(TAILS_FILE)).unwrap();
+        for tails_hash in ["", "../../etc/passwd", "4QPVbr", "not base58 0OIl"] {
+            let err = validate_tails_hash(tails_hash).unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_downloads_and_caches_tails_file() {
+        let cache_dir = _cache_dir("download");
+        let hash = tails_file_hash(TAILS_FILE);
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        let tails_dir = fetcher
+            .fetch(&_serve_once(TAILS_FILE), &hash)
+            .await
+            .unwrap();
+        assert_eq!(tails_dir, cache_dir.to_str().unwrap());
+        assert_eq!(fs::read(cache_dir.join(&hash)).unwrap(), TAILS_FILE);
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_tails_file_not_matching_hash() {
+        let cache_dir = _cache_dir("download_mismatch");
+        let hash = tails_file_hash(b"other content");
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        let err = fetcher
+            .fetch(&_serve_once(TAILS_FILE), &hash)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+        assert!(!cache_dir.join(&hash).exists());
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_malformed_tails_hash() {
+        let cache_dir = _cache_dir("malformed_hash");
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        // The location is never contacted because the hash is rejected upfront.
+        let err = fetcher
+            .fetch("http://127.0.0.1:1/unreachable", "../tails")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_tails_file_hash_is_base58_sha256() {
+        let hash = tails_file_hash(TAILS_FILE);
+        let decoded = bs58::decode(&hash).into_vec().unwrap();
+        assert_eq!(decoded, Sha256::digest(TAILS_FILE).to_vec());
+        verify_tails_file_hash(TAILS_FILE, &hash).unwrap();
+    }
+
+    #[test]
+    fn test_verify_tails_file_hash_rejects_mismatch() {
+        let hash = tails_file_hash(b"other content");
+        let err = verify_tails_file_hash(TAILS_FILE, &hash).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_uses_cached_tails_file() {
+        let cache_dir = _cache_dir("cached");
+        let hash = tails_file_hash(TAILS_FILE);
+        fs::write(cache_dir.join(&hash), TAILS_FILE).unwrap();
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        // The location is never contacted because the file is already cached.
+        let tails_dir = fetcher
+            .fetch("http://127.0.0.1:1/unreachable", &hash)
+            .await
+            .unwrap();
+        assert_eq!(tails_dir, cache_dir.to_str().unwrap());
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_is_cached_removes_corrupted_tails_file() {
+        let cache_dir = _cache_dir("corrupted");
+        let hash = tails_file_hash(TAILS_FILE);
+        fs::write(cache_dir.join(&hash), b"corrupted").unwrap();
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        assert!(!fetcher.is_cached(&hash).await.unwrap());
+        assert!(!fetcher.tails_file_path(&hash).unwrap().exists());
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_for_rev_reg_def_requires_tails_fields() {
+        let fetcher = TailsFetcher::new(_cache_dir("missing_fields"));
+        let rev_reg_def = json!({ "value": { "tailsLocation": "http://localhost/tails" } });
+        let err = fetcher
+            .fetch_for_rev_reg_def(&rev_reg_def.to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+
+        fs::remove_dir_all(fetcher.cache_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_is_cached_verifies_changed_tails_file() {
+        let cache_dir = _cache_dir("changed");
+        let hash = tails_file_hash(TAILS_FILE);
+        fs::write(cache_dir.join(&hash), TAILS_FILE).unwrap();
+
+        let fetcher = TailsFetcher::new(&cache_dir);
+        assert!(fetcher.is_cached(&hash).await.unwrap());
+        assert!(fetcher.is_cached(&hash).await.unwrap());
+
+        // a file changed after its verification is hashed again
+        fs::write(cache_dir.join(&hash), b"corrupted").unwrap();
+        assert!(!fetcher.is_cached(&hash).await.unwrap());
+        assert!(!cache_dir.join(&hash).exists());
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_tails_location_encodes_rev_reg_id() {
+        let rev_reg_id =
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/REV_REG_DEF/1281/tag1/tag1";
+        for base_url in ["http://localhost:8430", "http://localhost:8430/"] {
+            let client = TailsServerClient::new(base_url, "token").unwrap();
+            assert_eq!(
+                client.tails_location(rev_reg_id).as_str(),
+                "http://localhost:8430/did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f%2Fanoncreds%2Fv0%2FREV_REG_DEF%2F1281%2Ftag1%2Ftag1"
+            );
+        }
+        let client = TailsServerClient::new("http://localhost:8430/tails/", "token").unwrap();
+        assert_eq!(
+            client.tails_location("rev_reg_id").as_str(),
+            "http://localhost:8430/tails/rev_reg_id"
+        );
+
+        let err = TailsServerClient::new("mailto:issuer@example.com", "token").unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidUrl);
+    }
+
+    #[tokio::test]
+    async fn test_upload_puts_tails_file_with_token() {
+        let tails_dir = _cache_dir("upload");
+        let hash = tails_file_hash(TAILS_FILE);
+        fs::write(tails_dir.join(&hash), TAILS_FILE).unwrap();
+
+        let (base_url, request) = _accept_upload("201 Created");
+        let client = TailsServerClient::new(&base_url, "secret").unwrap();
+        let tails_location = client
+            .upload("rev_reg_id", tails_dir.to_str().unwrap(), &hash)
+            .await
+            .unwrap();
+        assert_eq!(tails_location, format!("{}/rev_reg_id", base_url));
+
+        let request = request.recv().unwrap();
+        assert!(request.starts_with(&format!("PUT /tails/rev_reg_id?tails_hash={} ", hash)));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer secret"));
+        assert!(request.ends_with("tails file content"));
+
+        fs::remove_dir_all(tails_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_fails_on_rejected_upload() {
+        let tails_dir = _cache_dir("upload_rejected");
+        let hash = tails_file_hash(TAILS_FILE);
+        fs::write(tails_dir.join(&hash), TAILS_FILE).unwrap();
+
+        let (base_url, _request) = _accept_upload("409 Conflict");
+        let client = TailsServerClient::new(&base_url, "secret").unwrap();
+        let err = client
+            .upload("rev_reg_id", tails_dir.to_str().unwrap(), &hash)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidHttpResponse);
+
+        fs::remove_dir_all(tails_dir).unwrap();
+    }
+}
@@ -8,7 +8,10 @@ use aries_vcx_core::{
 use serde_json::Value;
 
 use crate::{
-    common::proofs::{proof_request::ProofRequestData, proof_request_internal::NonRevokedInterval},
+    common::{
+        primitives::revocation_tails::TailsFetcher,
+        proofs::{proof_request::ProofRequestData, proof_request_internal::NonRevokedInterval},
+    },
     errors::error::prelude::*,
     handlers::proof_presentation::types::SelectedCredentials,
 };
@@ -175,6 +178,11 @@ pub async fn build_rev_states_json(
                 };
 
                 let rev_reg_def_json = ledger_read.get_rev_reg_def_json(rev_reg_id).await?;
+                // Downloads the tails file from the registry's tails location unless `tails_dir`
+                // already holds it
+                let tails_dir = TailsFetcher::new(tails_dir)
+                    .fetch_for_rev_reg_def(&rev_reg_def_json)
+                    .await?;
 
                 let (rev_reg_id, rev_reg_delta_json, timestamp) = ledger_read
                     .get_rev_reg_delta_json(rev_reg_id, from, to)
@@ -182,7 +190,7 @@ pub async fn build_rev_states_json(
 
                 let rev_state_json = anoncreds
                     .create_revocation_state(
-                        tails_dir,
+                        &tails_dir,
                         &rev_reg_def_json,
                         &rev_reg_delta_json,
                         timestamp,
@@ -325,18 +333,74 @@ pub mod pool_tests {
 
 #[cfg(test)]
 pub mod unit_tests {
-    use aries_vcx_core::ledger::indy::pool::test_utils::get_temp_dir_path;
+    use std::fs;
+
+    use aries_vcx_core::{
+        errors::error::VcxCoreResult, ledger::indy::pool::test_utils::get_temp_dir_path,
+    };
+    use async_trait::async_trait;
     use test_utils::{
         constants::{
-            ADDRESS_CRED_DEF_ID, ADDRESS_CRED_ID, ADDRESS_CRED_REV_ID, ADDRESS_REV_REG_ID,
-            ADDRESS_SCHEMA_ID, CRED_DEF_ID, CRED_REV_ID, LICENCE_CRED_ID, REV_REG_ID,
-            REV_STATE_JSON, SCHEMA_ID,
+            rev_def_json, ADDRESS_CRED_DEF_ID, ADDRESS_CRED_ID, ADDRESS_CRED_REV_ID,
+            ADDRESS_REV_REG_ID, ADDRESS_SCHEMA_ID, CRED_DEF_ID, CRED_REV_ID, LICENCE_CRED_ID,
+            REV_REG_ID, REV_STATE_JSON, SCHEMA_ID,
         },
         devsetup::*,
         mockdata::{mock_anoncreds::MockAnoncreds, mock_ledger::MockLedger},
     };
 
     use super::*;
+    use crate::common::primitives::revocation_tails::tails_file_hash;
+
+    /// Mock ledger whose revocation registry definition references the given tails hash
+    #[derive(Debug)]
+    struct TailsMockLedger {
+        tails_hash: String,
+    }
+
+    #[async_trait]
+    impl AnoncredsLedgerRead for TailsMockLedger {
+        async fn get_schema(
+            &self,
+            schema_id: &str,
+            submitter_did: Option<&str>,
+        ) -> VcxCoreResult<String> {
+            MockLedger.get_schema(schema_id, submitter_did).await
+        }
+
+        async fn get_cred_def(
+            &self,
+            cred_def_id: &str,
+            submitter_did: Option<&str>,
+        ) -> VcxCoreResult<String> {
+            MockLedger.get_cred_def(cred_def_id, submitter_did).await
+        }
+
+        async fn get_rev_reg_def_json(&self, _rev_reg_id: &str) -> VcxCoreResult<String> {
+            let mut rev_reg_def: Value = serde_json::from_str(&rev_def_json()).unwrap();
+            rev_reg_def["value"]["tailsHash"] = json!(self.tails_hash);
+            Ok(rev_reg_def.to_string())
+        }
+
+        async fn get_rev_reg_delta_json(
+            &self,
+            rev_reg_id: &str,
+            from: Option<u64>,
+            to: Option<u64>,
+        ) -> VcxCoreResult<(String, String, u64)> {
+            MockLedger
+                .get_rev_reg_delta_json(rev_reg_id, from, to)
+                .await
+        }
+
+        async fn get_rev_reg(
+            &self,
+            rev_reg_id: &str,
+            timestamp: u64,
+        ) -> VcxCoreResult<(String, String, u64)> {
+            MockLedger.get_rev_reg(rev_reg_id, timestamp).await
+        }
+    }
 
     fn proof_req_no_interval() -> ProofRequestData {
         let proof_req = json!({
@@ -694,6 +758,9 @@ pub mod unit_tests {
     #[tokio::test]
     async fn test_build_rev_states_json() {
         let _setup = SetupMocks::init();
+        let tails_file = b"tails file content";
+        let tails_hash = tails_file_hash(tails_file);
+        fs::write(get_temp_dir_path().join(&tails_hash), tails_file).unwrap();
 
         let cred1 = CredInfoProver {
             referent: "height".to_string(),
@@ -709,7 +776,7 @@ pub mod unit_tests {
         };
         let mut cred_info = vec![cred1];
         let anoncreds = MockAnoncreds;
-        let ledger_read = MockLedger;
+        let ledger_read = TailsMockLedger { tails_hash };
         let states = build_rev_states_json(&ledger_read, &anoncreds, cred_info.as_mut())
             .await
             .unwrap();
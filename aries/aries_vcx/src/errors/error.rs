@@ -163,6 +163,8 @@ pub enum AriesVcxErrorKind {
 
     #[error("Invalid message format")]
     InvalidMessageFormat,
+    #[error("Signature verification failed")]
+    InvalidSignature,
 }
 
 #[derive(thiserror::Error)]
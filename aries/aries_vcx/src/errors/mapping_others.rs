@@ -264,6 +264,7 @@ impl From<AriesVcxError> for AriesVcxCoreError {
             AriesVcxErrorKind::UrsaError => AriesVcxCoreErrorKind::UrsaError,
             AriesVcxErrorKind::NoAgentInformation => AriesVcxCoreErrorKind::NoAgentInformation,
             AriesVcxErrorKind::InvalidMessageFormat => AriesVcxCoreErrorKind::InvalidMessageFormat,
+            AriesVcxErrorKind::InvalidSignature => AriesVcxCoreErrorKind::InvalidMessageFormat,
         };
         AriesVcxCoreError::from_msg(kind, format!("AriesVcxError: {}", err))
     }
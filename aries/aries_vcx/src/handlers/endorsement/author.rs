@@ -0,0 +1,58 @@
+use messages::msg_fields::protocols::transactions::{
+    endorsed_response::EndorsedResponse, refused_response::RefusedResponse,
+};
+
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        endorsement::{
+            author::state_machine::{AuthorConfig, TransactionAuthorSM},
+            EndorsementOutcome,
+        },
+        SendClosure,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionAuthor {
+    author_sm: TransactionAuthorSM,
+}
+
+impl TransactionAuthor {
+    pub fn build() -> Self {
+        Self {
+            author_sm: TransactionAuthorSM::create(),
+        }
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        self.author_sm.get_thread_id()
+    }
+
+    pub fn get_transaction_id(&self) -> VcxResult<String> {
+        self.author_sm.get_transaction_id()
+    }
+
+    pub fn get_outcome(&self) -> VcxResult<EndorsementOutcome> {
+        self.author_sm.get_outcome()
+    }
+
+    pub async fn send_transaction_request(
+        self,
+        config: AuthorConfig,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let author_sm = self.author_sm.send_request(config, send_message).await?;
+        Ok(Self { author_sm })
+    }
+
+    pub fn handle_endorse(self, endorse: EndorsedResponse) -> VcxResult<Self> {
+        let author_sm = self.author_sm.handle_endorse(endorse)?;
+        Ok(Self { author_sm })
+    }
+
+    pub fn handle_refuse(self, refuse: RefusedResponse) -> VcxResult<Self> {
+        let author_sm = self.author_sm.handle_refuse(refuse)?;
+        Ok(Self { author_sm })
+    }
+}
@@ -0,0 +1,62 @@
+use aries_vcx_core::{ledger::base_ledger::IndyLedgerWrite, wallet::base_wallet::BaseWallet};
+use messages::msg_fields::protocols::transactions::request::TransactionRequest;
+
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        endorsement::{endorser::state_machine::TransactionEndorserSM, EndorsementOutcome},
+        SendClosure,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionEndorser {
+    endorser_sm: TransactionEndorserSM,
+}
+
+impl TransactionEndorser {
+    pub fn from_request(request: TransactionRequest) -> VcxResult<Self> {
+        Ok(Self {
+            endorser_sm: TransactionEndorserSM::from_request(request)?,
+        })
+    }
+
+    pub fn get_thread_id(&self) -> String {
+        self.endorser_sm.get_thread_id()
+    }
+
+    pub fn get_transaction_id(&self) -> String {
+        self.endorser_sm.get_transaction_id()
+    }
+
+    pub fn get_transaction(&self) -> VcxResult<String> {
+        self.endorser_sm.get_transaction()
+    }
+
+    pub fn get_outcome(&self) -> Option<EndorsementOutcome> {
+        self.endorser_sm.get_outcome()
+    }
+
+    pub async fn endorse(
+        self,
+        wallet: &impl BaseWallet,
+        ledger: &impl IndyLedgerWrite,
+        endorser_did: &str,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let endorser_sm = self
+            .endorser_sm
+            .endorse(wallet, ledger, endorser_did, send_message)
+            .await?;
+        Ok(Self { endorser_sm })
+    }
+
+    pub async fn refuse(
+        self,
+        endorser_did: &str,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let endorser_sm = self.endorser_sm.refuse(endorser_did, send_message).await?;
+        Ok(Self { endorser_sm })
+    }
+}
@@ -26,7 +26,7 @@ use messages::{
 use uuid::Uuid;
 
 use crate::{
-    common::credentials::get_cred_rev_id,
+    common::{credentials::get_cred_rev_id, primitives::revocation_tails::TailsFetcher},
     errors::error::prelude::*,
     handlers::{
         mediated_connection::MediatedConnection,
@@ -191,6 +191,15 @@ impl Holder {
         self.holder_sm.get_tails_hash()
     }
 
+    /// Downloads the tails file of a revocable credential into `tails_cache_dir`, verifying it
+    /// against the tails hash of the revocation registry definition. Returns the tails dir to be
+    /// used when building revocation states.
+    pub async fn fetch_tails_file(&self, tails_cache_dir: &str) -> VcxResult<String> {
+        TailsFetcher::new(tails_cache_dir)
+            .fetch(&self.get_tails_location()?, &self.get_tails_hash()?)
+            .await
+    }
+
     pub fn get_rev_reg_id(&self) -> VcxResult<String> {
         self.holder_sm.get_rev_reg_id()
     }
@@ -1,5 +1,6 @@
 use aries_vcx_core::{
-    anoncreds::base_anoncreds::BaseAnonCreds, ledger::base_ledger::AnoncredsLedgerRead,
+    anoncreds::base_anoncreds::BaseAnonCreds,
+    ledger::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite},
     wallet::base_wallet::BaseWallet,
 };
 use messages::{
@@ -21,6 +22,7 @@ use messages::{
 };
 
 use crate::{
+    common::primitives::revocation_registry_manager::RevocationRegistryManager,
     errors::error::prelude::*,
     handlers::util::OfferInfo,
     protocols::issuance::issuer::state_machine::{IssuerSM, IssuerState, RevocationInfoV1},
@@ -162,6 +164,60 @@ impl Issuer {
         Ok(())
     }
 
+    /// Builds an offer of a revocable credential of the manager's credential definition, issued
+    /// into a slot reserved in its active revocation registry. The slot is released if the offer
+    /// cannot be built.
+    pub async fn build_managed_credential_offer_msg(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        rev_reg_manager: &mut RevocationRegistryManager,
+        credential_json: String,
+        comment: Option<String>,
+    ) -> VcxResult<()> {
+        let slot = rev_reg_manager
+            .reserve_issuance(wallet, anoncreds, ledger_write)
+            .await?;
+        let offer_info = OfferInfo::new(
+            credential_json,
+            rev_reg_manager.cred_def_id().to_owned(),
+            Some(slot.rev_reg_id.clone()),
+            Some(slot.tails_dir),
+        );
+        if let Err(err) = self
+            .build_credential_offer_msg(wallet, anoncreds, offer_info, comment)
+            .await
+        {
+            rev_reg_manager
+                .release_issuance(wallet, &slot.rev_reg_id)
+                .await?;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Releases the revocation registry slot reserved by
+    /// [`Self::build_managed_credential_offer_msg`] once the issuance has failed before the
+    /// credential was issued, so that the slot can be issued into again
+    pub async fn release_managed_issuance(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_manager: &mut RevocationRegistryManager,
+    ) -> VcxResult<()> {
+        // A credential revocation ID is only assigned once the credential is issued
+        if self.get_state() != IssuerState::Failed || self.is_revokable() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Only the revocation registry slot of an issuance which failed before the \
+                 credential was issued can be released",
+            ));
+        }
+        rev_reg_manager
+            .release_issuance(wallet, &self.get_rev_reg_id()?)
+            .await
+    }
+
     pub fn get_credential_offer(&self) -> VcxResult<OfferCredentialV1> {
         self.issuer_sm.get_credential_offer_msg()
     }
@@ -1,3 +1,4 @@
+pub mod endorsement;
 pub mod issuance;
 pub mod mediated_connection;
 pub mod out_of_band;
@@ -3,7 +3,7 @@ use messages::{
         connection::{invitation::Invitation, Connection},
         coordinate_mediation::CoordinateMediation,
         cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
-        did_exchange::DidExchange,
+        did_exchange::DidExchangeV1,
         discover_features::DiscoverFeatures,
         notification::Notification,
         out_of_band::{invitation::Invitation as OobInvitation, OutOfBand},
@@ -18,6 +18,7 @@ use messages::{
         },
         report_problem::ProblemReport,
         revocation::Revocation,
+        transactions::Transactions,
         trust_ping::TrustPing,
     },
     AriesMessage,
@@ -240,13 +241,20 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(msg)) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::DidExchange(DidExchange::Request(msg)) => {
+        AriesMessage::DidExchange(msg) => match msg.inner() {
+            DidExchangeV1::Request(msg) => matches_opt_thread_id!(msg, thread_id),
+            DidExchangeV1::Response(msg) => matches_thread_id!(msg, thread_id),
+            DidExchangeV1::Complete(msg) => matches_thread_id!(msg, thread_id),
+            DidExchangeV1::ProblemReport(msg) => matches_thread_id!(msg, thread_id),
+        },
+        AriesMessage::Transactions(Transactions::Request(msg)) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::DidExchange(DidExchange::Response(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::DidExchange(DidExchange::Complete(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::DidExchange(DidExchange::ProblemReport(msg)) => {
-            matches_thread_id!(msg, thread_id)
+        AriesMessage::Transactions(Transactions::EndorsedResponse(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Transactions(Transactions::RefusedResponse(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
         }
     };
 
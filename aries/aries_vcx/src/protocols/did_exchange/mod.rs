@@ -3,10 +3,16 @@ use std::sync::Arc;
 use did_doc_sov::extra_fields::KeyKind;
 use did_resolver::traits::resolvable::resolution_output::DidResolutionOutput;
 use did_resolver_registry::ResolverRegistry;
-use messages::msg_fields::protocols::out_of_band::invitation::{
-    Invitation as OobInvitation, OobService,
+use messages::{
+    msg_fields::protocols::out_of_band::invitation::{Invitation as OobInvitation, OobService},
+    msg_types::{
+        protocols::did_exchange::{DidExchangeType, DidExchangeTypeV1},
+        traits::ProtocolVersion,
+        Protocol,
+    },
 };
 use public_key::{Key, KeyType};
+use shared::maybe_known::MaybeKnown;
 
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind};
 
@@ -64,3 +70,31 @@ pub async fn resolve_key_from_invitation(
         )?),
     }
 }
+
+/// Picks the highest DID Exchange version listed in the invitation's `handshake_protocols`
+pub fn negotiate_version(invitation: &OobInvitation) -> Result<DidExchangeTypeV1, AriesVcxError> {
+    invitation
+        .content
+        .handshake_protocols
+        .as_ref()
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Invitation does not contain handshake protocols",
+            )
+        })?
+        .iter()
+        .filter_map(|protocol| match protocol {
+            MaybeKnown::Known(Protocol::DidExchangeType(DidExchangeType::V1(version))) => {
+                Some(*version)
+            }
+            _ => None,
+        })
+        .max_by_key(|version| version.as_version_parts())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Invitation does not contain didexchange handshake protocol",
+            )
+        })
+}
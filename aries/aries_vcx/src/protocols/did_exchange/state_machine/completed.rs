@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use did_resolver_registry::ResolverRegistry;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::did_exchange::response::{
+        Response, ResponseContent, ResponseDecorators,
+    },
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
+};
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    helpers::{
+        attach_to_from_prior, create_our_did_document, ddo_sov_to_attach, from_prior_sign,
+        from_prior_to_attach, from_prior_verify, their_ddo_from_response, to_transition_error,
+    },
+    DidExchange,
+};
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind},
+    protocols::did_exchange::{
+        states::completed::Completed,
+        transition::{transition_error::TransitionError, transition_result::TransitionResult},
+    },
+};
+
+impl<I: Clone> DidExchange<I, Completed> {
+    /// Rotates our DID of an established relationship, producing a response on the exchange
+    /// thread with the new DID document and a `from_prior` JWT, signed by the key of the DID
+    /// being rotated away from, in its `did_rotate~attach`. As with `from_prior` in DIDComm v2,
+    /// the new DID is used from this message on.
+    pub async fn rotate_did(
+        self,
+        wallet: &impl BaseWallet,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+    ) -> Result<TransitionResult<Self, Response>, AriesVcxError> {
+        if let DidExchangeTypeV1::V1_0(_) = self.version {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                "DID rotation requires DID Exchange 1.1",
+            ));
+        }
+        let (our_did_document, _enc_key) =
+            create_our_did_document(wallet, service_endpoint, routing_keys).await?;
+        let our_did = our_did_document.id().to_string();
+        let from_prior = from_prior_sign(&self.our_did_document, &our_did, wallet).await?;
+
+        let content = ResponseContent::builder()
+            .did(our_did)
+            .did_doc(Some(ddo_sov_to_attach(our_did_document.clone())?))
+            .did_rotate(from_prior_to_attach(&from_prior))
+            .build();
+        let decorators = ResponseDecorators::builder()
+            .thread(
+                Thread::builder()
+                    .thid(self.state.request_id.clone())
+                    .pthid(self.state.invitation_id.clone())
+                    .build(),
+            )
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        let response = Response::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build();
+
+        Ok(TransitionResult {
+            state: DidExchange::from_parts(
+                self.state,
+                self.their_did_document,
+                our_did_document,
+                self.version,
+            ),
+            output: response,
+        })
+    }
+
+    /// Accepts the counterparty's DID rotation, provided its `from_prior` JWT is issued by their
+    /// current DID, signed by its key, and names the DID of the response as its subject. The
+    /// attached DID document is used if present, otherwise the new DID is resolved.
+    pub async fn receive_did_rotate(
+        self,
+        wallet: &impl BaseWallet,
+        resolver_registry: Arc<ResolverRegistry>,
+        response: Response,
+    ) -> Result<Self, TransitionError<Self>> {
+        if response.decorators.thread.thid != self.state.request_id {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "DID rotation thread ID does not match request ID",
+                ),
+                state: self,
+            });
+        }
+        let Some(did_rotate) = &response.content.did_rotate else {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidSignature,
+                    "DID rotation does not contain a from_prior JWT",
+                ),
+                state: self,
+            });
+        };
+        let from_prior =
+            attach_to_from_prior(did_rotate).map_err(to_transition_error(self.clone()))?;
+        from_prior_verify(
+            &from_prior,
+            &self.their_did_document,
+            &response.content.did,
+            wallet,
+        )
+        .await
+        .map_err(to_transition_error(self.clone()))?;
+        let their_did_document = their_ddo_from_response(
+            &resolver_registry,
+            &response.content.did,
+            response.content.did_doc,
+        )
+        .await
+        .map_err(to_transition_error(self.clone()))?;
+
+        Ok(DidExchange::from_parts(
+            self.state,
+            their_did_document,
+            self.our_did_document,
+            self.version,
+        ))
+    }
+}
@@ -1,14 +1,17 @@
 use std::sync::Arc;
 
-use aries_vcx_core::{ledger::base_ledger::IndyLedgerRead, wallet::base_wallet::BaseWallet};
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use did_doc_sov::DidDocumentSov;
 use did_parser::Did;
 use did_resolver_registry::ResolverRegistry;
-use messages::msg_fields::protocols::{
-    did_exchange::{
-        complete::Complete, problem_report::ProblemReport, request::Request, response::Response,
+use messages::{
+    msg_fields::protocols::{
+        did_exchange::{
+            complete::Complete, problem_report::ProblemReport, request::Request, response::Response,
+        },
+        out_of_band::invitation::Invitation,
     },
-    out_of_band::invitation::Invitation,
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
 };
 use public_key::Key;
 pub use thin_state::ThinState;
@@ -29,6 +32,53 @@ use crate::{
 mod conversions;
 mod thin_state;
 
+/// Applies a transition of the completed exchange to a [`GenericDidExchange`] of either role,
+/// wrapping the resulting state back up. `$transition` evaluates to
+/// `Result<(completed exchange, output), (completed exchange, error)>`.
+macro_rules! transition_completed {
+    ($exchange:expr, $action:literal, | $completed:ident | $transition:expr) => {
+        match $exchange {
+            GenericDidExchange::Requester(RequesterState::Completed($completed)) => $transition
+                .map(|(state, output)| {
+                    (
+                        GenericDidExchange::Requester(RequesterState::Completed(state)),
+                        output,
+                    )
+                })
+                .map_err(|(state, error)| {
+                    (
+                        GenericDidExchange::Requester(RequesterState::Completed(state)),
+                        error,
+                    )
+                }),
+            GenericDidExchange::Responder(ResponderState::Completed($completed)) => $transition
+                .map(|(state, output)| {
+                    (
+                        GenericDidExchange::Responder(ResponderState::Completed(state)),
+                        output,
+                    )
+                })
+                .map_err(|(state, error)| {
+                    (
+                        GenericDidExchange::Responder(ResponderState::Completed(state)),
+                        error,
+                    )
+                }),
+            exchange => Err((
+                exchange,
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    concat!(
+                        "Attempted to ",
+                        $action,
+                        " before the exchange was completed"
+                    ),
+                ),
+            )),
+        }
+    };
+}
+
 #[derive(Debug, Clone)]
 pub enum GenericDidExchange {
     Requester(RequesterState),
@@ -105,14 +155,31 @@ impl GenericDidExchange {
         }
     }
 
+    pub fn version(&self) -> DidExchangeTypeV1 {
+        match self {
+            GenericDidExchange::Requester(requester_state) => match requester_state {
+                RequesterState::RequestSent(request_sent_state) => request_sent_state.version(),
+                RequesterState::Completed(completed_state) => completed_state.version(),
+                RequesterState::Abandoned(abandoned_state) => abandoned_state.version(),
+            },
+            GenericDidExchange::Responder(responder_state) => match responder_state {
+                ResponderState::ResponseSent(response_sent_state) => response_sent_state.version(),
+                ResponderState::Completed(completed_state) => completed_state.version(),
+                ResponderState::Abandoned(abandoned_state) => abandoned_state.version(),
+            },
+        }
+    }
+
     pub async fn construct_request_public(
-        ledger: &impl IndyLedgerRead,
+        resolver_registry: Arc<ResolverRegistry>,
         their_did: Did,
         our_did: Did,
     ) -> Result<(Self, Request), AriesVcxError> {
         let TransitionResult { state, output } =
             DidExchangeRequester::<RequestSent>::construct_request_public(
-                ledger, their_did, our_did,
+                resolver_registry,
+                their_did,
+                our_did,
             )
             .await?;
         Ok((
@@ -151,6 +218,7 @@ impl GenericDidExchange {
         routing_keys: Vec<String>,
         invitation_id: String,
         invitation_key: Key,
+        version: DidExchangeTypeV1,
     ) -> Result<(Self, Response), AriesVcxError> {
         let TransitionResult { state, output } =
             DidExchangeResponder::<ResponseSent>::receive_request(
@@ -161,6 +229,7 @@ impl GenericDidExchange {
                 routing_keys,
                 invitation_id,
                 invitation_key,
+                version,
             )
             .await?;
         Ok((
@@ -171,12 +240,17 @@ impl GenericDidExchange {
 
     pub async fn handle_response(
         self,
+        wallet: &impl BaseWallet,
+        resolver_registry: Arc<ResolverRegistry>,
         response: Response,
     ) -> Result<(Self, Complete), (Self, AriesVcxError)> {
         match self {
             GenericDidExchange::Requester(requester_state) => match requester_state {
                 RequesterState::RequestSent(request_sent_state) => {
-                    match request_sent_state.receive_response(response).await {
+                    match request_sent_state
+                        .receive_response(wallet, resolver_registry, response)
+                        .await
+                    {
                         Ok(TransitionResult { state, output }) => Ok((
                             GenericDidExchange::Requester(RequesterState::Completed(state)),
                             output,
@@ -251,6 +325,36 @@ impl GenericDidExchange {
         }
     }
 
+    /// Rotates our DID, returning the response announcing the new DID to the counterparty
+    pub async fn rotate_did(
+        self,
+        wallet: &impl BaseWallet,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+    ) -> Result<(Self, Response), (Self, AriesVcxError)> {
+        transition_completed!(self, "rotate DID", |completed| completed
+            .clone()
+            .rotate_did(wallet, service_endpoint, routing_keys)
+            .await
+            .map(|TransitionResult { state, output }| (state, output))
+            .map_err(|error| (completed, error)))
+    }
+
+    /// Handles the response by which the counterparty rotates their DID
+    pub async fn handle_did_rotate(
+        self,
+        wallet: &impl BaseWallet,
+        resolver_registry: Arc<ResolverRegistry>,
+        response: Response,
+    ) -> Result<Self, (Self, AriesVcxError)> {
+        transition_completed!(self, "handle DID rotation", |completed| completed
+            .receive_did_rotate(wallet, resolver_registry, response)
+            .await
+            .map(|state| (state, ()))
+            .map_err(|TransitionError { state, error }| (state, error)))
+        .map(|(exchange, ())| exchange)
+    }
+
     pub fn handle_problem_report(
         self,
         problem_report: ProblemReport,
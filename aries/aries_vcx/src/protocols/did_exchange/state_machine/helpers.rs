@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
 use did_doc::schema::{
     types::uri::Uri,
     verification_method::{VerificationMethod, VerificationMethodKind, VerificationMethodType},
@@ -14,7 +15,12 @@ use did_doc_sov::{
 use did_key::DidKey;
 use did_parser::{Did, DidUrl};
 use did_peer::peer_did::{numalgos::numalgo2::Numalgo2, PeerDid};
-use messages::decorators::attachment::{Attachment, AttachmentData, AttachmentType};
+use did_resolver::traits::resolvable::resolution_output::DidResolutionOutput;
+use did_resolver_registry::ResolverRegistry;
+use messages::{
+    decorators::attachment::{Attachment, AttachmentData, AttachmentType},
+    misc::MimeType,
+};
 use public_key::{Key, KeyType};
 use serde_json::Value;
 use url::Url;
@@ -124,7 +130,6 @@ pub fn ddo_sov_to_attach(ddo: DidDocumentSov) -> Result<Attachment, AriesVcxErro
 }
 
 // TODO: Obviously, extract attachment signing
-// TODO: JWS verification
 pub async fn jws_sign_attach(
     mut attach: Attachment,
     verkey: Key,
@@ -170,6 +175,273 @@ pub async fn jws_sign_attach(
     }
 }
 
+/// Verifies the detached EdDSA JWS of a base64 attachment against `verkey`
+pub async fn jws_verify_attach(
+    attach: &Attachment,
+    verkey: &Key,
+    wallet: &impl BaseWallet,
+) -> Result<(), AriesVcxError> {
+    let AttachmentType::Base64(attach_base64) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Cannot verify signature of non-base64-encoded attachment",
+        ));
+    };
+    let jws = attach.data.jws.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidSignature,
+            "Attachment is not signed",
+        )
+    })?;
+    let jws_field = |name: &str| {
+        jws.get(name).and_then(Value::as_str).ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidSignature,
+                format!("Attachment JWS is missing the {name} field"),
+            )
+        })
+    };
+    let b64_protected = jws_field("protected")?;
+    let signature = jws_field("signature")?;
+
+    let decode = |value: &str| {
+        base64::Engine::decode(&URL_SAFE_NO_PAD, value).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidSignature,
+                format!("Attachment JWS base 64 decoding failed: {err}"),
+            )
+        })
+    };
+    let protected_header: Value = serde_json::from_slice(&decode(b64_protected)?)?;
+    if protected_header["alg"] != "EdDSA" {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidSignature,
+            format!(
+                "Unsupported attachment JWS algorithm: {}",
+                protected_header["alg"]
+            ),
+        ));
+    }
+
+    let sign_input = format!("{}.{}", b64_protected, attach_base64).into_bytes();
+    let valid = wallet
+        .verify(&verkey.base58(), &sign_input, &decode(signature)?)
+        .await?;
+    if !valid {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidSignature,
+            format!("Attachment is not signed by key {}", verkey.base58()),
+        ));
+    }
+    Ok(())
+}
+
+/// The first verification method key of a DID document, which its DID is expected to sign with
+pub fn ddo_sov_verkey(ddo: &DidDocumentSov) -> Result<Key, AriesVcxError> {
+    Ok(ddo
+        .verification_method()
+        .first()
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("No verification method found in did document {}", ddo.id()),
+            )
+        })?
+        .public_key()?)
+}
+
+/// Whether one of the verification methods of the DID document holds `key`, i.e. whether the
+/// holder of the key controls the DID
+pub fn ddo_sov_has_key(ddo: &DidDocumentSov, key: &Key) -> bool {
+    let key_agreements = ddo.key_agreement().iter().filter_map(|vm| match vm {
+        VerificationMethodKind::Resolved(vm) => Some(vm),
+        VerificationMethodKind::Resolvable(_) => None,
+    });
+    ddo.verification_method()
+        .iter()
+        .chain(key_agreements)
+        .filter_map(|vm| vm.public_key().ok())
+        .any(|vm_key| vm_key.key() == key.key())
+}
+
+pub fn did_to_attach(did: &str) -> Attachment {
+    let content_b64 = base64::engine::Engine::encode(&URL_SAFE_NO_PAD, did);
+    Attachment::builder()
+        .mime_type(MimeType::Plain)
+        .data(
+            AttachmentData::builder()
+                .content(AttachmentType::Base64(content_b64))
+                .build(),
+        )
+        .build()
+}
+
+/// Verifies that a `did_rotate~attach` holds `did` and is signed by `verkey`
+pub async fn jws_verify_did_rotate(
+    attach: &Attachment,
+    did: &str,
+    verkey: &Key,
+    wallet: &impl BaseWallet,
+) -> Result<(), AriesVcxError> {
+    let AttachmentType::Base64(content_b64) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "DID rotate attachment is not base64 encoded",
+        ));
+    };
+    jws_verify_attach(attach, verkey, wallet).await?;
+    let signed_did = base64::Engine::decode(&URL_SAFE_NO_PAD, content_b64).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::SerializationError,
+            format!("DID rotate attachment base 64 decoding failed: {err}"),
+        )
+    })?;
+    if signed_did != did.as_bytes() {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidSignature,
+            format!("DID rotate attachment is not signed for DID {did}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Signs a `from_prior` JWT as defined by DIDComm v2, by which the holder of the key of
+/// `prior_ddo` announces `did` as the DID replacing it
+pub async fn from_prior_sign(
+    prior_ddo: &DidDocumentSov,
+    did: &str,
+    wallet: &impl BaseWallet,
+) -> Result<String, AriesVcxError> {
+    let prior_did = prior_ddo.id().to_string();
+    let vm = prior_ddo.verification_method().first().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("No verification method found in did document {prior_did}"),
+        )
+    })?;
+    let kid = match vm.id().fragment() {
+        Some(fragment) => format!("{prior_did}#{fragment}"),
+        None => vm.id().to_string(),
+    };
+    let header = json!({
+        "alg": "EdDSA",
+        "typ": "JWT",
+        "kid": kid,
+    });
+    let claims = json!({
+        "iss": prior_did,
+        "sub": did,
+        "iat": Utc::now().timestamp(),
+    });
+    let sign_input = format!(
+        "{}.{}",
+        base64::engine::Engine::encode(&URL_SAFE_NO_PAD, header.to_string()),
+        base64::engine::Engine::encode(&URL_SAFE_NO_PAD, claims.to_string())
+    );
+    let signed = wallet
+        .sign(&vm.public_key()?.base58(), sign_input.as_bytes())
+        .await?;
+    let signature_base64 = base64::engine::Engine::encode(&URL_SAFE_NO_PAD, signed);
+    Ok(format!("{sign_input}.{signature_base64}"))
+}
+
+/// Verifies that a `from_prior` JWT is issued by the DID of `prior_ddo`, signed by its key, for
+/// `did` as its subject
+pub async fn from_prior_verify(
+    from_prior: &str,
+    prior_ddo: &DidDocumentSov,
+    did: &str,
+    wallet: &impl BaseWallet,
+) -> Result<(), AriesVcxError> {
+    let invalid = |msg: String| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidSignature, msg);
+    let &[b64_header, b64_claims, signature] = from_prior.split('.').collect::<Vec<_>>().as_slice()
+    else {
+        return Err(invalid("from_prior is not a compact JWT".to_owned()));
+    };
+    let decode = |value: &str| {
+        base64::Engine::decode(&URL_SAFE_NO_PAD, value)
+            .map_err(|err| invalid(format!("from_prior base 64 decoding failed: {err}")))
+    };
+    let header: Value = serde_json::from_slice(&decode(b64_header)?)?;
+    let claims: Value = serde_json::from_slice(&decode(b64_claims)?)?;
+
+    let prior_did = prior_ddo.id().to_string();
+    if header["alg"] != "EdDSA" {
+        return Err(invalid(format!(
+            "Unsupported from_prior algorithm: {}",
+            header["alg"]
+        )));
+    }
+    if claims["iss"] != prior_did.as_str() {
+        return Err(invalid(format!(
+            "from_prior is issued by {}, not by the prior DID {prior_did}",
+            claims["iss"]
+        )));
+    }
+    if !header["kid"]
+        .as_str()
+        .is_some_and(|kid| kid.starts_with(&format!("{prior_did}#")))
+    {
+        return Err(invalid(format!(
+            "from_prior key {} is not a key of the prior DID {prior_did}",
+            header["kid"]
+        )));
+    }
+    if claims["sub"] != did {
+        return Err(invalid(format!(
+            "from_prior rotates to {}, not to DID {did}",
+            claims["sub"]
+        )));
+    }
+
+    let sign_input = format!("{b64_header}.{b64_claims}").into_bytes();
+    let verkey = ddo_sov_verkey(prior_ddo)?;
+    let valid = wallet
+        .verify(&verkey.base58(), &sign_input, &decode(signature)?)
+        .await?;
+    if !valid {
+        return Err(invalid(format!(
+            "from_prior is not signed by key {}",
+            verkey.base58()
+        )));
+    }
+    Ok(())
+}
+
+/// Wraps a `from_prior` JWT in a `did_rotate~attach`
+pub fn from_prior_to_attach(from_prior: &str) -> Attachment {
+    let content_b64 = base64::engine::Engine::encode(&URL_SAFE_NO_PAD, from_prior);
+    Attachment::builder()
+        .data(
+            AttachmentData::builder()
+                .content(AttachmentType::Base64(content_b64))
+                .build(),
+        )
+        .build()
+}
+
+/// Reads the `from_prior` JWT of a `did_rotate~attach`
+pub fn attach_to_from_prior(attachment: &Attachment) -> Result<String, AriesVcxError> {
+    let AttachmentType::Base64(content_b64) = &attachment.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "DID rotate attachment is not base64 encoded",
+        ));
+    };
+    let bytes = base64::Engine::decode(&URL_SAFE_NO_PAD, content_b64).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::SerializationError,
+            format!("DID rotate attachment base 64 decoding failed: {err}"),
+        )
+    })?;
+    String::from_utf8(bytes).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("DID rotate attachment is not a JWT: {err}"),
+        )
+    })
+}
+
 pub fn attach_to_ddo_sov(attachment: Attachment) -> Result<DidDocumentSov, AriesVcxError> {
     match attachment.data.content {
         AttachmentType::Json(value) => serde_json::from_value(value).map_err(Into::into),
@@ -191,6 +463,39 @@ pub fn attach_to_ddo_sov(attachment: Attachment) -> Result<DidDocumentSov, Aries
     }
 }
 
+pub async fn resolve_ddo(
+    resolver_registry: &Arc<ResolverRegistry>,
+    did: &Did,
+) -> Result<DidDocumentSov, AriesVcxError> {
+    let DidResolutionOutput { did_document, .. } =
+        resolver_registry.resolve(did, &Default::default()).await?;
+    Ok(did_document.into())
+}
+
+/// Takes the DID document attached to a response, which the caller has to have verified the
+/// signature of, or of the DID it has to match, or resolves `did` through the registry if there
+/// is none
+pub async fn their_ddo_from_response(
+    resolver_registry: &Arc<ResolverRegistry>,
+    did: &str,
+    verified_did_doc: Option<Attachment>,
+) -> Result<DidDocumentSov, AriesVcxError> {
+    let Some(did_doc) = verified_did_doc else {
+        return resolve_ddo(resolver_registry, &did.parse::<Did>()?).await;
+    };
+    let ddo = attach_to_ddo_sov(did_doc)?;
+    if ddo.id().to_string() != did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!(
+                "Attached did document {} does not match DID {did}",
+                ddo.id()
+            ),
+        ));
+    }
+    Ok(ddo)
+}
+
 pub fn to_transition_error<S, T>(state: S) -> impl FnOnce(T) -> TransitionError<S>
 where
     T: Into<AriesVcxError>,
@@ -1,3 +1,4 @@
+mod completed;
 mod helpers;
 
 pub mod generic;
@@ -14,6 +15,7 @@ use messages::{
     msg_fields::protocols::did_exchange::problem_report::{
         ProblemCode, ProblemReport, ProblemReportContent, ProblemReportDecorators,
     },
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
 };
 use uuid::Uuid;
 
@@ -31,6 +33,7 @@ pub struct DidExchange<I, S> {
     initiation_type: PhantomData<I>,
     our_did_document: DidDocumentSov,
     their_did_document: DidDocumentSov,
+    version: DidExchangeTypeV1,
 }
 
 impl<I, S: ThreadId> DidExchange<I, S> {
@@ -70,6 +73,7 @@ impl<I, S: ThreadId> DidExchange<I, S> {
                 initiation_type: PhantomData,
                 our_did_document: self.our_did_document,
                 their_did_document: self.their_did_document,
+                version: self.version,
             },
             output: problem_report,
         }
@@ -87,6 +91,7 @@ impl<I, S: ThreadId> DidExchange<I, S> {
             initiation_type: PhantomData,
             our_did_document: self.our_did_document,
             their_did_document: self.their_did_document,
+            version: self.version,
         }
     }
 }
@@ -102,12 +107,14 @@ impl<I, S> DidExchange<I, S> {
         state: S,
         their_did_document: DidDocumentSov,
         our_did_document: DidDocumentSov,
+        version: DidExchangeTypeV1,
     ) -> Self {
         Self {
             state,
             initiation_type: PhantomData,
             our_did_document,
             their_did_document,
+            version,
         }
     }
 }
@@ -120,4 +127,9 @@ impl<I, S> DidExchange<I, S> {
     pub fn their_did_doc(&self) -> &DidDocumentSov {
         &self.their_did_document
     }
+
+    /// DID Exchange protocol version the messages of this exchange are sent with
+    pub fn version(&self) -> DidExchangeTypeV1 {
+        self.version
+    }
 }
@@ -1,10 +1,8 @@
 use std::sync::Arc;
 
-use aries_vcx_core::ledger::base_ledger::IndyLedgerRead;
 use chrono::Utc;
-use did_doc::schema::verification_method::{VerificationMethod, VerificationMethodType};
 use did_doc_sov::{service::ServiceSov, DidDocumentSov};
-use did_parser::{Did, DidUrl};
+use did_parser::Did;
 use did_resolver::traits::resolvable::resolution_output::DidResolutionOutput;
 use did_resolver_registry::ResolverRegistry;
 use messages::{
@@ -14,75 +12,13 @@ use messages::{
     },
     msg_fields::protocols::{
         did_exchange::request::{Request, RequestContent, RequestDecorators},
-        out_of_band::invitation::{Invitation as OobInvitation, Invitation, OobService},
+        out_of_band::invitation::{Invitation, OobService},
     },
 };
 use shared::maybe_known::MaybeKnown;
 use uuid::Uuid;
 
-use crate::{
-    common::ledger::transactions::resolve_service,
-    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
-    utils::from_legacy_service_to_service_sov,
-};
-
-pub fn verify_handshake_protocol(invitation: OobInvitation) -> Result<(), AriesVcxError> {
-    invitation
-        .content
-        .handshake_protocols
-        .ok_or_else(|| {
-            AriesVcxError::from_msg(
-                AriesVcxErrorKind::InvalidState,
-                "Invitation does not contain handshake protpcols",
-            )
-        })?
-        .iter()
-        .find(|protocol| match protocol {
-            // TODO: Improve this check
-            MaybeKnown::Known(protocol) if protocol.to_string().contains("didexchange") => true,
-            _ => false,
-        })
-        .ok_or_else(|| {
-            AriesVcxError::from_msg(
-                AriesVcxErrorKind::InvalidState,
-                "Invitation does not contain didexchange handshake protocol",
-            )
-        })?;
-    Ok(())
-}
-
-pub async fn did_doc_from_did(
-    ledger: &impl IndyLedgerRead,
-    did: Did,
-) -> Result<(DidDocumentSov, ServiceSov), AriesVcxError> {
-    let service = resolve_service(ledger, &OobService::Did(did.id().to_string())).await?;
-    let did_url: DidUrl = format!("{}#vm-0", did).try_into()?;
-    let vm = VerificationMethod::builder(
-        did_url,
-        did.clone(),
-        VerificationMethodType::Ed25519VerificationKey2020,
-    )
-    .add_public_key_base58(
-        service
-            .recipient_keys
-            .first()
-            .ok_or_else(|| {
-                AriesVcxError::from_msg(
-                    AriesVcxErrorKind::InvalidState,
-                    "No recipient keys found in resolved service",
-                )
-            })?
-            .clone(),
-    )
-    .build();
-    let sov_service = from_legacy_service_to_service_sov(service)?;
-    let did_document = DidDocumentSov::builder(did.clone())
-        .add_service(sov_service.clone())
-        .add_controller(did)
-        .add_verification_method(vm)
-        .build();
-    Ok((did_document, sov_service))
-}
+use crate::{errors::error::VcxResult, utils::from_legacy_service_to_service_sov};
 
 pub fn construct_request(invitation_id: String, our_did: String) -> Request {
     let request_id = Uuid::new_v4().to_string();
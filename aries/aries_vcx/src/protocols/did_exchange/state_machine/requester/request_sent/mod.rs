@@ -1,14 +1,10 @@
 use std::sync::Arc;
 
-use aries_vcx_core::{ledger::base_ledger::IndyLedgerRead, wallet::base_wallet::BaseWallet};
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use chrono::Utc;
 use did_parser::Did;
-use did_peer::resolver::PeerDidResolver;
-use did_resolver::traits::resolvable::DidResolvable;
 use did_resolver_registry::ResolverRegistry;
-use helpers::{
-    construct_request, did_doc_from_did, oob_invitation_to_diddoc, verify_handshake_protocol,
-};
+use helpers::{construct_request, oob_invitation_to_diddoc};
 use messages::{
     decorators::{thread::Thread, timing::Timing},
     msg_fields::protocols::{
@@ -19,6 +15,7 @@ use messages::{
         },
         out_of_band::invitation::Invitation,
     },
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
 };
 use url::Url;
 use uuid::Uuid;
@@ -27,7 +24,11 @@ use super::DidExchangeRequester;
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind},
     protocols::did_exchange::{
-        state_machine::helpers::{attach_to_ddo_sov, create_our_did_document, to_transition_error},
+        negotiate_version, resolve_key_from_invitation,
+        state_machine::helpers::{
+            create_our_did_document, ddo_sov_has_key, ddo_sov_verkey, jws_verify_attach,
+            jws_verify_did_rotate, resolve_ddo, their_ddo_from_response, to_transition_error,
+        },
         states::{completed::Completed, requester::request_sent::RequestSent},
         transition::{transition_error::TransitionError, transition_result::TransitionResult},
     },
@@ -43,11 +44,12 @@ impl DidExchangeRequester<RequestSent> {
         service_endpoint: Url,
         routing_keys: Vec<String>,
     ) -> Result<TransitionResult<Self, Request>, AriesVcxError> {
-        verify_handshake_protocol(invitation.clone())?;
+        let version = negotiate_version(&invitation)?;
         let (our_did_document, _our_verkey) =
             create_our_did_document(wallet, service_endpoint, routing_keys).await?;
         let their_did_document =
             oob_invitation_to_diddoc(&resolver_registry, invitation.clone()).await?;
+        let invitation_key = resolve_key_from_invitation(&invitation, &resolver_registry).await?;
 
         let request = construct_request(invitation.id.clone(), our_did_document.id().to_string());
 
@@ -56,22 +58,33 @@ impl DidExchangeRequester<RequestSent> {
                 RequestSent {
                     invitation_id: invitation.id.clone(),
                     request_id: request.id.clone(),
+                    invitation_key: Some(invitation_key),
                 },
                 their_did_document,
                 our_did_document,
+                version,
             ),
             output: request,
         })
     }
 
+    /// Requests a connection to a public DID. Without an invitation to negotiate the protocol
+    /// version from, DID Exchange 1.0 is used.
     pub async fn construct_request_public(
-        ledger: &impl IndyLedgerRead,
+        resolver_registry: Arc<ResolverRegistry>,
         their_did: Did,
         our_did: Did,
     ) -> Result<TransitionResult<Self, Request>, AriesVcxError> {
-        let (their_did_document, service) = did_doc_from_did(ledger, their_did.clone()).await?;
-        let (our_did_document, _) = did_doc_from_did(ledger, our_did.clone()).await?;
+        let their_did_document = resolve_ddo(&resolver_registry, &their_did).await?;
+        let our_did_document = resolve_ddo(&resolver_registry, &our_did).await?;
+        let service = their_did_document.service().first().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "No service found in resolved did document",
+            )
+        })?;
         let invitation_id = format!("{}#{}", their_did, service.id());
+        let invitation_key = ddo_sov_verkey(&their_did_document)?;
 
         let request = construct_request(invitation_id.clone(), our_did.to_string());
 
@@ -80,9 +93,11 @@ impl DidExchangeRequester<RequestSent> {
                 RequestSent {
                     request_id: request.id.clone(),
                     invitation_id,
+                    invitation_key: Some(invitation_key),
                 },
                 their_did_document,
                 our_did_document,
+                DidExchangeTypeV1::new_v1_0(),
             ),
             output: request,
         })
@@ -90,6 +105,8 @@ impl DidExchangeRequester<RequestSent> {
 
     pub async fn receive_response(
         self,
+        wallet: &impl BaseWallet,
+        resolver_registry: Arc<ResolverRegistry>,
         response: Response,
     ) -> Result<
         TransitionResult<DidExchangeRequester<Completed>, CompleteMessage>,
@@ -104,24 +121,55 @@ impl DidExchangeRequester<RequestSent> {
                 state: self,
             });
         }
-        let did_document = if let Some(ddo) = response.content.did_doc {
-            attach_to_ddo_sov(ddo).map_err(to_transition_error(self.clone()))?
-        } else {
-            PeerDidResolver::new()
-                .resolve(
-                    &response
-                        .content
-                        .did
-                        .parse()
-                        .map_err(to_transition_error(self.clone()))?,
-                    &Default::default(),
-                )
-                .await
-                .map_err(to_transition_error(self.clone()))?
-                .did_document()
-                .to_owned()
-                .into()
+        let Some(invitation_key) = &self.state.invitation_key else {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Request state has no invitation key to verify the response with",
+                ),
+                state: self,
+            });
         };
+        let verification = match self.version {
+            DidExchangeTypeV1::V1_0(_) => match &response.content.did_doc {
+                Some(ddo) => jws_verify_attach(ddo, invitation_key, wallet).await,
+                // Without an attachment, the resolved document is checked to hold the key below
+                None => Ok(()),
+            },
+            DidExchangeTypeV1::V1_1(_) => match &response.content.did_rotate {
+                Some(did_rotate) => {
+                    jws_verify_did_rotate(did_rotate, &response.content.did, invitation_key, wallet)
+                        .await
+                }
+                None => Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidSignature,
+                    "DID Exchange 1.1 response does not contain a signed DID rotation",
+                )),
+            },
+        };
+        verification.map_err(to_transition_error(self.clone()))?;
+        // Unless the invitation key signed the DID or its document, nothing ties the DID to the
+        // party invited, so its resolved document has to hold the invitation key
+        let requires_invitation_key = response.content.did_doc.is_none()
+            && matches!(self.version, DidExchangeTypeV1::V1_0(_));
+        // DID Exchange 1.1 signs the DID, which an attached document has to match
+        let did_document = their_ddo_from_response(
+            &resolver_registry,
+            &response.content.did,
+            response.content.did_doc,
+        )
+        .await
+        .map_err(to_transition_error(self.clone()))?;
+        if requires_invitation_key && !ddo_sov_has_key(&did_document, invitation_key) {
+            let error = AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidSignature,
+                format!(
+                    "Response DID {} is neither signed by nor holds the invitation key",
+                    response.content.did
+                ),
+            );
+            return Err(TransitionError { error, state: self });
+        }
         let decorators = CompleteDecorators::builder()
             .thread(
                 Thread::builder()
@@ -145,6 +193,7 @@ impl DidExchangeRequester<RequestSent> {
                 // TODO: Make sure to make the DDO identifier did:peer:3 for both
                 did_document,
                 self.our_did_document,
+                self.version,
             ),
             output: complete_message,
         })
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use chrono::Utc;
 use did_doc_sov::DidDocumentSov;
+use did_parser::Did;
 use did_resolver_registry::ResolverRegistry;
 use messages::{
     decorators::{thread::Thread, timing::Timing},
@@ -11,6 +12,7 @@ use messages::{
         request::Request,
         response::{Response, ResponseContent, ResponseDecorators},
     },
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
 };
 use public_key::Key;
 use url::Url;
@@ -21,7 +23,8 @@ use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind},
     protocols::did_exchange::{
         state_machine::helpers::{
-            attach_to_ddo_sov, create_our_did_document, ddo_sov_to_attach, jws_sign_attach,
+            attach_to_ddo_sov, create_our_did_document, ddo_sov_to_attach, ddo_sov_verkey,
+            did_to_attach, jws_sign_attach, jws_verify_attach, resolve_ddo,
         },
         states::{completed::Completed, responder::response_sent::ResponseSent},
         transition::{transition_error::TransitionError, transition_result::TransitionResult},
@@ -37,8 +40,9 @@ impl DidExchangeResponder<ResponseSent> {
         routing_keys: Vec<String>,
         invitation_id: String,
         invitation_key: Key,
+        version: DidExchangeTypeV1,
     ) -> Result<TransitionResult<DidExchangeResponder<ResponseSent>, Response>, AriesVcxError> {
-        let their_ddo = resolve_their_ddo(&resolver_registry, &request).await?;
+        let their_ddo = resolve_their_ddo(wallet, &resolver_registry, &request).await?;
         let (our_did_document, _enc_key) =
             create_our_did_document(wallet, service_endpoint, routing_keys).await?;
 
@@ -49,18 +53,28 @@ impl DidExchangeResponder<ResponseSent> {
             ));
         }
 
-        // TODO: Response should sign the new *did* with invitation_key only if key was rotated
-        let signed_attach = jws_sign_attach(
-            ddo_sov_to_attach(our_did_document.clone())?,
-            invitation_key,
-            wallet,
-        )
-        .await?;
-
-        let content = ResponseContent::builder()
-            .did(our_did_document.id().to_string())
-            .did_doc(Some(signed_attach))
-            .build();
+        let our_did = our_did_document.id().to_string();
+        let ddo_attach = ddo_sov_to_attach(our_did_document.clone())?;
+        let content = match version {
+            // DID Exchange 1.0 signs the whole document with the invitation key
+            DidExchangeTypeV1::V1_0(_) => {
+                let signed_attach = jws_sign_attach(ddo_attach, invitation_key, wallet).await?;
+                ResponseContent::builder()
+                    .did(our_did)
+                    .did_doc(Some(signed_attach))
+                    .build()
+            }
+            // DID Exchange 1.1 signs just the DID, rotating away from the invitation key
+            DidExchangeTypeV1::V1_1(_) => {
+                let did_attach = did_to_attach(&our_did);
+                let did_rotate = jws_sign_attach(did_attach, invitation_key, wallet).await?;
+                ResponseContent::builder()
+                    .did(our_did)
+                    .did_doc(Some(ddo_attach))
+                    .did_rotate(did_rotate)
+                    .build()
+            }
+        };
         let decorators = ResponseDecorators::builder()
             .thread(
                 Thread::builder()
@@ -84,6 +98,7 @@ impl DidExchangeResponder<ResponseSent> {
                 },
                 their_ddo,
                 our_did_document,
+                version,
             ),
             output: response,
         })
@@ -118,26 +133,33 @@ impl DidExchangeResponder<ResponseSent> {
             },
             self.their_did_document,
             self.our_did_document,
+            self.version,
         ))
     }
 }
 
+/// The requester's DID is the root of trust: an attached document has to describe it and be
+/// signed by the key its resolved document holds, otherwise the resolved document is used.
 async fn resolve_their_ddo(
+    wallet: &impl BaseWallet,
     resolver_registry: &Arc<ResolverRegistry>,
     request: &Request,
 ) -> Result<DidDocumentSov, AriesVcxError> {
-    Ok(request
-        .content
-        .did_doc
-        .clone()
-        .map(attach_to_ddo_sov)
-        .transpose()?
-        .unwrap_or(
-            resolver_registry
-                .resolve(&request.content.did.parse()?, &Default::default())
-                .await?
-                .did_document()
-                .to_owned()
-                .into(),
-        ))
+    let their_did = request.content.did.parse::<Did>()?;
+    let resolved_ddo = resolve_ddo(resolver_registry, &their_did).await?;
+    let Some(attach) = request.content.did_doc.clone() else {
+        return Ok(resolved_ddo);
+    };
+    jws_verify_attach(&attach, &ddo_sov_verkey(&resolved_ddo)?, wallet).await?;
+    let ddo = attach_to_ddo_sov(attach)?;
+    if ddo.id() != &their_did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!(
+                "Attached did document {} does not match DID {their_did}",
+                ddo.id()
+            ),
+        ));
+    }
+    Ok(ddo)
 }
@@ -1,9 +1,15 @@
+use public_key::Key;
+
 use crate::protocols::did_exchange::states::traits::{InvitationId, ThreadId};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RequestSent {
     pub invitation_id: String,
     pub request_id: String,
+    /// Key the responder is expected to sign its DID document with. Absent in states stored
+    /// before it was recorded, such exchanges can't verify the response and have to be restarted.
+    #[serde(default)]
+    pub invitation_key: Option<Key>,
 }
 
 impl ThreadId for RequestSent {
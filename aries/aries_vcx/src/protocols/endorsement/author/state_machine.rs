@@ -0,0 +1,388 @@
+use chrono::Utc;
+use messages::{
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        timing::Timing,
+    },
+    misc::MimeType,
+    msg_fields::protocols::transactions::{
+        endorsed_response::EndorsedResponse,
+        refused_response::RefusedResponse,
+        request::{
+            SignatureRequest, TransactionRequest, TransactionRequestContent,
+            TransactionRequestDecorators, TransactionType,
+        },
+    },
+};
+use serde_json::Value;
+use shared::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+use crate::{
+    errors::error::prelude::*,
+    handlers::util::verify_thread_id,
+    protocols::{
+        endorsement::{
+            author::states::{
+                finished::FinishedState, initial::InitialState, request_sent::RequestSentState,
+            },
+            request_thread_id, EndorsementOutcome,
+        },
+        SendClosure,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionAuthorSM {
+    state: AuthorFullState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AuthorFullState {
+    Initial(InitialState),
+    RequestSent(RequestSentState),
+    Finished(FinishedState),
+}
+
+#[derive(Builder)]
+pub struct AuthorConfig {
+    /// Transaction with the endorser set and signed by the author
+    transaction_json: String,
+    transaction_type: TransactionType,
+}
+
+impl TransactionAuthorSM {
+    pub fn create() -> Self {
+        Self {
+            state: AuthorFullState::Initial(InitialState),
+        }
+    }
+
+    pub fn get_request(&self) -> VcxResult<TransactionRequest> {
+        match &self.state {
+            AuthorFullState::RequestSent(state) => Ok(state.get_request()),
+            AuthorFullState::Finished(state) => Ok(state.get_request()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Transaction request not yet known in this state",
+            )),
+        }
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        Ok(request_thread_id(&self.get_request()?))
+    }
+
+    pub fn get_transaction_id(&self) -> VcxResult<String> {
+        Ok(self.get_request()?.content.transaction_id)
+    }
+
+    pub fn get_outcome(&self) -> VcxResult<EndorsementOutcome> {
+        match &self.state {
+            AuthorFullState::Finished(state) => Ok(state.get_outcome().clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Endorser has not responded to the transaction request yet",
+            )),
+        }
+    }
+
+    pub async fn send_request(
+        self,
+        config: AuthorConfig,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            AuthorFullState::Initial(_) => {
+                let AuthorConfig {
+                    transaction_json,
+                    transaction_type,
+                } = config;
+
+                serde_json::from_str::<Value>(&transaction_json).map_err(|err| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidJson,
+                        format!("Cannot deserialize transaction: {err}"),
+                    )
+                })?;
+
+                let id = Uuid::new_v4().to_string();
+                let attachment = Attachment::builder()
+                    .id(Uuid::new_v4().to_string())
+                    .mime_type(MimeType::Json)
+                    .data(
+                        AttachmentData::builder()
+                            .content(AttachmentType::Json(Value::String(transaction_json)))
+                            .build(),
+                    )
+                    .build();
+
+                let content = TransactionRequestContent::builder()
+                    .transaction_id(Uuid::new_v4().to_string())
+                    .signature_request(vec![SignatureRequest::builder().build()])
+                    .transaction_type(MaybeKnown::Known(transaction_type))
+                    .messages_attach(attachment)
+                    .endorser_write_txn(true)
+                    .build();
+
+                let decorators = TransactionRequestDecorators::builder()
+                    .timing(Timing::builder().out_time(Utc::now()).build())
+                    .build();
+
+                let request = TransactionRequest::builder()
+                    .id(id)
+                    .content(content)
+                    .decorators(decorators)
+                    .build();
+                send_message(request.clone().into()).await?;
+
+                AuthorFullState::RequestSent(RequestSentState::new(request))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Transaction request already sent",
+                ));
+            }
+        };
+        Ok(Self { state })
+    }
+
+    pub fn handle_endorse(self, endorse: EndorsedResponse) -> VcxResult<Self> {
+        let state = match self.state {
+            AuthorFullState::RequestSent(state) => {
+                let request = state.get_request();
+                Self::verify_response(&request, &endorse.content.transaction_id)?;
+                verify_thread_id(&request_thread_id(&request), &endorse.clone().into())?;
+                AuthorFullState::Finished(FinishedState::new(
+                    request,
+                    EndorsementOutcome::Endorsed(endorse),
+                ))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Endorsement not expected in this state",
+                ));
+            }
+        };
+        Ok(Self { state })
+    }
+
+    pub fn handle_refuse(self, refuse: RefusedResponse) -> VcxResult<Self> {
+        let state = match self.state {
+            AuthorFullState::RequestSent(state) => {
+                let request = state.get_request();
+                Self::verify_response(&request, &refuse.content.transaction_id)?;
+                verify_thread_id(&request_thread_id(&request), &refuse.clone().into())?;
+                AuthorFullState::Finished(FinishedState::new(
+                    request,
+                    EndorsementOutcome::Refused(refuse),
+                ))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Refusal not expected in this state",
+                ));
+            }
+        };
+        Ok(Self { state })
+    }
+
+    fn verify_response(request: &TransactionRequest, transaction_id: &str) -> VcxResult<()> {
+        if request.content.transaction_id != transaction_id {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessages,
+                format!(
+                    "Cannot handle endorser response: transaction id does not match, expected {}, \
+                     received {transaction_id}",
+                    request.content.transaction_id
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod unit_tests {
+    use messages::{
+        decorators::thread::Thread,
+        msg_fields::protocols::transactions::{
+            endorsed_response::{
+                EndorsedResponseContent, EndorsedResponseDecorators, SignatureResponse,
+            },
+            refused_response::{RefusedResponseContent, RefusedResponseDecorators},
+        },
+    };
+
+    use super::*;
+    use crate::protocols::endorsement::test_utils::{
+        _schema_transaction, _send_message, ENDORSER_DID,
+    };
+
+    fn _author_config() -> AuthorConfig {
+        AuthorConfigBuilder::default()
+            .transaction_json(_schema_transaction())
+            .transaction_type(TransactionType::Schema)
+            .build()
+            .unwrap()
+    }
+
+    fn _signature_response(signer_goal_code: &str) -> SignatureResponse {
+        SignatureResponse::builder()
+            .message_id(Uuid::new_v4().to_string())
+            .signer_goal_code(signer_goal_code.to_owned())
+            .build()
+    }
+
+    fn _endorse(transaction_id: &str, thread_id: &str) -> EndorsedResponse {
+        let content = EndorsedResponseContent::builder()
+            .transaction_id(transaction_id.to_owned())
+            .thread_id(Uuid::new_v4().to_string())
+            .signature_response(_signature_response(SignatureResponse::ENDORSE_GOAL_CODE))
+            .endorser_did(ENDORSER_DID.to_owned())
+            .build();
+        let decorators = EndorsedResponseDecorators::builder()
+            .thread(Thread::builder().thid(thread_id.to_owned()).build())
+            .build();
+        EndorsedResponse::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build()
+    }
+
+    fn _refuse(transaction_id: &str, thread_id: &str) -> RefusedResponse {
+        let content = RefusedResponseContent::builder()
+            .transaction_id(transaction_id.to_owned())
+            .thread_id(Uuid::new_v4().to_string())
+            .signature_response(_signature_response(SignatureResponse::REFUSE_GOAL_CODE))
+            .endorser_did(ENDORSER_DID.to_owned())
+            .build();
+        let decorators = RefusedResponseDecorators::builder()
+            .thread(Thread::builder().thid(thread_id.to_owned()).build())
+            .build();
+        RefusedResponse::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build()
+    }
+
+    async fn _to_request_sent_state() -> TransactionAuthorSM {
+        let sm = TransactionAuthorSM::create()
+            .send_request(_author_config(), _send_message())
+            .await
+            .unwrap();
+        assert!(matches!(sm.state, AuthorFullState::RequestSent(_)));
+        sm
+    }
+
+    #[tokio::test]
+    async fn test_send_request() {
+        let sm = _to_request_sent_state().await;
+
+        let request = sm.get_request().unwrap();
+        assert_eq!(
+            request.content.transaction_type,
+            Some(MaybeKnown::Known(TransactionType::Schema))
+        );
+        assert!(request.content.endorser_write_txn);
+        assert_eq!(
+            request.content.messages_attach.data.content,
+            AttachmentType::Json(Value::String(_schema_transaction()))
+        );
+        assert_eq!(sm.get_thread_id().unwrap(), request.id);
+        assert!(sm.get_outcome().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_request_fails_for_invalid_transaction() {
+        let config = AuthorConfigBuilder::default()
+            .transaction_json("not json".to_owned())
+            .transaction_type(TransactionType::Nym)
+            .build()
+            .unwrap();
+        let res = TransactionAuthorSM::create()
+            .send_request(config, _send_message())
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_endorse() {
+        let sm = _to_request_sent_state().await;
+        let endorse = _endorse(
+            &sm.get_transaction_id().unwrap(),
+            &sm.get_thread_id().unwrap(),
+        );
+
+        let sm = sm.handle_endorse(endorse.clone()).unwrap();
+        assert_eq!(
+            sm.get_outcome().unwrap(),
+            EndorsementOutcome::Endorsed(endorse)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_refuse() {
+        let sm = _to_request_sent_state().await;
+        let refuse = _refuse(
+            &sm.get_transaction_id().unwrap(),
+            &sm.get_thread_id().unwrap(),
+        );
+
+        let sm = sm.handle_refuse(refuse.clone()).unwrap();
+        assert_eq!(
+            sm.get_outcome().unwrap(),
+            EndorsementOutcome::Refused(refuse)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_endorse_without_thread() {
+        let sm = _to_request_sent_state().await;
+        let mut endorse = _endorse(
+            &sm.get_transaction_id().unwrap(),
+            &sm.get_thread_id().unwrap(),
+        );
+        endorse.decorators.thread = None;
+
+        let sm = sm.handle_endorse(endorse.clone()).unwrap();
+        assert_eq!(
+            sm.get_outcome().unwrap(),
+            EndorsementOutcome::Endorsed(endorse)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_endorse_fails_incorrect_transaction_id() {
+        let sm = _to_request_sent_state().await;
+        let endorse = _endorse("other_transaction_id", &sm.get_thread_id().unwrap());
+        assert!(sm.handle_endorse(endorse).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_refuse_fails_incorrect_thread_id() {
+        let sm = _to_request_sent_state().await;
+        let refuse = _refuse(&sm.get_transaction_id().unwrap(), "other_thread_id");
+        assert!(sm.handle_refuse(refuse).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_endorse_cant_handle_response_twice() {
+        let sm = _to_request_sent_state().await;
+        let endorse = _endorse(
+            &sm.get_transaction_id().unwrap(),
+            &sm.get_thread_id().unwrap(),
+        );
+        let refuse = _refuse(
+            &sm.get_transaction_id().unwrap(),
+            &sm.get_thread_id().unwrap(),
+        );
+        let sm = sm.handle_endorse(endorse).unwrap();
+        assert!(sm.handle_refuse(refuse).is_err());
+    }
+}
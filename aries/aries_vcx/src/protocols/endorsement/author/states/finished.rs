@@ -0,0 +1,23 @@
+use messages::msg_fields::protocols::transactions::request::TransactionRequest;
+
+use crate::protocols::endorsement::EndorsementOutcome;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FinishedState {
+    request: TransactionRequest,
+    outcome: EndorsementOutcome,
+}
+
+impl FinishedState {
+    pub fn new(request: TransactionRequest, outcome: EndorsementOutcome) -> Self {
+        Self { request, outcome }
+    }
+
+    pub fn get_request(&self) -> TransactionRequest {
+        self.request.clone()
+    }
+
+    pub fn get_outcome(&self) -> &EndorsementOutcome {
+        &self.outcome
+    }
+}
@@ -0,0 +1,3 @@
+pub(super) mod finished;
+pub(super) mod initial;
+pub(super) mod request_sent;
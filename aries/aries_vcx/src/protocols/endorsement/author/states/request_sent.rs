@@ -0,0 +1,16 @@
+use messages::msg_fields::protocols::transactions::request::TransactionRequest;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestSentState {
+    request: TransactionRequest,
+}
+
+impl RequestSentState {
+    pub fn new(request: TransactionRequest) -> Self {
+        Self { request }
+    }
+
+    pub fn get_request(&self) -> TransactionRequest {
+        self.request.clone()
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+
+use aries_vcx_core::{ledger::base_ledger::IndyLedgerWrite, wallet::base_wallet::BaseWallet};
+use chrono::Utc;
+use messages::{
+    decorators::{attachment::AttachmentType, thread::Thread, timing::Timing},
+    msg_fields::protocols::transactions::{
+        endorsed_response::{
+            EndorsedResponse, EndorsedResponseContent, EndorsedResponseDecorators,
+            SignatureResponse,
+        },
+        refused_response::{RefusedResponse, RefusedResponseContent, RefusedResponseDecorators},
+        request::{SignatureRequest, TransactionRequest},
+    },
+};
+use serde_json::Value;
+use shared::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        endorsement::{
+            endorser::states::{finished::FinishedState, request_received::RequestReceivedState},
+            request_thread_id, transaction_type_code, EndorsementOutcome,
+        },
+        SendClosure,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionEndorserSM {
+    state: EndorserFullState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum EndorserFullState {
+    RequestReceived(RequestReceivedState),
+    Finished(FinishedState),
+}
+
+impl TransactionEndorserSM {
+    /// Creates the endorser from the author's transaction request, checking that it carries a
+    /// single transaction of the declared type
+    pub fn from_request(request: TransactionRequest) -> VcxResult<Self> {
+        let transaction = attached_transaction(&request)?;
+        if let Some(MaybeKnown::Known(transaction_type)) = request.content.transaction_type {
+            let expected_code = transaction_type_code(transaction_type);
+            if transaction["operation"]["type"].as_str() != Some(expected_code) {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessages,
+                    format!(
+                        "Transaction request declares a transaction of type {transaction_type:?}, \
+                         but the attached transaction is of a different type: {transaction}"
+                    ),
+                ));
+            }
+        }
+        Ok(Self {
+            state: EndorserFullState::RequestReceived(RequestReceivedState::new(request)),
+        })
+    }
+
+    pub fn get_request(&self) -> TransactionRequest {
+        match &self.state {
+            EndorserFullState::RequestReceived(state) => state.get_request(),
+            EndorserFullState::Finished(state) => state.get_request(),
+        }
+    }
+
+    pub fn get_thread_id(&self) -> String {
+        request_thread_id(&self.get_request())
+    }
+
+    pub fn get_transaction_id(&self) -> String {
+        self.get_request().content.transaction_id
+    }
+
+    /// The transaction to be endorsed, for the endorser to inspect
+    pub fn get_transaction(&self) -> VcxResult<String> {
+        Ok(attached_transaction(&self.get_request())?.to_string())
+    }
+
+    pub fn get_outcome(&self) -> Option<EndorsementOutcome> {
+        match &self.state {
+            EndorserFullState::Finished(state) => Some(state.get_outcome().clone()),
+            _ => None,
+        }
+    }
+
+    /// Signs the transaction as its endorser and writes it to the ledger, informing the author.
+    /// If the author does not ask for the transaction to be written by the endorser, or if the
+    /// transaction names another endorser or cannot be written, it is refused instead.
+    pub async fn endorse(
+        self,
+        wallet: &impl BaseWallet,
+        ledger: &impl IndyLedgerWrite,
+        endorser_did: &str,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            EndorserFullState::RequestReceived(state) => {
+                let request = state.get_request();
+                let outcome = match write_endorsed_transaction(
+                    wallet,
+                    ledger,
+                    endorser_did,
+                    &request,
+                )
+                .await
+                {
+                    Ok(ledger_response) => {
+                        let endorse =
+                            build_endorsed_response(&request, endorser_did, ledger_response)?;
+                        send_message(endorse.clone().into()).await?;
+                        EndorsementOutcome::Endorsed(endorse)
+                    }
+                    Err(err) => {
+                        error!("Failed to endorse transaction, refusing it: {err}");
+                        let refuse = build_refused_response(&request, endorser_did);
+                        send_message(refuse.clone().into()).await?;
+                        EndorsementOutcome::Refused(refuse)
+                    }
+                };
+
+                EndorserFullState::Finished(FinishedState::new(request, outcome))
+            }
+            EndorserFullState::Finished(_) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Transaction request already responded to",
+                ));
+            }
+        };
+        Ok(Self { state })
+    }
+
+    pub async fn refuse(
+        self,
+        endorser_did: &str,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            EndorserFullState::RequestReceived(state) => {
+                let request = state.get_request();
+                let refuse = build_refused_response(&request, endorser_did);
+                send_message(refuse.clone().into()).await?;
+
+                EndorserFullState::Finished(FinishedState::new(
+                    request,
+                    EndorsementOutcome::Refused(refuse),
+                ))
+            }
+            EndorserFullState::Finished(_) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Transaction request already responded to",
+                ));
+            }
+        };
+        Ok(Self { state })
+    }
+}
+
+/// Endorses the requested transaction and writes it to the ledger, provided the author asks for
+/// it to be written by the endorser and it names `endorser_did` as its endorser
+async fn write_endorsed_transaction(
+    wallet: &impl BaseWallet,
+    ledger: &impl IndyLedgerWrite,
+    endorser_did: &str,
+    request: &TransactionRequest,
+) -> VcxResult<String> {
+    if !request.content.endorser_write_txn {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::ActionNotSupported,
+            "Transaction is to be written by its author, which is not supported",
+        ));
+    }
+    let transaction = attached_transaction(request)?;
+    if transaction["endorser"].as_str() != Some(endorser_did) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!(
+                "Transaction is to be endorsed by {}, not by {endorser_did}",
+                transaction["endorser"]
+            ),
+        ));
+    }
+    Ok(ledger
+        .endorse_transaction(wallet, endorser_did, &transaction.to_string())
+        .await?)
+}
+
+fn build_endorsed_response(
+    request: &TransactionRequest,
+    endorser_did: &str,
+    ledger_response: String,
+) -> VcxResult<EndorsedResponse> {
+    let ledger_response: Value = serde_json::from_str(&ledger_response).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot deserialize ledger response: {err}"),
+        )
+    })?;
+    let signature_response = SignatureResponse::builder()
+        .message_id(attachment_id(request))
+        .signer_goal_code(SignatureResponse::ENDORSE_GOAL_CODE.to_owned())
+        .signature_type(SignatureRequest::DEFAULT_SIGNATURE_TYPE.to_owned());
+    let signature_response = match endorsed_transaction(request, endorser_did, &ledger_response)? {
+        Some(transaction) => signature_response
+            .signature(HashMap::from([(endorser_did.to_owned(), transaction)]))
+            .build(),
+        None => signature_response.build(),
+    };
+    let content = EndorsedResponseContent::builder()
+        .transaction_id(request.content.transaction_id.clone())
+        .thread_id(request_thread_id(request))
+        .signature_response(signature_response)
+        .endorser_did(endorser_did.to_owned())
+        .ledger_response(ledger_response)
+        .build();
+    let decorators = EndorsedResponseDecorators::builder()
+        .thread(Thread::builder().thid(request_thread_id(request)).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    Ok(EndorsedResponse::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+fn build_refused_response(request: &TransactionRequest, endorser_did: &str) -> RefusedResponse {
+    let signature_response = SignatureResponse::builder()
+        .message_id(attachment_id(request))
+        .signer_goal_code(SignatureResponse::REFUSE_GOAL_CODE.to_owned())
+        .build();
+    let content = RefusedResponseContent::builder()
+        .transaction_id(request.content.transaction_id.clone())
+        .thread_id(request_thread_id(request))
+        .signature_response(signature_response)
+        .endorser_did(endorser_did.to_owned())
+        .build();
+    let decorators = RefusedResponseDecorators::builder()
+        .thread(Thread::builder().thid(request_thread_id(request)).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    RefusedResponse::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+fn attachment_id(request: &TransactionRequest) -> String {
+    request
+        .content
+        .messages_attach
+        .id
+        .clone()
+        .unwrap_or_default()
+}
+
+/// The requested transaction with the endorser's signature, as found in the reply of the ledger
+/// to the written transaction
+fn endorsed_transaction(
+    request: &TransactionRequest,
+    endorser_did: &str,
+    ledger_response: &Value,
+) -> VcxResult<Option<String>> {
+    let signature = ledger_response["result"]["reqSignature"]["values"]
+        .as_array()
+        .and_then(|values| {
+            values
+                .iter()
+                .find(|value| value["from"].as_str() == Some(endorser_did))
+        })
+        .map(|value| value["value"].clone());
+    let Some(signature) = signature else {
+        return Ok(None);
+    };
+    let mut transaction = attached_transaction(request)?;
+    transaction["signatures"][endorser_did] = signature;
+    Ok(Some(transaction.to_string()))
+}
+
+/// The attached transaction, which ACA-Py attaches as a JSON string
+fn attached_transaction(request: &TransactionRequest) -> VcxResult<Value> {
+    let transaction = match &request.content.messages_attach.data.content {
+        AttachmentType::Json(Value::String(transaction)) => transaction.as_bytes().to_vec(),
+        AttachmentType::Json(transaction) => return Ok(transaction.clone()),
+        AttachmentType::Base64(encoded) => {
+            base64::engine::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded)
+                .map_err(|err| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::SerializationError,
+                        format!("Cannot decode attached transaction: {err}"),
+                    )
+                })?
+        }
+        AttachmentType::Links(_) => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessages,
+                "Transaction attached by links is not supported",
+            ))
+        }
+    };
+    serde_json::from_slice(&transaction).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot deserialize attached transaction: {err}"),
+        )
+    })
+}
+
+#[cfg(test)]
+pub mod unit_tests {
+    use aries_vcx_core::wallet::mock_wallet::MockWallet;
+    use messages::msg_fields::protocols::transactions::request::TransactionType;
+    use serde_json::json;
+    use test_utils::mockdata::mock_ledger::MockLedger;
+
+    use super::*;
+    use crate::protocols::endorsement::test_utils::{
+        _schema_transaction, _send_message, _transaction_request, AUTHOR_DID, ENDORSER_DID,
+    };
+
+    fn _endorser() -> TransactionEndorserSM {
+        TransactionEndorserSM::from_request(_transaction_request(TransactionType::Schema)).unwrap()
+    }
+
+    #[test]
+    fn test_from_request() {
+        let sm = _endorser();
+        let expected: Value = serde_json::from_str(&_schema_transaction()).unwrap();
+        let transaction: Value = serde_json::from_str(&sm.get_transaction().unwrap()).unwrap();
+        assert_eq!(transaction, expected);
+        assert_eq!(sm.get_thread_id(), sm.get_request().id);
+        assert_eq!(sm.get_outcome(), None);
+    }
+
+    #[test]
+    fn test_from_request_fails_for_transaction_type_mismatch() {
+        let request = _transaction_request(TransactionType::CredDef);
+        assert!(TransactionEndorserSM::from_request(request).is_err());
+    }
+
+    #[test]
+    fn test_from_request_fails_for_invalid_transaction() {
+        let mut request = _transaction_request(TransactionType::Schema);
+        request.content.messages_attach.data.content =
+            AttachmentType::Json(Value::String("not json".to_owned()));
+        assert!(TransactionEndorserSM::from_request(request).is_err());
+    }
+
+    #[test]
+    fn test_from_request_accepts_transaction_attached_as_object() {
+        let mut request = _transaction_request(TransactionType::Schema);
+        let expected: Value = serde_json::from_str(&_schema_transaction()).unwrap();
+        request.content.messages_attach.data.content = AttachmentType::Json(expected.clone());
+        let sm = TransactionEndorserSM::from_request(request).unwrap();
+        let transaction: Value = serde_json::from_str(&sm.get_transaction().unwrap()).unwrap();
+        assert_eq!(transaction, expected);
+    }
+
+    #[tokio::test]
+    async fn test_endorse() {
+        let sm = _endorser();
+        let transaction_id = sm.get_transaction_id();
+        let thread_id = sm.get_thread_id();
+
+        let sm = sm
+            .endorse(&MockWallet, &MockLedger, ENDORSER_DID, _send_message())
+            .await
+            .unwrap();
+
+        let Some(EndorsementOutcome::Endorsed(endorse)) = sm.get_outcome() else {
+            panic!("Transaction should have been endorsed");
+        };
+        assert_eq!(endorse.content.transaction_id, transaction_id);
+        assert_eq!(endorse.content.thread_id, thread_id);
+        assert_eq!(endorse.content.endorser_did, ENDORSER_DID);
+        assert_eq!(
+            endorse.content.signature_response.signer_goal_code,
+            SignatureResponse::ENDORSE_GOAL_CODE
+        );
+        assert!(endorse.content.ledger_response.is_some());
+        assert_eq!(endorse.decorators.thread.unwrap().thid, thread_id);
+    }
+
+    #[test]
+    fn test_endorsed_response_carries_endorsed_transaction() {
+        let request = _transaction_request(TransactionType::Schema);
+        let ledger_response = json!({
+            "op": "REPLY",
+            "result": {
+                "reqSignature": {
+                    "type": "ED25519",
+                    "values": [
+                        { "from": AUTHOR_DID, "value": "signature" },
+                        { "from": ENDORSER_DID, "value": "endorser_signature" }
+                    ]
+                }
+            }
+        });
+
+        let endorse =
+            build_endorsed_response(&request, ENDORSER_DID, ledger_response.to_string()).unwrap();
+
+        let signature = endorse.content.signature_response.signature.unwrap();
+        let transaction: Value = serde_json::from_str(&signature[ENDORSER_DID]).unwrap();
+        assert_eq!(
+            transaction["signatures"][ENDORSER_DID],
+            "endorser_signature"
+        );
+        assert_eq!(transaction["signatures"][AUTHOR_DID], "signature");
+        assert_eq!(
+            endorse.content.signature_response.message_id,
+            request.content.messages_attach.id.unwrap()
+        );
+        assert_eq!(endorse.content.ledger_response, Some(ledger_response));
+    }
+
+    #[tokio::test]
+    async fn test_endorse_refuses_transaction_to_be_written_by_author() {
+        let mut request = _transaction_request(TransactionType::Schema);
+        request.content.endorser_write_txn = false;
+        let sm = TransactionEndorserSM::from_request(request)
+            .unwrap()
+            .endorse(&MockWallet, &MockLedger, ENDORSER_DID, _send_message())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            sm.get_outcome(),
+            Some(EndorsementOutcome::Refused(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_endorse_refuses_transaction_for_other_endorser() {
+        let sm = _endorser();
+        let transaction_id = sm.get_transaction_id();
+        let other_endorser_did = "Th7MpTaRZVRYnPiabds81Y";
+
+        let sm = sm
+            .endorse(
+                &MockWallet,
+                &MockLedger,
+                other_endorser_did,
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        let Some(EndorsementOutcome::Refused(refuse)) = sm.get_outcome() else {
+            panic!("Transaction for another endorser should have been refused");
+        };
+        assert_eq!(refuse.content.transaction_id, transaction_id);
+        assert_eq!(refuse.content.endorser_did, other_endorser_did);
+    }
+
+    #[tokio::test]
+    async fn test_refuse() {
+        let sm = _endorser();
+        let transaction_id = sm.get_transaction_id();
+
+        let sm = sm.refuse(ENDORSER_DID, _send_message()).await.unwrap();
+
+        let Some(EndorsementOutcome::Refused(refuse)) = sm.get_outcome() else {
+            panic!("Transaction should have been refused");
+        };
+        assert_eq!(refuse.content.transaction_id, transaction_id);
+        assert_eq!(refuse.content.state, RefusedResponseContent::STATE);
+        assert_eq!(
+            refuse.content.signature_response.signer_goal_code,
+            SignatureResponse::REFUSE_GOAL_CODE
+        );
+        assert_eq!(refuse.content.signature_response.signature, None);
+    }
+
+    #[tokio::test]
+    async fn test_cant_respond_twice() {
+        let sm = _endorser()
+            .refuse(ENDORSER_DID, _send_message())
+            .await
+            .unwrap();
+        assert!(sm
+            .endorse(&MockWallet, &MockLedger, ENDORSER_DID, _send_message())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub(super) mod finished;
+pub(super) mod request_received;
@@ -0,0 +1,110 @@
+//! Endorsement of ledger transactions written by authors without ledger write permissions.
+//!
+//! The author prepares a transaction with the endorser set and signs it, then sends it to the
+//! endorser in a `transaction_request` message, asking the endorser to write it. The endorser
+//! inspects the transaction and either endorses and writes it to the ledger or refuses it, replying
+//! with the transaction ID of the request. The messages are the ones of the `transactions`
+//! protocol of ACA-Py, so that either role can be played by an ACA-Py agent.
+
+pub mod author;
+pub mod endorser;
+
+use messages::msg_fields::protocols::transactions::{
+    endorsed_response::EndorsedResponse,
+    refused_response::RefusedResponse,
+    request::{TransactionRequest, TransactionType},
+};
+
+/// Final response of the endorser to a transaction request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum EndorsementOutcome {
+    Endorsed(EndorsedResponse),
+    Refused(RefusedResponse),
+}
+
+/// The ledger transaction type code of transactions of the given type
+pub fn transaction_type_code(transaction_type: TransactionType) -> &'static str {
+    match transaction_type {
+        TransactionType::Nym => "1",
+        TransactionType::Schema => "101",
+        TransactionType::CredDef => "102",
+        TransactionType::RevRegDef => "113",
+        TransactionType::RevRegEntry => "114",
+    }
+}
+
+/// The thread of a transaction request, to which the endorser responds
+pub fn request_thread_id(request: &TransactionRequest) -> String {
+    request
+        .decorators
+        .thread
+        .as_ref()
+        .map(|t| t.thid.clone())
+        .unwrap_or(request.id.clone())
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use messages::{
+        decorators::attachment::{Attachment, AttachmentData, AttachmentType},
+        misc::MimeType,
+        msg_fields::protocols::transactions::request::{
+            SignatureRequest, TransactionRequest, TransactionRequestContent,
+            TransactionRequestDecorators, TransactionType,
+        },
+        AriesMessage,
+    };
+    use serde_json::{json, Value};
+    use shared::maybe_known::MaybeKnown;
+    use uuid::Uuid;
+
+    use crate::{errors::error::VcxResult, protocols::SendClosure};
+
+    pub const AUTHOR_DID: &str = "VsKV7grR1BUE29mG2Fm2kX";
+    pub const ENDORSER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+
+    pub fn _send_message() -> SendClosure<'static> {
+        Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) }))
+    }
+
+    pub fn _schema_transaction() -> String {
+        json!({
+            "reqId": 1522866729726860308u64,
+            "identifier": AUTHOR_DID,
+            "endorser": ENDORSER_DID,
+            "operation": {
+                "type": "101",
+                "data": {"name": "degree", "version": "1.0", "attr_names": ["name"]}
+            },
+            "signatures": {AUTHOR_DID: "signature"},
+            "protocolVersion": 2
+        })
+        .to_string()
+    }
+
+    pub fn _transaction_request(transaction_type: TransactionType) -> TransactionRequest {
+        let attachment = Attachment::builder()
+            .id(Uuid::new_v4().to_string())
+            .mime_type(MimeType::Json)
+            .data(
+                AttachmentData::builder()
+                    .content(AttachmentType::Json(Value::String(_schema_transaction())))
+                    .build(),
+            )
+            .build();
+
+        let content = TransactionRequestContent::builder()
+            .transaction_id(Uuid::new_v4().to_string())
+            .signature_request(vec![SignatureRequest::builder().build()])
+            .transaction_type(MaybeKnown::Known(transaction_type))
+            .messages_attach(attachment)
+            .endorser_write_txn(true)
+            .build();
+
+        TransactionRequest::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(TransactionRequestDecorators::default())
+            .build()
+    }
+}
@@ -1,32 +1,47 @@
 extern crate log;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use aries_vcx::{
+    errors::error::AriesVcxErrorKind,
     handlers::out_of_band::sender::OutOfBandSender,
     protocols::did_exchange::{
-        resolve_key_from_invitation,
+        negotiate_version, resolve_key_from_invitation,
         state_machine::{
             generate_keypair, requester::DidExchangeRequester, responder::DidExchangeResponder,
         },
         states::{requester::request_sent::RequestSent, responder::response_sent::ResponseSent},
-        transition::transition_result::TransitionResult,
+        transition::{transition_error::TransitionError, transition_result::TransitionResult},
     },
 };
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use did_doc_sov::{
-    extra_fields::{didcommv1::ExtraFieldsDidCommV1, KeyKind},
+    extra_fields::{didcommv1::ExtraFieldsDidCommV1, ExtraFieldsSov, KeyKind},
     service::{didcommv1::ServiceDidCommV1, ServiceSov},
 };
-use did_peer::resolver::PeerDidResolver;
+use did_parser::Did;
+use did_peer::resolver::{options::ExtraFieldsOptions, PeerDidResolver};
+use did_resolver::{
+    error::GenericError,
+    traits::resolvable::{
+        resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
+        DidResolvable,
+    },
+};
 use did_resolver_registry::ResolverRegistry;
 use messages::{
-    msg_fields::protocols::out_of_band::invitation::OobService,
+    decorators::attachment::{Attachment, AttachmentType},
+    msg_fields::protocols::{
+        did_exchange::response::Response, out_of_band::invitation::OobService,
+    },
     msg_types::{
         protocols::did_exchange::{DidExchangeType, DidExchangeTypeV1},
         Protocol,
     },
 };
-use public_key::KeyType;
+use public_key::{Key, KeyType};
 use test_utils::devsetup::SetupPoolDirectory;
 use url::Url;
 use uuid::Uuid;
@@ -35,22 +50,51 @@ use crate::utils::test_agent::{create_test_agent, create_test_agent_trustee};
 
 pub mod utils;
 
-#[tokio::test]
-#[ignore]
-async fn did_exchange_test() {
-    let setup = SetupPoolDirectory::init().await;
-    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
-    let consumer = create_test_agent(setup.genesis_file_path).await;
+/// Peer DID resolver recording every DID it is asked to resolve
+#[derive(Default)]
+struct RecordingPeerDidResolver {
+    inner: PeerDidResolver,
+    resolved: Arc<Mutex<Vec<String>>>,
+}
 
-    let did_peer_resolver = PeerDidResolver::new();
-    let resolver_registry = Arc::new(
+#[async_trait]
+impl DidResolvable for RecordingPeerDidResolver {
+    type ExtraFieldsService = ExtraFieldsSov;
+    type ExtraFieldsOptions = ExtraFieldsOptions;
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
+    ) -> Result<DidResolutionOutput<Self::ExtraFieldsService>, GenericError> {
+        self.resolved.lock().unwrap().push(did.to_string());
+        self.inner.resolve(did, options).await
+    }
+}
+
+fn resolver_registry() -> Arc<ResolverRegistry> {
+    Arc::new(
         ResolverRegistry::new()
-            .register_resolver::<PeerDidResolver>("peer".into(), did_peer_resolver),
-    );
+            .register_resolver::<PeerDidResolver>("peer".into(), PeerDidResolver),
+    )
+}
 
+/// Invites the requester with the given handshake protocols, sends its request and lets the
+/// responder answer, signing with `responder_key` instead of the invitation key if given
+async fn request_and_respond(
+    institution_wallet: &impl BaseWallet,
+    consumer_wallet: &impl BaseWallet,
+    resolver_registry: &Arc<ResolverRegistry>,
+    handshake_versions: &[DidExchangeTypeV1],
+    responder_key: Option<Key>,
+) -> (
+    DidExchangeRequester<RequestSent>,
+    DidExchangeResponder<ResponseSent>,
+    Response,
+) {
     let url: Url = "http://dummyurl.org".parse().unwrap();
 
-    let public_key = generate_keypair(&institution.wallet, KeyType::Ed25519)
+    let public_key = generate_keypair(institution_wallet, KeyType::Ed25519)
         .await
         .unwrap();
     let service = {
@@ -66,17 +110,19 @@ async fn did_exchange_test() {
             .unwrap(),
         )
     };
-    let invitation = OutOfBandSender::create()
-        .append_service(&OobService::SovService(service))
-        .append_handshake_protocol(Protocol::DidExchangeType(DidExchangeType::V1(
-            DidExchangeTypeV1::new_v1_0(),
-        )))
-        .unwrap()
-        .oob
-        .clone();
+    let mut sender = OutOfBandSender::create().append_service(&OobService::SovService(service));
+    for version in handshake_versions {
+        sender = sender
+            .append_handshake_protocol(Protocol::DidExchangeType(DidExchangeType::V1(
+                version.clone(),
+            )))
+            .unwrap();
+    }
+    let invitation = sender.oob.clone();
 
     let invitation_id = invitation.id.clone();
-    let invitation_key = resolve_key_from_invitation(&invitation, &resolver_registry)
+    let version = negotiate_version(&invitation).unwrap();
+    let invitation_key = resolve_key_from_invitation(&invitation, resolver_registry)
         .await
         .unwrap();
 
@@ -84,7 +130,7 @@ async fn did_exchange_test() {
         state: requester,
         output: request,
     } = DidExchangeRequester::<RequestSent>::construct_request_pairwise(
-        &consumer.wallet,
+        consumer_wallet,
         invitation,
         resolver_registry.clone(),
         url.clone(),
@@ -97,23 +143,59 @@ async fn did_exchange_test() {
         state: responder,
         output: response,
     } = DidExchangeResponder::<ResponseSent>::receive_request(
-        &institution.wallet,
-        resolver_registry,
+        institution_wallet,
+        resolver_registry.clone(),
         request,
-        url.clone(),
+        url,
         vec![],
         invitation_id,
-        invitation_key,
+        responder_key.unwrap_or(invitation_key),
+        version,
     )
     .await
     .unwrap();
 
+    (requester, responder, response)
+}
+
+/// The attachment carrying the responder's signature in the given protocol version
+fn signed_attach(response: &mut Response, version: &DidExchangeTypeV1) -> &mut Attachment {
+    match version {
+        DidExchangeTypeV1::V1_0(_) => response.content.did_doc.as_mut(),
+        DidExchangeTypeV1::V1_1(_) => response.content.did_rotate.as_mut(),
+    }
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver_registry = resolver_registry();
+    let url: Url = "http://dummyurl.org".parse().unwrap();
+
+    let (requester, responder, response) = request_and_respond(
+        &institution.wallet,
+        &consumer.wallet,
+        &resolver_registry,
+        &[DidExchangeTypeV1::new_v1_0(), DidExchangeTypeV1::new_v1_1()],
+        None,
+    )
+    .await;
+    assert_eq!(responder.version(), DidExchangeTypeV1::new_v1_1());
+
     let TransitionResult {
         state: requester,
         output: complete,
-    } = requester.receive_response(response).await.unwrap();
+    } = requester
+        .receive_response(&consumer.wallet, resolver_registry.clone(), response)
+        .await
+        .unwrap();
 
     let responder = responder.receive_complete(complete).unwrap();
+    let responder_did = responder.our_did_doc().id().clone();
 
     let responder_key = responder
         .our_did_doc()
@@ -154,4 +236,183 @@ async fn did_exchange_test() {
             .base58(),
         requester_key
     );
+
+    let TransitionResult {
+        state: responder,
+        output: rotation,
+    } = responder
+        .rotate_did(&institution.wallet, url.clone(), vec![])
+        .await
+        .unwrap();
+    assert_ne!(responder.our_did_doc().id(), &responder_did);
+    let requester = requester
+        .receive_did_rotate(&consumer.wallet, resolver_registry.clone(), rotation)
+        .await
+        .unwrap();
+    assert_eq!(requester.their_did_doc().id(), responder.our_did_doc().id());
+
+    // The from_prior JWT names the DID rotated to, so it cannot be redirected to another one
+    let responder_did = responder.our_did_doc().id().clone();
+    let TransitionResult {
+        output: mut rotation,
+        ..
+    } = responder
+        .rotate_did(&institution.wallet, url, vec![])
+        .await
+        .unwrap();
+    rotation.content.did = "did:example:unresolvable".to_owned();
+    let TransitionError {
+        state: requester, ..
+    } = requester
+        .receive_did_rotate(&consumer.wallet, resolver_registry, rotation)
+        .await
+        .unwrap_err();
+    assert_eq!(requester.their_did_doc().id(), &responder_did);
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test_rejects_unsigned_response() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver_registry = resolver_registry();
+
+    for version in [DidExchangeTypeV1::new_v1_0(), DidExchangeTypeV1::new_v1_1()] {
+        let (requester, _, mut response) = request_and_respond(
+            &institution.wallet,
+            &consumer.wallet,
+            &resolver_registry,
+            &[version.clone()],
+            None,
+        )
+        .await;
+        signed_attach(&mut response, &version).data.jws = None;
+        let err = requester
+            .receive_response(&consumer.wallet, resolver_registry.clone(), response)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.kind(), AriesVcxErrorKind::InvalidSignature);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test_rejects_response_without_attachment_not_holding_invitation_key() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver_registry = resolver_registry();
+
+    let (requester, _, mut response) = request_and_respond(
+        &institution.wallet,
+        &consumer.wallet,
+        &resolver_registry,
+        &[DidExchangeTypeV1::new_v1_0()],
+        None,
+    )
+    .await;
+    // The responder's DID resolves, but its document does not hold the invitation key
+    response.content.did_doc = None;
+    let err = requester
+        .receive_response(&consumer.wallet, resolver_registry.clone(), response)
+        .await
+        .unwrap_err();
+    assert_eq!(err.error.kind(), AriesVcxErrorKind::InvalidSignature);
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test_rejects_tampered_response() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver_registry = resolver_registry();
+
+    for version in [DidExchangeTypeV1::new_v1_0(), DidExchangeTypeV1::new_v1_1()] {
+        let (requester, _, mut response) = request_and_respond(
+            &institution.wallet,
+            &consumer.wallet,
+            &resolver_registry,
+            &[version.clone()],
+            None,
+        )
+        .await;
+        // Still a valid document or DID, but not the one that was signed
+        let attach = signed_attach(&mut response, &version);
+        let AttachmentType::Base64(content) = &attach.data.content else {
+            panic!("Signed attachment is not base64 encoded");
+        };
+        let mut payload = URL_SAFE_NO_PAD.decode(content).unwrap();
+        payload.push(b' ');
+        attach.data.content = AttachmentType::Base64(URL_SAFE_NO_PAD.encode(payload));
+        let err = requester
+            .receive_response(&consumer.wallet, resolver_registry.clone(), response)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.kind(), AriesVcxErrorKind::InvalidSignature);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test_rejects_response_signed_with_other_key() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver_registry = resolver_registry();
+
+    for version in [DidExchangeTypeV1::new_v1_0(), DidExchangeTypeV1::new_v1_1()] {
+        let other_key = generate_keypair(&institution.wallet, KeyType::Ed25519)
+            .await
+            .unwrap();
+        let (requester, _, response) = request_and_respond(
+            &institution.wallet,
+            &consumer.wallet,
+            &resolver_registry,
+            &[version],
+            Some(other_key),
+        )
+        .await;
+        let err = requester
+            .receive_response(&consumer.wallet, resolver_registry.clone(), response)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.kind(), AriesVcxErrorKind::InvalidSignature);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn did_exchange_test_resolves_through_registry() {
+    let setup = SetupPoolDirectory::init().await;
+    let institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let consumer = create_test_agent(setup.genesis_file_path).await;
+    let resolver = RecordingPeerDidResolver::default();
+    let resolved = resolver.resolved.clone();
+    let resolver_registry = Arc::new(
+        ResolverRegistry::new()
+            .register_resolver::<RecordingPeerDidResolver>("peer".into(), resolver),
+    );
+
+    let (requester, responder, response) = request_and_respond(
+        &institution.wallet,
+        &consumer.wallet,
+        &resolver_registry,
+        &[DidExchangeTypeV1::new_v1_1()],
+        None,
+    )
+    .await;
+    let requester_did = requester.our_did_doc().id().to_string();
+    assert!(resolved.lock().unwrap().contains(&requester_did));
+
+    let TransitionResult {
+        state: requester, ..
+    } = requester
+        .receive_response(&consumer.wallet, resolver_registry, response)
+        .await
+        .unwrap();
+    let responder_did = responder.our_did_doc().id().to_string();
+    assert!(resolved.lock().unwrap().contains(&responder_did));
+    assert_eq!(requester.their_did_doc().id().to_string(), responder_did);
 }
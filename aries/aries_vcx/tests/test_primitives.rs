@@ -1,16 +1,23 @@
 use std::error::Error;
 
 use aries_vcx::common::primitives::{
-    credential_definition::generate_cred_def, revocation_registry::generate_rev_reg,
+    credential_definition::generate_cred_def,
+    revocation_registry::generate_rev_reg,
+    revocation_registry_manager::{
+        ManagedRegistryStatus, RevocationRegistryManager, RevocationRegistryManagerConfigBuilder,
+    },
 };
 use aries_vcx_core::ledger::{
     base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite},
     indy::pool::test_utils::get_temp_dir_path,
 };
 use serde_json::json;
-use test_utils::{constants::DEFAULT_SCHEMA_ATTRS, devsetup::build_setup_profile};
+use test_utils::{
+    constants::{DEFAULT_SCHEMA_ATTRS, TEST_TAILS_URL},
+    devsetup::build_setup_profile,
+};
 
-use crate::utils::create_and_write_test_schema;
+use crate::utils::{create_and_write_test_cred_def, create_and_write_test_schema};
 
 pub mod utils;
 
@@ -116,3 +123,98 @@ async fn test_pool_create_rev_reg_def() -> Result<(), Box<dyn Error>> {
         .await?;
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_pool_rev_reg_manager_rotates_full_registry() -> Result<(), Box<dyn Error>> {
+    let setup = build_setup_profile().await;
+    let schema = create_and_write_test_schema(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        &setup.institution_did,
+        DEFAULT_SCHEMA_ATTRS,
+    )
+    .await;
+    let cred_def = create_and_write_test_cred_def(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_read,
+        &setup.ledger_write,
+        &setup.institution_did,
+        &schema.schema_id,
+        true,
+    )
+    .await;
+    let config = RevocationRegistryManagerConfigBuilder::default()
+        .issuer_did(setup.institution_did.clone())
+        .cred_def_id(cred_def.get_cred_def_id())
+        .tails_dir(get_temp_dir_path().to_str().unwrap())
+        .tails_base_url(TEST_TAILS_URL)
+        .max_creds(2u32)
+        .build()?;
+
+    let mut manager = RevocationRegistryManager::init(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        config.clone(),
+    )
+    .await?;
+    let first_rev_reg_id = manager.active_rev_reg_id().unwrap();
+    assert_eq!(
+        manager
+            .prepare_standby(&setup.wallet, &setup.anoncreds, &setup.ledger_write)
+            .await?,
+        None
+    );
+
+    let mut standby_rev_reg_id = None;
+    for _ in 0..2 {
+        let slot = manager
+            .reserve_issuance(&setup.wallet, &setup.anoncreds, &setup.ledger_write)
+            .await?;
+        assert_eq!(slot.rev_reg_id, first_rev_reg_id);
+        if let Some(rev_reg_id) = manager
+            .prepare_standby(&setup.wallet, &setup.anoncreds, &setup.ledger_write)
+            .await?
+        {
+            standby_rev_reg_id = Some(rev_reg_id);
+        }
+    }
+    assert_eq!(manager.remaining_in_active(), 0);
+    let standby_rev_reg_id = standby_rev_reg_id.unwrap();
+    assert_eq!(
+        manager.get_status(&standby_rev_reg_id)?,
+        ManagedRegistryStatus::Standby
+    );
+
+    let slot = manager
+        .reserve_issuance(&setup.wallet, &setup.anoncreds, &setup.ledger_write)
+        .await?;
+    assert_eq!(slot.rev_reg_id, standby_rev_reg_id);
+    assert_eq!(
+        manager.get_status(&first_rev_reg_id)?,
+        ManagedRegistryStatus::Full
+    );
+
+    let mut manager = RevocationRegistryManager::init(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        config,
+    )
+    .await?;
+    assert_eq!(manager.active_rev_reg_id(), Some(slot.rev_reg_id.clone()));
+    assert_eq!(manager.remaining_in_active(), 1);
+
+    manager
+        .release_issuance(&setup.wallet, &slot.rev_reg_id)
+        .await?;
+    assert_eq!(manager.remaining_in_active(), 2);
+    assert!(manager
+        .release_issuance(&setup.wallet, &slot.rev_reg_id)
+        .await
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Identifiers of anoncreds objects. Objects of unqualified (legacy Indy) issuer DIDs keep the
+//! legacy identifiers, while objects of any other DID method are identified by
+//! `<issuer DID>/anoncreds/v0/<object type>/...` URIs, following the `did:indy` scheme.
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+const LEGACY_SCHEMA_MARKER: &str = "2";
+const LEGACY_CRED_DEF_MARKER: &str = "3";
+const LEGACY_REV_REG_MARKER: &str = "4";
+const URI_PATH_PREFIX: &str = "/anoncreds/v0/";
+const URI_SCHEMA_TYPE: &str = "SCHEMA";
+const URI_CRED_DEF_TYPE: &str = "CLAIM_DEF";
+const URI_REV_REG_TYPE: &str = "REV_REG_DEF";
+
+fn is_legacy_did(did: &str) -> bool {
+    !did.starts_with("did:")
+}
+
+pub fn make_schema_id(issuer_did: &str, name: &str, version: &str) -> String {
+    if is_legacy_did(issuer_did) {
+        format!("{issuer_did}:{LEGACY_SCHEMA_MARKER}:{name}:{version}")
+    } else {
+        format!("{issuer_did}{URI_PATH_PREFIX}{URI_SCHEMA_TYPE}/{name}/{version}")
+    }
+}
+
+/// Schemas are referred to by their ledger sequence number where the ledger assigns one, and by
+/// their ID otherwise.
+pub fn make_cred_def_id(
+    issuer_did: &str,
+    schema_id: &str,
+    schema_seq_no: Option<u64>,
+    tag: &str,
+) -> String {
+    let schema_ref = schema_seq_no.map_or_else(|| schema_id.to_owned(), |no| no.to_string());
+    if is_legacy_did(issuer_did) {
+        format!("{issuer_did}:{LEGACY_CRED_DEF_MARKER}:CL:{schema_ref}:{tag}")
+    } else {
+        format!("{issuer_did}{URI_PATH_PREFIX}{URI_CRED_DEF_TYPE}/{schema_ref}/{tag}")
+    }
+}
+
+pub fn make_rev_reg_id(issuer_did: &str, cred_def_id: &str, tag: &str) -> String {
+    if is_legacy_did(issuer_did) {
+        format!("{issuer_did}:{LEGACY_REV_REG_MARKER}:{cred_def_id}:CL_ACCUM:{tag}")
+    } else {
+        let cred_def_path = cred_def_id.replacen(
+            &format!("/{URI_CRED_DEF_TYPE}/"),
+            &format!("/{URI_REV_REG_TYPE}/"),
+            1,
+        );
+        format!("{cred_def_path}/{tag}")
+    }
+}
+
+/// The issuer of an object is the DID its URI is rooted at, or the DID its legacy ID starts with.
+pub fn issuer_id_from_object_id(id: &str) -> VcxCoreResult<String> {
+    let issuer_id = match id.split_once('/') {
+        Some((did, _)) => did,
+        None => id.split(':').next().unwrap_or_default(),
+    };
+    if issuer_id.is_empty() {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            format!("Unable to determine the issuer of anoncreds object {id}"),
+        ));
+    }
+    Ok(issuer_id.to_owned())
+}
+
+/// Returns the issuer DID, name and version of a schema, if its ID follows a known scheme
+pub fn schema_parts(schema_id: &str) -> Option<(&str, &str, &str)> {
+    if let Some((did, path)) = schema_id.split_once(URI_PATH_PREFIX) {
+        let mut segments = path.split('/');
+        return match (segments.next(), segments.next(), segments.next()) {
+            (Some(URI_SCHEMA_TYPE), Some(name), Some(version)) => Some((did, name, version)),
+            _ => None,
+        };
+    }
+    match schema_id.split(':').collect::<Vec<_>>()[..] {
+        [did, LEGACY_SCHEMA_MARKER, name, version] => Some((did, name, version)),
+        _ => None,
+    }
+}
+
+/// Returns the schema sequence number referenced by a legacy credential definition ID
+pub fn legacy_cred_def_schema_ref(cred_def_id: &str) -> Option<&str> {
+    match cred_def_id.splitn(5, ':').collect::<Vec<_>>()[..] {
+        [_, LEGACY_CRED_DEF_MARKER, _, schema_ref, _] => Some(schema_ref),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const INDY_DID: &str = "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f";
+
+    #[test]
+    fn test_legacy_identifiers() {
+        let schema_id = make_schema_id(LEGACY_DID, "degree", "1.0");
+        assert_eq!(schema_id, "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0");
+        assert_eq!(
+            schema_parts(&schema_id),
+            Some((LEGACY_DID, "degree", "1.0"))
+        );
+
+        let cred_def_id = make_cred_def_id(LEGACY_DID, &schema_id, Some(15), "tag1");
+        assert_eq!(cred_def_id, "V4SGRU86Z58d6TV7PBUe6f:3:CL:15:tag1");
+        assert_eq!(legacy_cred_def_schema_ref(&cred_def_id), Some("15"));
+
+        let rev_reg_id = make_rev_reg_id(LEGACY_DID, &cred_def_id, "tag2");
+        assert_eq!(
+            rev_reg_id,
+            "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:15:tag1:CL_ACCUM:tag2"
+        );
+        assert_eq!(issuer_id_from_object_id(&rev_reg_id).unwrap(), LEGACY_DID);
+    }
+
+    #[test]
+    fn test_uri_identifiers() {
+        let schema_id = make_schema_id(INDY_DID, "degree", "1.0");
+        assert_eq!(
+            schema_id,
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/SCHEMA/degree/1.0"
+        );
+        assert_eq!(schema_parts(&schema_id), Some((INDY_DID, "degree", "1.0")));
+
+        let cred_def_id = make_cred_def_id(INDY_DID, &schema_id, Some(15), "tag1");
+        assert_eq!(
+            cred_def_id,
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/CLAIM_DEF/15/tag1"
+        );
+        assert_eq!(legacy_cred_def_schema_ref(&cred_def_id), None);
+
+        let rev_reg_id = make_rev_reg_id(INDY_DID, &cred_def_id, "tag2");
+        assert_eq!(
+            rev_reg_id,
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/REV_REG_DEF/15/tag1/tag2"
+        );
+        assert_eq!(issuer_id_from_object_id(&rev_reg_id).unwrap(), INDY_DID);
+    }
+}
@@ -0,0 +1,1489 @@
+mod identifiers;
+pub mod type_conversion;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anoncreds::{
+    data_types::{
+        cred_def::CredentialDefinitionId,
+        issuer_id::IssuerId,
+        rev_reg_def::RevocationRegistryDefinitionId,
+        schema::{AttributeNames, SchemaId},
+    },
+    tails::TailsFileWriter,
+    types::{
+        Credential, CredentialDefinition, CredentialDefinitionConfig, CredentialDefinitionPrivate,
+        CredentialKeyCorrectnessProof, CredentialOffer, CredentialRequest,
+        CredentialRequestMetadata, CredentialRevocationConfig, CredentialRevocationState,
+        CredentialValues, LinkSecret, PresentCredentials, Presentation, PresentationRequest,
+        RegistryType, RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate,
+        SignatureType,
+    },
+};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use self::{
+    identifiers::{
+        legacy_cred_def_schema_ref, make_cred_def_id, make_rev_reg_id, make_schema_id, schema_parts,
+    },
+    type_conversion::{
+        cred_def_from_ledger, cred_def_to_ledger, map_from_ledger, rev_reg_def_from_ledger,
+        rev_reg_def_to_ledger, revocation_status_list, schema_from_ledger, schema_to_ledger,
+        status_list_accumulator, LedgerRevocationRegistry, LedgerRevocationRegistryDelta,
+    },
+};
+use super::base_anoncreds::BaseAnonCreds;
+use crate::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    utils::{
+        constants::ATTRS,
+        json::{AsTypeOrDeserializationError, TryGetIndex},
+    },
+    wallet::base_wallet::{AsyncFnIteratorCollect, BaseWallet},
+};
+
+// The record categories are shared with `IndyCredxAnonCreds`, whose records are converted in
+// place by the `credx2anoncreds` wallet migration.
+pub const CATEGORY_LINK_SECRET: &str = "VCX_LINK_SECRET";
+
+pub const CATEGORY_CREDENTIAL: &str = "VCX_CREDENTIAL";
+pub const CATEGORY_CRED_DEF: &str = "VCX_CRED_DEF";
+pub const CATEGORY_CRED_KEY_CORRECTNESS_PROOF: &str = "VCX_CRED_KEY_CORRECTNESS_PROOF";
+pub const CATEGORY_CRED_DEF_PRIV: &str = "VCX_CRED_DEF_PRIV";
+pub const CATEGORY_CRED_SCHEMA: &str = "VCX_CRED_SCHEMA";
+
+// Category used for mapping a cred_def_id to a schema_id
+pub const CATEGORY_CRED_MAP_SCHEMA_ID: &str = "VCX_CRED_MAP_SCHEMA_ID";
+
+pub const CATEGORY_REV_REG: &str = "VCX_REV_REG";
+pub const CATEGORY_REV_REG_DELTA: &str = "VCX_REV_REG_DELTA";
+pub const CATEGORY_REV_REG_INFO: &str = "VCX_REV_REG_INFO";
+pub const CATEGORY_REV_REG_DEF: &str = "VCX_REV_REG_DEF";
+pub const CATEGORY_REV_REG_DEF_PRIV: &str = "VCX_REV_REG_DEF_PRIV";
+
+/// Revocation indices used so far in a registry. As credentials are issued by default,
+/// `used_ids` holds the indices of the revoked credentials.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RevocationRegistryInfo {
+    pub id: RevocationRegistryDefinitionId,
+    pub curr_id: u32,
+    pub used_ids: HashSet<u32>,
+}
+
+/// [`BaseAnonCreds`] implemented on the hyperledger `anoncreds-rs` library.
+///
+/// Inputs and outputs keep the JSON formats of the legacy Indy ledger, so that this is a drop-in
+/// replacement of `IndyCredxAnonCreds`, while wallet records hold the `anoncreds-rs` objects.
+/// Identifiers are method-agnostic: objects of issuers with a qualified DID get URI identifiers.
+#[derive(Debug, Copy, Clone)]
+pub struct AnoncredsRsAnonCreds;
+
+impl AnoncredsRsAnonCreds {
+    async fn get_wallet_record_value<T>(
+        wallet: &impl BaseWallet,
+        category: &str,
+        id: &str,
+    ) -> VcxCoreResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let str_record = wallet.get_wallet_record_value(category, id).await?;
+        serde_json::from_str(&str_record).map_err(From::from)
+    }
+
+    async fn get_link_secret(
+        wallet: &impl BaseWallet,
+        link_secret_id: &str,
+    ) -> VcxCoreResult<LinkSecret> {
+        let ms_decimal = wallet
+            .get_wallet_record_value(CATEGORY_LINK_SECRET, link_secret_id)
+            .await?;
+
+        LinkSecret::try_from(ms_decimal.as_str()).map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::UrsaError,
+                format!("Failed to create link secret from decimal string: {err:?}"),
+            )
+        })
+    }
+
+    async fn _get_credential(
+        wallet: &impl BaseWallet,
+        credential_id: &str,
+    ) -> VcxCoreResult<Credential> {
+        Self::get_wallet_record_value(wallet, CATEGORY_CREDENTIAL, credential_id).await
+    }
+
+    async fn _get_credentials(
+        wallet: &impl BaseWallet,
+        wql: &str,
+    ) -> VcxCoreResult<Vec<(String, Credential)>> {
+        let mut record_iterator = wallet
+            .iterate_wallet_records(CATEGORY_CREDENTIAL, wql, "{}")
+            .await?;
+        let records = record_iterator.collect().await?;
+
+        records
+            .iter()
+            .map(|record| {
+                let cred_record: Value = serde_json::from_str(record)?;
+
+                let cred_record_id = (&cred_record).try_get("id")?.try_as_str()?.to_string();
+                let cred_json = (&cred_record).try_get("value")?.try_as_str()?;
+
+                let credential: Credential = serde_json::from_str(cred_json)?;
+
+                Ok((cred_record_id, credential))
+            })
+            .collect()
+    }
+
+    async fn _get_credentials_for_proof_req_for_attr_name(
+        &self,
+        wallet: &impl BaseWallet,
+        restrictions: Option<&Value>,
+        attr_names: Vec<String>,
+    ) -> VcxCoreResult<Vec<(String, Credential)>> {
+        let mut attrs: Vec<Value> = attr_names
+            .iter()
+            .map(|name| json!({ _format_attribute_as_marker_tag_name(name): "1" }))
+            .collect();
+
+        match restrictions {
+            Some(Value::Array(restrictions)) => attrs.push(json!({ "$or": restrictions })),
+            Some(Value::Object(restriction)) => attrs.push(Value::Object(restriction.clone())),
+            Some(_) => {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    "Invalid attribute restrictions (must be array or an object)",
+                ))
+            }
+            None => {}
+        }
+
+        let wql_query = serde_json::to_string(&json!({ "$and": attrs }))?;
+
+        Self::_get_credentials(wallet, &wql_query).await
+    }
+}
+
+#[async_trait]
+impl BaseAnonCreds for AnoncredsRsAnonCreds {
+    async fn verifier_verify_proof(
+        &self,
+        proof_req_json: &str,
+        proof_json: &str,
+        schemas_json: &str,
+        credential_defs_json: &str,
+        rev_reg_defs_json: &str,
+        rev_regs_json: &str,
+    ) -> VcxCoreResult<bool> {
+        let presentation: Presentation = serde_json::from_str(proof_json)?;
+        let pres_req: PresentationRequest = serde_json::from_str(proof_req_json)?;
+
+        let schemas = map_from_ledger(serde_json::from_str(schemas_json)?, schema_from_ledger)?;
+        let cred_defs = map_from_ledger(
+            serde_json::from_str(credential_defs_json)?,
+            cred_def_from_ledger,
+        )?;
+
+        let rev_reg_defs = serde_json::from_str::<Option<_>>(rev_reg_defs_json)?
+            .map(|rev_reg_defs| map_from_ledger(rev_reg_defs, rev_reg_def_from_ledger))
+            .transpose()?;
+
+        let rev_regs: Option<
+            HashMap<RevocationRegistryDefinitionId, HashMap<u64, LedgerRevocationRegistry>>,
+        > = serde_json::from_str(rev_regs_json)?;
+
+        // The verifier only needs the accumulators of the registries at the given timestamps
+        let rev_status_lists = match (&rev_reg_defs, rev_regs) {
+            (Some(rev_reg_defs), Some(rev_regs)) => {
+                let mut rev_status_lists = Vec::new();
+                for (rev_reg_id, rev_regs) in rev_regs {
+                    let rev_reg_def = rev_reg_defs.get(&rev_reg_id).ok_or_else(|| {
+                        AriesVcxCoreError::from_msg(
+                            AriesVcxCoreErrorKind::InvalidJson,
+                            format!(
+                                "No revocation registry definition provided for {}",
+                                rev_reg_id.0
+                            ),
+                        )
+                    })?;
+                    for (timestamp, rev_reg) in rev_regs {
+                        rev_status_lists.push(revocation_status_list(
+                            &rev_reg_id,
+                            rev_reg_def,
+                            &HashSet::new(),
+                            &rev_reg.value.accum,
+                            Some(timestamp),
+                        )?);
+                    }
+                }
+                Some(rev_status_lists)
+            }
+            _ => None,
+        };
+
+        Ok(anoncreds::verifier::verify_presentation(
+            &presentation,
+            &pres_req,
+            &schemas,
+            &cred_defs,
+            rev_reg_defs.as_ref(),
+            rev_status_lists,
+            None,
+        )?)
+    }
+
+    async fn issuer_create_and_store_revoc_reg(
+        &self,
+        wallet: &impl BaseWallet,
+        issuer_did: &str,
+        cred_def_id: &str,
+        tails_dir: &str,
+        max_creds: u32,
+        tag: &str,
+    ) -> VcxCoreResult<(String, String, String)> {
+        let rev_reg_id = make_rev_reg_id(issuer_did, cred_def_id, tag);
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked(rev_reg_id.clone());
+
+        let res_rev_reg = wallet
+            .get_wallet_record_value(CATEGORY_REV_REG, &rev_reg_id)
+            .await;
+        let res_rev_reg_def: VcxCoreResult<RevocationRegistryDefinition> =
+            Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_DEF, &rev_reg_id).await;
+
+        if let (Ok(rev_reg), Ok(rev_reg_def)) = (res_rev_reg, res_rev_reg_def) {
+            let rev_reg_def = rev_reg_def_to_ledger(&rev_reg_def_id, &rev_reg_def)?;
+            return Ok((rev_reg_id, rev_reg_def.to_string(), rev_reg));
+        }
+
+        let cred_def: CredentialDefinition =
+            Self::get_wallet_record_value(wallet, CATEGORY_CRED_DEF, cred_def_id).await?;
+
+        let mut tails_writer = TailsFileWriter::new(Some(tails_dir.to_owned()));
+
+        let (rev_reg_def, rev_reg_def_priv) = anoncreds::issuer::create_revocation_registry_def(
+            &cred_def,
+            CredentialDefinitionId::new_unchecked(cred_def_id),
+            tag,
+            RegistryType::CL_ACCUM,
+            max_creds,
+            &mut tails_writer,
+        )?;
+
+        let status_list = anoncreds::issuer::create_revocation_status_list(
+            &cred_def,
+            rev_reg_def_id.clone(),
+            &rev_reg_def,
+            &rev_reg_def_priv,
+            true,
+            None,
+        )?;
+        let rev_reg = LedgerRevocationRegistry::new(status_list_accumulator(&status_list)?);
+
+        // Store stuff in wallet
+        let rev_reg_info = RevocationRegistryInfo {
+            id: rev_reg_def_id.clone(),
+            curr_id: 0,
+            used_ids: HashSet::new(),
+        };
+
+        let str_rev_reg_info = serde_json::to_string(&rev_reg_info)?;
+
+        wallet
+            .add_wallet_record(CATEGORY_REV_REG_INFO, &rev_reg_id, &str_rev_reg_info, None)
+            .await?;
+
+        let str_rev_reg_def = serde_json::to_string(&rev_reg_def)?;
+
+        wallet
+            .add_wallet_record(CATEGORY_REV_REG_DEF, &rev_reg_id, &str_rev_reg_def, None)
+            .await?;
+
+        let str_rev_reg_def_priv = serde_json::to_string(&rev_reg_def_priv)?;
+
+        wallet
+            .add_wallet_record(
+                CATEGORY_REV_REG_DEF_PRIV,
+                &rev_reg_id,
+                &str_rev_reg_def_priv,
+                None,
+            )
+            .await?;
+
+        let str_rev_reg = serde_json::to_string(&rev_reg)?;
+
+        wallet
+            .add_wallet_record(CATEGORY_REV_REG, &rev_reg_id, &str_rev_reg, None)
+            .await?;
+
+        let rev_reg_def = rev_reg_def_to_ledger(&rev_reg_def_id, &rev_reg_def)?;
+
+        Ok((rev_reg_id, rev_reg_def.to_string(), str_rev_reg))
+    }
+
+    async fn issuer_create_and_store_credential_def(
+        &self,
+        wallet: &impl BaseWallet,
+        issuer_did: &str,
+        schema_json: &str,
+        tag: &str,
+        sig_type: Option<&str>,
+        config_json: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let schema: Value = serde_json::from_str(schema_json)?;
+        let schema_seq_no = schema.get("seqNo").and_then(Value::as_u64);
+        let (schema_id, schema) = schema_from_ledger(schema)?;
+        let sig_type = sig_type
+            .map(serde_json::from_str)
+            .unwrap_or(Ok(SignatureType::CL))?;
+        let config: CredentialDefinitionConfig = serde_json::from_str(config_json)?;
+
+        let cred_def_id = CredentialDefinitionId::new_unchecked(make_cred_def_id(
+            issuer_did,
+            &schema_id.0,
+            schema_seq_no,
+            tag,
+        ));
+
+        // If cred def already exists, return it
+        if let Ok(cred_def) = Self::get_wallet_record_value::<CredentialDefinition>(
+            wallet,
+            CATEGORY_CRED_DEF,
+            &cred_def_id.0,
+        )
+        .await
+        {
+            let cred_def = cred_def_to_ledger(&cred_def_id, &cred_def)?;
+            return Ok((cred_def_id.0, cred_def.to_string()));
+        }
+
+        // Otherwise, create cred def
+        let (cred_def, cred_def_priv, cred_key_correctness_proof) =
+            anoncreds::issuer::create_credential_definition(
+                schema_id.clone(),
+                &schema,
+                issuer_id(issuer_did)?,
+                tag,
+                sig_type,
+                config,
+            )?;
+
+        let str_cred_def = serde_json::to_string(&cred_def)?;
+
+        // Store stuff in wallet
+        wallet
+            .add_wallet_record(CATEGORY_CRED_DEF, &cred_def_id.0, &str_cred_def, None)
+            .await?;
+
+        let str_cred_def_priv = serde_json::to_string(&cred_def_priv)?;
+
+        wallet
+            .add_wallet_record(
+                CATEGORY_CRED_DEF_PRIV,
+                &cred_def_id.0,
+                &str_cred_def_priv,
+                None,
+            )
+            .await?;
+
+        let str_cred_key_proof = serde_json::to_string(&cred_key_correctness_proof)?;
+
+        wallet
+            .add_wallet_record(
+                CATEGORY_CRED_KEY_CORRECTNESS_PROOF,
+                &cred_def_id.0,
+                &str_cred_key_proof,
+                None,
+            )
+            .await?;
+
+        let str_schema = serde_json::to_string(&schema)?;
+        let store_schema_res = wallet
+            .add_wallet_record(CATEGORY_CRED_SCHEMA, &schema_id.0, &str_schema, None)
+            .await;
+
+        if let Err(e) = store_schema_res {
+            warn!("Storing schema {schema_json} failed - {e}. It's possible it is already stored.")
+        }
+
+        wallet
+            .add_wallet_record(
+                CATEGORY_CRED_MAP_SCHEMA_ID,
+                &cred_def_id.0,
+                &schema_id.0,
+                None,
+            )
+            .await?;
+
+        let cred_def = cred_def_to_ledger(&cred_def_id, &cred_def)?;
+
+        Ok((cred_def_id.0, cred_def.to_string()))
+    }
+
+    async fn issuer_create_credential_offer(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_id: &str,
+    ) -> VcxCoreResult<String> {
+        let correctness_proof: CredentialKeyCorrectnessProof =
+            Self::get_wallet_record_value(wallet, CATEGORY_CRED_KEY_CORRECTNESS_PROOF, cred_def_id)
+                .await?;
+
+        let schema_id = wallet
+            .get_wallet_record_value(CATEGORY_CRED_MAP_SCHEMA_ID, cred_def_id)
+            .await?;
+
+        let offer = anoncreds::issuer::create_credential_offer(
+            SchemaId::new_unchecked(schema_id),
+            CredentialDefinitionId::new_unchecked(cred_def_id),
+            &correctness_proof,
+        )?;
+
+        serde_json::to_string(&offer).map_err(From::from)
+    }
+
+    async fn issuer_create_credential(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_offer_json: &str,
+        cred_req_json: &str,
+        cred_values_json: &str,
+        rev_reg_id: Option<String>,
+        tails_dir: Option<String>,
+    ) -> VcxCoreResult<(String, Option<String>, Option<String>)> {
+        let cred_offer: CredentialOffer = serde_json::from_str(cred_offer_json)?;
+        let cred_request: CredentialRequest = serde_json::from_str(cred_req_json)?;
+        let cred_values: CredentialValues = serde_json::from_str(cred_values_json)?;
+
+        let cred_def_id = &cred_offer.cred_def_id.0;
+
+        let cred_def: CredentialDefinition =
+            Self::get_wallet_record_value(wallet, CATEGORY_CRED_DEF, cred_def_id).await?;
+
+        let cred_def_private: CredentialDefinitionPrivate =
+            Self::get_wallet_record_value(wallet, CATEGORY_CRED_DEF_PRIV, cred_def_id).await?;
+
+        let revocation_config_parts = match &rev_reg_id {
+            Some(rev_reg_id) => {
+                let rev_reg_def: RevocationRegistryDefinition =
+                    Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+
+                let rev_reg_def_priv: RevocationRegistryDefinitionPrivate =
+                    Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_DEF_PRIV, rev_reg_id)
+                        .await?;
+
+                let rev_reg: LedgerRevocationRegistry =
+                    Self::get_wallet_record_value(wallet, CATEGORY_REV_REG, rev_reg_id).await?;
+
+                let mut rev_reg_info: RevocationRegistryInfo =
+                    Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_INFO, rev_reg_id)
+                        .await?;
+
+                rev_reg_info.curr_id += 1;
+
+                // Revocation indices address the registry's status list of max_cred_num entries
+                if rev_reg_info.curr_id >= rev_reg_def.value.max_cred_num {
+                    return Err(AriesVcxCoreError::from_msg(
+                        AriesVcxCoreErrorKind::ActionNotSupported,
+                        "The revocation registry is full",
+                    ));
+                }
+
+                let status_list = revocation_status_list(
+                    &rev_reg_info.id,
+                    &rev_reg_def,
+                    &rev_reg_info.used_ids,
+                    &rev_reg.value.accum,
+                    None,
+                )?;
+
+                Some((rev_reg_def, rev_reg_def_priv, status_list, rev_reg_info))
+            }
+            None => {
+                warn!(
+                    "Missing revocation config params: tails_dir: {tails_dir:?} - {rev_reg_id:?}; \
+                     Issuing non revokable credential"
+                );
+                None
+            }
+        };
+
+        let revocation_config = revocation_config_parts.as_ref().map(
+            |(rev_reg_def, rev_reg_def_priv, status_list, rev_reg_info)| {
+                CredentialRevocationConfig {
+                    reg_def: rev_reg_def,
+                    reg_def_private: rev_reg_def_priv,
+                    status_list,
+                    registry_idx: rev_reg_info.curr_id,
+                }
+            },
+        );
+
+        let cred = anoncreds::issuer::create_credential(
+            &cred_def,
+            &cred_def_private,
+            &cred_offer,
+            &cred_request,
+            cred_values,
+            revocation_config,
+        )?;
+
+        let cred_rev_id = if let (Some(rev_reg_id), Some((_, _, _, rev_reg_info))) =
+            (rev_reg_id, revocation_config_parts)
+        {
+            let str_rev_reg_info = serde_json::to_string(&rev_reg_info)?;
+
+            wallet
+                .update_wallet_record_value(CATEGORY_REV_REG_INFO, &rev_reg_id, &str_rev_reg_info)
+                .await?;
+
+            Some(rev_reg_info.curr_id.to_string())
+        } else {
+            None
+        };
+
+        let str_cred = serde_json::to_string(&cred)?;
+
+        // Issuance by default leaves the revocation registry unchanged, so there is no delta
+        Ok((str_cred, cred_rev_id, None))
+    }
+
+    /// * `requested_credentials_json`: either a credential or self-attested attribute for each
+    ///   requested attribute { "self_attested_attributes": { "self_attested_attribute_referent":
+    ///   string }, "requested_attributes": { "requested_attribute_referent_1": {"cred_id": string,
+    ///   "timestamp": Optional<number>, revealed: <bool> }}, "requested_attribute_referent_2":
+    ///   {"cred_id": string, "timestamp": Optional<number>, revealed: <bool> }} },
+    ///   "requested_predicates": { "requested_predicates_referent_1": {"cred_id": string,
+    ///   "timestamp": Optional<number> }}, } }
+    async fn prover_create_proof(
+        &self,
+        wallet: &impl BaseWallet,
+        proof_req_json: &str,
+        requested_credentials_json: &str,
+        link_secret_id: &str,
+        schemas_json: &str,
+        credential_defs_json: &str,
+        revoc_states_json: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        let pres_req: PresentationRequest = serde_json::from_str(proof_req_json)?;
+
+        let requested_credentials: Value = serde_json::from_str(requested_credentials_json)?;
+        let requested_attributes = (&requested_credentials).try_get("requested_attributes")?;
+
+        let requested_predicates = (&requested_credentials).try_get("requested_predicates")?;
+        let self_attested_attributes = requested_credentials.get("self_attested_attributes");
+
+        let rev_states: Option<Value> = revoc_states_json.map(serde_json::from_str).transpose()?;
+
+        let schemas = map_from_ledger(serde_json::from_str(schemas_json)?, schema_from_ledger)?;
+        let cred_defs = map_from_ledger(
+            serde_json::from_str(credential_defs_json)?,
+            cred_def_from_ledger,
+        )?;
+
+        let mut present_credentials = PresentCredentials::default();
+
+        let mut proof_details_by_cred_id: HashMap<String, ProofDetails> = HashMap::new();
+
+        // add cred data and referent details for each requested attribute
+        for (reft, detail) in requested_attributes.try_as_object()?.iter() {
+            let cred_id = detail.try_get("cred_id")?.try_as_str()?;
+            let revealed = detail.try_get("revealed")?.try_as_bool()?;
+
+            if let Some(proof_details) = proof_details_by_cred_id.get_mut(cred_id) {
+                // mapping made for this credential already, add reft and its revealed status
+                proof_details
+                    .req_attr_refts_revealed
+                    .push((reft.to_string(), revealed));
+            } else {
+                let credential = Self::_get_credential(wallet, cred_id).await?;
+
+                let (timestamp, rev_state) =
+                    get_rev_state(cred_id, &credential, detail, rev_states.as_ref())?;
+
+                proof_details_by_cred_id.insert(
+                    cred_id.to_string(),
+                    ProofDetails {
+                        credential,
+                        timestamp,
+                        rev_state,
+                        req_attr_refts_revealed: vec![(reft.to_string(), revealed)],
+                        req_preds_refts: vec![],
+                    },
+                );
+            }
+        }
+
+        // add cred data and referent details for each requested predicate
+        for (reft, detail) in requested_predicates.try_as_object()?.iter() {
+            let cred_id = detail.try_get("cred_id")?.try_as_str()?;
+
+            if let Some(proof_details) = proof_details_by_cred_id.get_mut(cred_id) {
+                // mapping made for this credential already, add reft
+                proof_details.req_preds_refts.push(reft.to_string());
+            } else {
+                let credential = Self::_get_credential(wallet, cred_id).await?;
+
+                let (timestamp, rev_state) =
+                    get_rev_state(cred_id, &credential, detail, rev_states.as_ref())?;
+
+                proof_details_by_cred_id.insert(
+                    cred_id.to_string(),
+                    ProofDetails {
+                        credential,
+                        timestamp,
+                        rev_state,
+                        req_attr_refts_revealed: vec![],
+                        req_preds_refts: vec![reft.to_string()],
+                    },
+                );
+            }
+        }
+
+        // add all accumulated requested attributes and requested predicates to the
+        // [PresentCredentials] object
+        for proof_details in proof_details_by_cred_id.values() {
+            let mut add_cred = present_credentials.add_credential(
+                &proof_details.credential,
+                proof_details.timestamp,
+                proof_details.rev_state.as_ref(),
+            );
+
+            for (referent, revealed) in &proof_details.req_attr_refts_revealed {
+                add_cred.add_requested_attribute(referent, *revealed);
+            }
+
+            for referent in &proof_details.req_preds_refts {
+                add_cred.add_requested_predicate(referent);
+            }
+        }
+
+        // create self_attested by iterating thru self_attested_value
+        let self_attested = match self_attested_attributes {
+            Some(self_attested_value) => {
+                let mut self_attested_map: HashMap<String, String> = HashMap::new();
+                for (k, v) in self_attested_value.try_as_object()?.iter() {
+                    self_attested_map.insert(k.to_string(), v.try_as_str()?.to_string());
+                }
+                Some(self_attested_map).filter(|map| !map.is_empty())
+            }
+            None => None,
+        };
+
+        let link_secret = Self::get_link_secret(wallet, link_secret_id).await?;
+
+        let presentation = anoncreds::prover::create_presentation(
+            &pres_req,
+            present_credentials,
+            self_attested,
+            &link_secret,
+            &schemas,
+            &cred_defs,
+        )?;
+
+        Ok(serde_json::to_string(&presentation)?)
+    }
+
+    async fn prover_get_credential(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_id: &str,
+    ) -> VcxCoreResult<String> {
+        let cred = Self::_get_credential(wallet, cred_id).await?;
+
+        let cred_info = _make_cred_info(cred_id, &cred)?;
+
+        Ok(serde_json::to_string(&cred_info)?)
+    }
+
+    async fn prover_get_credentials(
+        &self,
+        wallet: &impl BaseWallet,
+        filter_json: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        // filter_json should map to WQL query directly
+        let creds_wql = filter_json.unwrap_or("{}");
+        let creds = Self::_get_credentials(wallet, creds_wql).await?;
+
+        let cred_info_list = creds
+            .iter()
+            .map(|(credential_id, cred)| _make_cred_info(credential_id, cred))
+            .collect::<VcxCoreResult<Vec<Value>>>()?;
+
+        Ok(serde_json::to_string(&cred_info_list)?)
+    }
+
+    async fn prover_get_credentials_for_proof_req(
+        &self,
+        wallet: &impl BaseWallet,
+        proof_req: &str,
+    ) -> VcxCoreResult<String> {
+        let proof_req_v: Value = serde_json::from_str(proof_req).map_err(|e| {
+            AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidProofRequest, e)
+        })?;
+
+        let requested_attributes = proof_req_v
+            .get("requested_attributes")
+            .map(|attrs| attrs.try_as_object().cloned())
+            .transpose()?;
+        let requested_predicates = proof_req_v
+            .get("requested_predicates")
+            .map(|preds| preds.try_as_object().cloned())
+            .transpose()?;
+
+        // handle special case of "empty because json is bad" vs "empty because no attributes
+        // sepected"
+        if requested_attributes.is_none() && requested_predicates.is_none() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidAttributesStructure,
+                "Invalid Json Parsing of Requested Attributes Retrieved From Proof Request",
+            ));
+        }
+
+        let referents: HashSet<&String> = requested_attributes
+            .iter()
+            .chain(requested_predicates.iter())
+            .flat_map(|requested| requested.keys())
+            .collect();
+
+        let mut cred_by_attr: Value = json!({});
+
+        for reft in referents {
+            let requested_val = requested_attributes
+                .as_ref()
+                .and_then(|req_attrs| req_attrs.get(reft))
+                .or_else(|| {
+                    requested_predicates
+                        .as_ref()
+                        .and_then(|req_preds| req_preds.get(reft))
+                })
+                .ok_or(AriesVcxCoreError::from_msg(
+                    // should not happen
+                    AriesVcxCoreErrorKind::InvalidState,
+                    format!("Unknown referent: {}", reft),
+                ))?;
+
+            let name = requested_val.get("name");
+            let names = requested_val.get("names").and_then(|v| v.as_array());
+
+            let attr_names = match (name, names) {
+                (Some(name), None) => vec![_normalize_attr_name(name.try_as_str()?)],
+                (None, Some(names)) => names
+                    .iter()
+                    .map(|v| v.try_as_str().map(_normalize_attr_name))
+                    .collect::<Result<_, _>>()?,
+                _ => Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    "exactly one of 'name' or 'names' must be present",
+                ))?,
+            };
+
+            let non_revoked = requested_val.get("non_revoked");
+            let restrictions = requested_val.get("restrictions");
+
+            let creds = self
+                ._get_credentials_for_proof_req_for_attr_name(wallet, restrictions, attr_names)
+                .await?;
+
+            let mut credentials_json = vec![];
+
+            for (cred_id, cred) in creds {
+                credentials_json.push(json!({
+                    "cred_info": _make_cred_info(&cred_id, &cred)?,
+                    "interval": non_revoked
+                }))
+            }
+
+            cred_by_attr[ATTRS][reft] = Value::Array(credentials_json);
+        }
+
+        Ok(serde_json::to_string(&cred_by_attr)?)
+    }
+
+    /// Offers of objects with legacy identifiers bind the request to the prover DID, whereas for
+    /// method-agnostic identifiers the prover DID only serves as the entropy of the request.
+    async fn prover_create_credential_req(
+        &self,
+        wallet: &impl BaseWallet,
+        prover_did: &str,
+        credential_offer_json: &str,
+        credential_def_json: &str,
+        link_secret_id: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let (_, cred_def) = cred_def_from_ledger(serde_json::from_str(credential_def_json)?)?;
+        let credential_offer: CredentialOffer = serde_json::from_str(credential_offer_json)?;
+        let link_secret = Self::get_link_secret(wallet, link_secret_id).await?;
+
+        let (entropy, prover_did) =
+            match legacy_cred_def_schema_ref(&credential_offer.cred_def_id.0) {
+                Some(_) => (None, Some(prover_did)),
+                None => (Some(prover_did), None),
+            };
+
+        let (cred_req, cred_req_metadata) = anoncreds::prover::create_credential_request(
+            entropy,
+            prover_did,
+            &cred_def,
+            &link_secret,
+            link_secret_id,
+            &credential_offer,
+        )?;
+
+        Ok((
+            serde_json::to_string(&cred_req)?,
+            serde_json::to_string(&cred_req_metadata)?,
+        ))
+    }
+
+    async fn create_revocation_state(
+        &self,
+        tails_dir: &str,
+        rev_reg_def_json: &str,
+        rev_reg_delta_json: &str,
+        timestamp: u64,
+        cred_rev_id: &str,
+    ) -> VcxCoreResult<String> {
+        let (rev_reg_def_id, rev_reg_def) =
+            rev_reg_def_from_ledger(serde_json::from_str(rev_reg_def_json)?)?;
+
+        let mut tails_file_path = std::path::PathBuf::new();
+        tails_file_path.push(tails_dir);
+        tails_file_path.push(&rev_reg_def.value.tails_hash);
+
+        let tails_path = tails_file_path.to_str().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidOption,
+                "tails file is not an unicode string",
+            )
+        })?;
+
+        // The delta is expected to span the registry from its creation, so that it lists all the
+        // revoked credentials
+        let rev_reg_delta: LedgerRevocationRegistryDelta =
+            serde_json::from_str(rev_reg_delta_json)?;
+        let status_list = revocation_status_list(
+            &rev_reg_def_id,
+            &rev_reg_def,
+            &rev_reg_delta.value.revoked,
+            &rev_reg_delta.value.accum,
+            Some(timestamp),
+        )?;
+        let rev_reg_idx: u32 = cred_rev_id
+            .parse()
+            .map_err(|e| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::ParsingError, e))?;
+
+        let rev_state = anoncreds::prover::create_or_update_revocation_state(
+            tails_path,
+            &rev_reg_def,
+            &status_list,
+            rev_reg_idx,
+            None,
+            None,
+        )?;
+
+        Ok(serde_json::to_string(&rev_state)?)
+    }
+
+    async fn prover_store_credential(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_id: Option<&str>,
+        cred_req_meta: &str,
+        cred_json: &str,
+        cred_def_json: &str,
+        rev_reg_def_json: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        let mut credential: Credential = serde_json::from_str(cred_json)?;
+        let cred_request_metadata: CredentialRequestMetadata = serde_json::from_str(cred_req_meta)?;
+        let link_secret_id = &cred_request_metadata.link_secret_name;
+        let link_secret = Self::get_link_secret(wallet, link_secret_id).await?;
+        let (_, cred_def) = cred_def_from_ledger(serde_json::from_str(cred_def_json)?)?;
+        let rev_reg_def = rev_reg_def_json
+            .map(|rev_reg_def_json| {
+                rev_reg_def_from_ledger(serde_json::from_str(rev_reg_def_json)?)
+                    .map(|(_, rev_reg_def)| rev_reg_def)
+            })
+            .transpose()?;
+
+        anoncreds::prover::process_credential(
+            &mut credential,
+            &cred_request_metadata,
+            &link_secret,
+            &cred_def,
+            rev_reg_def.as_ref(),
+        )?;
+
+        let schema_id = &credential.schema_id.0;
+        let cred_def_id = &credential.cred_def_id.0;
+
+        let mut tags = json!({
+            "schema_id": schema_id,
+            "issuer_did": cred_def.issuer_id.0,
+            "cred_def_id": cred_def_id
+        });
+
+        // Schema details are only known for schema IDs following the legacy or did:indy scheme
+        if let Some((schema_issuer_did, schema_name, schema_version)) = schema_parts(schema_id) {
+            tags["schema_issuer_did"] = json!(schema_issuer_did);
+            tags["schema_name"] = json!(schema_name);
+            tags["schema_version"] = json!(schema_version);
+        }
+
+        if let Some(rev_reg_id) = &credential.rev_reg_id {
+            tags["rev_reg_id"] = json!(rev_reg_id.0)
+        }
+
+        for (raw_attr_name, attr_value) in credential.values.0.iter() {
+            let attr_name = _normalize_attr_name(raw_attr_name);
+            // add attribute name and raw value pair
+            let value_tag_name = _format_attribute_as_value_tag_name(&attr_name);
+            tags[value_tag_name] = Value::String(attr_value.raw.to_string());
+
+            // add attribute name and marker (used for checking existent)
+            let marker_tag_name = _format_attribute_as_marker_tag_name(&attr_name);
+            tags[marker_tag_name] = Value::String("1".to_string());
+        }
+
+        let credential_id = cred_id.map_or(Uuid::new_v4().to_string(), String::from);
+
+        let record_value = serde_json::to_string(&credential)?;
+        let tags_json: HashMap<String, String> = serde_json::from_value(tags)?;
+
+        wallet
+            .add_wallet_record(
+                CATEGORY_CREDENTIAL,
+                &credential_id,
+                &record_value,
+                Some(tags_json),
+            )
+            .await?;
+
+        Ok(credential_id)
+    }
+
+    async fn prover_create_link_secret(
+        &self,
+        wallet: &impl BaseWallet,
+        link_secret_id: &str,
+    ) -> VcxCoreResult<String> {
+        let existing_record = wallet
+            .get_wallet_record(CATEGORY_LINK_SECRET, link_secret_id, "{}")
+            .await
+            .ok(); // ignore error, as we only care about whether it exists or not
+
+        if existing_record.is_some() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationMasterSecret,
+                format!(
+                    "Master secret id: {} already exists in wallet.",
+                    link_secret_id
+                ),
+            ));
+        }
+
+        let link_secret = anoncreds::prover::create_link_secret()?;
+        let ms_decimal: String = link_secret.try_into().map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::UrsaError,
+                format!("Failed to convert link secret to decimal string: {err:?}"),
+            )
+        })?;
+
+        wallet
+            .add_wallet_record(CATEGORY_LINK_SECRET, link_secret_id, &ms_decimal, None)
+            .await?;
+
+        Ok(link_secret_id.to_string())
+    }
+
+    async fn prover_delete_credential(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_id: &str,
+    ) -> VcxCoreResult<()> {
+        wallet
+            .delete_wallet_record(CATEGORY_CREDENTIAL, cred_id)
+            .await
+    }
+
+    async fn issuer_create_schema(
+        &self,
+        issuer_did: &str,
+        name: &str,
+        version: &str,
+        attrs: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let attr_names: AttributeNames = serde_json::from_str(attrs)?;
+
+        let schema =
+            anoncreds::issuer::create_schema(name, version, issuer_id(issuer_did)?, attr_names)?;
+        let schema_id = SchemaId::new_unchecked(make_schema_id(issuer_did, name, version));
+
+        let schema_json = schema_to_ledger(&schema_id, &schema)?.to_string();
+
+        Ok((schema_id.0, schema_json))
+    }
+
+    async fn revoke_credential_local(
+        &self,
+        wallet: &impl BaseWallet,
+        _tails_dir: &str,
+        rev_reg_id: &str,
+        cred_rev_id: &str,
+    ) -> VcxCoreResult<()> {
+        let cred_rev_id: u32 = cred_rev_id.parse().map_err(|e| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Invalid cred_rev_id {cred_rev_id} - {e}"),
+            )
+        })?;
+
+        let rev_reg: LedgerRevocationRegistry =
+            Self::get_wallet_record_value(wallet, CATEGORY_REV_REG, rev_reg_id).await?;
+
+        let rev_reg_def: RevocationRegistryDefinition =
+            Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+
+        let rev_reg_priv: RevocationRegistryDefinitionPrivate =
+            Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_DEF_PRIV, rev_reg_id).await?;
+
+        let mut rev_reg_info: RevocationRegistryInfo =
+            Self::get_wallet_record_value(wallet, CATEGORY_REV_REG_INFO, rev_reg_id).await?;
+
+        let cred_def: CredentialDefinition =
+            Self::get_wallet_record_value(wallet, CATEGORY_CRED_DEF, &rev_reg_def.cred_def_id.0)
+                .await?;
+
+        let status_list = revocation_status_list(
+            &rev_reg_info.id,
+            &rev_reg_def,
+            &rev_reg_info.used_ids,
+            &rev_reg.value.accum,
+            None,
+        )?;
+
+        if !rev_reg_info.used_ids.insert(cred_rev_id) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!(
+                    "Revocation id: {:?} not found in RevocationRegistry",
+                    cred_rev_id
+                ),
+            ));
+        }
+
+        let str_rev_reg_info = serde_json::to_string(&rev_reg_info)?;
+
+        let status_list = anoncreds::issuer::update_revocation_status_list(
+            &cred_def,
+            &rev_reg_def,
+            &rev_reg_priv,
+            &status_list,
+            None,
+            Some(BTreeSet::from([cred_rev_id])),
+            None,
+        )?;
+
+        let accum = status_list_accumulator(&status_list)?;
+        let new_rev_reg_delta = LedgerRevocationRegistryDelta::new(
+            Some(rev_reg.value.accum),
+            accum.clone(),
+            HashSet::new(),
+            HashSet::from([cred_rev_id]),
+        );
+
+        let old_str_rev_reg_delta = self.get_rev_reg_delta(wallet, rev_reg_id).await?;
+
+        let rev_reg_delta = old_str_rev_reg_delta
+            .as_deref()
+            .map(serde_json::from_str::<LedgerRevocationRegistryDelta>)
+            .transpose()?
+            .map(|rev_reg_delta| rev_reg_delta.merge(new_rev_reg_delta.clone()))
+            .unwrap_or(new_rev_reg_delta);
+
+        let str_rev_reg = serde_json::to_string(&LedgerRevocationRegistry::new(accum))?;
+        let str_rev_reg_delta = serde_json::to_string(&rev_reg_delta)?;
+
+        wallet
+            .update_wallet_record_value(CATEGORY_REV_REG, rev_reg_id, &str_rev_reg)
+            .await?;
+
+        wallet
+            .update_wallet_record_value(CATEGORY_REV_REG_INFO, rev_reg_id, &str_rev_reg_info)
+            .await?;
+
+        match old_str_rev_reg_delta {
+            Some(_) => {
+                wallet
+                    .update_wallet_record_value(
+                        CATEGORY_REV_REG_DELTA,
+                        rev_reg_id,
+                        &str_rev_reg_delta,
+                    )
+                    .await?
+            }
+            None => {
+                wallet
+                    .add_wallet_record(CATEGORY_REV_REG_DELTA, rev_reg_id, &str_rev_reg_delta, None)
+                    .await?
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_rev_reg_delta(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+    ) -> VcxCoreResult<Option<String>> {
+        let res_rev_reg_delta = Self::get_wallet_record_value::<LedgerRevocationRegistryDelta>(
+            wallet,
+            CATEGORY_REV_REG_DELTA,
+            rev_reg_id,
+        )
+        .await;
+
+        if let Err(err) = &res_rev_reg_delta {
+            warn!(
+                "get_rev_reg_delta >> Unable to get rev_reg_delta cache for rev_reg_id: {}, \
+                 error: {}",
+                rev_reg_id, err
+            );
+        }
+
+        let res_rev_reg_delta = res_rev_reg_delta
+            .ok()
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose();
+
+        if let Err(err) = &res_rev_reg_delta {
+            warn!(
+                "get_rev_reg_delta >> Unable to deserialize rev_reg_delta cache for rev_reg_id: \
+                 {}, error: {}",
+                rev_reg_id, err
+            );
+        }
+
+        Ok(res_rev_reg_delta.ok().flatten())
+    }
+
+    async fn clear_rev_reg_delta(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+    ) -> VcxCoreResult<()> {
+        if self.get_rev_reg_delta(wallet, rev_reg_id).await?.is_some() {
+            wallet
+                .delete_wallet_record(CATEGORY_REV_REG_DELTA, rev_reg_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn generate_nonce(&self) -> VcxCoreResult<String> {
+        let nonce = anoncreds::verifier::generate_nonce()?.to_string();
+        Ok(nonce)
+    }
+}
+
+/// Credential presented in a proof, with the referents it is presented for
+struct ProofDetails {
+    credential: Credential,
+    timestamp: Option<u64>,
+    rev_state: Option<CredentialRevocationState>,
+    req_attr_refts_revealed: Vec<(String, bool)>,
+    req_preds_refts: Vec<String>,
+}
+
+fn issuer_id(issuer_did: &str) -> VcxCoreResult<IssuerId> {
+    IssuerId::new(issuer_did).map_err(|err| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidDid,
+            format!("Invalid issuer DID {issuer_did}: {err:?}"),
+        )
+    })
+}
+
+fn get_rev_state(
+    cred_id: &str,
+    credential: &Credential,
+    detail: &Value,
+    rev_states: Option<&Value>,
+) -> VcxCoreResult<(Option<u64>, Option<CredentialRevocationState>)> {
+    let timestamp = detail
+        .get("timestamp")
+        .and_then(|timestamp| timestamp.as_u64());
+    let cred_rev_reg_id = credential.rev_reg_id.as_ref().map(|id| id.0.to_string());
+    let rev_state = if let (Some(timestamp), Some(cred_rev_reg_id)) = (timestamp, cred_rev_reg_id) {
+        let rev_state = rev_states
+            .and_then(|rev_states| rev_states.get(&cred_rev_reg_id))
+            .ok_or(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidJson,
+                format!(
+                    "No revocation states provided for credential '{}' with rev_reg_id '{}'",
+                    cred_id, cred_rev_reg_id
+                ),
+            ))?;
+
+        let rev_state = rev_state
+            .get(timestamp.to_string())
+            .ok_or(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidJson,
+                format!(
+                    "No revocation states provided for credential '{}' with rev_reg_id '{}' at \
+                     timestamp '{}'",
+                    cred_id, cred_rev_reg_id, timestamp
+                ),
+            ))?;
+
+        Some(serde_json::from_value(rev_state.clone())?)
+    } else {
+        None
+    };
+
+    Ok((timestamp, rev_state))
+}
+
+fn _normalize_attr_name(name: &str) -> String {
+    // "name": string, // attribute name, (case insensitive and ignore spaces)
+    name.replace(' ', "").to_lowercase()
+}
+
+fn _make_cred_info(credential_id: &str, cred: &Credential) -> VcxCoreResult<Value> {
+    let cred_sig = serde_json::to_value(&cred.signature)?;
+
+    let rev_info = cred_sig.get("r_credential");
+
+    let rev_reg_id = cred.rev_reg_id.as_ref().map(|x| x.0.to_string());
+    let cred_rev_id = rev_info.and_then(|x| x.get("i")).and_then(|i| {
+        i.as_str()
+            .map(|str_i| str_i.to_string())
+            .or(i.as_i64().map(|int_i| int_i.to_string()))
+    });
+
+    let mut attrs = json!({});
+    for (x, y) in cred.values.0.iter() {
+        attrs[x] = Value::String(y.raw.to_string());
+    }
+
+    Ok(json!({
+        "referent": credential_id,
+        "schema_id": cred.schema_id.0,
+        "cred_def_id": cred.cred_def_id.0,
+        "rev_reg_id": rev_reg_id,
+        "cred_rev_id": cred_rev_id,
+        "attrs": attrs
+    }))
+}
+
+fn _format_attribute_as_value_tag_name(attribute_name: &str) -> String {
+    format!("attr::{attribute_name}::value")
+}
+
+fn _format_attribute_as_marker_tag_name(attribute_name: &str) -> String {
+    format!("attr::{attribute_name}::marker")
+}
+
+#[cfg(all(test, feature = "askar_wallet"))]
+mod tests {
+    use super::*;
+    use crate::wallet::askar::{AskarWallet, AskarWalletConfigBuilder};
+
+    const ISSUER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const PROVER_DID: &str = "VsKV7grR1BUE29mG2Fm2kX";
+    const LINK_SECRET_ID: &str = "main";
+
+    async fn create_test_wallet() -> AskarWallet {
+        let config = AskarWalletConfigBuilder::default()
+            .db_url("sqlite://:memory:")
+            .key_method("none")
+            .build()
+            .unwrap();
+        AskarWallet::create(&config, true).await.unwrap()
+    }
+
+    fn tails_dir() -> String {
+        std::env::temp_dir().to_str().unwrap().to_owned()
+    }
+
+    /// Creates a schema and a credential definition as read from the ledger, which assigns the
+    /// schema the sequence number the credential definition refers to
+    async fn create_cred_def(
+        wallet: &impl BaseWallet,
+        support_revocation: bool,
+    ) -> (String, Value, String, Value) {
+        let (schema_id, schema_json) = AnoncredsRsAnonCreds
+            .issuer_create_schema(ISSUER_DID, "test", "1.0", r#"["name", "age"]"#)
+            .await
+            .unwrap();
+        let mut schema: Value = serde_json::from_str(&schema_json).unwrap();
+        schema["seqNo"] = json!(42);
+        let config = json!({ "support_revocation": support_revocation }).to_string();
+        let (cred_def_id, cred_def_json) = AnoncredsRsAnonCreds
+            .issuer_create_and_store_credential_def(
+                wallet,
+                ISSUER_DID,
+                &schema.to_string(),
+                "tag",
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        let cred_def = serde_json::from_str(&cred_def_json).unwrap();
+        (schema_id, schema, cred_def_id, cred_def)
+    }
+
+    #[tokio::test]
+    async fn test_ledger_objects_round_trip() {
+        let wallet = create_test_wallet().await;
+        let (_, _, cred_def_id, cred_def) = create_cred_def(&wallet, true).await;
+        assert_eq!(cred_def_id, "V4SGRU86Z58d6TV7PBUe6f:3:CL:42:tag");
+        assert_eq!(cred_def["schemaId"], "42");
+
+        let (id, anoncreds_cred_def) = cred_def_from_ledger(cred_def.clone()).unwrap();
+        assert_eq!(id.0, cred_def_id);
+        assert_eq!(anoncreds_cred_def.issuer_id.0, ISSUER_DID);
+        assert_eq!(
+            cred_def_to_ledger(&id, &anoncreds_cred_def).unwrap(),
+            cred_def
+        );
+
+        let (rev_reg_id, rev_reg_def_json, rev_reg_json) = AnoncredsRsAnonCreds
+            .issuer_create_and_store_revoc_reg(
+                &wallet,
+                ISSUER_DID,
+                &cred_def_id,
+                &tails_dir(),
+                5,
+                "1",
+            )
+            .await
+            .unwrap();
+        let rev_reg_def: Value = serde_json::from_str(&rev_reg_def_json).unwrap();
+        assert_eq!(rev_reg_def["value"]["issuanceType"], "ISSUANCE_BY_DEFAULT");
+        let (id, anoncreds_rev_reg_def) = rev_reg_def_from_ledger(rev_reg_def.clone()).unwrap();
+        assert_eq!(id.0, rev_reg_id);
+        assert_eq!(
+            rev_reg_def_to_ledger(&id, &anoncreds_rev_reg_def).unwrap(),
+            rev_reg_def
+        );
+
+        let rev_reg: LedgerRevocationRegistry = serde_json::from_str(&rev_reg_json).unwrap();
+        let status_list = revocation_status_list(
+            &id,
+            &anoncreds_rev_reg_def,
+            &HashSet::from([1]),
+            &rev_reg.value.accum,
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(
+            status_list_accumulator(&status_list).unwrap(),
+            rev_reg.value.accum
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issue_prove_verify() {
+        let anoncreds = AnoncredsRsAnonCreds;
+        let issuer_wallet = create_test_wallet().await;
+        let prover_wallet = create_test_wallet().await;
+        let (schema_id, schema, cred_def_id, cred_def) =
+            create_cred_def(&issuer_wallet, false).await;
+
+        let offer = anoncreds
+            .issuer_create_credential_offer(&issuer_wallet, &cred_def_id)
+            .await
+            .unwrap();
+        anoncreds
+            .prover_create_link_secret(&prover_wallet, LINK_SECRET_ID)
+            .await
+            .unwrap();
+        let (cred_req, cred_req_metadata) = anoncreds
+            .prover_create_credential_req(
+                &prover_wallet,
+                PROVER_DID,
+                &offer,
+                &cred_def.to_string(),
+                LINK_SECRET_ID,
+            )
+            .await
+            .unwrap();
+        let cred_values = json!({
+            "name": { "raw": "Alice", "encoded": "1139481716457488690172217916278103335" },
+            "age": { "raw": "28", "encoded": "28" },
+        });
+        let (cred, cred_rev_id, _) = anoncreds
+            .issuer_create_credential(
+                &issuer_wallet,
+                &offer,
+                &cred_req,
+                &cred_values.to_string(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(cred_rev_id, None);
+        let cred_id = anoncreds
+            .prover_store_credential(
+                &prover_wallet,
+                None,
+                &cred_req_metadata,
+                &cred,
+                &cred_def.to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let proof_req = json!({
+            "nonce": anoncreds.generate_nonce().await.unwrap(),
+            "name": "proof",
+            "version": "1.0",
+            "requested_attributes": { "name_ref": { "name": "name" } },
+            "requested_predicates": {
+                "age_ref": { "name": "age", "p_type": ">=", "p_value": 18 }
+            },
+        })
+        .to_string();
+        let requested_credentials = json!({
+            "requested_attributes": { "name_ref": { "cred_id": cred_id, "revealed": true } },
+            "requested_predicates": { "age_ref": { "cred_id": cred_id } },
+        })
+        .to_string();
+        let schemas = json!({ schema_id: schema }).to_string();
+        let cred_defs = json!({ cred_def_id: cred_def }).to_string();
+        let proof = anoncreds
+            .prover_create_proof(
+                &prover_wallet,
+                &proof_req,
+                &requested_credentials,
+                LINK_SECRET_ID,
+                &schemas,
+                &cred_defs,
+                None,
+            )
+            .await
+            .unwrap();
+        let revealed: Value = serde_json::from_str(&proof).unwrap();
+        assert_eq!(
+            revealed["requested_proof"]["revealed_attrs"]["name_ref"]["raw"],
+            "Alice"
+        );
+
+        assert!(anoncreds
+            .verifier_verify_proof(&proof_req, &proof, &schemas, &cred_defs, "null", "null")
+            .await
+            .unwrap());
+    }
+}
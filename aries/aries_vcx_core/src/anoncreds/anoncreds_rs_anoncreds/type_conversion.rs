@@ -0,0 +1,348 @@
+//! Conversions between the legacy Indy ledger (and `indy-credx`) representations of anoncreds
+//! objects and their method-agnostic `anoncreds-rs` counterparts.
+//!
+//! Ledger objects carry their own `id` and a `ver`, while `anoncreds-rs` objects are identified
+//! externally and carry the `issuerId` instead. Revocation is represented by registry entries
+//! and deltas on the ledger, but by revocation status lists in `anoncreds-rs`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use anoncreds::{
+    data_types::{
+        cred_def::{CredentialDefinition, CredentialDefinitionId},
+        rev_reg_def::RevocationRegistryDefinitionId,
+        schema::{Schema, SchemaId},
+    },
+    types::{RevocationRegistryDefinition, RevocationStatusList},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::identifiers::{issuer_id_from_object_id, legacy_cred_def_schema_ref};
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+const LEDGER_OBJECT_VERSION: &str = "1.0";
+const ISSUANCE_BY_DEFAULT: &str = "ISSUANCE_BY_DEFAULT";
+
+/// Revocation registry entry, as written to the ledger
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LedgerRevocationRegistry {
+    #[serde(default = "ledger_object_version")]
+    pub ver: String,
+    pub value: LedgerRevocationRegistryValue,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LedgerRevocationRegistryValue {
+    pub accum: String,
+}
+
+/// Revocation registry delta, as written to the ledger
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LedgerRevocationRegistryDelta {
+    #[serde(default = "ledger_object_version")]
+    pub ver: String,
+    pub value: LedgerRevocationRegistryDeltaValue,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerRevocationRegistryDeltaValue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_accum: Option<String>,
+    pub accum: String,
+    #[serde(default)]
+    pub issued: HashSet<u32>,
+    #[serde(default)]
+    pub revoked: HashSet<u32>,
+}
+
+impl LedgerRevocationRegistry {
+    pub fn new(accum: String) -> Self {
+        Self {
+            ver: ledger_object_version(),
+            value: LedgerRevocationRegistryValue { accum },
+        }
+    }
+}
+
+impl LedgerRevocationRegistryDelta {
+    pub fn new(
+        prev_accum: Option<String>,
+        accum: String,
+        issued: HashSet<u32>,
+        revoked: HashSet<u32>,
+    ) -> Self {
+        Self {
+            ver: ledger_object_version(),
+            value: LedgerRevocationRegistryDeltaValue {
+                prev_accum,
+                accum,
+                issued,
+                revoked,
+            },
+        }
+    }
+
+    /// Merges a delta following this one into a single delta spanning both
+    pub fn merge(self, next: Self) -> Self {
+        let mut issued = self.value.issued;
+        let mut revoked = self.value.revoked;
+        for idx in next.value.issued {
+            revoked.remove(&idx);
+            issued.insert(idx);
+        }
+        for idx in next.value.revoked {
+            issued.remove(&idx);
+            revoked.insert(idx);
+        }
+        Self::new(self.value.prev_accum, next.value.accum, issued, revoked)
+    }
+}
+
+fn ledger_object_version() -> String {
+    LEDGER_OBJECT_VERSION.to_owned()
+}
+
+pub fn schema_from_ledger(schema: Value) -> VcxCoreResult<(SchemaId, Schema)> {
+    let (id, schema) = from_ledger(schema, &["seqNo"])?;
+    Ok((SchemaId::new_unchecked(id), schema))
+}
+
+pub fn schema_to_ledger(schema_id: &SchemaId, schema: &Schema) -> VcxCoreResult<Value> {
+    Ok(Value::Object(to_ledger(&schema_id.0, schema)?))
+}
+
+pub fn cred_def_from_ledger(
+    cred_def: Value,
+) -> VcxCoreResult<(CredentialDefinitionId, CredentialDefinition)> {
+    let (id, cred_def) = from_ledger(cred_def, &[])?;
+    Ok((CredentialDefinitionId::new_unchecked(id), cred_def))
+}
+
+/// Legacy Indy credential definitions refer to their schema by its ledger sequence number, which
+/// is part of the credential definition ID.
+pub fn cred_def_to_ledger(
+    cred_def_id: &CredentialDefinitionId,
+    cred_def: &CredentialDefinition,
+) -> VcxCoreResult<Value> {
+    let mut cred_def = to_ledger(&cred_def_id.0, cred_def)?;
+    if let Some(schema_ref) = legacy_cred_def_schema_ref(&cred_def_id.0) {
+        cred_def.insert("schemaId".to_owned(), json!(schema_ref));
+    }
+    Ok(Value::Object(cred_def))
+}
+
+/// `anoncreds-rs` revocation registries have no issuance type, as revocable credentials are
+/// always issued by default.
+pub fn rev_reg_def_from_ledger(
+    mut rev_reg_def: Value,
+) -> VcxCoreResult<(RevocationRegistryDefinitionId, RevocationRegistryDefinition)> {
+    if let Some(Value::Object(value)) = rev_reg_def.get_mut("value") {
+        value.remove("issuanceType");
+    }
+    let (id, rev_reg_def) = from_ledger(rev_reg_def, &[])?;
+    Ok((
+        RevocationRegistryDefinitionId::new_unchecked(id),
+        rev_reg_def,
+    ))
+}
+
+pub fn rev_reg_def_to_ledger(
+    rev_reg_def_id: &RevocationRegistryDefinitionId,
+    rev_reg_def: &RevocationRegistryDefinition,
+) -> VcxCoreResult<Value> {
+    let mut rev_reg_def = to_ledger(&rev_reg_def_id.0, rev_reg_def)?;
+    if let Some(Value::Object(value)) = rev_reg_def.get_mut("value") {
+        value.insert("issuanceType".to_owned(), json!(ISSUANCE_BY_DEFAULT));
+    }
+    Ok(Value::Object(rev_reg_def))
+}
+
+/// Converts a map of ledger objects keyed by their IDs
+pub fn map_from_ledger<K, T>(
+    objects: HashMap<K, Value>,
+    from_ledger: impl Fn(Value) -> VcxCoreResult<(K, T)>,
+) -> VcxCoreResult<HashMap<K, T>>
+where
+    K: Eq + Hash,
+{
+    objects
+        .into_iter()
+        .map(|(id, object)| Ok((id, from_ledger(object)?.1)))
+        .collect()
+}
+
+/// Builds the status list of a revocation registry whose accumulator is `accum` and in which the
+/// credentials with `revoked` indices are revoked.
+pub fn revocation_status_list(
+    rev_reg_def_id: &RevocationRegistryDefinitionId,
+    rev_reg_def: &RevocationRegistryDefinition,
+    revoked: &HashSet<u32>,
+    accum: &str,
+    timestamp: Option<u64>,
+) -> VcxCoreResult<RevocationStatusList> {
+    let revocation_list: Vec<u8> = (0..rev_reg_def.value.max_cred_num)
+        .map(|idx| u8::from(revoked.contains(&idx)))
+        .collect();
+    let status_list = json!({
+        "revRegDefId": rev_reg_def_id,
+        "issuerId": rev_reg_def.issuer_id,
+        "revocationList": revocation_list,
+        "currentAccumulator": accum,
+        "timestamp": timestamp,
+    });
+    Ok(serde_json::from_value(status_list)?)
+}
+
+pub fn status_list_accumulator(status_list: &RevocationStatusList) -> VcxCoreResult<String> {
+    let status_list = serde_json::to_value(status_list)?;
+    status_list
+        .get("currentAccumulator")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                "Revocation status list has no accumulator",
+            )
+        })
+}
+
+fn from_ledger<T>(object: Value, ledger_only_fields: &[&str]) -> VcxCoreResult<(String, T)>
+where
+    T: DeserializeOwned,
+{
+    let Value::Object(mut object) = object else {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidJson,
+            format!("Ledger object is not a JSON object: {object}"),
+        ));
+    };
+    let Some(Value::String(id)) = object.remove("id") else {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidJson,
+            "Ledger object is missing its id",
+        ));
+    };
+    object.remove("ver");
+    for field in ledger_only_fields {
+        object.remove(*field);
+    }
+    if !object.contains_key("issuerId") {
+        object.insert("issuerId".to_owned(), json!(issuer_id_from_object_id(&id)?));
+    }
+    Ok((id, serde_json::from_value(Value::Object(object))?))
+}
+
+fn to_ledger<T>(id: &str, object: &T) -> VcxCoreResult<Map<String, Value>>
+where
+    T: Serialize,
+{
+    let mut object: Map<String, Value> = serde_json::from_value(serde_json::to_value(object)?)?;
+    object.remove("issuerId");
+    object.insert("ver".to_owned(), json!(LEDGER_OBJECT_VERSION));
+    object.insert("id".to_owned(), json!(id));
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_SCHEMA_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:2:test:1.0";
+    const URI_SCHEMA_ID: &str =
+        "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/SCHEMA/test/1.0";
+
+    fn ledger_schema(id: &str) -> Value {
+        json!({
+            "id": id,
+            "ver": LEDGER_OBJECT_VERSION,
+            "name": "test",
+            "version": "1.0",
+            "attrNames": ["name", "age"],
+            "seqNo": 42,
+        })
+    }
+
+    #[test]
+    fn test_schema_round_trip() {
+        for (id, issuer_id) in [
+            (LEGACY_SCHEMA_ID, "V4SGRU86Z58d6TV7PBUe6f"),
+            (URI_SCHEMA_ID, "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f"),
+        ] {
+            let (schema_id, schema) = schema_from_ledger(ledger_schema(id)).unwrap();
+            assert_eq!(schema_id.0, id);
+            assert_eq!(schema.issuer_id.0, issuer_id);
+
+            let mut expected = ledger_schema(id);
+            expected.as_object_mut().unwrap().remove("seqNo");
+            assert_eq!(schema_to_ledger(&schema_id, &schema).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_schema_from_ledger_requires_id() {
+        let mut schema = ledger_schema(LEGACY_SCHEMA_ID);
+        schema.as_object_mut().unwrap().remove("id");
+        assert_eq!(
+            schema_from_ledger(schema).unwrap_err().kind(),
+            AriesVcxCoreErrorKind::InvalidJson
+        );
+    }
+
+    #[test]
+    fn test_map_from_ledger() {
+        let schemas = HashMap::from([(
+            SchemaId::new_unchecked(LEGACY_SCHEMA_ID),
+            ledger_schema(LEGACY_SCHEMA_ID),
+        )]);
+        let schemas = map_from_ledger(schemas, schema_from_ledger).unwrap();
+        let schema = &schemas[&SchemaId::new_unchecked(LEGACY_SCHEMA_ID)];
+        assert_eq!(schema.name, "test");
+    }
+
+    #[test]
+    fn test_rev_reg_delta_merge() {
+        let first = LedgerRevocationRegistryDelta::new(
+            Some("accum_0".to_owned()),
+            "accum_1".to_owned(),
+            HashSet::from([1, 2]),
+            HashSet::from([4]),
+        );
+        let next = LedgerRevocationRegistryDelta::new(
+            Some("accum_1".to_owned()),
+            "accum_2".to_owned(),
+            HashSet::from([3, 4]),
+            HashSet::from([1]),
+        );
+        let merged = first.merge(next);
+        assert_eq!(merged.value.prev_accum.as_deref(), Some("accum_0"));
+        assert_eq!(merged.value.accum, "accum_2");
+        assert_eq!(merged.value.issued, HashSet::from([2, 3, 4]));
+        assert_eq!(merged.value.revoked, HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_rev_reg_delta_round_trip() {
+        let delta = LedgerRevocationRegistryDelta::new(
+            None,
+            "accum".to_owned(),
+            HashSet::from([1]),
+            HashSet::new(),
+        );
+        let json = serde_json::to_value(&delta).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "ver": LEDGER_OBJECT_VERSION,
+                "value": { "accum": "accum", "issued": [1], "revoked": [] },
+            })
+        );
+        let delta: LedgerRevocationRegistryDelta = serde_json::from_value(json).unwrap();
+        assert_eq!(delta.value.issued, HashSet::from([1]));
+    }
+}
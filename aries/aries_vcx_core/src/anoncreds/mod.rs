@@ -1,3 +1,5 @@
+#[cfg(feature = "anoncreds")]
+pub mod anoncreds_rs_anoncreds;
 pub mod base_anoncreds;
 #[cfg(feature = "credx")]
 pub mod credx_anoncreds;
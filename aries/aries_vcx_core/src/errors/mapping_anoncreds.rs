@@ -0,0 +1,20 @@
+use anoncreds::{Error as AnoncredsError, ErrorKind as AnoncredsErrorKind};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind};
+
+impl From<AnoncredsError> for AriesVcxCoreError {
+    fn from(err: AnoncredsError) -> Self {
+        let kind = match err.kind() {
+            AnoncredsErrorKind::Input | AnoncredsErrorKind::InvalidUserRevocId => {
+                AriesVcxCoreErrorKind::InvalidInput
+            }
+            AnoncredsErrorKind::IOError => AriesVcxCoreErrorKind::IOError,
+            AnoncredsErrorKind::InvalidState
+            | AnoncredsErrorKind::CredentialRevoked
+            | AnoncredsErrorKind::RevocationRegistryFull => AriesVcxCoreErrorKind::InvalidState,
+            AnoncredsErrorKind::Unexpected => AriesVcxCoreErrorKind::UnknownError,
+            AnoncredsErrorKind::ProofRejected => AriesVcxCoreErrorKind::ProofRejected,
+        };
+        AriesVcxCoreError::from_msg(kind, err)
+    }
+}
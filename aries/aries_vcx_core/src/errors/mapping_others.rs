@@ -11,6 +11,12 @@ impl From<serde_json::Error> for AriesVcxCoreError {
     }
 }
 
+impl From<public_key::PublicKeyError> for AriesVcxCoreError {
+    fn from(err: public_key::PublicKeyError) -> Self {
+        AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err.to_string())
+    }
+}
+
 impl<T> From<PoisonError<T>> for AriesVcxCoreError {
     fn from(err: PoisonError<T>) -> Self {
         AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, err.to_string())
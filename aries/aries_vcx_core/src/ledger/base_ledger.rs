@@ -29,6 +29,19 @@ pub trait IndyLedgerWrite: Debug + Send + Sync {
         data: Option<&str>,
         role: Option<&str>,
     ) -> VcxCoreResult<String>;
+    /// Builds a NYM request with the endorser set and signed by the submitter, to be endorsed
+    /// and submitted by the endorser
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        submitter_did: &str,
+        target_did: &str,
+        verkey: Option<&str>,
+        data: Option<&str>,
+        role: Option<&str>,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String>;
     async fn set_endorser(
         &self,
         wallet: &impl BaseWallet,
@@ -36,12 +49,17 @@ pub trait IndyLedgerWrite: Debug + Send + Sync {
         request: &str,
         endorser: &str,
     ) -> VcxCoreResult<String>;
+    /// Signs the request, which names `endorser_did` as its endorser, and submits it to the
+    /// ledger. Returns the ledger's reply, so that the endorser can pass the written transaction
+    /// on to its author.
+    ///
+    /// This returned `()` up to 0.61; implementations have to return the reply now.
     async fn endorse_transaction(
         &self,
         wallet: &impl BaseWallet,
         endorser_did: &str,
         request_json: &str,
-    ) -> VcxCoreResult<()>;
+    ) -> VcxCoreResult<String>;
     async fn add_attr(
         &self,
         wallet: &impl BaseWallet,
@@ -113,6 +131,37 @@ pub trait AnoncredsLedgerWrite: Debug + Send + Sync {
         rev_reg_entry_json: &str,
         submitter_did: &str,
     ) -> VcxCoreResult<()>;
+    /// Builds a schema request with the endorser set and signed by the submitter, to be endorsed
+    /// and submitted by the endorser
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String>;
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String>;
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String>;
+    async fn prepare_rev_reg_delta_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+        rev_reg_entry_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String>;
 }
 
 pub trait TaaConfigurator: Debug + Send + Sync {
@@ -55,6 +55,9 @@ use crate::{
     wallet::base_wallet::BaseWallet,
 };
 
+/// Reason the ledger gives for rejecting a schema that the submitter has already written
+const DUPLICATE_SCHEMA_REJECTION: &str = "can have one and only one SCHEMA";
+
 pub type DefaultIndyLedgerRead = IndyVdrLedgerRead<IndyVdrSubmitter, InMemoryResponseCacher>;
 pub type DefaultIndyLedgerWrite = IndyVdrLedgerWrite<IndyVdrSubmitter>;
 
@@ -152,6 +155,32 @@ where
         trace!("submit_request << ledger response (is from cache: {is_from_cache}): {response}");
         Ok(response)
     }
+
+    /// Like `submit_request`, but the ledger response is only cached if it's successfully parsed
+    async fn submit_cacheable_request<R, F>(
+        &self,
+        cache_id: &str,
+        request: PreparedRequest,
+        parse: F,
+    ) -> VcxCoreResult<R>
+    where
+        F: Fn(&str) -> VcxCoreResult<R> + Send + Sync,
+        R: Send,
+    {
+        trace!("submit_cacheable_request >> cache_id: {cache_id}, request: {request:?}");
+        if let Some(response) = self
+            .response_cacher
+            .get::<_, String>(cache_id, None)
+            .await?
+        {
+            trace!("submit_cacheable_request << Returning cached response");
+            return parse(&response);
+        }
+        let response = self.request_submitter.submit(request).await?;
+        let parsed = parse(&response)?;
+        self.response_cacher.put(cache_id, response).await?;
+        Ok(parsed)
+    }
 }
 
 impl<T> IndyVdrLedgerWrite<T>
@@ -324,22 +353,30 @@ where
             Ok(request)
         }
     }
-}
 
-#[async_trait]
-impl<T> IndyLedgerWrite for IndyVdrLedgerWrite<T>
-where
-    T: RequestSubmitter + Send + Sync,
-{
-    async fn publish_nym(
-        &self,
+    /// Sets the endorser of the request and adds the author's signature to it, returning the
+    /// request JSON to be passed to the endorser
+    async fn prepare_request_for_endorsement(
         wallet: &impl BaseWallet,
         submitter_did: &str,
+        request: PreparedRequest,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let mut request = request;
+        request.set_endorser(&DidValue::from_str(endorser_did)?)?;
+        let signature_submitter = Self::sign_request(wallet, submitter_did, &request).await?;
+        request.set_multi_signature(&DidValue::from_str(submitter_did)?, &signature_submitter)?;
+        Ok(request.req_json.to_string())
+    }
+
+    async fn build_nym_request(
+        &self,
+        submitter_did: &str,
         target_did: &str,
         verkey: Option<&str>,
         data: Option<&str>,
         role: Option<&str>,
-    ) -> VcxCoreResult<String> {
+    ) -> VcxCoreResult<PreparedRequest> {
         let identifier = DidValue::from_str(submitter_did)?;
         let dest = DidValue::from_str(target_did)?;
         let request = self.request_builder()?.build_nym_request(
@@ -351,11 +388,106 @@ where
             None,
             None,
         )?;
-        let request = self.append_txn_author_agreement_to_request(request).await?;
+        self.append_txn_author_agreement_to_request(request).await
+    }
+
+    async fn build_schema_request(
+        &self,
+        schema_json: &str,
+        submitter_did: &str,
+    ) -> VcxCoreResult<PreparedRequest> {
+        let identifier = DidValue::from_str(submitter_did)?;
+        let schema_data: SchemaV1 = serde_json::from_str(schema_json)?;
+        let request = self
+            .request_builder()?
+            .build_schema_request(&identifier, Schema::SchemaV1(schema_data))?;
+        self.append_txn_author_agreement_to_request(request).await
+    }
+
+    async fn build_cred_def_request(
+        &self,
+        cred_def_json: &str,
+        submitter_did: &str,
+    ) -> VcxCoreResult<PreparedRequest> {
+        let identifier = DidValue::from_str(submitter_did)?;
+        let cred_def_data: CredentialDefinitionV1 = serde_json::from_str(cred_def_json)?;
+        let request = self.request_builder()?.build_cred_def_request(
+            &identifier,
+            CredentialDefinition::CredentialDefinitionV1(cred_def_data),
+        )?;
+        self.append_txn_author_agreement_to_request(request).await
+    }
+
+    async fn build_rev_reg_def_request(
+        &self,
+        rev_reg_def: &str,
+        submitter_did: &str,
+    ) -> VcxCoreResult<PreparedRequest> {
+        let identifier = DidValue::from_str(submitter_did)?;
+        let rev_reg_def_data: RevocationRegistryDefinitionV1 = serde_json::from_str(rev_reg_def)?;
+        let request = self.request_builder()?.build_revoc_reg_def_request(
+            &identifier,
+            RevocationRegistryDefinition::RevocationRegistryDefinitionV1(rev_reg_def_data),
+        )?;
+        self.append_txn_author_agreement_to_request(request).await
+    }
+
+    async fn build_rev_reg_delta_request(
+        &self,
+        rev_reg_id: &str,
+        rev_reg_entry_json: &str,
+        submitter_did: &str,
+    ) -> VcxCoreResult<PreparedRequest> {
+        let identifier = DidValue::from_str(submitter_did)?;
+        let rev_reg_delta_data: RevocationRegistryDeltaV1 =
+            serde_json::from_str(rev_reg_entry_json)?;
+        let request = self.request_builder()?.build_revoc_reg_entry_request(
+            &identifier,
+            &RevocationRegistryId::from_str(rev_reg_id)?,
+            &RegistryType::CL_ACCUM,
+            RevocationRegistryDelta::RevocationRegistryDeltaV1(rev_reg_delta_data),
+        )?;
+        self.append_txn_author_agreement_to_request(request).await
+    }
+}
+
+#[async_trait]
+impl<T> IndyLedgerWrite for IndyVdrLedgerWrite<T>
+where
+    T: RequestSubmitter + Send + Sync,
+{
+    async fn publish_nym(
+        &self,
+        wallet: &impl BaseWallet,
+        submitter_did: &str,
+        target_did: &str,
+        verkey: Option<&str>,
+        data: Option<&str>,
+        role: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_nym_request(submitter_did, target_did, verkey, data, role)
+            .await?;
         self.sign_and_submit_request(wallet, submitter_did, request)
             .await
     }
 
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        submitter_did: &str,
+        target_did: &str,
+        verkey: Option<&str>,
+        data: Option<&str>,
+        role: Option<&str>,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_nym_request(submitter_did, target_did, verkey, data, role)
+            .await?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser_did).await
+    }
+
     async fn set_endorser(
         &self,
         wallet: &impl BaseWallet,
@@ -363,11 +495,8 @@ where
         request_json: &str,
         endorser: &str,
     ) -> VcxCoreResult<String> {
-        let mut request = PreparedRequest::from_request_json(request_json)?;
-        request.set_endorser(&DidValue::from_str(endorser)?)?;
-        let signature_submitter = Self::sign_request(wallet, submitter_did, &request).await?;
-        request.set_multi_signature(&DidValue::from_str(submitter_did)?, &signature_submitter)?;
-        Ok(request.req_json.to_string())
+        let request = PreparedRequest::from_request_json(request_json)?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser).await
     }
 
     async fn endorse_transaction(
@@ -375,12 +504,12 @@ where
         wallet: &impl BaseWallet,
         endorser_did: &str,
         request_json: &str,
-    ) -> VcxCoreResult<()> {
+    ) -> VcxCoreResult<String> {
         let mut request = PreparedRequest::from_request_json(request_json)?;
         verify_transaction_can_be_endorsed(request_json, endorser_did)?;
         let signature_endorser = Self::sign_request(wallet, endorser_did, &request).await?;
         request.set_multi_signature(&DidValue::from_str(endorser_did)?, &signature_endorser)?;
-        self.request_submitter.submit(request).await.map(|_| ())
+        self.request_submitter.submit(request).await
     }
 
     async fn add_attr(
@@ -451,11 +580,14 @@ where
         let request = self
             .request_builder()?
             .build_get_schema_request(None, &SchemaId::from_str(schema_id)?)?;
-        let response = self.submit_request(None, request).await?;
-        debug!("get_schema << response: {response}");
         let schema = self
-            .response_parser
-            .parse_get_schema_response(&response, None)?;
+            .submit_cacheable_request(schema_id, request, |response| {
+                debug!("get_schema << response: {response}");
+                Ok(self
+                    .response_parser
+                    .parse_get_schema_response(response, None)?)
+            })
+            .await?;
         Ok(serde_json::to_string(&schema)?)
     }
 
@@ -471,16 +603,16 @@ where
             .request_builder()?
             .build_get_cred_def_request(identifier.as_ref(), &id)?;
         // note: Before we try to create credential definition, we are checking if it already
-        //       doesn't exist on the ledger to prevent invalidating the old one.
-        //       When we make the first request, it typically doesn't exist, but we don't want to
-        //       cache such as result. So caching strategy should perhaps only store data in cache
-        //       if ledger response was found / the response is success.
-        //       Therefore parsing should happen prior to caching.
-        let response = self.submit_request(None, request).await?;
-        debug!("get_cred_def << response: {response}");
+        //       doesn't exist on the ledger to prevent invalidating the old one. The response
+        //       is therefore only cached once it's parsed, so that "not found" is never cached.
         let cred_def = self
-            .response_parser
-            .parse_get_cred_def_response(&response, None)?;
+            .submit_cacheable_request(cred_def_id, request, |response| {
+                debug!("get_cred_def << response: {response}");
+                Ok(self
+                    .response_parser
+                    .parse_get_cred_def_response(response, None)?)
+            })
+            .await?;
         Ok(serde_json::to_string(&cred_def)?)
     }
 
@@ -490,11 +622,14 @@ where
         let request = self
             .request_builder()?
             .build_get_revoc_reg_def_request(None, &id)?;
-        let response = self.submit_request(Some(rev_reg_id), request).await?;
-        debug!("get_rev_reg_def_json << response: {response}");
         let rev_reg_def = self
-            .response_parser
-            .parse_get_revoc_reg_def_response(&response)?;
+            .submit_cacheable_request(rev_reg_id, request, |response| {
+                debug!("get_rev_reg_def_json << response: {response}");
+                Ok(self
+                    .response_parser
+                    .parse_get_revoc_reg_def_response(response)?)
+            })
+            .await?;
         Ok(serde_json::to_string(&rev_reg_def)?)
     }
 
@@ -578,12 +713,9 @@ where
         submitter_did: &str,
         _endorser_did: Option<String>,
     ) -> VcxCoreResult<()> {
-        let identifier = DidValue::from_str(submitter_did)?;
-        let schema_data: SchemaV1 = serde_json::from_str(schema_json)?;
-        let mut request = self
-            .request_builder()?
-            .build_schema_request(&identifier, Schema::SchemaV1(schema_data))?;
-        request = self.append_txn_author_agreement_to_request(request).await?;
+        let request = self
+            .build_schema_request(schema_json, submitter_did)
+            .await?;
         // if let Some(endorser_did) = endorser_did {
         //     request = PreparedRequest::from_request_json(
         //         self.set_endorser(submitter_did, &request.req_json.to_string(), &endorser_did)
@@ -595,13 +727,12 @@ where
             .await;
 
         if let Err(err) = &sign_result {
-            if let AriesVcxCoreErrorKind::InvalidLedgerResponse = err.kind() {
+            if err.kind() == AriesVcxCoreErrorKind::InvalidLedgerResponse
+                && err.to_string().contains(DUPLICATE_SCHEMA_REJECTION)
+            {
                 return Err(AriesVcxCoreError::from_msg(
                     AriesVcxCoreErrorKind::DuplicationSchema,
-                    format!(
-                        "Schema probably already exists, ledger request failed: {:?}",
-                        &err
-                    ),
+                    format!("Schema already exists, ledger request failed: {:?}", &err),
                 ));
             }
         }
@@ -614,13 +745,9 @@ where
         cred_def_json: &str,
         submitter_did: &str,
     ) -> VcxCoreResult<()> {
-        let identifier = DidValue::from_str(submitter_did)?;
-        let cred_def_data: CredentialDefinitionV1 = serde_json::from_str(cred_def_json)?;
-        let request = self.request_builder()?.build_cred_def_request(
-            &identifier,
-            CredentialDefinition::CredentialDefinitionV1(cred_def_data),
-        )?;
-        let request = self.append_txn_author_agreement_to_request(request).await?;
+        let request = self
+            .build_cred_def_request(cred_def_json, submitter_did)
+            .await?;
         self.sign_and_submit_request(wallet, submitter_did, request)
             .await
             .map(|_| ())
@@ -632,13 +759,9 @@ where
         rev_reg_def: &str,
         submitter_did: &str,
     ) -> VcxCoreResult<()> {
-        let identifier = DidValue::from_str(submitter_did)?;
-        let rev_reg_def_data: RevocationRegistryDefinitionV1 = serde_json::from_str(rev_reg_def)?;
-        let request = self.request_builder()?.build_revoc_reg_def_request(
-            &identifier,
-            RevocationRegistryDefinition::RevocationRegistryDefinitionV1(rev_reg_def_data),
-        )?;
-        let request = self.append_txn_author_agreement_to_request(request).await?;
+        let request = self
+            .build_rev_reg_def_request(rev_reg_def, submitter_did)
+            .await?;
         self.sign_and_submit_request(wallet, submitter_did, request)
             .await
             .map(|_| ())
@@ -651,28 +774,85 @@ where
         rev_reg_entry_json: &str,
         submitter_did: &str,
     ) -> VcxCoreResult<()> {
-        let identifier = DidValue::from_str(submitter_did)?;
-        let rev_reg_delta_data: RevocationRegistryDeltaV1 =
-            serde_json::from_str(rev_reg_entry_json)?;
-        let request = self.request_builder()?.build_revoc_reg_entry_request(
-            &identifier,
-            &RevocationRegistryId::from_str(rev_reg_id)?,
-            &RegistryType::CL_ACCUM,
-            RevocationRegistryDelta::RevocationRegistryDeltaV1(rev_reg_delta_data),
-        )?;
-        let request = self.append_txn_author_agreement_to_request(request).await?;
+        let request = self
+            .build_rev_reg_delta_request(rev_reg_id, rev_reg_entry_json, submitter_did)
+            .await?;
         self.sign_and_submit_request(wallet, submitter_did, request)
             .await
             .map(|_| ())
     }
+
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_schema_request(schema_json, submitter_did)
+            .await?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser_did).await
+    }
+
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_cred_def_request(cred_def_json, submitter_did)
+            .await?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser_did).await
+    }
+
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_rev_reg_def_request(rev_reg_def, submitter_did)
+            .await?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser_did).await
+    }
+
+    async fn prepare_rev_reg_delta_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+        rev_reg_entry_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        let request = self
+            .build_rev_reg_delta_request(rev_reg_id, rev_reg_entry_json, submitter_did)
+            .await?;
+        Self::prepare_request_for_endorsement(wallet, submitter_did, request, endorser_did).await
+    }
 }
 
 pub fn indyvdr_build_ledger_read(
     request_submitter: IndyVdrSubmitter,
     cache_config: InMemoryResponseCacherConfig,
 ) -> VcxCoreResult<IndyVdrLedgerRead<IndyVdrSubmitter, InMemoryResponseCacher>> {
-    let response_parser = ResponseParser;
     let response_cacher = InMemoryResponseCacher::new(cache_config);
+    indyvdr_build_ledger_read_with_cacher(request_submitter, response_cacher)
+}
+
+/// Builds ledger read with a custom response cacher, such as `PersistentResponseCacher`
+pub fn indyvdr_build_ledger_read_with_cacher<V>(
+    request_submitter: IndyVdrSubmitter,
+    response_cacher: V,
+) -> VcxCoreResult<IndyVdrLedgerRead<IndyVdrSubmitter, V>>
+where
+    V: ResponseCacher + Send + Sync,
+{
+    let response_parser = ResponseParser;
 
     let config_read = IndyVdrLedgerReadConfig {
         request_submitter,
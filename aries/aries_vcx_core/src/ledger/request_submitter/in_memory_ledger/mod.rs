@@ -0,0 +1,578 @@
+//! In-process fake of an Indy ledger, for testing the ledger stack without a running pool.
+//!
+//! Write requests (NYM, ATTRIB, SCHEMA, CRED_DEF, REVOC_REG_DEF and REVOC_REG_ENTRY) are checked
+//! against the ledger's authorization rules and the transaction author agreement, and applied to
+//! the in-memory domain ledger. The matching GET requests are answered in the same format as the
+//! real ledger does, so the responses can be parsed by `ResponseParser`.
+//!
+//! Rejected requests fail the same way as on a pool, with the reply carrying the rejection reason.
+//! Signatures are required to be present, but they are not verified.
+
+mod state;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use indy_vdr::{
+    common::error::{err_msg, VdrErrorKind},
+    ledger::constants::LedgerRole,
+    pool::PreparedRequest,
+};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+use self::state::*;
+use super::RequestSubmitter;
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+#[derive(Clone, Default)]
+pub struct InMemoryLedgerSubmitter {
+    state: Arc<Mutex<LedgerState>>,
+}
+
+impl InMemoryLedgerSubmitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a NYM without any authorization, as if it was part of the genesis transactions.
+    /// Typically used to bootstrap the ledger with a trustee.
+    pub fn add_genesis_nym(
+        &self,
+        did: &str,
+        verkey: &str,
+        role: Option<LedgerRole>,
+    ) -> VcxCoreResult<()> {
+        let nym = json!({
+            "dest": did,
+            "verkey": verkey,
+            "role": role.map(|role| role.to_code()),
+        });
+        self.state
+            .lock()?
+            .write_nym(did, Some(ROLE_TRUSTEE), nym)
+            .map_err(|reason| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    format!("Invalid genesis NYM: {reason}"),
+                )
+            })?;
+        Ok(())
+    }
+
+    /// Makes acceptance of the agreement, by one of the `aml` mechanisms, mandatory for writes
+    pub fn set_txn_author_agreement(
+        &self,
+        text: &str,
+        version: &str,
+        aml: HashMap<String, String>,
+    ) -> VcxCoreResult<()> {
+        let digest = Sha256::digest(format!("{version}{text}").as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.state.lock()?.taa = Some(TxnAuthorAgreement {
+            text: text.to_owned(),
+            version: version.to_owned(),
+            digest,
+            aml: aml
+                .into_iter()
+                .map(|(mechanism, description)| (mechanism, Value::String(description)))
+                .collect(),
+            ratification_ts: OffsetDateTime::now_utc().unix_timestamp() as u64,
+        });
+        Ok(())
+    }
+
+    fn handle_request(&self, request: &Value) -> Result<Value, Rejection> {
+        let operation = request.get("operation").unwrap_or(&Value::Null);
+        let txn_type = operation
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        let result = match txn_type {
+            GET_TXN => state.get_txn(operation),
+            GET_TXN_AUTHR_AGRMT => state.get_txn_author_agreement(),
+            GET_NYM => state.get_nym(operation),
+            GET_ATTR => state.get_attr(operation),
+            GET_SCHEMA => state.get_schema(operation),
+            GET_CRED_DEF => state.get_cred_def(operation),
+            GET_REVOC_REG_DEF => state.get_rev_reg_def(operation),
+            GET_REVOC_REG => state.get_rev_reg(operation),
+            GET_REVOC_REG_DELTA => state.get_rev_reg_delta(operation),
+            NYM | ATTRIB | SCHEMA | CRED_DEF | REVOC_REG_DEF | REVOC_REG_ENTRY => {
+                return handle_write(&mut state, request, txn_type);
+            }
+            _ => return Err(format!("Unsupported transaction type {txn_type}")),
+        };
+        let mut result = as_object(result);
+        result.insert("type".to_owned(), json!(txn_type));
+        result.insert("identifier".to_owned(), request["identifier"].clone());
+        result.insert("reqId".to_owned(), request["reqId"].clone());
+        Ok(Value::Object(result))
+    }
+}
+
+#[async_trait]
+impl RequestSubmitter for InMemoryLedgerSubmitter {
+    async fn submit(&self, request: PreparedRequest) -> VcxCoreResult<String> {
+        trace!(
+            "InMemoryLedgerSubmitter::submit >> request: {:?}",
+            request.req_json
+        );
+        let result = self
+            .handle_request(&request.req_json)
+            .map_err(|reason| rejection_to_error(&request.req_json, reason))?;
+        Ok(json!({ "op": "REPLY", "result": result }).to_string())
+    }
+}
+
+fn handle_write(
+    state: &mut LedgerState,
+    request: &Value,
+    txn_type: &str,
+) -> Result<Value, Rejection> {
+    let from = request
+        .get("identifier")
+        .and_then(Value::as_str)
+        .ok_or("Request has no identifier")?;
+    let from_role = authorize_request(state, request, from)?;
+    check_taa_acceptance(state.taa.as_ref(), request)?;
+
+    let mut data = as_object(request["operation"].clone());
+    data.remove("type");
+    let data = Value::Object(data);
+    let seq_no = match txn_type {
+        NYM => state.write_nym(from, from_role.as_deref(), data),
+        ATTRIB => state.write_attrib(from, data),
+        SCHEMA | CRED_DEF | REVOC_REG_DEF if from_role.is_none() => Err(format!(
+            "{from} is neither an endorser nor endorsed, so it cannot write {txn_type}"
+        )),
+        SCHEMA => state.write_schema(from, data),
+        CRED_DEF => state.write_cred_def(from, data),
+        REVOC_REG_DEF => state.write_rev_reg_def(from, data),
+        _ => state.write_rev_reg_entry(from, data),
+    }?;
+
+    let mut result = state.txn(seq_no).cloned().unwrap_or_default();
+    result["txn"]["metadata"]["reqId"] = request["reqId"].clone();
+    if let Some(endorser) = request.get("endorser") {
+        result["txn"]["metadata"]["endorser"] = endorser.clone();
+    }
+    Ok(result)
+}
+
+/// Checks that the author, and the endorser if there is one, are on the ledger and signed the
+/// request. Returns the role the request is authorized with, which is the endorser's role for
+/// endorsed requests.
+fn authorize_request(
+    state: &LedgerState,
+    request: &Value,
+    from: &str,
+) -> Result<Option<String>, Rejection> {
+    let is_signed_by = |did: &str| {
+        request["signatures"].get(did).is_some()
+            || (did == from && request.get("signature").is_some())
+    };
+    let role_of = |did: &str| {
+        state
+            .role_of(did)
+            .map(|role| role.map(ToOwned::to_owned))
+            .ok_or_else(|| format!("Could not authenticate, verkey for {did} cannot be found"))
+    };
+
+    let author_role = role_of(from)?;
+    if !is_signed_by(from) {
+        return Err(format!("Missing signature of the author {from}"));
+    }
+    match request.get("endorser").and_then(Value::as_str) {
+        Some(endorser) => {
+            let endorser_role = role_of(endorser)?;
+            if !is_signed_by(endorser) {
+                return Err(format!("Missing signature of the endorser {endorser}"));
+            }
+            if endorser_role.is_none() {
+                return Err(format!("{endorser} is not an endorser"));
+            }
+            Ok(endorser_role)
+        }
+        None => Ok(author_role),
+    }
+}
+
+fn check_taa_acceptance(
+    taa: Option<&TxnAuthorAgreement>,
+    request: &Value,
+) -> Result<(), Rejection> {
+    match (taa, request.get("taaAcceptance")) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err("Txn Author Agreement acceptance is not expected".to_owned()),
+        (Some(_), None) => Err("Txn Author Agreement acceptance is required".to_owned()),
+        (Some(taa), Some(acceptance)) => {
+            if acceptance["taaDigest"] != taa.digest.as_str() {
+                return Err(format!(
+                    "Txn Author Agreement acceptance digest is invalid, expected {}",
+                    taa.digest
+                ));
+            }
+            let mechanism = acceptance["mechanism"].as_str().unwrap_or_default();
+            if !taa.aml.contains_key(mechanism) {
+                return Err(format!(
+                    "Txn Author Agreement acceptance mechanism {mechanism} is not allowed"
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn as_object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+/// Builds the error `VdrRequestSubmitter` fails with when the pool replies with a REJECT
+fn rejection_to_error(request: &Value, reason: Rejection) -> AriesVcxCoreError {
+    let reason = format!("client request invalid: InvalidClientRequest({reason})");
+    let reply = json!({
+        "op": "REJECT",
+        "identifier": request["identifier"],
+        "reqId": request["reqId"],
+        "reason": reason,
+    });
+    err_msg(VdrErrorKind::PoolRequestFailed(reply.to_string()), reason).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ledger::{
+            base_ledger::{
+                AnoncredsLedgerRead, AnoncredsLedgerWrite, IndyLedgerRead, IndyLedgerWrite,
+                TaaConfigurator, TxnAuthrAgrmtOptions,
+            },
+            indy_vdr_ledger::{
+                IndyVdrLedgerRead, IndyVdrLedgerReadConfig, IndyVdrLedgerWrite,
+                IndyVdrLedgerWriteConfig, ProtocolVersion,
+            },
+            response_cacher::noop::NoopResponseCacher,
+        },
+        utils::constants::VERKEY,
+        wallet::mock_wallet::MockWallet,
+    };
+
+    const TRUSTEE_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const AUTHOR_DID: &str = "FhrSrYtQcw3p9xwf7NYemf";
+    const ACCUM: &str = "1 0000000000000000000000000000000000000000000000000000000000000000 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000 2 \
+                         095E45DDF417D05FB10933FFC63D474548B7FFFF7888802F07FFFFFF7D07A8A8 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000";
+
+    type TestLedgerRead = IndyVdrLedgerRead<InMemoryLedgerSubmitter, NoopResponseCacher>;
+    type TestLedgerWrite = IndyVdrLedgerWrite<InMemoryLedgerSubmitter>;
+
+    fn _ledger() -> (InMemoryLedgerSubmitter, TestLedgerRead, TestLedgerWrite) {
+        let submitter = InMemoryLedgerSubmitter::new();
+        submitter
+            .add_genesis_nym(TRUSTEE_DID, VERKEY, Some(LedgerRole::Trustee))
+            .unwrap();
+        let ledger_read = IndyVdrLedgerRead::new(IndyVdrLedgerReadConfig {
+            request_submitter: submitter.clone(),
+            response_parser: indy_ledger_response_parser::ResponseParser,
+            response_cacher: NoopResponseCacher,
+            protocol_version: ProtocolVersion::Node1_4,
+        });
+        let ledger_write = IndyVdrLedgerWrite::new(IndyVdrLedgerWriteConfig {
+            request_submitter: submitter.clone(),
+            taa_options: None,
+            protocol_version: ProtocolVersion::Node1_4,
+        });
+        (submitter, ledger_read, ledger_write)
+    }
+
+    async fn _publish_schema(ledger_write: &TestLedgerWrite) -> VcxCoreResult<()> {
+        let schema = json!({
+            "ver": "1.0",
+            "id": format!("{TRUSTEE_DID}:2:degree:1.0"),
+            "name": "degree",
+            "version": "1.0",
+            "attrNames": ["name", "age"],
+        });
+        ledger_write
+            .publish_schema(&MockWallet, &schema.to_string(), TRUSTEE_DID, None)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_nym_and_attrib() {
+        let (_, ledger_read, ledger_write) = _ledger();
+        let wallet = MockWallet;
+
+        ledger_write
+            .publish_nym(&wallet, TRUSTEE_DID, AUTHOR_DID, Some(VERKEY), None, None)
+            .await
+            .unwrap();
+        let nym: Value =
+            serde_json::from_str(&ledger_read.get_nym(AUTHOR_DID).await.unwrap()).unwrap();
+        let nym_data: Value =
+            serde_json::from_str(nym["result"]["data"].as_str().unwrap()).unwrap();
+        assert_eq!(nym_data["verkey"], VERKEY);
+        assert_eq!(nym_data["role"], Value::Null);
+
+        ledger_write
+            .add_attr(
+                &wallet,
+                AUTHOR_DID,
+                r#"{"endpoint":{"endpoint":"http://localhost"}}"#,
+            )
+            .await
+            .unwrap();
+        let attr: Value =
+            serde_json::from_str(&ledger_read.get_attr(AUTHOR_DID, "endpoint").await.unwrap())
+                .unwrap();
+        let attr_data: Value =
+            serde_json::from_str(attr["result"]["data"].as_str().unwrap()).unwrap();
+        assert_eq!(attr_data["endpoint"]["endpoint"], "http://localhost");
+
+        let missing: Value =
+            serde_json::from_str(&ledger_read.get_attr(AUTHOR_DID, "service").await.unwrap())
+                .unwrap();
+        assert_eq!(missing["result"]["data"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_submitter_is_rejected() {
+        let (_, _, ledger_write) = _ledger();
+
+        let err = ledger_write
+            .publish_nym(
+                &MockWallet,
+                AUTHOR_DID,
+                TRUSTEE_DID,
+                Some(VERKEY),
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidLedgerResponse);
+    }
+
+    #[tokio::test]
+    async fn test_schema_and_cred_def() {
+        let (_, ledger_read, ledger_write) = _ledger();
+        let schema_id = format!("{TRUSTEE_DID}:2:degree:1.0");
+
+        let err = ledger_read.get_schema(&schema_id, None).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::LedgerItemNotFound);
+
+        _publish_schema(&ledger_write).await.unwrap();
+        let schema: Value =
+            serde_json::from_str(&ledger_read.get_schema(&schema_id, None).await.unwrap()).unwrap();
+        assert_eq!(schema["id"], schema_id);
+        assert_eq!(schema["seqNo"], 2);
+
+        let err = _publish_schema(&ledger_write).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::DuplicationSchema);
+        assert!(err.to_string().contains("can have one and only one SCHEMA"));
+
+        let cred_def_id = format!("{TRUSTEE_DID}:3:CL:2:tag1");
+        let cred_def = json!({
+            "ver": "1.0",
+            "id": cred_def_id,
+            "schemaId": "2",
+            "type": "CL",
+            "tag": "tag1",
+            "value": { "primary": { "n": "1", "s": "2", "r": {}, "rctxt": "3", "z": "4" } },
+        });
+        ledger_write
+            .publish_cred_def(&MockWallet, &cred_def.to_string(), TRUSTEE_DID)
+            .await
+            .unwrap();
+        let fetched: Value =
+            serde_json::from_str(&ledger_read.get_cred_def(&cred_def_id, None).await.unwrap())
+                .unwrap();
+        assert_eq!(fetched["id"], cred_def_id);
+        assert_eq!(fetched["schemaId"], "2");
+        assert_eq!(fetched["value"], cred_def["value"]);
+    }
+
+    #[tokio::test]
+    async fn test_revocation_registry() {
+        let (_, ledger_read, ledger_write) = _ledger();
+        let wallet = MockWallet;
+        _publish_schema(&ledger_write).await.unwrap();
+        let cred_def_id = format!("{TRUSTEE_DID}:3:CL:2:tag1");
+        let cred_def = json!({
+            "ver": "1.0",
+            "id": cred_def_id,
+            "schemaId": "2",
+            "type": "CL",
+            "tag": "tag1",
+            "value": { "primary": {} },
+        });
+        ledger_write
+            .publish_cred_def(&wallet, &cred_def.to_string(), TRUSTEE_DID)
+            .await
+            .unwrap();
+
+        let rev_reg_id = format!("{TRUSTEE_DID}:4:{cred_def_id}:CL_ACCUM:tag1");
+        let rev_reg_def = json!({
+            "ver": "1.0",
+            "id": rev_reg_id,
+            "revocDefType": "CL_ACCUM",
+            "tag": "tag1",
+            "credDefId": cred_def_id,
+            "value": {
+                "issuanceType": "ISSUANCE_BY_DEFAULT",
+                "maxCredNum": 5,
+                "publicKeys": { "accumKey": { "z": "1 0 1 0" } },
+                "tailsHash": "hash",
+                "tailsLocation": "/tmp/tails",
+            },
+        });
+        ledger_write
+            .publish_rev_reg_def(&wallet, &rev_reg_def.to_string(), TRUSTEE_DID)
+            .await
+            .unwrap();
+        let fetched: Value =
+            serde_json::from_str(&ledger_read.get_rev_reg_def_json(&rev_reg_id).await.unwrap())
+                .unwrap();
+        assert_eq!(fetched["id"], rev_reg_id);
+        assert_eq!(fetched["value"]["maxCredNum"], 5);
+
+        let entry = json!({ "ver": "1.0", "value": { "accum": ACCUM } });
+        ledger_write
+            .publish_rev_reg_delta(&wallet, &rev_reg_id, &entry.to_string(), TRUSTEE_DID)
+            .await
+            .unwrap();
+        let entry = json!({
+            "ver": "1.0",
+            "value": { "prevAccum": ACCUM, "accum": ACCUM, "revoked": [1, 3] },
+        });
+        ledger_write
+            .publish_rev_reg_delta(&wallet, &rev_reg_id, &entry.to_string(), TRUSTEE_DID)
+            .await
+            .unwrap();
+
+        let (id, delta, _) = ledger_read
+            .get_rev_reg_delta_json(&rev_reg_id, None, None)
+            .await
+            .unwrap();
+        assert_eq!(id, rev_reg_id);
+        let delta: Value = serde_json::from_str(&delta).unwrap();
+        let mut revoked: Vec<u64> =
+            serde_json::from_value(delta["value"]["revoked"].clone()).unwrap();
+        revoked.sort();
+        assert_eq!(revoked, vec![1, 3]);
+
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        let (id, rev_reg, _) = ledger_read.get_rev_reg(&rev_reg_id, now).await.unwrap();
+        assert_eq!(id, rev_reg_id);
+        let rev_reg: Value = serde_json::from_str(&rev_reg).unwrap();
+        assert_eq!(rev_reg["value"]["accum"], ACCUM);
+    }
+
+    #[tokio::test]
+    async fn test_endorsed_schema() {
+        let (_, ledger_read, ledger_write) = _ledger();
+        let wallet = MockWallet;
+        ledger_write
+            .publish_nym(&wallet, TRUSTEE_DID, AUTHOR_DID, Some(VERKEY), None, None)
+            .await
+            .unwrap();
+
+        let schema = json!({
+            "ver": "1.0",
+            "id": format!("{AUTHOR_DID}:2:degree:1.0"),
+            "name": "degree",
+            "version": "1.0",
+            "attrNames": ["name"],
+        });
+        let err = ledger_write
+            .publish_schema(&wallet, &schema.to_string(), AUTHOR_DID, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidLedgerResponse);
+        assert!(err
+            .to_string()
+            .contains("is neither an endorser nor endorsed"));
+
+        let request = ledger_write
+            .request_builder()
+            .unwrap()
+            .build_schema_request(
+                &AUTHOR_DID.parse().unwrap(),
+                serde_json::from_value(schema).unwrap(),
+            )
+            .unwrap();
+        let request = ledger_write
+            .set_endorser(
+                &wallet,
+                AUTHOR_DID,
+                &request.req_json.to_string(),
+                TRUSTEE_DID,
+            )
+            .await
+            .unwrap();
+        ledger_write
+            .endorse_transaction(&wallet, TRUSTEE_DID, &request)
+            .await
+            .unwrap();
+
+        ledger_read
+            .get_schema(&format!("{AUTHOR_DID}:2:degree:1.0"), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_txn_author_agreement() {
+        let (submitter, ledger_read, ledger_write) = _ledger();
+        assert_eq!(ledger_read.get_txn_author_agreement().await.unwrap(), None);
+
+        let aml = HashMap::from([("on_file".to_owned(), "Agreement on file".to_owned())]);
+        submitter
+            .set_txn_author_agreement("agreement text", "1.0", aml)
+            .unwrap();
+        let taa: Value = serde_json::from_str(
+            &ledger_read
+                .get_txn_author_agreement()
+                .await
+                .unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(taa["text"], "agreement text");
+        assert_eq!(taa["version"], "1.0");
+
+        let err = _publish_schema(&ledger_write).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidLedgerResponse);
+        assert!(err
+            .to_string()
+            .contains("Txn Author Agreement acceptance is required"));
+
+        ledger_write
+            .set_txn_author_agreement_options(TxnAuthrAgrmtOptions {
+                text: "agreement text".to_owned(),
+                version: "1.0".to_owned(),
+                mechanism: "on_file".to_owned(),
+            })
+            .unwrap();
+        _publish_schema(&ledger_write).await.unwrap();
+
+        let txn: Value =
+            serde_json::from_str(&ledger_read.get_ledger_txn(2, None).await.unwrap()).unwrap();
+        assert_eq!(txn["result"]["data"]["txn"]["type"], SCHEMA);
+    }
+}
@@ -0,0 +1,530 @@
+//! State of the in-memory ledger: the domain ledger transactions, in the order they were written,
+//! and indices pointing to the transactions which define the current state of ledger objects.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{json, Map, Value};
+use time::OffsetDateTime;
+
+pub(super) const NYM: &str = "1";
+pub(super) const GET_TXN: &str = "3";
+pub(super) const GET_TXN_AUTHR_AGRMT: &str = "6";
+pub(super) const ATTRIB: &str = "100";
+pub(super) const SCHEMA: &str = "101";
+pub(super) const CRED_DEF: &str = "102";
+pub(super) const GET_ATTR: &str = "104";
+pub(super) const GET_NYM: &str = "105";
+pub(super) const GET_SCHEMA: &str = "107";
+pub(super) const GET_CRED_DEF: &str = "108";
+pub(super) const REVOC_REG_DEF: &str = "113";
+pub(super) const REVOC_REG_ENTRY: &str = "114";
+pub(super) const GET_REVOC_REG_DEF: &str = "115";
+pub(super) const GET_REVOC_REG: &str = "116";
+pub(super) const GET_REVOC_REG_DELTA: &str = "117";
+
+pub(super) const ROLE_TRUSTEE: &str = "0";
+pub(super) const ROLE_STEWARD: &str = "2";
+pub(super) const ROLE_ENDORSER: &str = "101";
+
+const DOMAIN_LEDGER_ID: u64 = 1;
+const REVOC_DEF_TYPE: &str = "CL_ACCUM";
+
+/// Reason for which the ledger rejects a request
+pub(super) type Rejection = String;
+
+pub(super) struct TxnAuthorAgreement {
+    pub text: String,
+    pub version: String,
+    pub digest: String,
+    pub aml: Map<String, Value>,
+    pub ratification_ts: u64,
+}
+
+struct Nym {
+    identifier: String,
+    verkey: Option<String>,
+    role: Option<String>,
+    seq_no: u64,
+}
+
+struct RevRegEntry {
+    accum: String,
+    issued: HashSet<u32>,
+    revoked: HashSet<u32>,
+    seq_no: u64,
+    txn_time: u64,
+}
+
+#[derive(Default)]
+pub(super) struct LedgerState {
+    txns: Vec<Value>,
+    nyms: HashMap<String, Nym>,
+    attribs: HashMap<(String, String), u64>,
+    schemas: HashMap<String, u64>,
+    cred_defs: HashMap<String, u64>,
+    rev_reg_defs: HashMap<String, u64>,
+    rev_reg_entries: HashMap<String, Vec<RevRegEntry>>,
+    pub taa: Option<TxnAuthorAgreement>,
+}
+
+impl LedgerState {
+    pub fn role_of(&self, did: &str) -> Option<Option<&str>> {
+        self.nyms.get(did).map(|nym| nym.role.as_deref())
+    }
+
+    pub fn txn(&self, seq_no: u64) -> Option<&Value> {
+        seq_no
+            .checked_sub(1)
+            .and_then(|idx| self.txns.get(idx as usize))
+    }
+
+    pub fn write_nym(
+        &mut self,
+        from: &str,
+        from_role: Option<&str>,
+        data: Value,
+    ) -> Result<u64, Rejection> {
+        let dest = str_field(&data, "dest")?.to_owned();
+        let role_update = data
+            .get("role")
+            .map(|role| role.as_str().map(ToOwned::to_owned));
+        if let Some(Some(role)) = &role_update {
+            if !can_assign_role(from_role, role) {
+                return Err(format!("{from} is not allowed to assign role {role}"));
+            }
+        }
+        let verkey = data
+            .get("verkey")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        let nym = match self.nyms.get(&dest) {
+            Some(nym) => {
+                if verkey.is_some() && from != dest && from != nym.identifier {
+                    return Err(format!("{from} is not the owner of NYM {dest}"));
+                }
+                Nym {
+                    identifier: nym.identifier.clone(),
+                    verkey: verkey.or_else(|| nym.verkey.clone()),
+                    role: role_update.unwrap_or_else(|| nym.role.clone()),
+                    seq_no: nym.seq_no,
+                }
+            }
+            None if from_role.is_none() => {
+                return Err(format!("{from} is not allowed to create NYM {dest}"));
+            }
+            None => Nym {
+                identifier: from.to_owned(),
+                verkey,
+                role: role_update.flatten(),
+                seq_no: 0,
+            },
+        };
+        let seq_no = self.append_txn(NYM, from, data);
+        self.nyms.insert(dest, Nym { seq_no, ..nym });
+        Ok(seq_no)
+    }
+
+    pub fn write_attrib(&mut self, from: &str, data: Value) -> Result<u64, Rejection> {
+        let dest = str_field(&data, "dest")?.to_owned();
+        let owner = self
+            .nyms
+            .get(&dest)
+            .map(|nym| nym.identifier.as_str())
+            .ok_or_else(|| format!("NYM {dest} does not exist"))?;
+        if from != dest && from != owner {
+            return Err(format!("{from} is not the owner of NYM {dest}"));
+        }
+        let raw = data
+            .get("raw")
+            .and_then(Value::as_str)
+            .ok_or("Only raw attributes are supported")?;
+        let attr_name = match serde_json::from_str::<Map<String, Value>>(raw) {
+            Ok(raw) if raw.len() == 1 => raw.keys().next().cloned().unwrap_or_default(),
+            _ => {
+                return Err(format!(
+                    "Raw attribute {raw} must be a JSON object with one key"
+                ))
+            }
+        };
+        let seq_no = self.append_txn(ATTRIB, from, data);
+        self.attribs.insert((dest, attr_name), seq_no);
+        Ok(seq_no)
+    }
+
+    pub fn write_schema(&mut self, from: &str, data: Value) -> Result<u64, Rejection> {
+        let schema = data.get("data").unwrap_or(&Value::Null);
+        let name = str_field(schema, "name")?;
+        let version = str_field(schema, "version")?;
+        let schema_id = format!("{from}:2:{name}:{version}");
+        if self.schemas.contains_key(&schema_id) {
+            return Err(format!(
+                "{from} can have one and only one SCHEMA with name {name} and version {version}"
+            ));
+        }
+        let seq_no = self.append_txn(SCHEMA, from, data);
+        self.schemas.insert(schema_id, seq_no);
+        Ok(seq_no)
+    }
+
+    pub fn write_cred_def(&mut self, from: &str, data: Value) -> Result<u64, Rejection> {
+        let schema_ref = data.get("ref").and_then(Value::as_u64).unwrap_or_default();
+        if self.txn_of_type(schema_ref, SCHEMA).is_none() {
+            return Err(format!(
+                "Mentioned seqNo ({schema_ref}) isn't seqNo of the schema"
+            ));
+        }
+        let signature_type = str_field(&data, "signature_type")?;
+        let tag = str_field(&data, "tag")?;
+        let cred_def_id = format!("{from}:3:{signature_type}:{schema_ref}:{tag}");
+        let seq_no = self.append_txn(CRED_DEF, from, data);
+        self.cred_defs.insert(cred_def_id, seq_no);
+        Ok(seq_no)
+    }
+
+    pub fn write_rev_reg_def(&mut self, from: &str, data: Value) -> Result<u64, Rejection> {
+        let rev_reg_def_id = str_field(&data, "id")?.to_owned();
+        let cred_def_id = str_field(&data, "credDefId")?;
+        if !self.cred_defs.contains_key(cred_def_id) {
+            return Err(format!("There is no any CRED_DEF by key {cred_def_id}"));
+        }
+        if !rev_reg_def_id.starts_with(&format!("{from}:4:")) {
+            return Err(format!(
+                "{from} is not the owner of revocation registry {rev_reg_def_id}"
+            ));
+        }
+        let seq_no = self.append_txn(REVOC_REG_DEF, from, data);
+        self.rev_reg_defs.insert(rev_reg_def_id, seq_no);
+        Ok(seq_no)
+    }
+
+    pub fn write_rev_reg_entry(&mut self, from: &str, data: Value) -> Result<u64, Rejection> {
+        let rev_reg_def_id = str_field(&data, "revocRegDefId")?.to_owned();
+        let def_txn = self
+            .rev_reg_defs
+            .get(&rev_reg_def_id)
+            .and_then(|seq_no| self.txn(*seq_no))
+            .ok_or_else(|| format!("There is no any REVOC_REG_DEF by key {rev_reg_def_id}"))?;
+        if def_txn["txn"]["metadata"]["from"] != from {
+            return Err(format!(
+                "{from} is not the owner of revocation registry {rev_reg_def_id}"
+            ));
+        }
+        let value = data.get("value").unwrap_or(&Value::Null);
+        let accum = str_field(value, "accum")?.to_owned();
+        let current_accum = self
+            .rev_reg_entries
+            .get(&rev_reg_def_id)
+            .and_then(|entries| entries.last())
+            .map(|entry| entry.accum.as_str());
+        if let (Some(current), Some(prev)) = (current_accum, value.get("prevAccum")) {
+            if prev != current {
+                return Err(format!(
+                    "Incorrect previous accumulator {prev} for revocation registry \
+                     {rev_reg_def_id}"
+                ));
+            }
+        }
+        let issued = index_set(value, "issued");
+        let revoked = index_set(value, "revoked");
+        let seq_no = self.append_txn(REVOC_REG_ENTRY, from, data);
+        let txn_time = txn_time_of(self.txn(seq_no));
+        self.rev_reg_entries
+            .entry(rev_reg_def_id)
+            .or_default()
+            .push(RevRegEntry {
+                accum,
+                issued,
+                revoked,
+                seq_no,
+                txn_time,
+            });
+        Ok(seq_no)
+    }
+
+    pub fn get_txn(&self, operation: &Value) -> Value {
+        let seq_no = operation.get("data").and_then(Value::as_u64);
+        let txn = match operation.get("ledgerId").and_then(Value::as_u64) {
+            Some(DOMAIN_LEDGER_ID) => seq_no.and_then(|seq_no| self.txn(seq_no)),
+            _ => None,
+        };
+        json!({
+            "seqNo": seq_no,
+            "data": txn,
+        })
+    }
+
+    pub fn get_txn_author_agreement(&self) -> Value {
+        let data = self.taa.as_ref().map(|taa| {
+            json!({
+                "text": taa.text,
+                "version": taa.version,
+                "digest": taa.digest,
+                "ratification_ts": taa.ratification_ts,
+                "aml": taa.aml,
+            })
+        });
+        json!({ "data": data })
+    }
+
+    pub fn get_nym(&self, operation: &Value) -> Value {
+        let dest = operation
+            .get("dest")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let nym = self.nyms.get(dest);
+        let data = nym.map(|nym| {
+            json!({
+                "identifier": nym.identifier,
+                "dest": dest,
+                "verkey": nym.verkey,
+                "role": nym.role,
+                "seqNo": nym.seq_no,
+                "txnTime": txn_time_of(self.txn(nym.seq_no)),
+            })
+            .to_string()
+        });
+        let seq_no = nym.map(|nym| nym.seq_no);
+        json!({
+            "dest": dest,
+            "data": data,
+            "seqNo": seq_no,
+            "txnTime": seq_no.map(|seq_no| txn_time_of(self.txn(seq_no))),
+        })
+    }
+
+    pub fn get_attr(&self, operation: &Value) -> Value {
+        let dest = operation
+            .get("dest")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let attr_name = operation
+            .get("raw")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let txn = self
+            .attribs
+            .get(&(dest.to_owned(), attr_name.to_owned()))
+            .and_then(|seq_no| self.txn(*seq_no));
+        json!({
+            "dest": dest,
+            "raw": attr_name,
+            "data": txn.map(|txn| txn["txn"]["data"]["raw"].clone()),
+            "seqNo": txn.map(|txn| txn["txnMetadata"]["seqNo"].clone()),
+            "txnTime": txn.map(|txn| txn["txnMetadata"]["txnTime"].clone()),
+        })
+    }
+
+    /// Like the real ledger, answers with the requested name and version, but no attribute names
+    /// if the schema doesn't exist
+    pub fn get_schema(&self, operation: &Value) -> Value {
+        let dest = operation
+            .get("dest")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let requested = operation.get("data").cloned().unwrap_or_default();
+        let name = requested
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let version = requested
+            .get("version")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let txn = self
+            .schemas
+            .get(&format!("{dest}:2:{name}:{version}"))
+            .and_then(|seq_no| self.txn(*seq_no));
+        json!({
+            "dest": dest,
+            "data": txn.map_or(requested, |txn| txn["txn"]["data"]["data"].clone()),
+            "seqNo": txn.map(|txn| txn["txnMetadata"]["seqNo"].clone()),
+            "txnTime": txn.map(|txn| txn["txnMetadata"]["txnTime"].clone()),
+        })
+    }
+
+    pub fn get_cred_def(&self, operation: &Value) -> Value {
+        let origin = operation
+            .get("origin")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let schema_ref = operation
+            .get("ref")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let signature_type = operation
+            .get("signature_type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let tag = operation
+            .get("tag")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let txn = self
+            .cred_defs
+            .get(&format!("{origin}:3:{signature_type}:{schema_ref}:{tag}"))
+            .and_then(|seq_no| self.txn(*seq_no));
+        json!({
+            "origin": origin,
+            "ref": schema_ref,
+            "signature_type": signature_type,
+            "tag": tag,
+            "data": txn.map(|txn| txn["txn"]["data"]["data"].clone()),
+            "seqNo": txn.map(|txn| txn["txnMetadata"]["seqNo"].clone()),
+            "txnTime": txn.map(|txn| txn["txnMetadata"]["txnTime"].clone()),
+        })
+    }
+
+    pub fn get_rev_reg_def(&self, operation: &Value) -> Value {
+        let id = operation
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let txn = self
+            .rev_reg_defs
+            .get(id)
+            .and_then(|seq_no| self.txn(*seq_no));
+        json!({
+            "id": id,
+            "data": txn.map(|txn| txn["txn"]["data"].clone()),
+            "seqNo": txn.map(|txn| txn["txnMetadata"]["seqNo"].clone()),
+            "txnTime": txn.map(|txn| txn["txnMetadata"]["txnTime"].clone()),
+        })
+    }
+
+    pub fn get_rev_reg(&self, operation: &Value) -> Value {
+        let id = operation
+            .get("revocRegDefId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let timestamp = operation
+            .get("timestamp")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let entry = self
+            .entries_until(id, timestamp)
+            .and_then(|entries| entries.last());
+        json!({
+            "revocRegDefId": id,
+            "data": entry.map(|entry| json!({
+                "revocDefType": REVOC_DEF_TYPE,
+                "revocRegDefId": id,
+                "value": { "accum": entry.accum },
+            })),
+            "seqNo": entry.map(|entry| entry.seq_no),
+            "txnTime": entry.map(|entry| entry.txn_time),
+        })
+    }
+
+    /// The delta accumulates the issuances and revocations written after `from` (or since the
+    /// registry was created) until `to`
+    pub fn get_rev_reg_delta(&self, operation: &Value) -> Value {
+        let id = operation
+            .get("revocRegDefId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let from = operation.get("from").and_then(Value::as_u64);
+        let to = operation
+            .get("to")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let entries = self.entries_until(id, to).unwrap_or_default();
+        let (before, after) =
+            entries.split_at(entries.partition_point(|entry| Some(entry.txn_time) <= from));
+        let accum_state = |entry: &RevRegEntry| {
+            json!({
+                "revocDefType": REVOC_DEF_TYPE,
+                "revocRegDefId": id,
+                "seqNo": entry.seq_no,
+                "txnTime": entry.txn_time,
+                "value": { "accum": entry.accum },
+            })
+        };
+        let data = entries.last().map(|accum_to| {
+            let mut issued = HashSet::new();
+            let mut revoked = HashSet::new();
+            for entry in after {
+                for idx in &entry.issued {
+                    revoked.remove(idx);
+                    issued.insert(*idx);
+                }
+                for idx in &entry.revoked {
+                    issued.remove(idx);
+                    revoked.insert(*idx);
+                }
+            }
+            json!({
+                "revocDefType": REVOC_DEF_TYPE,
+                "revocRegDefId": id,
+                "value": {
+                    "accum_from": before.last().map(accum_state),
+                    "accum_to": accum_state(accum_to),
+                    "issued": issued,
+                    "revoked": revoked,
+                },
+            })
+        });
+        json!({
+            "revocRegDefId": id,
+            "data": data,
+            "seqNo": entries.last().map(|entry| entry.seq_no),
+        })
+    }
+
+    fn entries_until(&self, rev_reg_def_id: &str, timestamp: u64) -> Option<&[RevRegEntry]> {
+        self.rev_reg_entries
+            .get(rev_reg_def_id)
+            .map(|entries| &entries[..entries.partition_point(|entry| entry.txn_time <= timestamp)])
+    }
+
+    fn txn_of_type(&self, seq_no: u64, txn_type: &str) -> Option<&Value> {
+        self.txn(seq_no)
+            .filter(|txn| txn["txn"]["type"] == txn_type)
+    }
+
+    fn append_txn(&mut self, txn_type: &str, from: &str, data: Value) -> u64 {
+        let seq_no = self.txns.len() as u64 + 1;
+        self.txns.push(json!({
+            "ver": "1",
+            "txn": {
+                "type": txn_type,
+                "data": data,
+                "metadata": { "from": from },
+                "protocolVersion": 2,
+            },
+            "txnMetadata": {
+                "seqNo": seq_no,
+                "txnTime": OffsetDateTime::now_utc().unix_timestamp(),
+            },
+            "reqSignature": {},
+        }));
+        seq_no
+    }
+}
+
+/// Trustees may assign any role, stewards may only make other DIDs endorsers
+fn can_assign_role(assigner_role: Option<&str>, role: &str) -> bool {
+    match assigner_role {
+        Some(ROLE_TRUSTEE) => true,
+        Some(ROLE_STEWARD) => role == ROLE_ENDORSER,
+        _ => false,
+    }
+}
+
+fn str_field<'a>(object: &'a Value, field: &str) -> Result<&'a str, Rejection> {
+    object
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing or invalid field {field} in {object}"))
+}
+
+fn index_set(object: &Value, field: &str) -> HashSet<u32> {
+    object
+        .get(field)
+        .and_then(|indices| serde_json::from_value(indices.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn txn_time_of(txn: Option<&Value>) -> u64 {
+    txn.and_then(|txn| txn["txnMetadata"]["txnTime"].as_u64())
+        .unwrap_or_default()
+}
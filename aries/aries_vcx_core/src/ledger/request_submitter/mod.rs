@@ -3,6 +3,8 @@ use indy_vdr::pool::PreparedRequest;
 
 use crate::errors::error::VcxCoreResult;
 
+#[cfg(any(test, feature = "test_utils"))]
+pub mod in_memory_ledger;
 pub mod vdr_ledger;
 #[cfg(feature = "vdr_proxy_ledger")]
 pub mod vdr_proxy;
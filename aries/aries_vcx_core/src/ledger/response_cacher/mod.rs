@@ -1,5 +1,6 @@
 pub mod in_memory;
 pub mod noop;
+pub mod persistent;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,110 @@
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PersistentResponseCacherConfig {
+    path: PathBuf,
+    ttl: Duration,
+    capacity: NonZeroUsize,
+    #[serde(default)]
+    cache_immutable_objects: bool,
+}
+
+impl PersistentResponseCacherConfig {
+    pub fn builder() -> PersistentResponseCacherConfigBuilder {
+        PersistentResponseCacherConfigBuilder::default()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Whether responses for schemas, credential definitions and revocation registry definitions
+    /// are cached, without expiring
+    pub fn cache_immutable_objects(&self) -> bool {
+        self.cache_immutable_objects
+    }
+}
+
+#[derive(Default)]
+pub struct PersistentResponseCacherConfigBuilder {}
+
+pub struct PersistentResponseCacherConfigBuilderPathSet {
+    path: PathBuf,
+}
+
+pub struct PersistentResponseCacherConfigBuilderTtlSet {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+pub struct PersistentResponseCacherConfigBuilderReady {
+    path: PathBuf,
+    ttl: Duration,
+    capacity: NonZeroUsize,
+    cache_immutable_objects: bool,
+}
+
+impl PersistentResponseCacherConfigBuilder {
+    pub fn path(self, path: impl Into<PathBuf>) -> PersistentResponseCacherConfigBuilderPathSet {
+        PersistentResponseCacherConfigBuilderPathSet { path: path.into() }
+    }
+}
+
+impl PersistentResponseCacherConfigBuilderPathSet {
+    pub fn ttl(self, ttl: Duration) -> PersistentResponseCacherConfigBuilderTtlSet {
+        PersistentResponseCacherConfigBuilderTtlSet {
+            path: self.path,
+            ttl,
+        }
+    }
+}
+
+impl PersistentResponseCacherConfigBuilderTtlSet {
+    pub fn capacity(
+        self,
+        capacity: usize,
+    ) -> Result<PersistentResponseCacherConfigBuilderReady, AriesVcxCoreError> {
+        let capacity = NonZeroUsize::new(capacity).ok_or(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidOption,
+            "Failed to parse cache capacity into NonZeroUsize",
+        ))?;
+        Ok(PersistentResponseCacherConfigBuilderReady {
+            path: self.path,
+            ttl: self.ttl,
+            capacity,
+            cache_immutable_objects: false,
+        })
+    }
+}
+
+impl PersistentResponseCacherConfigBuilderReady {
+    pub fn cache_immutable_objects(mut self, cache_immutable_objects: bool) -> Self {
+        self.cache_immutable_objects = cache_immutable_objects;
+        self
+    }
+
+    pub fn build(self) -> PersistentResponseCacherConfig {
+        PersistentResponseCacherConfig {
+            path: self.path,
+            ttl: self.ttl,
+            capacity: self.capacity,
+            cache_immutable_objects: self.cache_immutable_objects,
+        }
+    }
+}
@@ -0,0 +1,586 @@
+//! Response cacher persisting the cached responses into a file, so that they survive restarts.
+//! The file holds a JSON record per line, appended on every `put`. It is loaded when the cacher is
+//! created, and compacted down to the cached responses once it holds twice as many records as the
+//! cache capacity.
+//!
+//! Only the `capacity` most recently used responses are kept. Responses expire after the TTL of
+//! the `get` options, or of the configuration otherwise. Responses cached under the ID of an
+//! immutable ledger object (schema, credential definition or revocation registry definition) are
+//! only cached if the configuration enables it, and then only expire after a TTL given to `get`.
+
+mod config;
+mod options;
+
+use std::{
+    io::ErrorKind,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+pub use config::*;
+use lru::LruCache;
+pub use options::*;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use super::ResponseCacher;
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+const LEGACY_IMMUTABLE_OBJECT_MARKERS: [&str; 3] = ["2", "3", "4"];
+const URI_IMMUTABLE_OBJECT_PATHS: [&str; 3] = [
+    "/anoncreds/v0/SCHEMA/",
+    "/anoncreds/v0/CLAIM_DEF/",
+    "/anoncreds/v0/REV_REG_DEF/",
+];
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    obj: String,
+    cached_at_ms: u64,
+    immutable: bool,
+}
+
+impl CacheEntry {
+    fn is_expired(&self, ttl: Option<Duration>) -> bool {
+        ttl.is_some_and(|ttl| now_ms().saturating_sub(self.cached_at_ms) > ttl.as_millis() as u64)
+    }
+}
+
+/// Line of the cache file
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheRecord {
+    id: String,
+    #[serde(flatten)]
+    entry: CacheEntry,
+}
+
+struct CacheState {
+    entries: LruCache<String, CacheEntry>,
+    /// Records in the cache file, including the ones overwritten or evicted since
+    file_records: usize,
+}
+
+pub struct PersistentResponseCacher {
+    state: Arc<Mutex<CacheState>>,
+    config: PersistentResponseCacherConfig,
+}
+
+impl PersistentResponseCacher {
+    /// Creates the cacher, warming it up with the responses persisted in the cache file
+    pub async fn new(config: PersistentResponseCacherConfig) -> VcxCoreResult<Self> {
+        info!("PersistentResponseCacher::new >> config: {config:?}");
+        let state = load_cache_file(&config).await?;
+        info!(
+            "PersistentResponseCacher::new << loaded {} cached responses",
+            state.entries.len()
+        );
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl ResponseCacher for PersistentResponseCacher {
+    type Options = PersistentResponseCacherOptions;
+
+    async fn put<S, T>(&self, id: S, obj: T) -> VcxCoreResult<()>
+    where
+        S: ToString + Send,
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        let id = id.to_string();
+        let immutable = is_immutable_ledger_object_id(&id);
+        if immutable && !self.config.cache_immutable_objects() {
+            trace!("PersistentResponseCacher::put << caching of ledger object {id} is disabled");
+            return Ok(());
+        }
+        let record = CacheRecord {
+            id,
+            entry: CacheEntry {
+                obj: serde_json::to_string(&obj)?,
+                cached_at_ms: now_ms(),
+                immutable,
+            },
+        };
+
+        let mut state = self.state.lock().await;
+        state.entries.put(record.id.clone(), record.entry.clone());
+        if state.file_records >= 2 * self.config.capacity().get() {
+            store_cache_file(&self.config, &state.entries).await?;
+            state.file_records = state.entries.len();
+        } else {
+            append_cache_record(&self.config, &record).await?;
+            state.file_records += 1;
+        }
+        Ok(())
+    }
+
+    async fn get<S, T>(&self, id: S, opt: Option<Self::Options>) -> VcxCoreResult<Option<T>>
+    where
+        S: ToString + Send,
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        let id = id.to_string();
+
+        let mut state = self.state.lock().await;
+        match state.entries.get(&id) {
+            Some(entry) => {
+                let ttl = match opt.and_then(|opt| opt.ttl()) {
+                    Some(ttl) => Some(ttl),
+                    None if entry.immutable => None,
+                    None => Some(self.config.ttl()),
+                };
+                if entry.is_expired(ttl) {
+                    state.entries.pop(&id);
+                    Ok(None)
+                } else {
+                    let obj: T = serde_json::from_str(&entry.obj)?;
+                    Ok(Some(obj))
+                }
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Whether the ID is a legacy or `did:indy` ID of a schema, credential definition or revocation
+/// registry definition
+fn is_immutable_ledger_object_id(id: &str) -> bool {
+    if URI_IMMUTABLE_OBJECT_PATHS
+        .iter()
+        .any(|path| id.contains(path))
+    {
+        return true;
+    }
+    !id.starts_with("did:")
+        && id
+            .split(':')
+            .nth(1)
+            .is_some_and(|marker| LEGACY_IMMUTABLE_OBJECT_MARKERS.contains(&marker))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as u64)
+}
+
+fn io_error(config: &PersistentResponseCacherConfig, err: std::io::Error) -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::IOError,
+        format!(
+            "Failed to access response cache file {:?}: {err}",
+            config.path()
+        ),
+    )
+}
+
+/// Replays the records of the cache file, compacting it if it holds corrupted records, which a
+/// later append would be mixed up with, or more records than a put would let it grow to
+async fn load_cache_file(config: &PersistentResponseCacherConfig) -> VcxCoreResult<CacheState> {
+    let mut entries = LruCache::new(config.capacity());
+    let contents = match tokio::fs::read_to_string(config.path()).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Ok(CacheState {
+                entries,
+                file_records: 0,
+            })
+        }
+        Err(err) => return Err(io_error(config, err)),
+    };
+    let mut file_records = 0;
+    let mut corrupted_records = 0;
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        file_records += 1;
+        match serde_json::from_str::<CacheRecord>(line) {
+            Ok(record) if record.entry.immutable && !config.cache_immutable_objects() => {}
+            Ok(record) => {
+                entries.put(record.id, record.entry);
+            }
+            Err(_) => corrupted_records += 1,
+        }
+    }
+    if corrupted_records > 0 || file_records > 2 * config.capacity().get() {
+        if corrupted_records > 0 {
+            warn!(
+                "Response cache file {:?} has {corrupted_records} corrupted records, which are \
+                 dropped",
+                config.path()
+            );
+        }
+        store_cache_file(config, &entries).await?;
+        file_records = entries.len();
+    }
+    Ok(CacheState {
+        entries,
+        file_records,
+    })
+}
+
+async fn append_cache_record(
+    config: &PersistentResponseCacherConfig,
+    record: &CacheRecord,
+) -> VcxCoreResult<()> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    if let Some(dir) = config.path().parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|err| io_error(config, err))?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(config.path())
+        .await
+        .map_err(|err| io_error(config, err))?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|err| io_error(config, err))?;
+    file.flush().await.map_err(|err| io_error(config, err))
+}
+
+/// Rewrites the cache file with the cached responses, least recently used first so that loading
+/// it restores their order. The records are written into a temporary file first, so that the
+/// cache file is replaced atomically.
+async fn store_cache_file(
+    config: &PersistentResponseCacherConfig,
+    entries: &LruCache<String, CacheEntry>,
+) -> VcxCoreResult<()> {
+    let mut contents = String::new();
+    for (id, entry) in entries.iter().rev() {
+        let record = CacheRecord {
+            id: id.clone(),
+            entry: entry.clone(),
+        };
+        contents.push_str(&serde_json::to_string(&record)?);
+        contents.push('\n');
+    }
+    let tmp_path = config.path().with_extension("tmp");
+    if let Some(dir) = config.path().parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|err| io_error(config, err))?;
+    }
+    tokio::fs::write(&tmp_path, contents)
+        .await
+        .map_err(|err| io_error(config, err))?;
+    tokio::fs::rename(&tmp_path, config.path())
+        .await
+        .map_err(|err| io_error(config, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        path::{Path, PathBuf},
+    };
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct TestStruct {
+        field: String,
+    }
+
+    const SCHEMA_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0";
+
+    fn _cache_path() -> PathBuf {
+        env::temp_dir().join(format!("response_cache_{}.json", uuid::Uuid::new_v4()))
+    }
+
+    fn _cacher_config(path: &Path, ttl: Duration) -> PersistentResponseCacherConfig {
+        PersistentResponseCacherConfig::builder()
+            .path(path)
+            .ttl(ttl)
+            .capacity(10)
+            .unwrap()
+            .build()
+    }
+
+    fn _ledger_object_cacher_config(path: &Path, ttl: Duration) -> PersistentResponseCacherConfig {
+        PersistentResponseCacherConfig::builder()
+            .path(path)
+            .ttl(ttl)
+            .capacity(10)
+            .unwrap()
+            .cache_immutable_objects(true)
+            .build()
+    }
+
+    async fn _file_records(path: &Path) -> usize {
+        tokio::fs::read_to_string(path)
+            .await
+            .unwrap()
+            .lines()
+            .count()
+    }
+
+    fn _cacher_options(ttl: Duration) -> PersistentResponseCacherOptions {
+        PersistentResponseCacherOptions::builder().ttl(ttl).build()
+    }
+
+    fn _test_object() -> TestStruct {
+        TestStruct {
+            field: "test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(1))).await?;
+        let test_object = _test_object();
+
+        cacher.put("id1", test_object.clone()).await?;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(Some(test_object), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expiration() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_millis(1))).await?;
+
+        cacher.put("id1", _test_object()).await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(None, cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_immutable_objects_do_not_expire() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher = PersistentResponseCacher::new(_ledger_object_cacher_config(
+            &path,
+            Duration::from_millis(1),
+        ))
+        .await?;
+        let test_object = _test_object();
+
+        cacher.put(SCHEMA_ID, test_object.clone()).await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let cached_object: Option<TestStruct> = cacher.get(SCHEMA_ID, None).await?;
+        assert_eq!(Some(test_object), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_from_file() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let test_object = _test_object();
+        {
+            let cacher = PersistentResponseCacher::new(_ledger_object_cacher_config(
+                &path,
+                Duration::from_secs(60),
+            ))
+            .await?;
+            cacher.put("id1", test_object.clone()).await?;
+            cacher.put(SCHEMA_ID, test_object.clone()).await?;
+        }
+
+        let cacher = PersistentResponseCacher::new(_ledger_object_cacher_config(
+            &path,
+            Duration::from_secs(60),
+        ))
+        .await?;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(Some(test_object.clone()), cached_object);
+        let cached_object: Option<TestStruct> = cacher.get(SCHEMA_ID, None).await?;
+        assert_eq!(Some(test_object), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_file_is_ignored() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        tokio::fs::write(&path, "not json").await.unwrap();
+
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(1))).await?;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(None, cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_options_ttl_override_global_config_ttl() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_millis(1))).await?;
+        let test_object = _test_object();
+
+        cacher.put("id1", test_object.clone()).await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let cached_object: Option<TestStruct> = cacher
+            .get("id1", Some(_cacher_options(Duration::from_secs(10))))
+            .await?;
+        assert_eq!(Some(test_object), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_immutable_objects_are_not_cached_by_default() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+
+        cacher.put(SCHEMA_ID, _test_object()).await?;
+
+        let cached_object: Option<TestStruct> = cacher.get(SCHEMA_ID, None).await?;
+        assert_eq!(None, cached_object);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_options_ttl_expires_immutable_objects() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher = PersistentResponseCacher::new(_ledger_object_cacher_config(
+            &path,
+            Duration::from_secs(60),
+        ))
+        .await?;
+
+        cacher.put(SCHEMA_ID, _test_object()).await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let cached_object: Option<TestStruct> = cacher
+            .get(SCHEMA_ID, Some(_cacher_options(Duration::from_millis(1))))
+            .await?;
+        assert_eq!(None, cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_responses_are_evicted() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let test_object = _test_object();
+        {
+            let cacher =
+                PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60)))
+                    .await?;
+            for i in 0..10 {
+                cacher.put(format!("id{i}"), test_object.clone()).await?;
+            }
+            let _: Option<TestStruct> = cacher.get("id0", None).await?;
+            cacher.put("id10", test_object.clone()).await?;
+
+            let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+            assert_eq!(None, cached_object);
+            let cached_object: Option<TestStruct> = cacher.get("id0", None).await?;
+            assert_eq!(Some(test_object.clone()), cached_object);
+        }
+
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+        assert_eq!(10, cacher.state.lock().await.entries.len());
+        let cached_object: Option<TestStruct> = cacher.get("id10", None).await?;
+        assert_eq!(Some(test_object), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_puts_are_appended_and_compacted() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+
+        for i in 0..20 {
+            cacher.put("id1", format!("value{i}")).await?;
+            assert_eq!(i + 1, _file_records(&path).await);
+        }
+        cacher.put("id1", "value20".to_string()).await?;
+        assert_eq!(1, _file_records(&path).await);
+
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+        let cached_object: Option<String> = cacher.get("id1", None).await?;
+        assert_eq!(Some("value20".to_string()), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_records_are_dropped() -> VcxCoreResult<()> {
+        let path = _cache_path();
+        {
+            let cacher =
+                PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60)))
+                    .await?;
+            cacher.put("id1", _test_object()).await?;
+        }
+        let mut contents = tokio::fs::read_to_string(&path).await.unwrap();
+        contents.push_str("{\"id\":\"id2\",\"ob");
+        tokio::fs::write(&path, contents).await.unwrap();
+
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+        assert_eq!(1, _file_records(&path).await);
+        cacher.put("id2", _test_object()).await?;
+
+        let cacher =
+            PersistentResponseCacher::new(_cacher_config(&path, Duration::from_secs(60))).await?;
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(Some(_test_object()), cached_object);
+        let cached_object: Option<TestStruct> = cacher.get("id2", None).await?;
+        assert_eq!(Some(_test_object()), cached_object);
+
+        tokio::fs::remove_file(path).await.unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_immutable_ledger_object_id() {
+        assert!(is_immutable_ledger_object_id(SCHEMA_ID));
+        assert!(is_immutable_ledger_object_id(
+            "V4SGRU86Z58d6TV7PBUe6f:3:CL:15:tag1"
+        ));
+        assert!(is_immutable_ledger_object_id(
+            "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:15:tag1:CL_ACCUM:tag2"
+        ));
+        assert!(is_immutable_ledger_object_id(
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/SCHEMA/degree/1.0"
+        ));
+        assert!(!is_immutable_ledger_object_id("V4SGRU86Z58d6TV7PBUe6f"));
+        assert!(!is_immutable_ledger_object_id(
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/REV_REG_ENTRY/15/tag1/tag2"
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+use std::{collections::HashMap, str::FromStr};
+
+use aries_askar::{
+    entry::TagFilter,
+    kms::{KeyAlg, LocalKey},
+    ErrorKind as AskarErrorKind,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "vdrtools_wallet")]
+use crate::WalletHandle;
+use crate::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    utils::async_fn_iterator::AsyncFnIterator,
+    wallet::{
+        askar::{
+            ed25519_public_key, encode_verkey, from_entry_tags, packing, to_entry_tags,
+            AskarRecordIterator, AskarWallet, RecordJson, RecordOptions, CATEGORY_DID,
+            CATEGORY_TEMPORARY_DID,
+        },
+        base_wallet::BaseWallet,
+        structs_io::UnpackMessageOutput,
+    },
+};
+
+/// DID record value, same as stored by vdrtools
+#[derive(Debug, Serialize, Deserialize)]
+struct DidValue {
+    did: String,
+    verkey: String,
+}
+
+fn seed_key(seed: &str) -> VcxCoreResult<LocalKey> {
+    if seed.len() != 32 {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            "Seed must be 32 bytes long",
+        ));
+    }
+    Ok(LocalKey::from_seed(KeyAlg::Ed25519, seed.as_bytes(), None)?)
+}
+
+#[async_trait]
+impl BaseWallet for AskarWallet {
+    #[cfg(feature = "vdrtools_wallet")]
+    fn get_wallet_handle(&self) -> WalletHandle {
+        crate::INVALID_WALLET_HANDLE
+    }
+
+    async fn create_and_store_my_did(
+        &self,
+        seed: Option<&str>,
+        method_name: Option<&str>,
+    ) -> VcxCoreResult<(String, String)> {
+        let key = match seed {
+            Some(seed) => seed_key(seed)?,
+            None => LocalKey::generate(KeyAlg::Ed25519, false)?,
+        };
+        let verkey = encode_verkey(&key)?;
+        let unqualified_did = bs58::encode(&key.to_public_bytes()?[..16]).into_string();
+        let did = match method_name {
+            Some(method_name) => format!("did:{method_name}:{unqualified_did}"),
+            None => unqualified_did,
+        };
+
+        let mut session = self.transaction().await?;
+        if session.fetch(CATEGORY_DID, &did, false).await?.is_some() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationDid,
+                format!("DID {did} already exists in wallet"),
+            ));
+        }
+        match session.insert_key(&verkey, &key, None, None, None).await {
+            // same seed may have been used for a key before
+            Err(err) if err.kind() == AskarErrorKind::Duplicate => {}
+            res => res?,
+        }
+        let value = serde_json::to_vec(&DidValue {
+            did: did.clone(),
+            verkey: verkey.clone(),
+        })?;
+        session
+            .insert(CATEGORY_DID, &did, &value, None, None)
+            .await?;
+        session.commit().await?;
+        Ok((did, verkey))
+    }
+
+    async fn key_for_local_did(&self, did: &str) -> VcxCoreResult<String> {
+        let mut session = self.session().await?;
+        let entry = self
+            .fetch_entry(&mut session, CATEGORY_DID, did, false)
+            .await?;
+        let did_value: DidValue = serde_json::from_slice(&entry.value)?;
+        Ok(did_value.verkey)
+    }
+
+    async fn replace_did_keys_start(&self, target_did: &str) -> VcxCoreResult<String> {
+        let key = LocalKey::generate(KeyAlg::Ed25519, false)?;
+        let verkey = encode_verkey(&key)?;
+        let value = serde_json::to_vec(&DidValue {
+            did: target_did.to_owned(),
+            verkey: verkey.clone(),
+        })?;
+
+        let mut session = self.transaction().await?;
+        self.fetch_entry(&mut session, CATEGORY_DID, target_did, false)
+            .await?;
+        session.insert_key(&verkey, &key, None, None, None).await?;
+        if session
+            .fetch(CATEGORY_TEMPORARY_DID, target_did, true)
+            .await?
+            .is_some()
+        {
+            session
+                .replace(CATEGORY_TEMPORARY_DID, target_did, &value, None, None)
+                .await?;
+        } else {
+            session
+                .insert(CATEGORY_TEMPORARY_DID, target_did, &value, None, None)
+                .await?;
+        }
+        session.commit().await?;
+        Ok(verkey)
+    }
+
+    async fn replace_did_keys_apply(&self, target_did: &str) -> VcxCoreResult<()> {
+        let mut session = self.transaction().await?;
+        let temporary = self
+            .fetch_entry(&mut session, CATEGORY_TEMPORARY_DID, target_did, true)
+            .await?;
+        session
+            .replace(CATEGORY_DID, target_did, &temporary.value, None, None)
+            .await?;
+        session.remove(CATEGORY_TEMPORARY_DID, target_did).await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn add_wallet_record(
+        &self,
+        xtype: &str,
+        id: &str,
+        value: &str,
+        tags: Option<HashMap<String, String>>,
+    ) -> VcxCoreResult<()> {
+        let tags = tags.as_ref().map(to_entry_tags);
+        let mut session = self.session().await?;
+        session
+            .insert(xtype, id, value.as_bytes(), tags.as_deref(), None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_wallet_record(
+        &self,
+        xtype: &str,
+        id: &str,
+        options: &str,
+    ) -> VcxCoreResult<String> {
+        let options = RecordOptions::parse(options)?;
+        let mut session = self.session().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, false).await?;
+        Ok(serde_json::to_string(&RecordJson::from_entry(
+            &entry, &options,
+        )?)?)
+    }
+
+    async fn get_wallet_record_value(&self, xtype: &str, id: &str) -> VcxCoreResult<String> {
+        let mut session = self.session().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, false).await?;
+        String::from_utf8(entry.value.to_vec())
+            .map_err(|err| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::ParsingError, err))
+    }
+
+    async fn delete_wallet_record(&self, xtype: &str, id: &str) -> VcxCoreResult<()> {
+        let mut session = self.session().await?;
+        Ok(session.remove(xtype, id).await?)
+    }
+
+    async fn update_wallet_record_value(
+        &self,
+        xtype: &str,
+        id: &str,
+        value: &str,
+    ) -> VcxCoreResult<()> {
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, true).await?;
+        session
+            .replace(xtype, id, value.as_bytes(), Some(&entry.tags), None)
+            .await?;
+        Ok(session.commit().await?)
+    }
+
+    async fn add_wallet_record_tags(
+        &self,
+        xtype: &str,
+        id: &str,
+        tags: HashMap<String, String>,
+    ) -> VcxCoreResult<()> {
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, true).await?;
+        let mut merged_tags = from_entry_tags(&entry.tags);
+        merged_tags.extend(tags);
+        session
+            .replace(
+                xtype,
+                id,
+                &entry.value,
+                Some(&to_entry_tags(&merged_tags)),
+                None,
+            )
+            .await?;
+        Ok(session.commit().await?)
+    }
+
+    async fn update_wallet_record_tags(
+        &self,
+        xtype: &str,
+        id: &str,
+        tags: HashMap<String, String>,
+    ) -> VcxCoreResult<()> {
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, true).await?;
+        session
+            .replace(xtype, id, &entry.value, Some(&to_entry_tags(&tags)), None)
+            .await?;
+        Ok(session.commit().await?)
+    }
+
+    async fn delete_wallet_record_tags(
+        &self,
+        xtype: &str,
+        id: &str,
+        tag_names: &str,
+    ) -> VcxCoreResult<()> {
+        let tag_names: Vec<String> = serde_json::from_str(tag_names)?;
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id, true).await?;
+        let mut tags = from_entry_tags(&entry.tags);
+        tags.retain(|name, _| !tag_names.contains(name));
+        session
+            .replace(xtype, id, &entry.value, Some(&to_entry_tags(&tags)), None)
+            .await?;
+        Ok(session.commit().await?)
+    }
+
+    async fn iterate_wallet_records(
+        &self,
+        xtype: &str,
+        query: &str,
+        options: &str,
+    ) -> VcxCoreResult<Box<dyn AsyncFnIterator<Item = VcxCoreResult<String>>>> {
+        let options = RecordOptions::parse(options)?;
+        let tag_filter = match query.trim() {
+            "" | "{}" => None,
+            query => Some(TagFilter::from_str(query)?),
+        };
+        let scan = self
+            .store
+            .scan(
+                self.profile.clone(),
+                Some(xtype.to_owned()),
+                tag_filter,
+                None,
+                None,
+            )
+            .await?;
+        Ok(Box::new(AskarRecordIterator::new(scan, options)))
+    }
+
+    async fn sign(&self, my_vk: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let mut session = self.session().await?;
+        let key = self.fetch_local_key(&mut session, my_vk).await?;
+        Ok(key.sign_message(msg, None)?)
+    }
+
+    async fn verify(&self, vk: &str, msg: &[u8], signature: &[u8]) -> VcxCoreResult<bool> {
+        Ok(ed25519_public_key(vk)?.verify_signature(msg, signature, None)?)
+    }
+
+    async fn pack_message(
+        &self,
+        sender_vk: Option<&str>,
+        receiver_keys: &str,
+        msg: &[u8],
+    ) -> VcxCoreResult<Vec<u8>> {
+        let receiver_keys: Vec<String> = serde_json::from_str(receiver_keys).map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidJson,
+                "Invalid RecipientKeys has been passed",
+            )
+        })?;
+        let sender_key = match sender_vk {
+            Some(sender_vk) => {
+                let mut session = self.session().await?;
+                Some((
+                    sender_vk,
+                    self.fetch_local_key(&mut session, sender_vk).await?,
+                ))
+            }
+            None => None,
+        };
+        let sender = sender_key.as_ref().map(|(verkey, key)| (*verkey, key));
+        packing::pack(sender, &receiver_keys, msg)
+    }
+
+    async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<UnpackMessageOutput> {
+        let jwe: packing::Jwe = serde_json::from_slice(msg)?;
+        let protected = jwe.protected()?;
+        let mut session = self.session().await?;
+        for recipient in &protected.recipients {
+            let Some(key_entry) = session.fetch_key(&recipient.header.kid, false).await? else {
+                continue;
+            };
+            let recipient_key = key_entry.load_local_key()?;
+            return packing::unpack(&jwe, &protected, recipient, &recipient_key);
+        }
+        Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::WalletRecordNotFound,
+            "None of the message recipient keys are in the wallet",
+        ))
+    }
+}
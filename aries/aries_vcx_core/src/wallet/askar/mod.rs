@@ -0,0 +1,478 @@
+use std::{collections::HashMap, fmt};
+
+use aries_askar::{
+    entry::{Entry, EntryTag, Scan},
+    kms::{KeyAlg, LocalKey},
+    PassKey, Session, Store, StoreKeyMethod,
+};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    utils::async_fn_iterator::AsyncFnIterator,
+};
+
+pub mod askar_wallet;
+mod packing;
+
+/// Same record categories as the vdrtools wallet, so that migrated DIDs keep working
+pub(crate) const CATEGORY_DID: &str = "Indy::Did";
+pub(crate) const CATEGORY_TEMPORARY_DID: &str = "Indy::TemporaryDid";
+
+#[derive(Clone, Debug, Default, Builder, Serialize, Deserialize)]
+#[builder(setter(into, strip_option), default)]
+pub struct AskarWalletConfig {
+    /// Store location, e.g. `sqlite://wallet.db` or `sqlite://:memory:`
+    pub db_url: String,
+    /// Askar key method uri, e.g. `kdf:argon2i:mod` or `raw`
+    pub key_method: String,
+    pub pass_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl AskarWalletConfig {
+    fn store_key_method(&self) -> VcxCoreResult<StoreKeyMethod> {
+        Ok(StoreKeyMethod::parse_uri(&self.key_method)?)
+    }
+}
+
+/// [BaseWallet](crate::wallet::base_wallet::BaseWallet) on an Aries Askar store
+pub struct AskarWallet {
+    store: Store,
+    profile: Option<String>,
+}
+
+impl fmt::Debug for AskarWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AskarWallet")
+            .field("profile", &self.profile)
+            .finish()
+    }
+}
+
+impl AskarWallet {
+    /// Provisions a new store, replacing an existing one at the same location if `recreate`
+    pub async fn create(config: &AskarWalletConfig, recreate: bool) -> VcxCoreResult<Self> {
+        let store = Store::provision(
+            &config.db_url,
+            config.store_key_method()?,
+            PassKey::from(config.pass_key.as_str()),
+            config.profile.clone(),
+            recreate,
+        )
+        .await?;
+        Ok(Self {
+            store,
+            profile: config.profile.clone(),
+        })
+    }
+
+    pub async fn open(config: &AskarWalletConfig) -> VcxCoreResult<Self> {
+        let store = Store::open(
+            &config.db_url,
+            Some(config.store_key_method()?),
+            PassKey::from(config.pass_key.as_str()),
+            config.profile.clone(),
+        )
+        .await?;
+        Ok(Self {
+            store,
+            profile: config.profile.clone(),
+        })
+    }
+
+    pub async fn close(self) -> VcxCoreResult<()> {
+        Ok(self.store.close().await?)
+    }
+
+    /// Imports an ed25519 key as stored by vdrtools, where `signkey` is the base58 encoded
+    /// 64 byte keypair
+    pub async fn import_indy_key(&self, verkey: &str, signkey: &str) -> VcxCoreResult<()> {
+        let keypair = decode_base58(signkey)?;
+        if keypair.len() != 64 {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!(
+                    "Expected 64 byte ed25519 keypair, got {} bytes",
+                    keypair.len()
+                ),
+            ));
+        }
+        let key = LocalKey::from_secret_bytes(KeyAlg::Ed25519, &keypair[..32])?;
+        if encode_verkey(&key)? != verkey {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidVerkey,
+                format!("Signkey does not belong to verkey {verkey}"),
+            ));
+        }
+        let mut session = self.session().await?;
+        session.insert_key(verkey, &key, None, None, None).await?;
+        Ok(())
+    }
+
+    async fn session(&self) -> VcxCoreResult<Session> {
+        Ok(self.store.session(self.profile.clone()).await?)
+    }
+
+    async fn transaction(&self) -> VcxCoreResult<Session> {
+        Ok(self.store.transaction(self.profile.clone()).await?)
+    }
+
+    async fn fetch_local_key(
+        &self,
+        session: &mut Session,
+        verkey: &str,
+    ) -> VcxCoreResult<LocalKey> {
+        let key_entry = session.fetch_key(verkey, false).await?.ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("Key {verkey} not found in wallet"),
+            )
+        })?;
+        Ok(key_entry.load_local_key()?)
+    }
+
+    async fn fetch_entry(
+        &self,
+        session: &mut Session,
+        category: &str,
+        name: &str,
+        for_update: bool,
+    ) -> VcxCoreResult<Entry> {
+        session
+            .fetch(category, name, for_update)
+            .await?
+            .ok_or_else(|| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::WalletRecordNotFound,
+                    format!("Wallet record {category} {name} not found"),
+                )
+            })
+    }
+}
+
+pub(crate) fn decode_base58(value: &str) -> VcxCoreResult<Vec<u8>> {
+    bs58::decode(value)
+        .into_vec()
+        .map_err(|err| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::NotBase58, err))
+}
+
+pub(crate) fn encode_verkey(key: &LocalKey) -> VcxCoreResult<String> {
+    Ok(bs58::encode(key.to_public_bytes()?).into_string())
+}
+
+pub(crate) fn ed25519_public_key(verkey: &str) -> VcxCoreResult<LocalKey> {
+    Ok(LocalKey::from_public_bytes(
+        KeyAlg::Ed25519,
+        &decode_base58(verkey)?,
+    )?)
+}
+
+/// Tags named with a `~` prefix are stored unencrypted, as in the vdrtools wallet
+pub(crate) fn to_entry_tags(tags: &HashMap<String, String>) -> Vec<EntryTag> {
+    tags.iter()
+        .map(|(name, value)| match name.strip_prefix('~') {
+            Some(name) => EntryTag::Plaintext(name.to_owned(), value.to_owned()),
+            None => EntryTag::Encrypted(name.to_owned(), value.to_owned()),
+        })
+        .collect()
+}
+
+pub(crate) fn from_entry_tags(tags: &[EntryTag]) -> HashMap<String, String> {
+    tags.iter()
+        .map(|tag| match tag {
+            EntryTag::Plaintext(name, value) => (format!("~{name}"), value.to_owned()),
+            EntryTag::Encrypted(name, value) => (name.to_owned(), value.to_owned()),
+        })
+        .collect()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Same retrieve options as the vdrtools wallet record and search APIs
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordOptions {
+    #[serde(default)]
+    pub retrieve_type: bool,
+    #[serde(default = "default_true")]
+    pub retrieve_value: bool,
+    #[serde(default)]
+    pub retrieve_tags: bool,
+}
+
+impl RecordOptions {
+    pub fn parse(options: &str) -> VcxCoreResult<Self> {
+        if options.trim().is_empty() {
+            return Ok(serde_json::from_str("{}")?);
+        }
+        Ok(serde_json::from_str(options)?)
+    }
+}
+
+/// Record in the json format returned by the vdrtools wallet
+#[derive(Debug, Serialize)]
+pub(crate) struct RecordJson {
+    id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    record_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<HashMap<String, String>>,
+}
+
+impl RecordJson {
+    pub fn from_entry(entry: &Entry, options: &RecordOptions) -> VcxCoreResult<Self> {
+        let value = if options.retrieve_value {
+            Some(String::from_utf8(entry.value.to_vec()).map_err(|err| {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::ParsingError, err)
+            })?)
+        } else {
+            None
+        };
+        Ok(Self {
+            id: entry.name.clone(),
+            record_type: options.retrieve_type.then(|| entry.category.clone()),
+            value,
+            tags: options.retrieve_tags.then(|| from_entry_tags(&entry.tags)),
+        })
+    }
+}
+
+/// Records matching a search, fetched from the store a page at a time as they are iterated
+pub(crate) struct AskarRecordIterator {
+    scan: Scan<'static, Entry>,
+    options: RecordOptions,
+    page: std::vec::IntoIter<Entry>,
+}
+
+impl AskarRecordIterator {
+    pub fn new(scan: Scan<'static, Entry>, options: RecordOptions) -> Self {
+        Self {
+            scan,
+            options,
+            page: Vec::new().into_iter(),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncFnIterator for AskarRecordIterator {
+    type Item = VcxCoreResult<String>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.page.next() {
+                return Some(
+                    RecordJson::from_entry(&entry, &self.options)
+                        .and_then(|record| Ok(serde_json::to_string(&record)?)),
+                );
+            }
+            match self.scan.fetch_next().await {
+                Ok(Some(entries)) => self.page = entries.into_iter(),
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::wallet::{
+        base_wallet::{AsyncFnIteratorCollect, BaseWallet},
+        didcomm_v2::{DidcommV2Key, Jwk, KeyAgreementCurve},
+    };
+
+    async fn create_test_wallet() -> AskarWallet {
+        let config = AskarWalletConfigBuilder::default()
+            .db_url("sqlite://:memory:")
+            .key_method("none")
+            .build()
+            .unwrap();
+        AskarWallet::create(&config, true).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_did_sign_verify() {
+        let wallet = create_test_wallet().await;
+        let (did, verkey) = wallet
+            .create_and_store_my_did(Some("000000000000000000000000Trustee1"), None)
+            .await
+            .unwrap();
+        assert_eq!(did, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(verkey, "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL");
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+
+        let signature = wallet.sign(&verkey, b"hello").await.unwrap();
+        assert!(wallet.verify(&verkey, b"hello", &signature).await.unwrap());
+        assert!(!wallet.verify(&verkey, b"other", &signature).await.unwrap());
+
+        let new_verkey = wallet.replace_did_keys_start(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+        wallet.replace_did_keys_apply(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), new_verkey);
+    }
+
+    #[tokio::test]
+    async fn test_records_and_tag_queries() {
+        let wallet = create_test_wallet().await;
+        let tags = HashMap::from([
+            ("~color".to_owned(), "red".to_owned()),
+            ("secret".to_owned(), "1".to_owned()),
+        ]);
+        wallet
+            .add_wallet_record("fruit", "apple", "crunchy", Some(tags))
+            .await
+            .unwrap();
+        wallet
+            .add_wallet_record("fruit", "cherry", "juicy", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            wallet
+                .get_wallet_record_value("fruit", "apple")
+                .await
+                .unwrap(),
+            "crunchy"
+        );
+        let err = wallet
+            .add_wallet_record("fruit", "apple", "again", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::DuplicationWalletRecord);
+
+        let found = wallet
+            .iterate_wallet_records("fruit", r#"{"~color": "red"}"#, "{}")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("apple"));
+
+        wallet
+            .delete_wallet_record_tags("fruit", "apple", r#"["~color"]"#)
+            .await
+            .unwrap();
+        let record = wallet
+            .get_wallet_record("fruit", "apple", r#"{"retrieveTags": true}"#)
+            .await
+            .unwrap();
+        let record: serde_json::Value = serde_json::from_str(&record).unwrap();
+        assert_eq!(record["tags"], serde_json::json!({"secret": "1"}));
+
+        wallet.delete_wallet_record("fruit", "apple").await.unwrap();
+        let err = wallet
+            .get_wallet_record_value("fruit", "apple")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::WalletRecordNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_iterate_records_across_pages() {
+        let wallet = create_test_wallet().await;
+        for i in 0..100 {
+            wallet
+                .add_wallet_record("number", &i.to_string(), "value", None)
+                .await
+                .unwrap();
+        }
+        let mut records = wallet
+            .iterate_wallet_records("number", "{}", "{}")
+            .await
+            .unwrap();
+        let mut count = 0;
+        while let Some(record) = records.next().await {
+            record.unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 100);
+    }
+
+    #[tokio::test]
+    async fn test_pack_unpack() {
+        let wallet = create_test_wallet().await;
+        let (_, sender_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let (_, recipient_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver_keys = serde_json::to_string(&[&recipient_vk]).unwrap();
+
+        let packed = wallet
+            .pack_message(Some(&sender_vk), &receiver_keys, b"authcrypted")
+            .await
+            .unwrap();
+        let unpacked = wallet.unpack_message(&packed).await.unwrap();
+        assert_eq!(unpacked.message, "authcrypted");
+        assert_eq!(unpacked.recipient_verkey, recipient_vk);
+        assert_eq!(unpacked.sender_verkey, Some(sender_vk));
+
+        let packed = wallet
+            .pack_message(None, &receiver_keys, b"anoncrypted")
+            .await
+            .unwrap();
+        let unpacked = wallet.unpack_message(&packed).await.unwrap();
+        assert_eq!(unpacked.message, "anoncrypted");
+        assert_eq!(unpacked.sender_verkey, None);
+    }
+
+    #[tokio::test]
+    async fn test_unpack_v2_rejects_sender_other_than_from() {
+        let wallet = create_test_wallet().await;
+        let mallory_kid = "did:example:mallory#key-1";
+        let bob_kid = "did:example:bob#key-x25519-1";
+        let (_, mallory_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let message = br#"{"id":"1","type":"test","from":"did:example:alice","body":{}}"#;
+
+        let signed = wallet
+            .sign_message_v2(mallory_kid, &mallory_vk, message)
+            .await
+            .unwrap();
+        let mallory_signing_key = DidcommV2Key {
+            kid: mallory_kid.to_owned(),
+            jwk: Jwk::from_ed25519_verkey(&mallory_vk).unwrap(),
+        };
+        let err = wallet
+            .unpack_message_v2(&signed, &[mallory_signing_key])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidMessageFormat);
+
+        let mallory_x25519_kid = "did:example:mallory#key-x25519-1";
+        let mallory_jwk = wallet
+            .create_key_agreement_key(mallory_x25519_kid, KeyAgreementCurve::X25519)
+            .await
+            .unwrap();
+        let bob_jwk = wallet
+            .create_key_agreement_key(bob_kid, KeyAgreementCurve::X25519)
+            .await
+            .unwrap();
+        let bob_key = DidcommV2Key {
+            kid: bob_kid.to_owned(),
+            jwk: bob_jwk,
+        };
+        let authcrypted = wallet
+            .pack_message_v2(Some(mallory_x25519_kid), &[bob_key], message)
+            .await
+            .unwrap();
+        let mallory_key = DidcommV2Key {
+            kid: mallory_x25519_kid.to_owned(),
+            jwk: mallory_jwk,
+        };
+        let err = wallet
+            .unpack_message_v2(&authcrypted, &[mallory_key])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidMessageFormat);
+    }
+}
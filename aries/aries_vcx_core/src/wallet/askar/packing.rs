@@ -0,0 +1,217 @@
+//! DIDComm v1 envelopes (Aries RFC 0019) compatible with the vdrtools `pack_msg`. Despite the
+//! `enc` header, the content is encrypted with ChaCha20-Poly1305 IETF, as vdrtools does.
+
+use aries_askar::kms::{
+    crypto_box, crypto_box_open, crypto_box_random_nonce, crypto_box_seal, crypto_box_seal_open,
+    Chacha20Types, KeyAlg, LocalKey,
+};
+use base64::{
+    alphabet,
+    engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ed25519_public_key;
+use crate::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    wallet::structs_io::UnpackMessageOutput,
+};
+
+const PROTECTED_HEADER_ENC: &str = "xchacha20poly1305_ietf";
+const PROTECTED_HEADER_TYP: &str = "JWM/1.0";
+const PROTECTED_HEADER_ALG_AUTH: &str = "Authcrypt";
+const PROTECTED_HEADER_ALG_ANON: &str = "Anoncrypt";
+
+const CONTENT_ALG: KeyAlg = KeyAlg::Chacha20(Chacha20Types::C20P);
+
+/// vdrtools encodes padded, but accepts either when decoding
+const URL_SAFE_ANY_PADDING: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Header {
+    pub kid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iv: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Recipient {
+    pub encrypted_key: String,
+    pub header: Header,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Protected {
+    pub enc: String,
+    pub typ: String,
+    pub alg: String,
+    pub recipients: Vec<Recipient>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Jwe {
+    pub protected: String,
+    pub iv: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+impl Jwe {
+    pub fn protected(&self) -> VcxCoreResult<Protected> {
+        Ok(serde_json::from_slice(&decode(&self.protected)?)?)
+    }
+}
+
+fn encode(data: &[u8]) -> String {
+    general_purpose::URL_SAFE.encode(data)
+}
+
+fn decode(data: &str) -> VcxCoreResult<Vec<u8>> {
+    URL_SAFE_ANY_PADDING.decode(data).map_err(|err| {
+        AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidMessageFormat, err)
+    })
+}
+
+fn missing_header(name: &str) -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::InvalidMessageFormat,
+        format!("Authcrypted message recipient is missing {name} header"),
+    )
+}
+
+/// Encrypts `msg` for each of `receiver_keys`, authcrypted if the sender's key is given
+pub(crate) fn pack(
+    sender: Option<(&str, &LocalKey)>,
+    receiver_keys: &[String],
+    msg: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    if receiver_keys.is_empty() {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidLibindyParam,
+            "Empty RecipientKeys has been passed",
+        ));
+    }
+    let cek = LocalKey::generate(CONTENT_ALG, true)?;
+    let cek_bytes = cek.to_secret_bytes()?;
+    let sender = match sender {
+        Some((verkey, key)) => Some((verkey, key.convert_key(KeyAlg::X25519)?)),
+        None => None,
+    };
+
+    let recipients = receiver_keys
+        .iter()
+        .map(|receiver_vk| {
+            let receiver_key = ed25519_public_key(receiver_vk)?.convert_key(KeyAlg::X25519)?;
+            let recipient = match &sender {
+                Some((sender_vk, sender_key)) => {
+                    let nonce = crypto_box_random_nonce()?;
+                    let encrypted_key = crypto_box(&receiver_key, sender_key, &cek_bytes, &nonce)?;
+                    let encrypted_sender = crypto_box_seal(&receiver_key, sender_vk.as_bytes())?;
+                    Recipient {
+                        encrypted_key: encode(&encrypted_key),
+                        header: Header {
+                            kid: receiver_vk.to_owned(),
+                            sender: Some(encode(&encrypted_sender)),
+                            iv: Some(encode(&nonce)),
+                        },
+                    }
+                }
+                None => Recipient {
+                    encrypted_key: encode(&crypto_box_seal(&receiver_key, &cek_bytes)?),
+                    header: Header {
+                        kid: receiver_vk.to_owned(),
+                        sender: None,
+                        iv: None,
+                    },
+                },
+            };
+            Ok(recipient)
+        })
+        .collect::<VcxCoreResult<Vec<_>>>()?;
+
+    let alg = if sender.is_some() {
+        PROTECTED_HEADER_ALG_AUTH
+    } else {
+        PROTECTED_HEADER_ALG_ANON
+    };
+    let protected = Protected {
+        enc: PROTECTED_HEADER_ENC.to_owned(),
+        typ: PROTECTED_HEADER_TYP.to_owned(),
+        alg: alg.to_owned(),
+        recipients,
+    };
+    let protected = encode(&serde_json::to_vec(&protected)?);
+
+    let nonce = cek.aead_random_nonce()?;
+    let encrypted = cek.aead_encrypt(msg, &nonce, protected.as_bytes())?;
+    let jwe = Jwe {
+        protected,
+        iv: encode(&nonce),
+        ciphertext: encode(encrypted.ciphertext()),
+        tag: encode(encrypted.tag()),
+    };
+    Ok(serde_json::to_vec(&jwe)?)
+}
+
+/// Decrypts message for `recipient`, whose ed25519 secret key is `recipient_key`
+pub(crate) fn unpack(
+    jwe: &Jwe,
+    protected: &Protected,
+    recipient: &Recipient,
+    recipient_key: &LocalKey,
+) -> VcxCoreResult<UnpackMessageOutput> {
+    let recipient_key = recipient_key.convert_key(KeyAlg::X25519)?;
+    let encrypted_key = decode(&recipient.encrypted_key)?;
+    let (cek_bytes, sender_verkey) = match protected.alg.as_str() {
+        PROTECTED_HEADER_ALG_AUTH => {
+            let encrypted_sender = recipient
+                .header
+                .sender
+                .as_deref()
+                .ok_or_else(|| missing_header("sender"))?;
+            let nonce = recipient
+                .header
+                .iv
+                .as_deref()
+                .ok_or_else(|| missing_header("iv"))?;
+            let sender_vk = crypto_box_seal_open(&recipient_key, &decode(encrypted_sender)?)?;
+            let sender_vk = String::from_utf8(sender_vk.to_vec()).map_err(|err| {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidMessageFormat, err)
+            })?;
+            let sender_key = ed25519_public_key(&sender_vk)?.convert_key(KeyAlg::X25519)?;
+            let cek_bytes =
+                crypto_box_open(&recipient_key, &sender_key, &encrypted_key, &decode(nonce)?)?;
+            (cek_bytes, Some(sender_vk))
+        }
+        PROTECTED_HEADER_ALG_ANON => (crypto_box_seal_open(&recipient_key, &encrypted_key)?, None),
+        alg => {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                format!("Unsupported pack algorithm {alg}"),
+            ))
+        }
+    };
+
+    let cek = LocalKey::from_secret_bytes(CONTENT_ALG, &cek_bytes)?;
+    let ciphertext = decode(&jwe.ciphertext)?;
+    let tag = decode(&jwe.tag)?;
+    let message = cek.aead_decrypt(
+        (ciphertext.as_slice(), tag.as_slice()),
+        &decode(&jwe.iv)?,
+        jwe.protected.as_bytes(),
+    )?;
+    let message = String::from_utf8(message.to_vec()).map_err(|err| {
+        AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidMessageFormat, err)
+    })?;
+    Ok(UnpackMessageOutput {
+        message,
+        recipient_verkey: recipient.header.kid.clone(),
+        sender_verkey,
+    })
+}
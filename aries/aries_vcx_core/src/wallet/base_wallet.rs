@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use async_trait::async_trait;
 
-use super::structs_io::UnpackMessageOutput;
+use super::{
+    didcomm_v2::{self, DidcommV2Key, Jwk, KeyAgreementCurve},
+    structs_io::{UnpackMessageOutput, UnpackMessageOutputV2},
+};
 #[cfg(feature = "vdrtools_wallet")]
 use crate::WalletHandle;
 use crate::{errors::error::VcxCoreResult, utils::async_fn_iterator::AsyncFnIterator};
@@ -101,6 +104,48 @@ pub trait BaseWallet: std::fmt::Debug + Send + Sync {
     ) -> VcxCoreResult<Vec<u8>>;
 
     async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<UnpackMessageOutput>;
+
+    // ---- DIDComm v2
+    // Key agreement secrets are kept as wallet records, signing uses the wallet's verkeys.
+
+    /// Generates key agreement key for DID URL `kid` and returns its public JWK
+    async fn create_key_agreement_key(
+        &self,
+        kid: &str,
+        curve: KeyAgreementCurve,
+    ) -> VcxCoreResult<Jwk> {
+        didcomm_v2::create_key_agreement_key(self, kid, curve).await
+    }
+
+    /// Encrypts `msg` as JWE, authcrypted with key `sender_kid` if given, anoncrypted otherwise
+    async fn pack_message_v2(
+        &self,
+        sender_kid: Option<&str>,
+        recipients: &[DidcommV2Key],
+        msg: &[u8],
+    ) -> VcxCoreResult<Vec<u8>> {
+        didcomm_v2::pack_encrypted(self, sender_kid, recipients, msg).await
+    }
+
+    /// Signs `msg` as JWS with verkey `signer_vk`, identified by DID URL `signer_kid`
+    async fn sign_message_v2(
+        &self,
+        signer_kid: &str,
+        signer_vk: &str,
+        msg: &[u8],
+    ) -> VcxCoreResult<Vec<u8>> {
+        didcomm_v2::pack_signed(self, signer_kid, signer_vk, msg).await
+    }
+
+    /// Decrypts and verifies nested JWE / JWS envelopes. `known_keys` are the public keys of
+    /// possible senders and signers.
+    async fn unpack_message_v2(
+        &self,
+        msg: &[u8],
+        known_keys: &[DidcommV2Key],
+    ) -> VcxCoreResult<UnpackMessageOutputV2> {
+        didcomm_v2::unpack(self, msg, known_keys).await
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,340 @@
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit};
+use aes_kw::KekAes256;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+use super::{
+    decode_b64,
+    jwk::{Jwk, PublicKey, SecretKey},
+};
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+pub const ENCRYPTED_MESSAGE_TYP: &str = "application/didcomm-encrypted+json";
+
+const ALG_AUTHCRYPT: &str = "ECDH-1PU+A256KW";
+const ALG_ANONCRYPT: &str = "ECDH-ES+A256KW";
+const ENC_A256CBC_HS512: &str = "A256CBC-HS512";
+const ENC_A256GCM: &str = "A256GCM";
+
+/// Protected header shared by all recipients of a JWE
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct ProtectedHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    pub alg: String,
+    pub enc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apu: Option<String>,
+    pub apv: String,
+    pub epk: Jwk,
+}
+
+impl ProtectedHeader {
+    pub fn is_authcrypt(&self) -> bool {
+        self.alg == ALG_AUTHCRYPT
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct RecipientHeader {
+    pub kid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Recipient {
+    pub header: RecipientHeader,
+    pub encrypted_key: String,
+}
+
+/// JWE in general JSON serialization
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Jwe {
+    pub protected: String,
+    pub recipients: Vec<Recipient>,
+    pub iv: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+impl Jwe {
+    pub fn protected_header(&self) -> VcxCoreResult<ProtectedHeader> {
+        Ok(serde_json::from_slice(&decode_b64(&self.protected)?)?)
+    }
+
+    /// Encrypts `plaintext` for recipients, all of whose keys must be on the same curve.
+    /// With a sender the message is authcrypted (ECDH-1PU), otherwise anoncrypted (ECDH-ES).
+    pub fn encrypt(
+        sender: Option<(&str, &SecretKey)>,
+        recipients: &[(&str, PublicKey)],
+        plaintext: &[u8],
+    ) -> VcxCoreResult<Self> {
+        let curve = match recipients.first() {
+            Some((_, key)) => key.curve(),
+            None => {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    "Cannot encrypt message without recipients",
+                ))
+            }
+        };
+        let mixed_curves = recipients.iter().any(|(_, key)| key.curve() != curve)
+            || sender.is_some_and(|(_, key)| key.curve() != curve);
+        if mixed_curves {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                "Sender and recipient keys must all be on the same curve",
+            ));
+        }
+        let ephemeral = SecretKey::generate(curve);
+        let mut kids: Vec<&str> = recipients.iter().map(|(kid, _)| *kid).collect();
+        kids.sort_unstable();
+        let header = ProtectedHeader {
+            typ: Some(ENCRYPTED_MESSAGE_TYP.to_string()),
+            alg: if sender.is_some() {
+                ALG_AUTHCRYPT
+            } else {
+                ALG_ANONCRYPT
+            }
+            .to_string(),
+            enc: ENC_A256CBC_HS512.to_string(),
+            skid: sender.map(|(kid, _)| kid.to_string()),
+            apu: sender.map(|(kid, _)| URL_SAFE_NO_PAD.encode(kid)),
+            apv: URL_SAFE_NO_PAD.encode(Sha256::digest(kids.join(".").as_bytes())),
+            epk: ephemeral.public_key().to_jwk()?,
+        };
+        let protected = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?);
+
+        let mut cek = [0u8; 64];
+        OsRng.fill_bytes(&mut cek);
+        let mut iv = [0u8; 16];
+        OsRng.fill_bytes(&mut iv);
+        let (ciphertext, tag) = a256cbc_hs512_encrypt(&cek, &iv, protected.as_bytes(), plaintext);
+
+        let recipients = recipients
+            .iter()
+            .map(|(kid, key)| {
+                let mut shared = ephemeral.diffie_hellman(key)?;
+                if let Some((_, sender_key)) = sender {
+                    shared.extend(sender_key.diffie_hellman(key)?);
+                }
+                let kek = derive_kek(&shared, &header, &tag)?;
+                let encrypted_key = KekAes256::new(&kek.into()).wrap_vec(&cek).map_err(|err| {
+                    AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::EncodeError, err)
+                })?;
+                Ok(Recipient {
+                    header: RecipientHeader {
+                        kid: kid.to_string(),
+                    },
+                    encrypted_key: URL_SAFE_NO_PAD.encode(encrypted_key),
+                })
+            })
+            .collect::<VcxCoreResult<_>>()?;
+
+        Ok(Self {
+            protected,
+            recipients,
+            iv: URL_SAFE_NO_PAD.encode(iv),
+            ciphertext: URL_SAFE_NO_PAD.encode(ciphertext),
+            tag: URL_SAFE_NO_PAD.encode(tag),
+        })
+    }
+
+    /// Decrypts the message as recipient `kid`. Authcrypted messages need the public key of
+    /// the sender identified by the header's `skid`.
+    pub fn decrypt(
+        &self,
+        kid: &str,
+        recipient_key: &SecretKey,
+        sender_key: Option<&PublicKey>,
+    ) -> VcxCoreResult<Vec<u8>> {
+        let header = self.protected_header()?;
+        let tag = decode_b64(&self.tag)?;
+        let cek = self.unwrap_content_key(&header, kid, recipient_key, sender_key)?;
+        let iv = decode_b64(&self.iv)?;
+        let ciphertext = decode_b64(&self.ciphertext)?;
+        let aad = self.protected.as_bytes();
+        match header.enc.as_str() {
+            ENC_A256CBC_HS512 => a256cbc_hs512_decrypt(&cek, &iv, aad, &ciphertext, &tag),
+            ENC_A256GCM => a256gcm_decrypt(&cek, &iv, aad, &ciphertext, &tag),
+            enc => Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::ActionNotSupported,
+                format!("Unsupported JWE content encryption {enc}"),
+            )),
+        }
+    }
+
+    /// Content encryption key of recipient `kid`, derived and unwrapped per the header's `alg`
+    pub fn unwrap_content_key(
+        &self,
+        header: &ProtectedHeader,
+        kid: &str,
+        recipient_key: &SecretKey,
+        sender_key: Option<&PublicKey>,
+    ) -> VcxCoreResult<Vec<u8>> {
+        let recipient = self
+            .recipients
+            .iter()
+            .find(|recipient| recipient.header.kid == kid)
+            .ok_or_else(|| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    format!("Message is not encrypted for {kid}"),
+                )
+            })?;
+        // ECDH-1PU in key wrapping mode is only defined with AES-CBC-HMAC content encryption
+        if header.is_authcrypt() && header.enc != ENC_A256CBC_HS512 {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                format!(
+                    "Authcrypted message must use {ENC_A256CBC_HS512}, not {}",
+                    header.enc
+                ),
+            ));
+        }
+        let ephemeral = PublicKey::from_jwk(&header.epk)?;
+        let mut shared = recipient_key.diffie_hellman(&ephemeral)?;
+        match (header.alg.as_str(), sender_key) {
+            (ALG_AUTHCRYPT, Some(sender_key)) => {
+                shared.extend(recipient_key.diffie_hellman(sender_key)?)
+            }
+            (ALG_AUTHCRYPT, None) => {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidInput,
+                    "Sender key is required to decrypt authcrypted message",
+                ))
+            }
+            (ALG_ANONCRYPT, _) => {}
+            (alg, _) => {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::ActionNotSupported,
+                    format!("Unsupported JWE algorithm {alg}"),
+                ))
+            }
+        }
+        let tag = decode_b64(&self.tag)?;
+        let kek = derive_kek(&shared, header, &tag)?;
+        KekAes256::new(&kek.into())
+            .unwrap_vec(&decode_b64(&recipient.encrypted_key)?)
+            .map_err(|_| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidMessageFormat,
+                    "Failed to unwrap content encryption key",
+                )
+            })
+    }
+}
+
+/// Concat KDF (NIST SP 800-56A) of the key wrapping key. ECDH-1PU in key wrapping mode also
+/// binds the content authentication tag.
+fn derive_kek(shared: &[u8], header: &ProtectedHeader, tag: &[u8]) -> VcxCoreResult<[u8; 32]> {
+    fn length_prefixed(out: &mut Vec<u8>, data: &[u8]) {
+        out.extend((data.len() as u32).to_be_bytes());
+        out.extend(data);
+    }
+    let apu = header.apu.as_deref().map(decode_b64).transpose()?;
+    let apv = decode_b64(&header.apv)?;
+    let mut other_info = Vec::new();
+    length_prefixed(&mut other_info, header.alg.as_bytes());
+    length_prefixed(&mut other_info, apu.as_deref().unwrap_or_default());
+    length_prefixed(&mut other_info, &apv);
+    other_info.extend(256u32.to_be_bytes());
+    if header.is_authcrypt() {
+        length_prefixed(&mut other_info, tag);
+    }
+    // single round of the KDF yields the whole 256 bit key
+    let mut hasher = Sha256::new();
+    hasher.update(1u32.to_be_bytes());
+    hasher.update(shared);
+    hasher.update(other_info);
+    Ok(hasher.finalize().into())
+}
+
+type HmacSha512 = Hmac<Sha512>;
+
+fn a256cbc_hs512_mac(mac_key: &[u8], iv: &[u8], aad: &[u8], ciphertext: &[u8]) -> HmacSha512 {
+    // any key length is accepted by HMAC
+    let mut mac = <HmacSha512 as Mac>::new_from_slice(mac_key).unwrap();
+    mac.update(aad);
+    mac.update(iv);
+    mac.update(ciphertext);
+    mac.update(&((aad.len() as u64) * 8).to_be_bytes());
+    mac
+}
+
+fn a256cbc_hs512_encrypt(
+    cek: &[u8; 64],
+    iv: &[u8; 16],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> (Vec<u8>, Vec<u8>) {
+    let (mac_key, enc_key) = cek.split_at(32);
+    let ciphertext = cbc::Encryptor::<aes::Aes256>::new(enc_key.into(), iv.into())
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+    let tag = a256cbc_hs512_mac(mac_key, iv, aad, &ciphertext)
+        .finalize()
+        .into_bytes()[..32]
+        .to_vec();
+    (ciphertext, tag)
+}
+
+fn a256cbc_hs512_decrypt(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    if cek.len() != 64 || iv.len() != 16 || tag.len() != 32 {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidMessageFormat,
+            "Invalid A256CBC-HS512 key, iv or tag length",
+        ));
+    }
+    let (mac_key, enc_key) = cek.split_at(32);
+    a256cbc_hs512_mac(mac_key, iv, aad, ciphertext)
+        .verify_truncated_left(tag)
+        .map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                "JWE authentication tag mismatch",
+            )
+        })?;
+    cbc::Decryptor::<aes::Aes256>::new(enc_key.into(), iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                "Invalid JWE ciphertext padding",
+            )
+        })
+}
+
+fn a256gcm_decrypt(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    if cek.len() != 32 || iv.len() != 12 {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidMessageFormat,
+            "Invalid A256GCM key or iv length",
+        ));
+    }
+    let msg = [ciphertext, tag].concat();
+    Aes256Gcm::new(cek.into())
+        .decrypt(iv.into(), aes_gcm::aead::Payload { msg: &msg, aad })
+        .map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                "Failed to decrypt JWE ciphertext",
+            )
+        })
+}
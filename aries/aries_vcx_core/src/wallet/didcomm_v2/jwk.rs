@@ -0,0 +1,210 @@
+use base64::{
+    engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use public_key::{Key, KeyType};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+/// JSON Web Key (RFC 7517) of an OKP (X25519, Ed25519) or EC (P-256) key. Secret keys carry
+/// the private part in `d`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub d: Option<String>,
+}
+
+impl Jwk {
+    pub fn from_ed25519_verkey(verkey: &str) -> VcxCoreResult<Self> {
+        let key = Key::from_base58(verkey, KeyType::Ed25519).map_err(|err| {
+            AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::NotBase58, err.to_string())
+        })?;
+        Self::from_public_key(&key)
+    }
+
+    /// Base58 encoded verkey of an Ed25519 key, as used by wallet `sign` and `verify`
+    pub fn to_ed25519_verkey(&self) -> VcxCoreResult<String> {
+        let key = self.to_public_key()?;
+        if key.key_type() != &KeyType::Ed25519 {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidVerkey,
+                format!("Expected Ed25519 key, got {} {}", self.kty, self.crv),
+            ));
+        }
+        Ok(key.base58())
+    }
+
+    fn from_public_key(key: &Key) -> VcxCoreResult<Self> {
+        Ok(serde_json::from_str(&key.to_jwk()?)?)
+    }
+
+    // Private key material in `d` is ignored
+    fn to_public_key(&self) -> VcxCoreResult<Key> {
+        Ok(Key::from_jwk(&serde_json::to_string(self)?)?)
+    }
+}
+
+/// Curves usable for DIDComm v2 key agreement
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAgreementCurve {
+    X25519,
+    P256,
+}
+
+pub(crate) enum SecretKey {
+    X25519(x25519_dalek::StaticSecret),
+    P256(p256::SecretKey),
+}
+
+pub(crate) enum PublicKey {
+    X25519(x25519_dalek::PublicKey),
+    P256(p256::PublicKey),
+}
+
+impl SecretKey {
+    pub fn generate(curve: KeyAgreementCurve) -> Self {
+        match curve {
+            KeyAgreementCurve::X25519 => {
+                Self::X25519(x25519_dalek::StaticSecret::random_from_rng(OsRng))
+            }
+            KeyAgreementCurve::P256 => Self::P256(p256::SecretKey::random(&mut OsRng)),
+        }
+    }
+
+    pub fn curve(&self) -> KeyAgreementCurve {
+        match self {
+            Self::X25519(_) => KeyAgreementCurve::X25519,
+            Self::P256(_) => KeyAgreementCurve::P256,
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            Self::X25519(secret) => PublicKey::X25519(secret.into()),
+            Self::P256(secret) => PublicKey::P256(secret.public_key()),
+        }
+    }
+
+    pub fn to_jwk(&self) -> VcxCoreResult<Jwk> {
+        let d = match self {
+            Self::X25519(secret) => secret.to_bytes().to_vec(),
+            Self::P256(secret) => secret.to_bytes().to_vec(),
+        };
+        Ok(Jwk {
+            d: Some(URL_SAFE_NO_PAD.encode(d)),
+            ..self.public_key().to_jwk()?
+        })
+    }
+
+    pub fn from_jwk(jwk: &Jwk) -> VcxCoreResult<Self> {
+        let d = jwk.d.as_deref().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                "JWK does not contain a private key",
+            )
+        })?;
+        let d = decode_coordinate(d, 32)?;
+        let secret = match PublicKey::from_jwk(jwk)?.curve() {
+            KeyAgreementCurve::X25519 => {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(&d);
+                Self::X25519(bytes.into())
+            }
+            KeyAgreementCurve::P256 => {
+                Self::P256(p256::SecretKey::from_slice(&d).map_err(|err| {
+                    AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err)
+                })?)
+            }
+        };
+        Ok(secret)
+    }
+
+    /// Raw shared secret of ECDH between this key and `public`
+    pub fn diffie_hellman(&self, public: &PublicKey) -> VcxCoreResult<Vec<u8>> {
+        match (self, public) {
+            (Self::X25519(secret), PublicKey::X25519(public)) => {
+                let shared = secret.diffie_hellman(public);
+                if !shared.was_contributory() {
+                    return Err(AriesVcxCoreError::from_msg(
+                        AriesVcxCoreErrorKind::InvalidInput,
+                        "X25519 key agreement with low order point",
+                    ));
+                }
+                Ok(shared.as_bytes().to_vec())
+            }
+            (Self::P256(secret), PublicKey::P256(public)) => {
+                let shared =
+                    p256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine());
+                Ok(shared.raw_secret_bytes().to_vec())
+            }
+            _ => Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                "Key agreement between keys on different curves",
+            )),
+        }
+    }
+}
+
+impl PublicKey {
+    pub fn curve(&self) -> KeyAgreementCurve {
+        match self {
+            Self::X25519(_) => KeyAgreementCurve::X25519,
+            Self::P256(_) => KeyAgreementCurve::P256,
+        }
+    }
+
+    pub fn to_jwk(&self) -> VcxCoreResult<Jwk> {
+        let key = match self {
+            Self::X25519(public) => raw_key(public.as_bytes(), KeyType::X25519)?,
+            Self::P256(public) => raw_key(public.to_encoded_point(true).as_bytes(), KeyType::P256)?,
+        };
+        Jwk::from_public_key(&key)
+    }
+
+    pub fn from_jwk(jwk: &Jwk) -> VcxCoreResult<Self> {
+        let key = jwk.to_public_key()?;
+        match key.key_type() {
+            KeyType::X25519 => {
+                // the length of OKP keys is validated when parsing the JWK
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(key.key());
+                Ok(Self::X25519(bytes.into()))
+            }
+            KeyType::P256 => p256::PublicKey::from_sec1_bytes(key.key())
+                .map(Self::P256)
+                .map_err(|err| {
+                    AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err)
+                }),
+            _ => Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Unsupported key agreement JWK {} {}", jwk.kty, jwk.crv),
+            )),
+        }
+    }
+}
+
+// Unlike Key::new, keeps raw keys which happen to start with their multicodec prefix intact
+fn raw_key(bytes: &[u8], key_type: KeyType) -> VcxCoreResult<Key> {
+    Ok(Key::from_base64(&STANDARD_NO_PAD.encode(bytes), key_type)?)
+}
+
+fn decode_coordinate(value: &str, len: usize) -> VcxCoreResult<Vec<u8>> {
+    let bytes = URL_SAFE_NO_PAD.decode(value).map_err(|err| {
+        AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err.to_string())
+    })?;
+    if bytes.len() != len {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            format!("Expected JWK member of {len} bytes, got {}", bytes.len()),
+        ));
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,85 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use super::decode_b64;
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+pub const SIGNED_MESSAGE_TYP: &str = "application/didcomm-signed+json";
+
+const ALG_EDDSA: &str = "EdDSA";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ProtectedHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+    alg: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct SignatureHeader {
+    pub kid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Signature {
+    pub protected: String,
+    pub header: SignatureHeader,
+    pub signature: String,
+}
+
+impl Signature {
+    /// Fails unless the signature was made with an algorithm we can verify
+    pub fn check_alg(&self) -> VcxCoreResult<()> {
+        let header: ProtectedHeader = serde_json::from_slice(&decode_b64(&self.protected)?)?;
+        if header.alg != ALG_EDDSA {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::ActionNotSupported,
+                format!("Unsupported JWS algorithm {}", header.alg),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// JWS in general JSON serialization, signed with Ed25519 keys
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Jws {
+    pub payload: String,
+    pub signatures: Vec<Signature>,
+}
+
+impl Jws {
+    /// Unsigned JWS of `payload`, sign its `signing_input` and `add_signature` afterwards
+    pub fn new(payload: &[u8]) -> Self {
+        Self {
+            payload: URL_SAFE_NO_PAD.encode(payload),
+            signatures: Vec::new(),
+        }
+    }
+
+    pub fn protected_header() -> VcxCoreResult<String> {
+        let header = ProtectedHeader {
+            typ: Some(SIGNED_MESSAGE_TYP.to_string()),
+            alg: ALG_EDDSA.to_string(),
+        };
+        Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?))
+    }
+
+    pub fn signing_input(&self, protected: &str) -> Vec<u8> {
+        format!("{}.{}", protected, self.payload).into_bytes()
+    }
+
+    pub fn add_signature(&mut self, kid: &str, protected: String, signature: &[u8]) {
+        self.signatures.push(Signature {
+            protected,
+            header: SignatureHeader {
+                kid: kid.to_string(),
+            },
+            signature: URL_SAFE_NO_PAD.encode(signature),
+        });
+    }
+
+    pub fn payload(&self) -> VcxCoreResult<Vec<u8>> {
+        decode_b64(&self.payload)
+    }
+}
@@ -0,0 +1,501 @@
+//! DIDComm v2 envelopes: JWE encrypted messages, either authcrypted (ECDH-1PU+A256KW) or
+//! anoncrypted (ECDH-ES+A256KW) over X25519 or P-256, and JWS messages signed with Ed25519.
+//! Keys are identified by DID URLs, e.g. `did:example:alice#key-x25519-1`.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use self::{
+    jwe::Jwe,
+    jwk::{PublicKey, SecretKey},
+    jws::Jws,
+};
+pub use self::{
+    jwe::ENCRYPTED_MESSAGE_TYP,
+    jwk::{Jwk, KeyAgreementCurve},
+    jws::SIGNED_MESSAGE_TYP,
+};
+use super::{base_wallet::BaseWallet, structs_io::UnpackMessageOutputV2};
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+mod jwe;
+mod jwk;
+mod jws;
+
+/// Wallet record type of key agreement secret keys, the record id is the key's kid
+pub const KEY_AGREEMENT_RECORD_TYPE: &str = "DidcommV2KeyAgreementKey";
+
+/// Signed message may be encrypted, and that again anoncrypted to hide the sender
+const MAX_ENVELOPE_NESTING: usize = 3;
+
+/// Public key of another party, as found in the verification methods of its DID document
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DidcommV2Key {
+    /// DID URL of the verification method
+    pub kid: String,
+    pub jwk: Jwk,
+}
+
+pub(crate) fn decode_b64(value: &str) -> VcxCoreResult<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(value).map_err(|err| {
+        AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidMessageFormat, err.to_string())
+    })
+}
+
+fn validate_kid(kid: &str) -> VcxCoreResult<()> {
+    let is_did_url = kid.starts_with("did:")
+        && kid
+            .split_once('#')
+            .is_some_and(|(_, fragment)| !fragment.is_empty());
+    if !is_did_url {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidDid,
+            format!("Key id {kid} is not a DID URL with fragment"),
+        ));
+    }
+    Ok(())
+}
+
+fn find_key<'a>(known_keys: &'a [DidcommV2Key], kid: &str) -> VcxCoreResult<&'a Jwk> {
+    known_keys
+        .iter()
+        .find(|key| key.kid == kid)
+        .map(|key| &key.jwk)
+        .ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Key {kid} is not among the known keys"),
+            )
+        })
+}
+
+async fn get_secret_key<W: BaseWallet + ?Sized>(wallet: &W, kid: &str) -> VcxCoreResult<SecretKey> {
+    let jwk = wallet
+        .get_wallet_record_value(KEY_AGREEMENT_RECORD_TYPE, kid)
+        .await?;
+    SecretKey::from_jwk(&serde_json::from_str(&jwk)?)
+}
+
+pub(crate) async fn create_key_agreement_key<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    kid: &str,
+    curve: KeyAgreementCurve,
+) -> VcxCoreResult<Jwk> {
+    validate_kid(kid)?;
+    let secret = SecretKey::generate(curve);
+    wallet
+        .add_wallet_record(
+            KEY_AGREEMENT_RECORD_TYPE,
+            kid,
+            &serde_json::to_string(&secret.to_jwk()?)?,
+            None,
+        )
+        .await?;
+    secret.public_key().to_jwk()
+}
+
+pub(crate) async fn pack_encrypted<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    sender_kid: Option<&str>,
+    recipients: &[DidcommV2Key],
+    msg: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    let sender = match sender_kid {
+        Some(kid) => {
+            validate_kid(kid)?;
+            Some((kid, get_secret_key(wallet, kid).await?))
+        }
+        None => None,
+    };
+    let recipients = recipients
+        .iter()
+        .map(|recipient| {
+            validate_kid(&recipient.kid)?;
+            Ok((recipient.kid.as_str(), PublicKey::from_jwk(&recipient.jwk)?))
+        })
+        .collect::<VcxCoreResult<Vec<_>>>()?;
+    let sender = sender.as_ref().map(|(kid, key)| (*kid, key));
+    Ok(serde_json::to_vec(&Jwe::encrypt(
+        sender,
+        &recipients,
+        msg,
+    )?)?)
+}
+
+pub(crate) async fn pack_signed<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    signer_kid: &str,
+    signer_verkey: &str,
+    msg: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    validate_kid(signer_kid)?;
+    let mut jws = Jws::new(msg);
+    let protected = Jws::protected_header()?;
+    let signature = wallet
+        .sign(signer_verkey, &jws.signing_input(&protected))
+        .await?;
+    jws.add_signature(signer_kid, protected, &signature);
+    Ok(serde_json::to_vec(&jws)?)
+}
+
+/// Unwraps encrypted and signed envelopes until the plaintext message is reached
+pub(crate) async fn unpack<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    msg: &[u8],
+    known_keys: &[DidcommV2Key],
+) -> VcxCoreResult<UnpackMessageOutputV2> {
+    let mut output = UnpackMessageOutputV2::default();
+    let mut envelope = msg.to_vec();
+    for _ in 0..=MAX_ENVELOPE_NESTING {
+        let value: Value = serde_json::from_slice(&envelope)?;
+        if value.get("ciphertext").is_some() {
+            let jwe: Jwe = serde_json::from_value(value)?;
+            let (recipient_kid, sender_kid, plaintext) = decrypt(wallet, &jwe, known_keys).await?;
+            output.recipient_kid = Some(recipient_kid);
+            output.sender_kid = sender_kid.or(output.sender_kid);
+            envelope = plaintext;
+        } else if value.get("signatures").is_some() {
+            let jws: Jws = serde_json::from_value(value)?;
+            output.signer_kid = Some(verify(wallet, &jws, known_keys).await?);
+            envelope = jws.payload()?;
+        } else {
+            output.message = String::from_utf8(envelope).map_err(|err| {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidMessageFormat, err)
+            })?;
+            validate_from(&output)?;
+            return Ok(output);
+        }
+    }
+    Err(AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::InvalidMessageFormat,
+        "Message envelopes are nested too deeply",
+    ))
+}
+
+/// Authcrypt sender and signer keys must belong to the DID in the message's `from`, otherwise
+/// anyone holding a known key could send messages in the name of another party
+fn validate_from(output: &UnpackMessageOutputV2) -> VcxCoreResult<()> {
+    let kids = [output.sender_kid.as_deref(), output.signer_kid.as_deref()];
+    if kids.iter().all(Option::is_none) {
+        return Ok(());
+    }
+    let message: Value = serde_json::from_str(&output.message)?;
+    let from = message.get("from").and_then(Value::as_str);
+    for kid in kids.into_iter().flatten() {
+        let did = kid.split_once('#').map_or(kid, |(did, _)| did);
+        if from != Some(did) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                format!("Key {kid} does not belong to message sender {from:?}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Decrypts with the first recipient key found in the wallet,
+/// returns the recipient kid, sender kid if authcrypted, and plaintext
+async fn decrypt<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    jwe: &Jwe,
+    known_keys: &[DidcommV2Key],
+) -> VcxCoreResult<(String, Option<String>, Vec<u8>)> {
+    let header = jwe.protected_header()?;
+    let sender_key = if header.is_authcrypt() {
+        let skid = header.skid.as_deref().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                "Authcrypted message is missing skid",
+            )
+        })?;
+        if header.apu.as_deref() != Some(URL_SAFE_NO_PAD.encode(skid).as_str()) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidMessageFormat,
+                "Authcrypted message apu does not match skid",
+            ));
+        }
+        Some(PublicKey::from_jwk(find_key(known_keys, skid)?)?)
+    } else {
+        None
+    };
+    for recipient in &jwe.recipients {
+        let kid = &recipient.header.kid;
+        let recipient_key = match get_secret_key(wallet, kid).await {
+            Ok(key) => key,
+            Err(err) if err.kind() == AriesVcxCoreErrorKind::WalletRecordNotFound => continue,
+            Err(err) => return Err(err),
+        };
+        let plaintext = jwe.decrypt(kid, &recipient_key, sender_key.as_ref())?;
+        return Ok((kid.to_owned(), header.skid, plaintext));
+    }
+    Err(AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::WalletRecordNotFound,
+        "None of the message recipient keys are in the wallet",
+    ))
+}
+
+/// Returns kid of the first known key with a valid signature
+async fn verify<W: BaseWallet + ?Sized>(
+    wallet: &W,
+    jws: &Jws,
+    known_keys: &[DidcommV2Key],
+) -> VcxCoreResult<String> {
+    for signature in &jws.signatures {
+        let Ok(jwk) = find_key(known_keys, &signature.header.kid) else {
+            continue;
+        };
+        signature.check_alg()?;
+        let verkey = jwk.to_ed25519_verkey()?;
+        let signing_input = jws.signing_input(&signature.protected);
+        let signature_bytes = decode_b64(&signature.signature)?;
+        if wallet
+            .verify(&verkey, &signing_input, &signature_bytes)
+            .await?
+        {
+            return Ok(signature.header.kid.clone());
+        }
+    }
+    Err(AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::InvalidMessageFormat,
+        "Message has no valid signature by a known key",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jwe::Jwe, jwk::SecretKey, jws::Jws, *};
+
+    const ALICE_KID: &str = "did:example:alice#key-agreement-1";
+    const BOB_KID: &str = "did:example:bob#key-agreement-1";
+    const CAROL_KID: &str = "did:example:carol#key-agreement-1";
+
+    fn roundtrip(curve: KeyAgreementCurve, authcrypt: bool) {
+        let alice = SecretKey::generate(curve);
+        let bob = SecretKey::generate(curve);
+        let carol = SecretKey::generate(curve);
+        let sender = authcrypt.then_some((ALICE_KID, &alice));
+        let jwe = Jwe::encrypt(
+            sender,
+            &[(BOB_KID, bob.public_key()), (CAROL_KID, carol.public_key())],
+            b"hello",
+        )
+        .unwrap();
+        let header = jwe.protected_header().unwrap();
+        assert_eq!(header.is_authcrypt(), authcrypt);
+        assert_eq!(header.skid.as_deref(), sender.map(|(kid, _)| kid));
+
+        let alice_public = alice.public_key();
+        let sender_key = authcrypt.then_some(&alice_public);
+        assert_eq!(jwe.decrypt(BOB_KID, &bob, sender_key).unwrap(), b"hello");
+        assert_eq!(
+            jwe.decrypt(CAROL_KID, &carol, sender_key).unwrap(),
+            b"hello"
+        );
+        assert!(jwe.decrypt(BOB_KID, &carol, sender_key).is_err());
+    }
+
+    #[test]
+    fn test_jwe_roundtrip() {
+        roundtrip(KeyAgreementCurve::X25519, true);
+        roundtrip(KeyAgreementCurve::X25519, false);
+        roundtrip(KeyAgreementCurve::P256, true);
+        roundtrip(KeyAgreementCurve::P256, false);
+    }
+
+    #[test]
+    fn test_jwe_authcrypt_requires_sender_key() {
+        let alice = SecretKey::generate(KeyAgreementCurve::X25519);
+        let bob = SecretKey::generate(KeyAgreementCurve::X25519);
+        let mallory = SecretKey::generate(KeyAgreementCurve::X25519);
+        let jwe = Jwe::encrypt(
+            Some((ALICE_KID, &alice)),
+            &[(BOB_KID, bob.public_key())],
+            b"hello",
+        )
+        .unwrap();
+        assert!(jwe.decrypt(BOB_KID, &bob, None).is_err());
+        assert!(jwe
+            .decrypt(BOB_KID, &bob, Some(&mallory.public_key()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_jwe_rejects_tampered_ciphertext() {
+        let bob = SecretKey::generate(KeyAgreementCurve::P256);
+        let mut jwe = Jwe::encrypt(None, &[(BOB_KID, bob.public_key())], b"hello").unwrap();
+        let mut ciphertext = decode_b64(&jwe.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        jwe.ciphertext = URL_SAFE_NO_PAD.encode(ciphertext);
+        assert!(jwe.decrypt(BOB_KID, &bob, None).is_err());
+    }
+
+    #[test]
+    fn test_jwe_rejects_mixed_curves() {
+        let alice = SecretKey::generate(KeyAgreementCurve::P256);
+        let bob = SecretKey::generate(KeyAgreementCurve::X25519);
+        assert!(Jwe::encrypt(
+            Some((ALICE_KID, &alice)),
+            &[(BOB_KID, bob.public_key())],
+            b"hello"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_secret_key_jwk_roundtrip() {
+        for curve in [KeyAgreementCurve::X25519, KeyAgreementCurve::P256] {
+            let secret = SecretKey::generate(curve);
+            let jwk = secret.to_jwk().unwrap();
+            let restored = SecretKey::from_jwk(&jwk).unwrap();
+            assert_eq!(restored.to_jwk().unwrap(), jwk);
+            assert_eq!(restored.public_key().to_jwk().unwrap().d, None);
+        }
+    }
+
+    fn unpacked(
+        message: &str,
+        sender_kid: Option<&str>,
+        signer_kid: Option<&str>,
+    ) -> UnpackMessageOutputV2 {
+        UnpackMessageOutputV2 {
+            message: message.to_owned(),
+            recipient_kid: Some(BOB_KID.to_owned()),
+            sender_kid: sender_kid.map(str::to_owned),
+            signer_kid: signer_kid.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_validate_from() {
+        let from_alice = r#"{"id":"1","type":"test","from":"did:example:alice","body":{}}"#;
+        let anonymous = r#"{"id":"1","type":"test","body":{}}"#;
+        assert!(validate_from(&unpacked(from_alice, Some(ALICE_KID), None)).is_ok());
+        assert!(validate_from(&unpacked(from_alice, None, Some(ALICE_KID))).is_ok());
+        assert!(validate_from(&unpacked(from_alice, Some(ALICE_KID), Some(ALICE_KID))).is_ok());
+        assert!(validate_from(&unpacked(anonymous, None, None)).is_ok());
+        assert!(validate_from(&unpacked("not json", None, None)).is_ok());
+
+        assert!(validate_from(&unpacked(from_alice, Some(CAROL_KID), None)).is_err());
+        assert!(validate_from(&unpacked(from_alice, None, Some(CAROL_KID))).is_err());
+        assert!(validate_from(&unpacked(from_alice, Some(ALICE_KID), Some(CAROL_KID))).is_err());
+        assert!(validate_from(&unpacked(anonymous, Some(ALICE_KID), None)).is_err());
+        assert!(validate_from(&unpacked(anonymous, None, Some(ALICE_KID))).is_err());
+    }
+
+    // Authcrypt example (ECDH-1PU+A256KW, A256CBC-HS512, X25519) of the DIDComm Messaging
+    // spec appendix. Only the header, recipients and tag take part in key agreement.
+    const SPEC_AUTHCRYPT_PROTECTED: &str = "eyJlcGsiOnsia3R5IjoiT0tQIiwiY3J2IjoiWDI1NTE5IiwieCI6IkdGY01vcEpsamY0cExaZmNoNGFfR2hUTV9ZQWY2aU5JMWRXREd5VkNhdzAifSwiYXB2IjoiTmNzdUFuclJmUEs2OUEtcmtaMEw5WFdVRzRqTXZOQzNaZzc0QlB6NTNQQSIsInNraWQiOiJkaWQ6ZXhhbXBsZTphbGljZSNrZXkteDI1NTE5LTEiLCJhcHUiOiJaR2xrT21WNFlXMXdiR1U2WVd4cFkyVWphMlY1TFhneU5UVXhPUzB4IiwidHlwIjoiYXBwbGljYXRpb24vZGlkY29tbS1lbmNyeXB0ZWQranNvbiIsImVuYyI6IkEyNTZDQkMtSFM1MTIiLCJhbGciOiJFQ0RILTFQVStBMjU2S1cifQ";
+    const SPEC_AUTHCRYPT_ENCRYPTED_KEY: &str =
+        "o0FJASHkQKhnFo_rTMHTI9qTm_m2mkJp-wv96mKyT5TP7QjBDuiQ0AMKaPI_RLLB7jpyE-Q80Mwos7CvwbMJDhIEBnk2qHVB";
+    const SPEC_AUTHCRYPT_TAG: &str = "uYeo7IsZjN7AnvBjUZE5lNryNENbf6_zew_VC-d4b3U";
+    const SPEC_ALICE_X25519: &str = r#"{"kty":"OKP","crv":"X25519","x":"avH0O2Y4tqLAq8y9zpianr8ajii5m4F_mICrzNlatXs","d":"r-jK2cO3taR8LQnJB1_ikLBTAnOtShJOsHXRUWT-aZA"}"#;
+    const SPEC_BOB_X25519: &str = r#"{"kty":"OKP","crv":"X25519","x":"GDTrI66K0pFfO54tlCSvfjjNapIs44dzpneBgyx0S3E","d":"b9NnuOCB0hm7YGNvaE9DMhwH_wjZA1-gWD6dA0JWdL0"}"#;
+
+    #[test]
+    fn test_spec_authcrypt_key_agreement() {
+        let bob_kid = "did:example:bob#key-x25519-1";
+        let alice = SecretKey::from_jwk(&serde_json::from_str(SPEC_ALICE_X25519).unwrap()).unwrap();
+        let bob = SecretKey::from_jwk(&serde_json::from_str(SPEC_BOB_X25519).unwrap()).unwrap();
+        let jwe: Jwe = serde_json::from_value(serde_json::json!({
+            "protected": SPEC_AUTHCRYPT_PROTECTED,
+            "recipients": [{
+                "header": { "kid": bob_kid },
+                "encrypted_key": SPEC_AUTHCRYPT_ENCRYPTED_KEY,
+            }],
+            "iv": "o02OXDQ6_-sKz2PX_6oyJg",
+            "ciphertext": "",
+            "tag": SPEC_AUTHCRYPT_TAG,
+        }))
+        .unwrap();
+        let header = jwe.protected_header().unwrap();
+        assert_eq!(
+            header.skid.as_deref(),
+            Some("did:example:alice#key-x25519-1")
+        );
+
+        // A256KW checks integrity, so the key only unwraps if ECDH-1PU derived the same KEK
+        let cek = jwe
+            .unwrap_content_key(&header, bob_kid, &bob, Some(&alice.public_key()))
+            .unwrap();
+        assert_eq!(cek.len(), 64);
+        let mallory = SecretKey::generate(KeyAgreementCurve::X25519);
+        assert!(jwe
+            .unwrap_content_key(&header, bob_kid, &bob, Some(&mallory.public_key()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_authcrypt_requires_a256cbc_hs512() {
+        let alice = SecretKey::generate(KeyAgreementCurve::X25519);
+        let bob = SecretKey::generate(KeyAgreementCurve::X25519);
+        let jwe = Jwe::encrypt(
+            Some((ALICE_KID, &alice)),
+            &[(BOB_KID, bob.public_key())],
+            b"hello",
+        )
+        .unwrap();
+        let mut header = jwe.protected_header().unwrap();
+        header.enc = "A256GCM".to_owned();
+        let err = jwe
+            .unwrap_content_key(&header, BOB_KID, &bob, Some(&alice.public_key()))
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::InvalidMessageFormat);
+    }
+
+    // Signed message example of the DIDComm Messaging spec appendix, by Alice's Ed25519 key
+    const SPEC_SIGNED_MESSAGE: &str = r#"{"payload":"eyJpZCI6IjEyMzQ1Njc4OTAiLCJ0eXAiOiJhcHBsaWNhdGlvbi9kaWRjb21tLXBsYWluK2pzb24iLCJ0eXBlIjoiaHR0cDovL2V4YW1wbGUuY29tL3Byb3RvY29scy9sZXRzX2RvX2x1bmNoLzEuMC9wcm9wb3NhbCIsImZyb20iOiJkaWQ6ZXhhbXBsZTphbGljZSIsInRvIjpbImRpZDpleGFtcGxlOmJvYiJdLCJjcmVhdGVkX3RpbWUiOjE1MTYyNjkwMjIsImV4cGlyZXNfdGltZSI6MTUxNjM4NTkzMSwiYm9keSI6eyJtZXNzYWdlc3BlY2lmaWNhdHRyaWJ1dGUiOiJhbmQgaXRzIHZhbHVlIn19","signatures":[{"protected":"eyJ0eXAiOiJhcHBsaWNhdGlvbi9kaWRjb21tLXNpZ25lZCtqc29uIiwiYWxnIjoiRWREU0EifQ","signature":"FW33NnvOHV0Ted9-F7GZbkia-vYAfBKtH4oBxbrttWAhBZ6UFJMxcGjL3lwOl4YohI3kyyd08LHPWNMgP2EVCQ","header":{"kid":"did:example:alice#key-1"}}]}"#;
+    const SPEC_ALICE_ED25519_X: &str = "G-boxFB6vOZBu-wXkm-9Lh79I8nf9Z50cILaOgKKGww";
+
+    fn spec_signature_is_valid(jws: &Jws) -> bool {
+        let signature = &jws.signatures[0];
+        signature.check_alg().unwrap();
+        let alice: [u8; 32] = decode_b64(SPEC_ALICE_ED25519_X)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let alice = ed25519_dalek::VerifyingKey::from_bytes(&alice).unwrap();
+        let signature_bytes = decode_b64(&signature.signature).unwrap();
+        ed25519_dalek::Signature::from_slice(&signature_bytes)
+            .and_then(|signature_bytes| {
+                alice.verify_strict(&jws.signing_input(&signature.protected), &signature_bytes)
+            })
+            .is_ok()
+    }
+
+    #[test]
+    fn test_spec_signed_message() {
+        let jws: Jws = serde_json::from_str(SPEC_SIGNED_MESSAGE).unwrap();
+        assert_eq!(jws.signatures[0].header.kid, "did:example:alice#key-1");
+        assert!(spec_signature_is_valid(&jws));
+        let message: Value = serde_json::from_slice(&jws.payload().unwrap()).unwrap();
+        assert_eq!(message["from"], "did:example:alice");
+
+        let tampered: Jws =
+            serde_json::from_str(&SPEC_SIGNED_MESSAGE.replace("FW33", "FW34")).unwrap();
+        assert!(!spec_signature_is_valid(&tampered));
+
+        // the wallet verifies with the verkey of the key's JWK
+        let jwk = Jwk {
+            kty: "OKP".to_owned(),
+            crv: "Ed25519".to_owned(),
+            x: SPEC_ALICE_ED25519_X.to_owned(),
+            y: None,
+            d: None,
+        };
+        assert_eq!(
+            bs58::decode(jwk.to_ed25519_verkey().unwrap())
+                .into_vec()
+                .unwrap(),
+            decode_b64(SPEC_ALICE_ED25519_X).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_kid() {
+        assert!(validate_kid(ALICE_KID).is_ok());
+        assert!(validate_kid("did:example:alice").is_err());
+        assert!(validate_kid("did:example:alice#").is_err());
+        assert!(validate_kid("key-agreement-1").is_err());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod agency_client_wallet;
+#[cfg(feature = "askar_wallet")]
+pub mod askar;
 pub mod base_wallet;
+pub mod didcomm_v2;
 #[cfg(feature = "vdrtools_wallet")]
 pub mod indy;
 pub mod mock_wallet;
@@ -7,3 +7,16 @@ pub struct UnpackMessageOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sender_verkey: Option<String>,
 }
+
+/// Result of unpacking a DIDComm v2 message. Kids are the DID URLs of the keys
+/// the message was encrypted for, authcrypted by and signed with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnpackMessageOutputV2 {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer_kid: Option<String>,
+}
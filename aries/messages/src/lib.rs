@@ -33,7 +33,7 @@ use crate::{
             coordinate_mediation::CoordinateMediation, discover_features::DiscoverFeatures,
             notification::Notification, out_of_band::OutOfBand, present_proof::v1::PresentProofV1,
             report_problem::ProblemReport, revocation::Revocation, routing::Forward,
-            trust_ping::TrustPing,
+            transactions::Transactions, trust_ping::TrustPing,
         },
         traits::DelayedSerde,
     },
@@ -71,6 +71,7 @@ pub enum AriesMessage {
     Pickup(Pickup),
     CoordinateMediation(CoordinateMediation),
     DidExchange(DidExchange),
+    Transactions(Transactions),
 }
 
 impl DelayedSerde for AriesMessage {
@@ -190,6 +191,10 @@ impl DelayedSerde for AriesMessage {
             Protocol::DidExchangeType(msg_type) => {
                 DidExchange::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
             }
+            Protocol::TransactionsType(msg_type) => {
+                Transactions::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(From::from)
+            }
         }
     }
 
@@ -214,6 +219,7 @@ impl DelayedSerde for AriesMessage {
             Self::Pickup(v) => v.delayed_serialize(serializer),
             Self::CoordinateMediation(v) => v.delayed_serialize(serializer),
             Self::DidExchange(v) => v.delayed_serialize(serializer),
+            Self::Transactions(v) => v.delayed_serialize(serializer),
         }
     }
 }
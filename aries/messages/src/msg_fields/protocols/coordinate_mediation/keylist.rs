@@ -21,9 +21,9 @@ pub struct KeylistItem {
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
 pub struct KeylistPagination {
-    count: u64,
-    offset: u64,
-    remaining: u64,
+    pub count: u64,
+    pub offset: u64,
+    pub remaining: u64,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
@@ -16,24 +16,68 @@ use self::{
     response::{Response, ResponseContent, ResponseDecorators},
 };
 use crate::{
-    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    misc::utils::transit_to_aries_msg,
     msg_fields::traits::DelayedSerde,
     msg_types::{
         protocols::did_exchange::{
             DidExchangeType as DidExchangeKind, DidExchangeTypeV1, DidExchangeTypeV1_0,
+            DidExchangeTypeV1_1,
         },
         MsgWithType,
     },
 };
 
-#[derive(Clone, Debug, From, PartialEq)]
+/// A DID Exchange message, tagged with the minor version of the protocol it belongs to.
+/// The message fields are the same across 1.x versions.
+#[derive(Clone, Debug, PartialEq)]
 pub enum DidExchange {
+    V1_0(DidExchangeV1),
+    V1_1(DidExchangeV1),
+}
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum DidExchangeV1 {
     Request(Request),
     Response(Response),
     ProblemReport(ProblemReport),
     Complete(Complete),
 }
 
+impl DidExchange {
+    pub fn new(version: DidExchangeTypeV1, msg: impl Into<DidExchangeV1>) -> Self {
+        match version {
+            DidExchangeTypeV1::V1_0(_) => Self::V1_0(msg.into()),
+            DidExchangeTypeV1::V1_1(_) => Self::V1_1(msg.into()),
+        }
+    }
+
+    pub fn version(&self) -> DidExchangeTypeV1 {
+        match self {
+            Self::V1_0(_) => DidExchangeTypeV1::new_v1_0(),
+            Self::V1_1(_) => DidExchangeTypeV1::new_v1_1(),
+        }
+    }
+
+    pub fn inner(&self) -> &DidExchangeV1 {
+        match self {
+            Self::V1_0(msg) | Self::V1_1(msg) => msg,
+        }
+    }
+
+    pub fn into_inner(self) -> DidExchangeV1 {
+        match self {
+            Self::V1_0(msg) | Self::V1_1(msg) => msg,
+        }
+    }
+}
+
+/// Messages not explicitly tagged with a version default to DID Exchange 1.0.
+impl From<DidExchangeV1> for DidExchange {
+    fn from(value: DidExchangeV1) -> Self {
+        Self::V1_0(value)
+    }
+}
+
 impl DelayedSerde for DidExchange {
     type MsgType<'a> = (DidExchangeKind, &'a str);
 
@@ -46,17 +90,39 @@ impl DelayedSerde for DidExchange {
     {
         let (protocol, kind_str) = msg_type;
 
-        let kind = match protocol {
-            DidExchangeKind::V1(DidExchangeTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
-        };
-
-        match kind.map_err(D::Error::custom)? {
-            DidExchangeTypeV1_0::Request => Request::deserialize(deserializer).map(From::from),
-            DidExchangeTypeV1_0::Response => Response::deserialize(deserializer).map(From::from),
-            DidExchangeTypeV1_0::ProblemReport => {
-                ProblemReport::deserialize(deserializer).map(From::from)
+        match protocol {
+            DidExchangeKind::V1(DidExchangeTypeV1::V1_0(kind)) => match kind
+                .kind_from_str(kind_str)
+                .map_err(D::Error::custom)?
+            {
+                DidExchangeTypeV1_0::Request => Request::deserialize(deserializer).map(From::from),
+                DidExchangeTypeV1_0::Response => {
+                    Response::deserialize(deserializer).map(From::from)
+                }
+                DidExchangeTypeV1_0::ProblemReport => {
+                    ProblemReport::deserialize(deserializer).map(From::from)
+                }
+                DidExchangeTypeV1_0::Complete => {
+                    Complete::deserialize(deserializer).map(From::from)
+                }
+            }
+            .map(Self::V1_0),
+            DidExchangeKind::V1(DidExchangeTypeV1::V1_1(kind)) => match kind
+                .kind_from_str(kind_str)
+                .map_err(D::Error::custom)?
+            {
+                DidExchangeTypeV1_1::Request => Request::deserialize(deserializer).map(From::from),
+                DidExchangeTypeV1_1::Response => {
+                    Response::deserialize(deserializer).map(From::from)
+                }
+                DidExchangeTypeV1_1::ProblemReport => {
+                    ProblemReport::deserialize(deserializer).map(From::from)
+                }
+                DidExchangeTypeV1_1::Complete => {
+                    Complete::deserialize(deserializer).map(From::from)
+                }
             }
-            DidExchangeTypeV1_0::Complete => Complete::deserialize(deserializer).map(From::from),
+            .map(Self::V1_1),
         }
     }
 
@@ -65,21 +131,40 @@ impl DelayedSerde for DidExchange {
         S: serde::Serializer,
     {
         match self {
-            Self::Request(v) => MsgWithType::from(v).serialize(serializer),
-            Self::Response(v) => MsgWithType::from(v).serialize(serializer),
-            Self::ProblemReport(v) => MsgWithType::from(v).serialize(serializer),
-            Self::Complete(v) => MsgWithType::from(v).serialize(serializer),
+            Self::V1_0(msg) => match msg {
+                DidExchangeV1::Request(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_0::Request, v).serialize(serializer)
+                }
+                DidExchangeV1::Response(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_0::Response, v).serialize(serializer)
+                }
+                DidExchangeV1::ProblemReport(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_0::ProblemReport, v).serialize(serializer)
+                }
+                DidExchangeV1::Complete(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_0::Complete, v).serialize(serializer)
+                }
+            },
+            Self::V1_1(msg) => match msg {
+                DidExchangeV1::Request(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_1::Request, v).serialize(serializer)
+                }
+                DidExchangeV1::Response(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_1::Response, v).serialize(serializer)
+                }
+                DidExchangeV1::ProblemReport(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_1::ProblemReport, v).serialize(serializer)
+                }
+                DidExchangeV1::Complete(v) => {
+                    MsgWithType::new(DidExchangeTypeV1_1::Complete, v).serialize(serializer)
+                }
+            },
         }
     }
 }
 
 // TODO: Seems to be required only for tests?
-transit_to_aries_msg!(RequestContent: RequestDecorators, DidExchange);
-transit_to_aries_msg!(ResponseContent: ResponseDecorators, DidExchange);
-transit_to_aries_msg!(ProblemReportContent: ProblemReportDecorators, DidExchange);
-transit_to_aries_msg!(NoContent: CompleteDecorators, DidExchange);
-
-into_msg_with_type!(Request, DidExchangeTypeV1_0, Request);
-into_msg_with_type!(Response, DidExchangeTypeV1_0, Response);
-into_msg_with_type!(ProblemReport, DidExchangeTypeV1_0, ProblemReport);
-into_msg_with_type!(Complete, DidExchangeTypeV1_0, Complete);
+transit_to_aries_msg!(RequestContent: RequestDecorators, DidExchangeV1, DidExchange);
+transit_to_aries_msg!(ResponseContent: ResponseDecorators, DidExchangeV1, DidExchange);
+transit_to_aries_msg!(ProblemReportContent: ProblemReportDecorators, DidExchangeV1, DidExchange);
+transit_to_aries_msg!(NoContent: CompleteDecorators, DidExchangeV1, DidExchange);
@@ -13,6 +13,11 @@ pub struct ResponseContent {
     pub did: String, // TODO: Use Did
     #[serde(rename = "did_doc~attach")]
     pub did_doc: Option<Attachment>,
+    /// The `did`, signed with the key it replaces. Only used in DID Exchange 1.1.
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "did_rotate~attach")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_rotate: Option<Attachment>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, TypedBuilder)]
@@ -40,7 +45,9 @@ mod tests {
             timing::tests::make_extended_timing,
         },
         misc::test_utils,
-        msg_types::protocols::did_exchange::DidExchangeTypeV1_0,
+        msg_fields::protocols::did_exchange::DidExchange,
+        msg_types::protocols::did_exchange::{DidExchangeTypeV1, DidExchangeTypeV1_0},
+        AriesMessage,
     };
 
     fn response_content() -> ResponseContent {
@@ -58,6 +65,7 @@ mod tests {
                     )
                     .build(),
             ),
+            did_rotate: None,
         }
     }
 
@@ -97,4 +105,41 @@ mod tests {
 
         test_utils::test_msg(content, decorators, DidExchangeTypeV1_0::Response, expected);
     }
+
+    #[test]
+    fn test_did_rotate_response_v1_1() {
+        let mut content = response_content();
+        content.did_rotate = Some(
+            Attachment::builder()
+                .data(
+                    AttachmentData::builder()
+                        .content(AttachmentType::Base64("ZGlkOmV4YW1wbGU".to_owned()))
+                        .build(),
+                )
+                .build(),
+        );
+
+        let decorators = ResponseDecorators {
+            thread: make_extended_thread(),
+            timing: None,
+        };
+
+        let expected = json!({
+            "@id": "test",
+            "@type": "https://didcomm.org/didexchange/1.1/response",
+            "did": content.did,
+            "did_doc~attach": content.did_doc,
+            "did_rotate~attach": content.did_rotate,
+            "~thread": decorators.thread
+        });
+
+        let response: Response = Response::builder()
+            .id("test".to_owned())
+            .content(content)
+            .decorators(decorators)
+            .build();
+        let msg = AriesMessage::from(DidExchange::new(DidExchangeTypeV1::new_v1_1(), response));
+
+        test_utils::test_serde(msg, expected);
+    }
 }
@@ -12,4 +12,5 @@ pub mod present_proof;
 pub mod report_problem;
 pub mod revocation;
 pub mod routing;
+pub mod transactions;
 pub mod trust_ping;
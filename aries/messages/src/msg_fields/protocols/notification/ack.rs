@@ -18,6 +18,7 @@ pub struct AckContent {
 #[serde(rename_all = "UPPERCASE")]
 pub enum AckStatus {
     Ok,
+    Fail,
     Pending,
 }
 
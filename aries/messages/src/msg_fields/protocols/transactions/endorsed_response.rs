@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+use super::request::SignatureRequest;
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type EndorsedResponse = MsgParts<EndorsedResponseContent, EndorsedResponseDecorators>;
+
+/// Response of the endorser after it endorsed the requested transaction, and wrote it to the
+/// ledger if asked to.
+///
+/// The `transaction_id` is the one of the request, while the `thread_id` identifies the
+/// transaction on the endorser's side.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct EndorsedResponseContent {
+    pub transaction_id: String,
+    pub thread_id: String,
+    pub signature_response: SignatureResponse,
+    #[builder(default = EndorsedResponseContent::STATE.to_owned())]
+    pub state: String,
+    pub endorser_did: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_response: Option<Value>,
+}
+
+impl EndorsedResponseContent {
+    pub const STATE: &'static str = "transaction_endorsed";
+}
+
+/// Signature of the endorser, in response to the `signature_request` of the transaction request.
+///
+/// The `signature` maps the endorser's DID to the transaction signed by the endorser, and is
+/// only present if the transaction was endorsed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct SignatureResponse {
+    /// ID of the attachment carrying the transaction
+    pub message_id: String,
+    #[builder(default = SignatureRequest::DEFAULT_CONTEXT.to_owned())]
+    pub context: String,
+    #[builder(default = SignatureRequest::DEFAULT_METHOD.to_owned())]
+    pub method: String,
+    pub signer_goal_code: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_type: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<HashMap<String, String>>,
+}
+
+impl SignatureResponse {
+    pub const ENDORSE_GOAL_CODE: &'static str = "aries.transaction.endorse";
+    pub const REFUSE_GOAL_CODE: &'static str = "aries.transaction.refuse";
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct EndorsedResponseDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    fn make_signature_response() -> SignatureResponse {
+        SignatureResponse::builder()
+            .message_id("test_message_id".to_owned())
+            .signer_goal_code(SignatureResponse::ENDORSE_GOAL_CODE.to_owned())
+            .signature_type(SignatureRequest::DEFAULT_SIGNATURE_TYPE.to_owned())
+            .signature(HashMap::from([(
+                "test_endorser_did".to_owned(),
+                "test_signed_transaction".to_owned(),
+            )]))
+            .build()
+    }
+
+    #[test]
+    fn test_minimal_endorsed_response() {
+        let content = EndorsedResponseContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .thread_id("test_thread_id".to_owned())
+            .signature_response(make_signature_response())
+            .endorser_did("test_endorser_did".to_owned())
+            .build();
+
+        let decorators = EndorsedResponseDecorators::default();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "thread_id": content.thread_id,
+            "signature_response": {
+                "message_id": "test_message_id",
+                "context": "did:sov",
+                "method": "add-signature",
+                "signer_goal_code": "aries.transaction.endorse",
+                "signature_type": "default",
+                "signature": { "test_endorser_did": "test_signed_transaction" }
+            },
+            "state": "transaction_endorsed",
+            "endorser_did": content.endorser_did
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::EndorsedTransactionResponse,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_endorsed_response() {
+        let content = EndorsedResponseContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .thread_id("test_thread_id".to_owned())
+            .signature_response(make_signature_response())
+            .endorser_did("test_endorser_did".to_owned())
+            .ledger_response(json!({ "op": "REPLY" }))
+            .build();
+
+        let decorators = EndorsedResponseDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "thread_id": content.thread_id,
+            "signature_response": content.signature_response,
+            "state": "transaction_endorsed",
+            "endorser_did": content.endorser_did,
+            "ledger_response": { "op": "REPLY" },
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::EndorsedTransactionResponse,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Module containing the `transactions` protocol messages, used by transaction authors without
+//! ledger write permissions to have their ledger transactions endorsed, as done by
+//! [ACA-Py](<https://github.com/hyperledger/aries-cloudagent-python/blob/main/Endorser.md>).
+
+pub mod endorsed_response;
+pub mod refused_response;
+pub mod request;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use self::{
+    endorsed_response::{EndorsedResponse, EndorsedResponseContent, EndorsedResponseDecorators},
+    refused_response::{RefusedResponse, RefusedResponseContent, RefusedResponseDecorators},
+    request::{TransactionRequest, TransactionRequestContent, TransactionRequestDecorators},
+};
+use crate::{
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::transactions::{
+            TransactionsType as TransactionsKind, TransactionsTypeV1, TransactionsTypeV1_0,
+        },
+        MsgWithType,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum Transactions {
+    Request(TransactionRequest),
+    EndorsedResponse(EndorsedResponse),
+    RefusedResponse(RefusedResponse),
+}
+
+impl DelayedSerde for Transactions {
+    type MsgType<'a> = (TransactionsKind, &'a str);
+
+    fn delayed_deserialize<'de, D>(
+        msg_type: Self::MsgType<'de>,
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+
+        let kind = match protocol {
+            TransactionsKind::V1(TransactionsTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
+        };
+
+        match kind.map_err(D::Error::custom)? {
+            TransactionsTypeV1_0::TransactionRequest => {
+                TransactionRequest::deserialize(deserializer).map(From::from)
+            }
+            TransactionsTypeV1_0::EndorsedTransactionResponse => {
+                EndorsedResponse::deserialize(deserializer).map(From::from)
+            }
+            TransactionsTypeV1_0::RefusedTransactionResponse => {
+                RefusedResponse::deserialize(deserializer).map(From::from)
+            }
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Request(v) => MsgWithType::from(v).serialize(serializer),
+            Self::EndorsedResponse(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RefusedResponse(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+transit_to_aries_msg!(
+    TransactionRequestContent: TransactionRequestDecorators,
+    Transactions
+);
+transit_to_aries_msg!(
+    EndorsedResponseContent: EndorsedResponseDecorators,
+    Transactions
+);
+transit_to_aries_msg!(
+    RefusedResponseContent: RefusedResponseDecorators,
+    Transactions
+);
+
+into_msg_with_type!(TransactionRequest, TransactionsTypeV1_0, TransactionRequest);
+into_msg_with_type!(
+    EndorsedResponse,
+    TransactionsTypeV1_0,
+    EndorsedTransactionResponse
+);
+into_msg_with_type!(
+    RefusedResponse,
+    TransactionsTypeV1_0,
+    RefusedTransactionResponse
+);
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use super::endorsed_response::SignatureResponse;
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type RefusedResponse = MsgParts<RefusedResponseContent, RefusedResponseDecorators>;
+
+/// Response of the endorser after it refused to endorse the requested transaction.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RefusedResponseContent {
+    pub transaction_id: String,
+    pub thread_id: String,
+    pub signature_response: SignatureResponse,
+    #[builder(default = RefusedResponseContent::STATE.to_owned())]
+    pub state: String,
+    pub endorser_did: String,
+}
+
+impl RefusedResponseContent {
+    pub const STATE: &'static str = "transaction_refused";
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RefusedResponseDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    fn make_signature_response() -> SignatureResponse {
+        SignatureResponse::builder()
+            .message_id("test_message_id".to_owned())
+            .signer_goal_code(SignatureResponse::REFUSE_GOAL_CODE.to_owned())
+            .build()
+    }
+
+    #[test]
+    fn test_minimal_refused_response() {
+        let content = RefusedResponseContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .thread_id("test_thread_id".to_owned())
+            .signature_response(make_signature_response())
+            .endorser_did("test_endorser_did".to_owned())
+            .build();
+
+        let decorators = RefusedResponseDecorators::default();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "thread_id": content.thread_id,
+            "signature_response": {
+                "message_id": "test_message_id",
+                "context": "did:sov",
+                "method": "add-signature",
+                "signer_goal_code": "aries.transaction.refuse"
+            },
+            "state": "transaction_refused",
+            "endorser_did": content.endorser_did
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::RefusedTransactionResponse,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_refused_response() {
+        let content = RefusedResponseContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .thread_id("test_thread_id".to_owned())
+            .signature_response(make_signature_response())
+            .endorser_did("test_endorser_did".to_owned())
+            .build();
+
+        let decorators = RefusedResponseDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "thread_id": content.thread_id,
+            "signature_response": content.signature_response,
+            "state": "transaction_refused",
+            "endorser_did": content.endorser_did,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::RefusedTransactionResponse,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use shared::maybe_known::MaybeKnown;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type TransactionRequest = MsgParts<TransactionRequestContent, TransactionRequestDecorators>;
+
+/// Request of the transaction author for the endorser to endorse the attached ledger transaction,
+/// with the `signature_request` describing the signature expected from the endorser.
+///
+/// The transaction is attached as a JSON string, unsigned by the endorser. When
+/// `endorser_write_txn` is set, the endorser is also asked to write it to the ledger.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct TransactionRequestContent {
+    pub transaction_id: String,
+    pub signature_request: Vec<SignatureRequest>,
+    #[builder(default)]
+    #[serde(default)]
+    pub timing: TransactionTiming,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<MaybeKnown<TransactionType>>,
+    pub messages_attach: Attachment,
+    #[builder(default)]
+    #[serde(default)]
+    pub endorser_write_txn: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct SignatureRequest {
+    #[builder(default = SignatureRequest::DEFAULT_CONTEXT.to_owned())]
+    pub context: String,
+    #[builder(default = SignatureRequest::DEFAULT_METHOD.to_owned())]
+    pub method: String,
+    #[builder(default = SignatureRequest::DEFAULT_SIGNATURE_TYPE.to_owned())]
+    pub signature_type: String,
+    #[builder(default = SignatureRequest::DEFAULT_SIGNER_GOAL_CODE.to_owned())]
+    pub signer_goal_code: String,
+    #[builder(default = SignatureRequest::DEFAULT_AUTHOR_GOAL_CODE.to_owned())]
+    pub author_goal_code: String,
+}
+
+impl SignatureRequest {
+    pub const DEFAULT_CONTEXT: &'static str = "did:sov";
+    pub const DEFAULT_METHOD: &'static str = "add-signature";
+    pub const DEFAULT_SIGNATURE_TYPE: &'static str = "default";
+    pub const DEFAULT_SIGNER_GOAL_CODE: &'static str = "aries.transaction.endorse";
+    pub const DEFAULT_AUTHOR_GOAL_CODE: &'static str = "aries.transaction.ledger.write";
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct TransactionTiming {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<String>,
+}
+
+/// Type of the requested transaction, by its ledger transaction type code
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum TransactionType {
+    #[serde(rename = "1")]
+    Nym,
+    #[serde(rename = "101")]
+    Schema,
+    #[serde(rename = "102")]
+    CredDef,
+    #[serde(rename = "113")]
+    RevRegDef,
+    #[serde(rename = "114")]
+    RevRegEntry,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct TransactionRequestDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, thread::tests::make_extended_thread,
+            timing::tests::make_extended_timing,
+        },
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_transaction_request() {
+        let content = TransactionRequestContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .signature_request(vec![SignatureRequest::builder().build()])
+            .messages_attach(make_extended_attachment())
+            .build();
+
+        let decorators = TransactionRequestDecorators::default();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "signature_request": [{
+                "context": "did:sov",
+                "method": "add-signature",
+                "signature_type": "default",
+                "signer_goal_code": "aries.transaction.endorse",
+                "author_goal_code": "aries.transaction.ledger.write"
+            }],
+            "timing": {},
+            "messages_attach": content.messages_attach,
+            "endorser_write_txn": false
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::TransactionRequest,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_transaction_request() {
+        let content = TransactionRequestContent::builder()
+            .transaction_id("test_transaction_id".to_owned())
+            .signature_request(vec![SignatureRequest::builder().build()])
+            .timing(
+                TransactionTiming::builder()
+                    .expires_time("2024-01-01T00:00:00Z".to_owned())
+                    .build(),
+            )
+            .transaction_type(MaybeKnown::Known(TransactionType::RevRegEntry))
+            .messages_attach(make_extended_attachment())
+            .endorser_write_txn(true)
+            .build();
+
+        let decorators = TransactionRequestDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "transaction_id": content.transaction_id,
+            "signature_request": content.signature_request,
+            "timing": { "expires_time": "2024-01-01T00:00:00Z" },
+            "transaction_type": "114",
+            "messages_attach": content.messages_attach,
+            "endorser_write_txn": true,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            TransactionsTypeV1_0::TransactionRequest,
+            expected,
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use derive_more::From;
 use messages_macros::MessageType;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use strum_macros::{AsRefStr, EnumString};
 use transitive::Transitive;
 
@@ -18,6 +19,33 @@ pub enum DidExchangeType {
 pub enum DidExchangeTypeV1 {
     #[msg_type(minor = 0, roles = "Role::Requester, Role::Responder")]
     V1_0(MsgKindType<DidExchangeTypeV1_0>),
+    #[msg_type(minor = 1, roles = "Role::Requester, Role::Responder")]
+    V1_1(MsgKindType<DidExchangeTypeV1_1>),
+}
+
+/// Serialized as the protocol URI, e.g. `https://didcomm.org/didexchange/1.1`,
+/// so that state machines can persist the negotiated version.
+impl Serialize for DidExchangeTypeV1 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Protocol::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DidExchangeTypeV1 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Protocol::deserialize(deserializer)? {
+            Protocol::DidExchangeType(DidExchangeType::V1(version)) => Ok(version),
+            protocol => Err(D::Error::custom(format!(
+                "expected a didexchange/1.x protocol, got {protocol:?}"
+            ))),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
@@ -29,6 +57,15 @@ pub enum DidExchangeTypeV1_0 {
     Complete,
 }
 
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum DidExchangeTypeV1_1 {
+    Request,
+    Response,
+    ProblemReport,
+    Complete,
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -44,11 +81,27 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_protocol_didexchange_v1_1() {
+        test_utils::test_serde(
+            Protocol::from(DidExchangeTypeV1::new_v1_1()),
+            json!("https://didcomm.org/didexchange/1.1"),
+        )
+    }
+
     #[test]
     fn test_version_resolution_didexchange() {
         test_utils::test_msg_type_resolution(
             "https://didcomm.org/didexchange/1.255",
-            DidExchangeTypeV1::new_v1_0(),
+            DidExchangeTypeV1::new_v1_1(),
+        )
+    }
+
+    #[test]
+    fn test_serde_didexchange_version() {
+        test_utils::test_serde(
+            DidExchangeTypeV1::new_v1_1(),
+            json!("https://didcomm.org/didexchange/1.1"),
         )
     }
 
@@ -96,4 +149,13 @@ mod tests {
             DidExchangeTypeV1::new_v1_0(),
         )
     }
+
+    #[test]
+    fn test_msg_type_response_v1_1() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/didexchange/1.1",
+            "response",
+            DidExchangeTypeV1::new_v1_1(),
+        )
+    }
 }
@@ -10,7 +10,8 @@ use self::{
     did_exchange::DidExchangeType, discover_features::DiscoverFeaturesType,
     notification::NotificationType, out_of_band::OutOfBandType, pickup::PickupType,
     present_proof::PresentProofType, report_problem::ReportProblemType, revocation::RevocationType,
-    routing::RoutingType, signature::SignatureType, trust_ping::TrustPingType,
+    routing::RoutingType, signature::SignatureType, transactions::TransactionsType,
+    trust_ping::TrustPingType,
 };
 use crate::{
     error::{MsgTypeError, MsgTypeResult},
@@ -31,6 +32,7 @@ pub mod report_problem;
 pub mod revocation;
 pub mod routing;
 pub mod signature;
+pub mod transactions;
 pub mod trust_ping;
 
 /// Type representing all protocols that are currently supported.
@@ -65,6 +67,7 @@ pub enum Protocol {
     PickupType(PickupType),
     CoordinateMediationType(CoordinateMediationType),
     DidExchangeType(DidExchangeType),
+    TransactionsType(TransactionsType),
 }
 
 /// Utility macro to avoid harder to read and error prone calling
@@ -103,6 +106,7 @@ impl Protocol {
         match_protocol!(PickupType, protocol, major, minor);
         match_protocol!(CoordinateMediationType, protocol, major, minor);
         match_protocol!(DidExchangeType, protocol, major, minor);
+        match_protocol!(TransactionsType, protocol, major, minor);
 
         Err(MsgTypeError::unknown_protocol(protocol.to_owned()))
     }
@@ -125,6 +129,7 @@ impl Protocol {
             Self::PickupType(v) => v.as_protocol_parts(),
             Self::CoordinateMediationType(v) => v.as_protocol_parts(),
             Self::DidExchangeType(v) => v.as_protocol_parts(),
+            Self::TransactionsType(v) => v.as_protocol_parts(),
         }
     }
 
@@ -0,0 +1,89 @@
+use derive_more::From;
+use messages_macros::MessageType;
+use strum_macros::{AsRefStr, EnumString};
+use transitive::Transitive;
+
+use super::Protocol;
+use crate::msg_types::{role::Role, MsgKindType};
+
+#[derive(Copy, Clone, Debug, From, PartialEq, MessageType)]
+#[msg_type(protocol = "transactions")]
+pub enum TransactionsType {
+    V1(TransactionsTypeV1),
+}
+
+#[derive(Copy, Clone, Debug, From, PartialEq, Transitive, MessageType)]
+#[transitive(into(TransactionsType, Protocol))]
+#[msg_type(major = 1)]
+pub enum TransactionsTypeV1 {
+    #[msg_type(minor = 0, roles = "Role::Author, Role::Endorser")]
+    V1_0(MsgKindType<TransactionsTypeV1_0>),
+}
+
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum TransactionsTypeV1_0 {
+    TransactionRequest,
+    EndorsedTransactionResponse,
+    RefusedTransactionResponse,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    #[test]
+    fn test_protocol_transactions() {
+        test_utils::test_serde(
+            Protocol::from(TransactionsTypeV1::new_v1_0()),
+            json!("https://didcomm.org/transactions/1.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_transactions() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/transactions/1.255",
+            TransactionsTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_version_transactions() {
+        test_utils::test_serde(
+            Protocol::from(TransactionsTypeV1::new_v1_0()),
+            json!("https://didcomm.org/transactions/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_transaction_request() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/transactions/1.0",
+            "transaction_request",
+            TransactionsTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_endorsed_transaction_response() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/transactions/1.0",
+            "endorsed_transaction_response",
+            TransactionsTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_refused_transaction_response() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/transactions/1.0",
+            "refused_transaction_response",
+            TransactionsTypeV1::new_v1_0(),
+        )
+    }
+}
@@ -21,6 +21,7 @@ use crate::msg_types::{
         revocation::RevocationTypeV2,
         routing::RoutingTypeV1,
         signature::SignatureTypeV1,
+        transactions::TransactionsTypeV1,
         trust_ping::TrustPingTypeV1,
     },
 };
@@ -97,6 +98,8 @@ lazy_static! {
         map_insert(&mut m, extract_parts!(PickupTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(CoordinateMediationTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_1()));
+        map_insert(&mut m, extract_parts!(TransactionsTypeV1::new_v1_0()));
         m
     };
 }
@@ -19,4 +19,6 @@ pub enum Role {
     Notifier,
     Mediator,
     Recipient,
+    Author,
+    Endorser,
 }
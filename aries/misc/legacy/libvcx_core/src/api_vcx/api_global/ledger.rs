@@ -30,7 +30,8 @@ pub async fn endorse_transaction(transaction: &str, endorser_did: &str) -> Libvc
     map_ariesvcx_core_result(
         ledger
             .endorse_transaction(wallet.as_ref(), endorser_did, transaction)
-            .await,
+            .await
+            .map(|_| ()),
     )
 }
 
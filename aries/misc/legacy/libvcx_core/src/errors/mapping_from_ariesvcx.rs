@@ -88,6 +88,7 @@ impl From<AriesVcxErrorKind> for LibvcxErrorKind {
             AriesVcxErrorKind::VdrToolsError(num) => LibvcxErrorKind::LibndyError(num),
             AriesVcxErrorKind::NoAgentInformation => LibvcxErrorKind::NoAgentInformation,
             AriesVcxErrorKind::InvalidMessageFormat => LibvcxErrorKind::InvalidMessageFormat,
+            AriesVcxErrorKind::InvalidSignature => LibvcxErrorKind::InvalidMessageFormat,
             AriesVcxErrorKind::LedgerItemNotFound => LibvcxErrorKind::LedgerItemNotFound,
             AriesVcxErrorKind::UrsaError => LibvcxErrorKind::UrsaError,
             AriesVcxErrorKind::ProofRejected => LibvcxErrorKind::ProofRejected,
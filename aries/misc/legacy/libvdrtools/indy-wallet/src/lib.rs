@@ -709,6 +709,30 @@ impl WalletService {
         Ok(())
     }
 
+    /// Reads every record of the wallet, skipping those missing type or value
+    pub async fn get_all_records(&self, handle: WalletHandle) -> IndyResult<Vec<Record>> {
+        let wallet = self.get_wallet(handle).await?;
+        let mut records = wallet.get_all().await?;
+        let mut all_records = Vec::new();
+
+        while let Some(record) = records.next().await? {
+            let (Some(type_), Some(value)) = (record.type_, record.value) else {
+                warn!(
+                    "Skipping item missing 'type' or 'value' field, id: {}",
+                    record.id
+                );
+                continue;
+            };
+            all_records.push(Record {
+                type_,
+                id: record.id,
+                value,
+                tags: record.tags.unwrap_or_default(),
+            });
+        }
+        Ok(all_records)
+    }
+
     pub async fn migrate_records<E>(
         &self,
         old_wh: WalletHandle,
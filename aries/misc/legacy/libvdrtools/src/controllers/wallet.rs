@@ -387,6 +387,10 @@ impl WalletController {
         res
     }
 
+    pub async fn get_all_records(&self, wallet_handle: WalletHandle) -> IndyResult<Vec<Record>> {
+        self.wallet_service.get_all_records(wallet_handle).await
+    }
+
     pub async fn migrate_records<E>(
         &self,
         old_wh: WalletHandle,
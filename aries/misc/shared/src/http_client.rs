@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use reqwest::{
     self,
-    header::{CONTENT_TYPE, USER_AGENT},
+    header::{AUTHORIZATION, CONTENT_TYPE, USER_AGENT},
     Client, Response, Url,
 };
 
@@ -28,6 +28,68 @@ pub async fn post_message(body_content: Vec<u8>, url: Url) -> HttpResult<Vec<u8>
     process_response(response).await
 }
 
+pub async fn get_bytes(url: Url) -> HttpResult<Vec<u8>> {
+    debug!("get_bytes >> http client sending request GET {}", &url);
+
+    let response = HTTP_CLIENT
+        .get(url.clone())
+        .header(USER_AGENT, "reqwest")
+        .send()
+        .await
+        .map_err(|err| {
+            HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err))
+        })?;
+    let response_status = response.status();
+    if !response_status.is_success() {
+        return Err(HttpError::from_msg(format!(
+            "GET {} failed due to non-success HTTP status: {}",
+            url, response_status
+        )));
+    }
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|err| {
+            HttpError::from_msg(format!(
+                "GET {} failed because response body could not be read, err: {}",
+                url, err
+            ))
+        })
+}
+
+pub async fn put_bytes(url: Url, body_content: Vec<u8>, bearer_token: &str) -> HttpResult<Vec<u8>> {
+    debug!("put_bytes >> http client sending request PUT {}", &url);
+
+    let response = HTTP_CLIENT
+        .put(url.clone())
+        .body(body_content)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .header(AUTHORIZATION, format!("Bearer {}", bearer_token))
+        .header(USER_AGENT, "reqwest")
+        .send()
+        .await
+        .map_err(|err| {
+            HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err))
+        })?;
+    let response_status = response.status();
+    let payload = response.bytes().await.map_err(|err| {
+        HttpError::from_msg(format!(
+            "PUT {} failed because response body could not be read, err: {}",
+            url, err
+        ))
+    })?;
+    if !response_status.is_success() {
+        return Err(HttpError::from_msg(format!(
+            "PUT {} failed due to non-success HTTP status: {}, response body: {}",
+            url,
+            response_status,
+            String::from_utf8_lossy(&payload)
+        )));
+    }
+    Ok(payload.to_vec())
+}
+
 async fn send_post_request(url: &Url, body_content: Vec<u8>) -> HttpResult<Response> {
     HTTP_CLIENT
         .post(url.clone())
@@ -11,6 +11,8 @@ use std::{
 };
 
 use agency_client::testing::mocking::{enable_agency_mocks, AgencyMockDecrypted};
+#[cfg(feature = "anoncreds")]
+use aries_vcx_core::anoncreds::anoncreds_rs_anoncreds::AnoncredsRsAnonCreds;
 #[cfg(feature = "credx")]
 use aries_vcx_core::anoncreds::credx_anoncreds::IndyCredxAnonCreds;
 use aries_vcx_core::{
@@ -270,13 +272,27 @@ pub async fn dev_build_indy_wallet(key_seed: &str) -> (String, impl BaseWallet)
     (public_did, IndySdkWallet::new(wallet_handle))
 }
 
+/// Builds the anoncreds implementation selected by the enabled features. The `anoncreds` feature
+/// takes precedence over `credx`, so that enabling both (e.g. `vdr_proxy_ledger` implies `credx`)
+/// tests the anoncreds-rs implementation.
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
 pub async fn dev_build_featured_anoncreds() -> impl BaseAnonCreds {
-    #[cfg(feature = "credx")]
-    return IndyCredxAnonCreds;
+    #[cfg(feature = "anoncreds")]
+    return {
+        #[cfg(feature = "credx")]
+        warn!("SetupProfile >> both anoncreds and credx features are enabled, using anoncreds");
+        info!("SetupProfile >> using anoncreds-rs anoncreds");
+        AnoncredsRsAnonCreds
+    };
+
+    #[cfg(all(feature = "credx", not(feature = "anoncreds")))]
+    return {
+        info!("SetupProfile >> using credx anoncreds");
+        IndyCredxAnonCreds
+    };
 
-    #[cfg(not(feature = "credx"))]
+    #[cfg(not(any(feature = "credx", feature = "anoncreds")))]
     return MockAnoncreds;
 }
 
@@ -62,8 +62,8 @@ impl IndyLedgerWrite for MockLedger {
         wallet: &impl BaseWallet,
         endorser_did: &str,
         request_json: &str,
-    ) -> VcxCoreResult<()> {
-        Ok(())
+    ) -> VcxCoreResult<String> {
+        Ok(r#"{"rc":"success"}"#.to_string())
     }
 
     async fn publish_nym(
@@ -78,6 +78,19 @@ impl IndyLedgerWrite for MockLedger {
         Ok(r#"{"rc":"success"}"#.to_string())
     }
 
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        submitter_did: &str,
+        target_did: &str,
+        verkey: Option<&str>,
+        data: Option<&str>,
+        role: Option<&str>,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
     async fn add_attr(
         &self,
         wallet: &impl BaseWallet,
@@ -181,4 +194,45 @@ impl AnoncredsLedgerWrite for MockLedger {
     ) -> VcxCoreResult<()> {
         Ok(())
     }
+
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_rev_reg_delta_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+        rev_reg_entry_json: &str,
+        submitter_did: &str,
+        endorser_did: &str,
+    ) -> VcxCoreResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
 }
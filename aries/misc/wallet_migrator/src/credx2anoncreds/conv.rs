@@ -1,43 +1,75 @@
+use aries_vcx_core::anoncreds::anoncreds_rs_anoncreds::{
+    type_conversion::{
+        cred_def_from_ledger, rev_reg_def_from_ledger, schema_from_ledger,
+        LedgerRevocationRegistry, LedgerRevocationRegistryDelta,
+    },
+    RevocationRegistryInfo,
+};
 use vdrtools::types::domain::wallet::Record;
 
 use crate::error::MigrationResult;
 
+// The link secret is stored as a decimal string by both implementations.
 pub fn convert_master_secret(record: Record) -> MigrationResult<Record> {
     Ok(record)
 }
 
 pub fn convert_cred(record: Record) -> MigrationResult<Record> {
+    let _: anoncreds::types::Credential = serde_json::from_str(&record.value)?;
     Ok(record)
 }
 
-pub fn convert_cred_def(record: Record) -> MigrationResult<Record> {
+pub fn convert_cred_def(mut record: Record) -> MigrationResult<Record> {
+    let (_, cred_def) = cred_def_from_ledger(serde_json::from_str(&record.value)?)?;
+    record.value = serde_json::to_string(&cred_def)?;
     Ok(record)
 }
 
 pub fn convert_cred_def_priv_key(record: Record) -> MigrationResult<Record> {
+    let _: anoncreds::types::CredentialDefinitionPrivate = serde_json::from_str(&record.value)?;
     Ok(record)
 }
 
 pub fn convert_cred_def_correctness_proof(record: Record) -> MigrationResult<Record> {
+    let _: anoncreds::types::CredentialKeyCorrectnessProof = serde_json::from_str(&record.value)?;
     Ok(record)
 }
 
-pub fn convert_schema(record: Record) -> MigrationResult<Record> {
+pub fn convert_schema(mut record: Record) -> MigrationResult<Record> {
+    let (_, schema) = schema_from_ledger(serde_json::from_str(&record.value)?)?;
+    record.value = serde_json::to_string(&schema)?;
     Ok(record)
 }
 
+// The plain ID is stored as a String, so not that much to check.
+pub fn convert_schema_id(record: Record) -> MigrationResult<Record> {
+    Ok(record)
+}
+
+// Revocation registry entries and deltas are kept in the ledger format.
 pub fn convert_rev_reg(record: Record) -> MigrationResult<Record> {
+    let _: LedgerRevocationRegistry = serde_json::from_str(&record.value)?;
     Ok(record)
 }
 
 pub fn convert_rev_reg_delta(record: Record) -> MigrationResult<Record> {
+    let _: LedgerRevocationRegistryDelta = serde_json::from_str(&record.value)?;
+    Ok(record)
+}
+
+pub fn convert_rev_reg_info(record: Record) -> MigrationResult<Record> {
+    let _: RevocationRegistryInfo = serde_json::from_str(&record.value)?;
     Ok(record)
 }
 
-pub fn convert_rev_reg_def(record: Record) -> MigrationResult<Record> {
+pub fn convert_rev_reg_def(mut record: Record) -> MigrationResult<Record> {
+    let (_, rev_reg_def) = rev_reg_def_from_ledger(serde_json::from_str(&record.value)?)?;
+    record.value = serde_json::to_string(&rev_reg_def)?;
     Ok(record)
 }
 
 pub fn convert_rev_reg_def_priv(record: Record) -> MigrationResult<Record> {
+    let _: anoncreds::types::RevocationRegistryDefinitionPrivate =
+        serde_json::from_str(&record.value)?;
     Ok(record)
 }
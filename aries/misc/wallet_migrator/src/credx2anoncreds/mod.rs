@@ -1 +1,93 @@
 pub mod conv;
+
+use aries_vcx_core::anoncreds::anoncreds_rs_anoncreds::{
+    CATEGORY_CREDENTIAL, CATEGORY_CRED_DEF, CATEGORY_CRED_DEF_PRIV,
+    CATEGORY_CRED_KEY_CORRECTNESS_PROOF, CATEGORY_CRED_MAP_SCHEMA_ID, CATEGORY_CRED_SCHEMA,
+    CATEGORY_LINK_SECRET, CATEGORY_REV_REG, CATEGORY_REV_REG_DEF, CATEGORY_REV_REG_DEF_PRIV,
+    CATEGORY_REV_REG_DELTA, CATEGORY_REV_REG_INFO,
+};
+use log::trace;
+use vdrtools::types::domain::wallet::Record;
+
+use crate::error::MigrationResult;
+
+/// Contains the logic for record mapping and migration.
+/// Both implementations share the record categories, so only the record values are converted.
+pub fn migrate_any_record(record: Record) -> MigrationResult<Option<Record>> {
+    trace!("Migrating wallet record {record:?}");
+
+    let record = match record.type_.as_str() {
+        // Link secret
+        CATEGORY_LINK_SECRET => Some(conv::convert_master_secret(record)).transpose(),
+        // Credential
+        CATEGORY_CREDENTIAL => Some(conv::convert_cred(record)).transpose(),
+        CATEGORY_CRED_DEF => Some(conv::convert_cred_def(record)).transpose(),
+        CATEGORY_CRED_DEF_PRIV => Some(conv::convert_cred_def_priv_key(record)).transpose(),
+        CATEGORY_CRED_KEY_CORRECTNESS_PROOF => {
+            Some(conv::convert_cred_def_correctness_proof(record)).transpose()
+        }
+        // Schema
+        CATEGORY_CRED_SCHEMA => Some(conv::convert_schema(record)).transpose(),
+        CATEGORY_CRED_MAP_SCHEMA_ID => Some(conv::convert_schema_id(record)).transpose(),
+        // Revocation registry
+        CATEGORY_REV_REG => Some(conv::convert_rev_reg(record)).transpose(),
+        CATEGORY_REV_REG_DELTA => Some(conv::convert_rev_reg_delta(record)).transpose(),
+        CATEGORY_REV_REG_INFO => Some(conv::convert_rev_reg_info(record)).transpose(),
+        CATEGORY_REV_REG_DEF => Some(conv::convert_rev_reg_def(record)).transpose(),
+        CATEGORY_REV_REG_DEF_PRIV => Some(conv::convert_rev_reg_def_priv(record)).transpose(),
+        // Anything else, such as DIDs and keys, is left alone
+        _ => Ok(Some(record)),
+    };
+
+    trace!("Converted wallet record to {record:?}");
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn make_record(category: &str, value: Value) -> Record {
+        Record {
+            type_: category.to_owned(),
+            id: "test_id".to_owned(),
+            value: value.to_string(),
+            tags: Default::default(),
+        }
+    }
+
+    fn migrated_value(record: Record) -> Value {
+        let record = migrate_any_record(record).unwrap().unwrap();
+        serde_json::from_str(&record.value).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_schema() {
+        let schema = make_record(
+            CATEGORY_CRED_SCHEMA,
+            json!({
+                "ver": "1.0",
+                "id": "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0",
+                "name": "degree",
+                "version": "1.0",
+                "attrNames": ["name", "age"],
+                "seqNo": 15
+            }),
+        );
+
+        let schema = migrated_value(schema);
+        assert_eq!(schema["issuerId"], "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(schema["name"], "degree");
+        assert!(schema.get("id").is_none());
+        assert!(schema.get("seqNo").is_none());
+    }
+
+    #[test]
+    fn test_migrate_leaves_other_records_alone() {
+        let did = json!({ "did": "V4SGRU86Z58d6TV7PBUe6f" });
+        let migrated = migrated_value(make_record("Indy::Did", did.clone()));
+        assert_eq!(migrated, did);
+    }
+}
@@ -1,5 +1,8 @@
+#[cfg(feature = "anoncreds")]
 pub mod credx2anoncreds;
 pub mod error;
+#[cfg(feature = "askar_wallet")]
+pub mod vdrtools2askar;
 pub mod vdrtools2credx;
 
 use std::fmt::Display;
@@ -0,0 +1,254 @@
+use std::fmt::Display;
+
+use aries_vcx_core::{
+    errors::error::AriesVcxCoreErrorKind,
+    wallet::{askar::AskarWallet, base_wallet::BaseWallet},
+};
+use log::{info, trace, warn};
+use serde::Deserialize;
+use vdrtools::{types::domain::wallet::Record, Locator, WalletHandle};
+
+use crate::{error::MigrationResult, vdrtools2credx::INDY_KEY};
+
+/// Value of vdrtools key records
+#[derive(Deserialize)]
+struct IndyKey {
+    verkey: String,
+    signkey: String,
+}
+
+/// Moves all records of the vdrtools wallet into the Askar wallet. Indy keys are imported
+/// as Askar keys, every other record is mapped by `migrate_fn` and stored as is.
+/// Records already present in the destination are skipped.
+pub async fn migrate_wallet_to_askar<E>(
+    src_wallet_handle: WalletHandle,
+    dest_wallet: &AskarWallet,
+    mut migrate_fn: impl FnMut(Record) -> Result<Option<Record>, E>,
+) -> MigrationResult<()>
+where
+    E: Display,
+{
+    info!("Starting wallet migration to Askar from wallet with handle {src_wallet_handle:?}");
+
+    let records = Locator::instance()
+        .wallet_controller
+        .get_all_records(src_wallet_handle)
+        .await?;
+    let total = records.len();
+    let mut migrated = 0;
+
+    for record in records {
+        trace!("Migrating record of type {} to Askar", record.type_);
+        let res = if record.type_ == INDY_KEY {
+            let key: IndyKey = serde_json::from_str(&record.value)?;
+            dest_wallet.import_indy_key(&key.verkey, &key.signkey).await
+        } else {
+            let record = match migrate_fn(record) {
+                Ok(Some(record)) => record,
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!("Skipping record due to failed migration: {err}");
+                    continue;
+                }
+            };
+            dest_wallet
+                .add_wallet_record(&record.type_, &record.id, &record.value, Some(record.tags))
+                .await
+        };
+        match res {
+            Ok(()) => migrated += 1,
+            Err(err) if err.kind() == AriesVcxCoreErrorKind::DuplicationWalletRecord => {
+                trace!("Record already exists in destination wallet, skipping");
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    info!("Migrated {migrated} of {total} records to Askar");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, convert::Infallible};
+
+    use aries_vcx_core::wallet::{
+        askar::{AskarWallet, AskarWalletConfigBuilder},
+        base_wallet::AsyncFnIteratorCollect,
+        indy::IndySdkWallet,
+    };
+    use serde_json::Value;
+    use vdrtools::{
+        types::domain::wallet::{Config, Credentials, KeyDerivationMethod},
+        MyDidInfo,
+    };
+
+    use super::*;
+    use crate::vdrtools2credx::migrate_any_record;
+
+    fn wallet_credentials() -> Credentials {
+        Credentials {
+            key: "8dvfYSt5d1taSd6yJdpjq4emkwsPDDLYxkNFysFD2cZY".to_owned(),
+            key_derivation_method: KeyDerivationMethod::RAW,
+            rekey: None,
+            rekey_derivation_method: KeyDerivationMethod::ARGON2I_MOD,
+            storage_credentials: None,
+        }
+    }
+
+    fn wallet_config(id: &str) -> Config {
+        Config {
+            id: id.to_owned(),
+            storage_type: None,
+            storage_config: None,
+            cache: None,
+        }
+    }
+
+    /// Creates and opens an empty vdrtools wallet
+    async fn open_vdrtools_wallet(config: &Config) -> WalletHandle {
+        let wallet_controller = &Locator::instance().wallet_controller;
+        wallet_controller
+            .delete(config.clone(), wallet_credentials())
+            .await
+            .ok();
+        wallet_controller
+            .create(config.clone(), wallet_credentials())
+            .await
+            .unwrap();
+        wallet_controller
+            .open(config.clone(), wallet_credentials())
+            .await
+            .unwrap()
+    }
+
+    async fn create_askar_wallet() -> AskarWallet {
+        let askar_config = AskarWalletConfigBuilder::default()
+            .db_url("sqlite://:memory:")
+            .key_method("none")
+            .build()
+            .unwrap();
+        AskarWallet::create(&askar_config, true).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migration_to_askar() {
+        let credentials = wallet_credentials();
+        let config = wallet_config("wallet_test_askar_migration");
+        let wallet_controller = &Locator::instance().wallet_controller;
+        let src_wallet_handle = open_vdrtools_wallet(&config).await;
+        let (did, verkey) = Locator::instance()
+            .did_controller
+            .create_and_store_my_did(src_wallet_handle, MyDidInfo::default())
+            .await
+            .unwrap();
+
+        let dest_wallet = create_askar_wallet().await;
+        migrate_wallet_to_askar(src_wallet_handle, &dest_wallet, migrate_any_record)
+            .await
+            .unwrap();
+
+        wallet_controller.close(src_wallet_handle).await.unwrap();
+        wallet_controller.delete(config, credentials).await.unwrap();
+
+        assert_eq!(dest_wallet.key_for_local_did(&did).await.unwrap(), verkey);
+        let signature = dest_wallet.sign(&verkey, b"migrated").await.unwrap();
+        assert!(dest_wallet
+            .verify(&verkey, b"migrated", &signature)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_migrated_records_read_back() {
+        let config = wallet_config("wallet_test_askar_migration_records");
+        let src_wallet_handle = open_vdrtools_wallet(&config).await;
+        let src_wallet = IndySdkWallet::new(src_wallet_handle);
+        let records = [
+            (
+                "apple",
+                "crunchy",
+                [("~color", "red"), ("origin", "orchard")],
+            ),
+            ("cherry", "juicy", [("~color", "red"), ("origin", "market")]),
+            ("lime", "sour", [("~color", "green"), ("origin", "market")]),
+        ];
+        for (id, value, tags) in records {
+            let tags = tags
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>();
+            src_wallet
+                .add_wallet_record("fruit", id, value, Some(tags))
+                .await
+                .unwrap();
+        }
+
+        let dest_wallet = create_askar_wallet().await;
+        migrate_wallet_to_askar(src_wallet_handle, &dest_wallet, |record| {
+            Ok::<_, Infallible>(Some(record))
+        })
+        .await
+        .unwrap();
+
+        // records read back from Askar are the same as from vdrtools, through the same API
+        let options = r#"{"retrieveType": true, "retrieveValue": true, "retrieveTags": true}"#;
+        for (id, _, _) in records {
+            let src_record: Value = serde_json::from_str(
+                &src_wallet
+                    .get_wallet_record("fruit", id, options)
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            let dest_record: Value = serde_json::from_str(
+                &dest_wallet
+                    .get_wallet_record("fruit", id, options)
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(dest_record, src_record);
+        }
+        for query in [
+            r#"{"~color": "red"}"#,
+            r#"{"origin": "market"}"#,
+            r#"{"~color": "red", "origin": "market"}"#,
+            "{}",
+        ] {
+            let mut src_found = search_ids(&src_wallet, query).await;
+            let mut dest_found = search_ids(&dest_wallet, query).await;
+            src_found.sort();
+            dest_found.sort();
+            assert!(!src_found.is_empty(), "{query}");
+            assert_eq!(dest_found, src_found, "{query}");
+        }
+
+        Locator::instance()
+            .wallet_controller
+            .close(src_wallet_handle)
+            .await
+            .unwrap();
+        Locator::instance()
+            .wallet_controller
+            .delete(config, wallet_credentials())
+            .await
+            .unwrap();
+    }
+
+    async fn search_ids(wallet: &impl BaseWallet, query: &str) -> Vec<String> {
+        wallet
+            .iterate_wallet_records("fruit", query, "{}")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| {
+                let record: Value = serde_json::from_str(&record).unwrap();
+                record["id"].as_str().unwrap().to_owned()
+            })
+            .collect()
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     str::FromStr,
 };
 
+use public_key::Key;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -31,6 +32,43 @@ impl JsonWebKey {
     pub fn to_vec(&self) -> Result<Vec<u8>, DidDocumentBuilderError> {
         serde_json::to_vec(self).map_err(|e| e.into())
     }
+
+    pub fn kty(&self) -> &str {
+        self.kty.as_ref()
+    }
+
+    pub fn crv(&self) -> &str {
+        self.crv.as_ref()
+    }
+
+    pub fn x(&self) -> &str {
+        self.x.as_ref()
+    }
+
+    pub fn extra_field(&self, key: &str) -> Option<&Value> {
+        self.extra.get(key)
+    }
+
+    /// RFC 7638 thumbprint of the key, commonly used as key ID.
+    pub fn thumbprint(&self) -> Result<String, DidDocumentBuilderError> {
+        Ok(Key::try_from(self)?.jwk_thumbprint()?)
+    }
+}
+
+impl TryFrom<&Key> for JsonWebKey {
+    type Error = DidDocumentBuilderError;
+
+    fn try_from(key: &Key) -> Result<Self, Self::Error> {
+        Self::new(&key.to_jwk()?)
+    }
+}
+
+impl TryFrom<&JsonWebKey> for Key {
+    type Error = DidDocumentBuilderError;
+
+    fn try_from(jwk: &JsonWebKey) -> Result<Self, Self::Error> {
+        Ok(Key::from_jwk(&jwk.to_string())?)
+    }
 }
 
 impl FromStr for JsonWebKey {
@@ -46,3 +84,45 @@ impl Display for JsonWebKey {
         write!(f, "{}", serde_json::to_string(&self).unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use public_key::KeyType;
+
+    use super::*;
+
+    const JWK_P256: &str = r#"{
+        "kty": "EC",
+        "crv": "P-256",
+        "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI",
+        "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU",
+        "kid": "key-1"
+    }"#;
+
+    #[test]
+    fn test_jwk_key_conversion() {
+        let jwk = JsonWebKey::new(JWK_P256).unwrap();
+        assert_eq!(jwk.kty(), "EC");
+        assert_eq!(jwk.crv(), "P-256");
+        assert_eq!(jwk.extra_field("kid").unwrap(), "key-1");
+
+        let key = Key::try_from(&jwk).unwrap();
+        assert_eq!(key.key_type(), &KeyType::P256);
+        assert_eq!(
+            key.fingerprint(),
+            "zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169"
+        );
+
+        let jwk_from_key = JsonWebKey::try_from(&key).unwrap();
+        assert_eq!(jwk_from_key.x(), jwk.x());
+        assert_eq!(
+            jwk_from_key.extra_field("y"),
+            Some(&Value::from("hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"))
+        );
+        assert!(jwk_from_key.extra_field("kid").is_none());
+        assert_eq!(
+            jwk_from_key.thumbprint().unwrap(),
+            jwk.thumbprint().unwrap()
+        );
+    }
+}
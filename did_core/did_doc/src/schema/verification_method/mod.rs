@@ -2,7 +2,7 @@ mod public_key;
 mod verification_method_kind;
 mod verification_method_type;
 
-use ::public_key::Key;
+use ::public_key::{Key, KeyType};
 use did_parser::{Did, DidUrl};
 use serde::{Deserialize, Serialize};
 pub use verification_method_kind::VerificationMethodKind;
@@ -48,10 +48,36 @@ impl VerificationMethod {
     }
 
     pub fn public_key(&self) -> Result<Key, DidDocumentBuilderError> {
-        Ok(Key::new(
-            self.public_key.key_decoded()?,
-            self.verification_method_type.try_into()?,
-        )?)
+        match (&self.verification_method_type, &self.public_key) {
+            // Multikey values are multicodec prefixed, so the key type is encoded in the key itself
+            (
+                VerificationMethodType::Multikey,
+                PublicKeyField::Multibase {
+                    public_key_multibase,
+                },
+            ) => Ok(Key::from_fingerprint(public_key_multibase)?),
+            // Same goes for JWKs, which carry the key type in their `kty` and `crv` members
+            (_, PublicKeyField::Jwk { public_key_jwk }) => Key::try_from(public_key_jwk),
+            _ => Ok(Key::new(
+                self.public_key.key_decoded()?,
+                self.verification_method_type.try_into()?,
+            )?),
+        }
+    }
+
+    /// Expresses the key of this verification method as a verification method of another type,
+    /// e.g. turns an `Ed25519VerificationKey2018` into a `JsonWebKey2020` or `Multikey`.
+    pub fn convert(
+        &self,
+        verification_method_type: VerificationMethodType,
+    ) -> Result<Self, DidDocumentBuilderError> {
+        Ok(Self::builder(
+            self.id.to_owned(),
+            self.controller.to_owned(),
+            verification_method_type,
+        )
+        .add_public_key(&self.public_key()?)?
+        .build())
     }
 }
 
@@ -83,6 +109,52 @@ impl IncompleteVerificationMethodBuilder {
         }
     }
 
+    /// Adds `key` in the encoding defined by the verification method type, i.e. `publicKeyJwk`
+    /// for `JsonWebKey2020`, `publicKeyMultibase` for `Multikey` and the 2020 suites and
+    /// `publicKeyBase58` for the older suites.
+    pub fn add_public_key(
+        self,
+        key: &Key,
+    ) -> Result<CompleteVerificationMethodBuilder, DidDocumentBuilderError> {
+        if let Ok(key_type) = KeyType::try_from(self.verification_method_type) {
+            if &key_type != key.key_type() {
+                return Err(DidDocumentBuilderError::InvalidInput(format!(
+                    "{:?} key can not be used in a {} verification method",
+                    key.key_type(),
+                    self.verification_method_type
+                )));
+            }
+        }
+        let public_key = match self.verification_method_type {
+            VerificationMethodType::JsonWebKey2020
+            | VerificationMethodType::EcdsaSecp256k1VerificationKey2019 => PublicKeyField::Jwk {
+                public_key_jwk: JsonWebKey::try_from(key)?,
+            },
+            VerificationMethodType::Multikey
+            | VerificationMethodType::Ed25519VerificationKey2020
+            | VerificationMethodType::X25519KeyAgreementKey2020 => PublicKeyField::Multibase {
+                public_key_multibase: key.fingerprint(),
+            },
+            VerificationMethodType::Ed25519VerificationKey2018
+            | VerificationMethodType::X25519KeyAgreementKey2019
+            | VerificationMethodType::Bls12381G1Key2020
+            | VerificationMethodType::Bls12381G2Key2020 => PublicKeyField::Base58 {
+                public_key_base58: key.base58(),
+            },
+            vm_type => {
+                return Err(DidDocumentBuilderError::UnsupportedVerificationMethodType(
+                    vm_type,
+                ))
+            }
+        };
+        Ok(CompleteVerificationMethodBuilder {
+            id: self.id,
+            controller: self.controller,
+            verification_method_type: self.verification_method_type,
+            public_key: Some(public_key),
+        })
+    }
+
     pub fn add_public_key_multibase(
         self,
         public_key_multibase: String,
@@ -317,4 +389,92 @@ mod tests {
         let public_key = vm.public_key().unwrap();
         assert_eq!(public_key.multibase58(), public_key_multibase_expected);
     }
+
+    #[test]
+    fn test_verification_method_multikey_public_key() {
+        let fingerprint = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let vm = VerificationMethod::builder(
+            create_valid_did_url(),
+            create_valid_did(),
+            VerificationMethodType::Multikey,
+        )
+        .add_public_key_multibase(fingerprint.to_string())
+        .build();
+
+        let public_key = vm.public_key().unwrap();
+        assert_eq!(public_key.key_type(), &::public_key::KeyType::Ed25519);
+        assert_eq!(public_key.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_verification_method_add_public_key() {
+        let key =
+            Key::from_fingerprint("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").unwrap();
+        let vm = VerificationMethod::builder(
+            create_valid_did_url(),
+            create_valid_did(),
+            VerificationMethodType::JsonWebKey2020,
+        )
+        .add_public_key(&key)
+        .unwrap()
+        .build();
+
+        let vm_value = serde_json::to_value(&vm).unwrap();
+        assert_eq!(
+            vm_value["publicKeyJwk"],
+            serde_json::json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": "Lm_M42cB3HkUiODQsXRcweM6TByfzEHGO9ND274JcOY"
+            })
+        );
+        assert_eq!(vm.public_key().unwrap(), key);
+
+        let result = VerificationMethod::builder(
+            create_valid_did_url(),
+            create_valid_did(),
+            VerificationMethodType::X25519KeyAgreementKey2020,
+        )
+        .add_public_key(&key);
+        assert!(matches!(
+            result,
+            Err(DidDocumentBuilderError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_verification_method_convert() {
+        let vm: VerificationMethod = serde_json::from_value(serde_json::json!({
+            "id": "did:example:123456789abcdefghi#key-1",
+            "type": "Ed25519VerificationKey2018",
+            "controller": "did:example:123456789abcdefghi",
+            "publicKeyBase58": "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"
+        }))
+        .unwrap();
+        let key = vm.public_key().unwrap();
+
+        for vm_type in [
+            VerificationMethodType::Ed25519VerificationKey2020,
+            VerificationMethodType::Multikey,
+            VerificationMethodType::JsonWebKey2020,
+        ] {
+            let converted_vm = vm.convert(vm_type).unwrap();
+            assert_eq!(converted_vm.id(), vm.id());
+            assert_eq!(converted_vm.verification_method_type(), &vm_type);
+            assert_eq!(converted_vm.public_key().unwrap(), key);
+            assert_eq!(
+                converted_vm
+                    .convert(VerificationMethodType::Ed25519VerificationKey2018)
+                    .unwrap(),
+                vm
+            );
+        }
+
+        assert!(matches!(
+            vm.convert(VerificationMethodType::PgpVerificationKey2021),
+            Err(DidDocumentBuilderError::UnsupportedVerificationMethodType(
+                VerificationMethodType::PgpVerificationKey2021
+            ))
+        ));
+    }
 }
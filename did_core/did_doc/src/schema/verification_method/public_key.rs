@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use ::public_key::Key;
 use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
 
@@ -37,7 +38,9 @@ impl PublicKeyField {
                 let multibase = Multibase::from_str(public_key_multibase)?;
                 Ok(multibase.as_ref().to_vec())
             }
-            PublicKeyField::Jwk { public_key_jwk } => public_key_jwk.to_vec(),
+            PublicKeyField::Jwk { public_key_jwk } => {
+                Ok(Key::try_from(public_key_jwk)?.key().to_vec())
+            }
             PublicKeyField::Base58 { public_key_base58 } => {
                 Ok(bs58::decode(public_key_base58).into_vec()?)
             }
@@ -54,10 +57,13 @@ impl PublicKeyField {
         }
     }
 
-    // TODO: Other formats
     pub fn base58(&self) -> Result<String, DidDocumentBuilderError> {
         Ok(bs58::encode(self.key_decoded()?).into_string())
     }
+
+    pub fn base64(&self) -> Result<String, DidDocumentBuilderError> {
+        Ok(general_purpose::STANDARD_NO_PAD.encode(self.key_decoded()?))
+    }
 }
 
 #[cfg(test)]
@@ -83,6 +89,24 @@ mod tests {
         assert_eq!(public_key_field.base58().unwrap(), PUBLIC_KEY_BASE58);
     }
 
+    #[test]
+    fn test_jwk() {
+        let public_key_field = PublicKeyField::Jwk {
+            public_key_jwk: JsonWebKey::new(
+                r#"{"kty":"OKP","crv":"X25519","x":"BIiFcQEn3dfvB2pjlhOQQour6jXy9d5s2FKEJNTOJik"}"#,
+            )
+            .unwrap(),
+        };
+        assert_eq!(
+            public_key_field.key_decoded().unwrap(),
+            PUBLIC_KEY_BYTES[2..].to_vec()
+        );
+        assert_eq!(
+            public_key_field.base64().unwrap(),
+            "BIiFcQEn3dfvB2pjlhOQQour6jXy9d5s2FKEJNTOJik"
+        );
+    }
+
     #[test]
     fn test_base58() {
         let public_key_field = PublicKeyField::Base58 {
@@ -18,6 +18,8 @@ pub enum VerificationMethodType {
     X25519KeyAgreementKey2019,
     X25519KeyAgreementKey2020,
     EcdsaSecp256k1RecoveryMethod2020,
+    /// https://www.w3.org/TR/controller-document/#multikey
+    Multikey,
 }
 
 impl Display for VerificationMethodType {
@@ -46,6 +48,7 @@ impl Display for VerificationMethodType {
             VerificationMethodType::EcdsaSecp256k1RecoveryMethod2020 => {
                 write!(f, "EcdsaSecp256k1RecoveryMethod2020")
             }
+            VerificationMethodType::Multikey => write!(f, "Multikey"),
         }
     }
 }
@@ -61,6 +64,7 @@ impl TryFrom<VerificationMethodType> for KeyType {
             VerificationMethodType::Bls12381G2Key2020 => Ok(KeyType::Bls12381g2),
             VerificationMethodType::X25519KeyAgreementKey2019
             | VerificationMethodType::X25519KeyAgreementKey2020 => Ok(KeyType::X25519),
+            VerificationMethodType::EcdsaSecp256k1VerificationKey2019 => Ok(KeyType::Secp256k1),
             _ => Err(DidDocumentBuilderError::UnsupportedVerificationMethodType(
                 value,
             )),
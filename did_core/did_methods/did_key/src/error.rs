@@ -6,4 +6,8 @@ pub enum DidKeyError {
     PublicKeyError(#[from] public_key::PublicKeyError),
     #[error("DID parser error: {0}")]
     DidParserError(#[from] did_parser::ParseError),
+    #[error("DID document builder error: {0}")]
+    DidDocumentBuilderError(#[from] did_doc::error::DidDocumentBuilderError),
+    #[error("Method not supported: {0}")]
+    MethodNotSupported(String),
 }
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod resolver;
 
 use core::fmt;
 use std::fmt::Display;
@@ -0,0 +1,454 @@
+use async_trait::async_trait;
+use did_doc::schema::{
+    did_doc::{DidDocument, DidDocumentBuilder},
+    verification_method::{VerificationMethod, VerificationMethodType},
+};
+use did_parser::{Did, DidUrl};
+use did_resolver::{
+    error::GenericError,
+    traits::resolvable::{
+        resolution_metadata::DidResolutionMetadata, resolution_options::DidResolutionOptions,
+        resolution_output::DidResolutionOutput, DidResolvable,
+    },
+};
+use public_key::{Key, KeyType, PublicKeyError};
+use serde_json::json;
+
+use crate::{
+    error::DidKeyError,
+    resolver::options::{ExtraFieldsOptions, PublicKeyFormat},
+    DidKey,
+};
+
+pub mod options;
+
+const BLS12381G1_KEY_LENGTH: usize = 48;
+const BLS12381G2_KEY_LENGTH: usize = 96;
+
+/// Resolves did:key DIDs by expanding the key encoded in the DID into a DID document, following
+/// https://w3c-ccg.github.io/did-method-key/#document-creation-algorithm
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DidKeyResolver;
+
+impl DidKeyResolver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DidResolvable for DidKeyResolver {
+    type ExtraFieldsService = ();
+    type ExtraFieldsOptions = ExtraFieldsOptions;
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
+    ) -> Result<DidResolutionOutput<()>, GenericError> {
+        match did.method() {
+            Some("key") => {}
+            method => {
+                return Err(Box::new(DidKeyError::MethodNotSupported(
+                    method.unwrap_or_default().to_string(),
+                )))
+            }
+        }
+        let did_key = DidKey::parse(did.did().to_string())?;
+        let did_doc = resolve_did_key(&did_key, options.extra())?;
+        let resolution_metadata = DidResolutionMetadata::builder()
+            .content_type("application/did+json".to_string())
+            .build();
+        let builder =
+            DidResolutionOutput::builder(did_doc).did_resolution_metadata(resolution_metadata);
+        Ok(builder.build())
+    }
+}
+
+pub fn resolve_did_key(
+    did_key: &DidKey,
+    options: &ExtraFieldsOptions,
+) -> Result<DidDocument<()>, DidKeyError> {
+    let did = did_key.did();
+    let key = did_key.key();
+    let format = options.public_key_format();
+    let mut contexts = vec!["https://www.w3.org/ns/did/v1"];
+    let mut builder = DidDocument::builder(did.to_owned());
+
+    match key.key_type() {
+        KeyType::Ed25519 => {
+            builder = add_signature_method(builder, &mut contexts, did, key, format)?;
+            if options.enable_encryption_key_derivation() {
+                let key_agreement_key = key.ed25519_to_x25519()?;
+                builder = add_key_agreement_method(
+                    builder,
+                    &mut contexts,
+                    did,
+                    &key_agreement_key,
+                    format,
+                )?;
+            }
+        }
+        KeyType::X25519 => {
+            builder = add_key_agreement_method(builder, &mut contexts, did, key, format)?;
+        }
+        KeyType::Bls12381g1 | KeyType::Bls12381g2 => {
+            builder = add_signature_method(builder, &mut contexts, did, key, format)?;
+        }
+        KeyType::Bls12381g1g2 => {
+            let (g1_key, g2_key) = split_bls12381g1g2_key(key)?;
+            builder = add_signature_method(builder, &mut contexts, did, &g1_key, format)?;
+            builder = add_signature_method(builder, &mut contexts, did, &g2_key, format)?;
+        }
+        KeyType::P256 | KeyType::P384 | KeyType::P521 | KeyType::Secp256k1 => {
+            let vm = build_verification_method(&mut contexts, did, key, format)?;
+            let vm_id = vm.id().to_owned();
+            builder = add_signature_references(builder.add_verification_method(vm), &vm_id)
+                .add_key_agreement_reference(vm_id);
+        }
+    }
+
+    Ok(builder
+        .add_extra_field("@context".to_string(), json!(contexts))
+        .build())
+}
+
+fn add_signature_method(
+    builder: DidDocumentBuilder<()>,
+    contexts: &mut Vec<&'static str>,
+    did: &Did,
+    key: &Key,
+    format: PublicKeyFormat,
+) -> Result<DidDocumentBuilder<()>, DidKeyError> {
+    let vm = build_verification_method(contexts, did, key, format)?;
+    let vm_id = vm.id().to_owned();
+    Ok(add_signature_references(
+        builder.add_verification_method(vm),
+        &vm_id,
+    ))
+}
+
+fn add_key_agreement_method(
+    builder: DidDocumentBuilder<()>,
+    contexts: &mut Vec<&'static str>,
+    did: &Did,
+    key: &Key,
+    format: PublicKeyFormat,
+) -> Result<DidDocumentBuilder<()>, DidKeyError> {
+    let vm = build_verification_method(contexts, did, key, format)?;
+    let vm_id = vm.id().to_owned();
+    Ok(builder
+        .add_verification_method(vm)
+        .add_key_agreement_reference(vm_id))
+}
+
+fn add_signature_references(
+    builder: DidDocumentBuilder<()>,
+    vm_id: &DidUrl,
+) -> DidDocumentBuilder<()> {
+    builder
+        .add_authentication_reference(vm_id.to_owned())
+        .add_assertion_method_reference(vm_id.to_owned())
+        .add_capability_invocation_reference(vm_id.to_owned())
+        .add_capability_delegation_refrence(vm_id.to_owned())
+}
+
+fn build_verification_method(
+    contexts: &mut Vec<&'static str>,
+    did: &Did,
+    key: &Key,
+    format: PublicKeyFormat,
+) -> Result<VerificationMethod, DidKeyError> {
+    let (vm_type, context) = verification_method_type(key.key_type(), format);
+    if !contexts.contains(&context) {
+        contexts.push(context);
+    }
+    let id = DidUrl::parse(format!("{}#{}", did, key.fingerprint()))?;
+    Ok(VerificationMethod::builder(id, did.to_owned(), vm_type)
+        .add_public_key(key)?
+        .build())
+}
+
+fn verification_method_type(
+    key_type: &KeyType,
+    format: PublicKeyFormat,
+) -> (VerificationMethodType, &'static str) {
+    match (format, key_type) {
+        (PublicKeyFormat::JsonWebKey2020, _) => (
+            VerificationMethodType::JsonWebKey2020,
+            "https://w3id.org/security/suites/jws-2020/v1",
+        ),
+        (PublicKeyFormat::VerificationKey2020, KeyType::Ed25519) => (
+            VerificationMethodType::Ed25519VerificationKey2020,
+            "https://w3id.org/security/suites/ed25519-2020/v1",
+        ),
+        (PublicKeyFormat::VerificationKey2020, KeyType::X25519) => (
+            VerificationMethodType::X25519KeyAgreementKey2020,
+            "https://w3id.org/security/suites/x25519-2020/v1",
+        ),
+        (PublicKeyFormat::VerificationKey2020, KeyType::Bls12381g1) => (
+            VerificationMethodType::Bls12381G1Key2020,
+            "https://w3id.org/security/suites/bls12381-2020/v1",
+        ),
+        (PublicKeyFormat::VerificationKey2020, KeyType::Bls12381g2) => (
+            VerificationMethodType::Bls12381G2Key2020,
+            "https://w3id.org/security/suites/bls12381-2020/v1",
+        ),
+        // Key types without a 2020 suite are expressed as Multikey
+        (PublicKeyFormat::Multikey, _) | (PublicKeyFormat::VerificationKey2020, _) => (
+            VerificationMethodType::Multikey,
+            "https://w3id.org/security/multikey/v1",
+        ),
+    }
+}
+
+fn split_bls12381g1g2_key(key: &Key) -> Result<(Key, Key), DidKeyError> {
+    if key.key().len() != BLS12381G1_KEY_LENGTH + BLS12381G2_KEY_LENGTH {
+        return Err(PublicKeyError::InvalidKey(format!(
+            "Bls12381g1g2 key must be {} bytes long, got {} bytes",
+            BLS12381G1_KEY_LENGTH + BLS12381G2_KEY_LENGTH,
+            key.key().len()
+        ))
+        .into());
+    }
+    let (g1, g2) = key.key().split_at(BLS12381G1_KEY_LENGTH);
+    Ok((
+        Key::new(g1.to_vec(), KeyType::Bls12381g1)?,
+        Key::new(g2.to_vec(), KeyType::Bls12381g2)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use did_doc::schema::verification_method::VerificationMethodKind;
+
+    use super::*;
+
+    // Test vector from https://w3c-ccg.github.io/did-method-key/#example-a-simple-ed25519-did-key-value
+    const DID_KEY_ED25519: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+    const X25519_FINGERPRINT: &str = "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p";
+
+    fn resolve(did: &str, options: ExtraFieldsOptions) -> Result<DidDocument<()>, DidKeyError> {
+        resolve_did_key(&DidKey::parse(did.to_string()).unwrap(), &options)
+    }
+
+    fn reference_ids(methods: &[VerificationMethodKind]) -> Vec<String> {
+        methods
+            .iter()
+            .map(|method| match method {
+                VerificationMethodKind::Resolved(vm) => vm.id().to_string(),
+                VerificationMethodKind::Resolvable(id) => id.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_ed25519_derives_key_agreement() {
+        let did_doc = resolve(DID_KEY_ED25519, ExtraFieldsOptions::new()).unwrap();
+        let signature_vm_id = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#\
+                               z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let key_agreement_vm_id = format!("{}#{}", DID_KEY_ED25519, X25519_FINGERPRINT);
+
+        let vms = did_doc.verification_method();
+        assert_eq!(vms.len(), 2);
+        assert_eq!(vms[0].id().to_string(), signature_vm_id);
+        assert_eq!(
+            vms[0].verification_method_type(),
+            &VerificationMethodType::Ed25519VerificationKey2020
+        );
+        assert_eq!(vms[1].id().to_string(), key_agreement_vm_id);
+        assert_eq!(
+            vms[1].verification_method_type(),
+            &VerificationMethodType::X25519KeyAgreementKey2020
+        );
+        assert_eq!(
+            vms[1].public_key().unwrap().fingerprint(),
+            X25519_FINGERPRINT
+        );
+
+        assert_eq!(
+            reference_ids(did_doc.authentication()),
+            vec![signature_vm_id]
+        );
+        assert_eq!(
+            reference_ids(did_doc.assertion_method()),
+            vec![signature_vm_id]
+        );
+        assert_eq!(
+            reference_ids(did_doc.capability_invocation()),
+            vec![signature_vm_id]
+        );
+        assert_eq!(
+            reference_ids(did_doc.capability_delegation()),
+            vec![signature_vm_id]
+        );
+        assert_eq!(
+            reference_ids(did_doc.key_agreement()),
+            vec![key_agreement_vm_id]
+        );
+        assert_eq!(
+            did_doc.extra_field("@context").unwrap(),
+            &json!([
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/ed25519-2020/v1",
+                "https://w3id.org/security/suites/x25519-2020/v1"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_ed25519_without_key_agreement_derivation() {
+        let options = ExtraFieldsOptions::new().set_enable_encryption_key_derivation(false);
+        let did_doc = resolve(DID_KEY_ED25519, options).unwrap();
+        assert_eq!(did_doc.verification_method().len(), 1);
+        assert!(did_doc.key_agreement().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_ed25519_multikey() {
+        let options = ExtraFieldsOptions::new().set_public_key_format(PublicKeyFormat::Multikey);
+        let did_doc = resolve(DID_KEY_ED25519, options).unwrap();
+        let vms = did_doc.verification_method();
+        assert_eq!(vms.len(), 2);
+        for vm in vms {
+            assert_eq!(
+                vm.verification_method_type(),
+                &VerificationMethodType::Multikey
+            );
+        }
+        assert_eq!(
+            vms[0].public_key().unwrap(),
+            *DidKey::parse(DID_KEY_ED25519.to_string()).unwrap().key()
+        );
+        assert_eq!(
+            did_doc.extra_field("@context").unwrap(),
+            &json!([
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/multikey/v1"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_x25519() {
+        let did = format!("did:key:{}", X25519_FINGERPRINT);
+        let did_doc = resolve(&did, ExtraFieldsOptions::new()).unwrap();
+        assert_eq!(did_doc.verification_method().len(), 1);
+        assert_eq!(did_doc.key_agreement().len(), 1);
+        assert!(did_doc.authentication().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_p256() {
+        // Test vector from https://w3c-ccg.github.io/did-method-key/#p-256
+        let did = "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169";
+
+        let did_doc = resolve(
+            did,
+            ExtraFieldsOptions::new().set_public_key_format(PublicKeyFormat::Multikey),
+        )
+        .unwrap();
+        assert_eq!(did_doc.verification_method().len(), 1);
+        assert_eq!(
+            did_doc.verification_method()[0]
+                .public_key()
+                .unwrap()
+                .key_type(),
+            &KeyType::P256
+        );
+        assert_eq!(did_doc.authentication().len(), 1);
+        assert_eq!(did_doc.key_agreement().len(), 1);
+
+        // There is no 2020 suite for P-256 keys, the default format falls back to Multikey
+        let did_doc = resolve(did, ExtraFieldsOptions::new()).unwrap();
+        assert_eq!(
+            did_doc.verification_method()[0].verification_method_type(),
+            &VerificationMethodType::Multikey
+        );
+        assert_eq!(
+            did_doc.extra_field("@context").unwrap(),
+            &json!([
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/multikey/v1"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_json_web_key() {
+        // Test vectors from https://w3c-ccg.github.io/did-method-key/#test-vectors
+        let options =
+            ExtraFieldsOptions::new().set_public_key_format(PublicKeyFormat::JsonWebKey2020);
+        let did_doc = resolve(
+            "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169",
+            options,
+        )
+        .unwrap();
+        let vm_value = serde_json::to_value(&did_doc.verification_method()[0]).unwrap();
+        assert_eq!(vm_value["type"], "JsonWebKey2020");
+        assert_eq!(
+            vm_value["publicKeyJwk"],
+            json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI",
+                "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"
+            })
+        );
+        assert_eq!(
+            did_doc.extra_field("@context").unwrap(),
+            &json!([
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/jws-2020/v1"
+            ])
+        );
+
+        let did = "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme";
+        let did_doc = resolve(did, options).unwrap();
+        assert_eq!(
+            did_doc.verification_method()[0].public_key().unwrap(),
+            *DidKey::parse(did.to_string()).unwrap().key()
+        );
+        assert_eq!(did_doc.key_agreement().len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_bls12381g1g2_splits_keys() {
+        let key = Key::new(vec![1; 144], KeyType::Bls12381g1g2).unwrap();
+        let did_key = DidKey::try_from(key).unwrap();
+        let did_doc = resolve_did_key(&did_key, &ExtraFieldsOptions::new()).unwrap();
+
+        let vms = did_doc.verification_method();
+        assert_eq!(vms.len(), 2);
+        assert_eq!(
+            vms[0].verification_method_type(),
+            &VerificationMethodType::Bls12381G1Key2020
+        );
+        assert_eq!(
+            vms[1].verification_method_type(),
+            &VerificationMethodType::Bls12381G2Key2020
+        );
+        assert_eq!(did_doc.assertion_method().len(), 2);
+        assert!(did_doc.key_agreement().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_rejects_other_methods() {
+        let did = Did::parse("did:web:example.com".to_string()).unwrap();
+        let result = DidKeyResolver::new()
+            .resolve(&did, &DidResolutionOptions::default())
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_resolves_did_key() {
+        let did = Did::parse(DID_KEY_ED25519.to_string()).unwrap();
+        let output = DidKeyResolver::new()
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(output.did_document().id(), &did);
+        assert_eq!(output.did_document().key_agreement().len(), 1);
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Verification method type used to express keys in the resolved DID document.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PublicKeyFormat {
+    /// `Multikey` verification methods, supported for every key type.
+    Multikey,
+    /// Key type specific 2020 suites, e.g. `Ed25519VerificationKey2020` or
+    /// `X25519KeyAgreementKey2020`. NIST curve and secp256k1 keys, which have no such suite, are
+    /// expressed as `Multikey` instead.
+    VerificationKey2020,
+    /// `JsonWebKey2020` verification methods, supported for every key type.
+    JsonWebKey2020,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExtraFieldsOptions {
+    public_key_format: PublicKeyFormat,
+    enable_encryption_key_derivation: bool,
+}
+
+impl Default for ExtraFieldsOptions {
+    fn default() -> Self {
+        Self {
+            public_key_format: PublicKeyFormat::VerificationKey2020,
+            enable_encryption_key_derivation: true,
+        }
+    }
+}
+
+impl ExtraFieldsOptions {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn set_public_key_format(mut self, public_key_format: PublicKeyFormat) -> Self {
+        self.public_key_format = public_key_format;
+        self
+    }
+
+    /// Whether an X25519 key agreement key is derived for Ed25519 keys.
+    pub fn set_enable_encryption_key_derivation(mut self, enable: bool) -> Self {
+        self.enable_encryption_key_derivation = enable;
+        self
+    }
+
+    pub fn public_key_format(&self) -> PublicKeyFormat {
+        self.public_key_format
+    }
+
+    pub fn enable_encryption_key_derivation(&self) -> bool {
+        self.enable_encryption_key_derivation
+    }
+}
@@ -35,6 +35,12 @@ pub enum DidPeerError {
     RegexError(#[from] regex::Error),
     #[error("Public key error: {0}")]
     PublicKeyError(#[from] public_key::PublicKeyError),
+    #[error("Multibase decoding error: {0}")]
+    MultibaseDecodingError(#[from] multibase::Error),
+    #[error("Varint decoding error: {0}")]
+    VarintDecodingError(#[from] unsigned_varint::decode::Error),
+    #[error("Long form of the short form DID was not seen before: {0}")]
+    UnknownShortFormDid(String),
 }
 
 impl From<Infallible> for DidPeerError {
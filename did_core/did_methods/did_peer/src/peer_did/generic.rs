@@ -5,7 +5,9 @@ use super::PeerDid;
 use crate::{
     error::DidPeerError,
     peer_did::{
-        numalgos::{kind::NumalgoKind, numalgo2::Numalgo2, numalgo3::Numalgo3},
+        numalgos::{
+            kind::NumalgoKind, numalgo2::Numalgo2, numalgo3::Numalgo3, numalgo4::Numalgo4, Numalgo,
+        },
         parse::parse_numalgo,
         validate::validate,
     },
@@ -15,6 +17,7 @@ use crate::{
 pub enum AnyPeerDid {
     Numalgo2(PeerDid<Numalgo2>),
     Numalgo3(PeerDid<Numalgo3>),
+    Numalgo4(PeerDid<Numalgo4>),
 }
 
 impl AnyPeerDid {
@@ -30,6 +33,9 @@ impl AnyPeerDid {
             NumalgoKind::MultipleInceptionKeys(numalgo) => {
                 AnyPeerDid::Numalgo2(PeerDid { did, numalgo })
             }
+            NumalgoKind::ShortFormAndLongForm(_) => {
+                AnyPeerDid::Numalgo4(Numalgo4::parse::<Did>(did)?)
+            }
             _ => AnyPeerDid::Numalgo3(PeerDid {
                 did,
                 numalgo: Numalgo3,
@@ -42,6 +48,7 @@ impl AnyPeerDid {
         match self {
             AnyPeerDid::Numalgo2(peer_did) => NumalgoKind::MultipleInceptionKeys(peer_did.numalgo),
             AnyPeerDid::Numalgo3(peer_did) => NumalgoKind::DidShortening(peer_did.numalgo),
+            AnyPeerDid::Numalgo4(peer_did) => NumalgoKind::ShortFormAndLongForm(peer_did.numalgo),
         }
     }
 }
@@ -54,6 +61,7 @@ impl Serialize for AnyPeerDid {
         match &self {
             AnyPeerDid::Numalgo2(peer_did) => serializer.serialize_str(peer_did.did().did()),
             AnyPeerDid::Numalgo3(peer_did) => serializer.serialize_str(peer_did.did().did()),
+            AnyPeerDid::Numalgo4(peer_did) => serializer.serialize_str(peer_did.did().did()),
         }
     }
 }
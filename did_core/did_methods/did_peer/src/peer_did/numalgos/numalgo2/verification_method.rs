@@ -1,5 +1,5 @@
 use did_doc::schema::verification_method::{
-    IncompleteVerificationMethodBuilder, VerificationMethod, VerificationMethodType,
+    IncompleteVerificationMethodBuilder, PublicKeyField, VerificationMethod, VerificationMethodType,
 };
 use did_parser::{Did, DidUrl};
 use public_key::{Key, KeyType};
@@ -20,6 +20,7 @@ pub fn get_verification_methods_by_key(
         KeyType::P256 => VerificationMethodType::JsonWebKey2020,
         KeyType::P384 => VerificationMethodType::JsonWebKey2020,
         KeyType::P521 => VerificationMethodType::JsonWebKey2020,
+        KeyType::Secp256k1 => VerificationMethodType::JsonWebKey2020,
         KeyType::Bls12381g1g2 => {
             return Ok(build_verification_methods_from_bls_multikey(
                 &Key::new(key.key()[..48].to_vec(), KeyType::Bls12381g1)?,
@@ -39,6 +40,9 @@ pub fn get_verification_methods_by_key(
 }
 
 pub fn get_key_by_verification_method(vm: &VerificationMethod) -> Result<Key, DidPeerError> {
+    if let PublicKeyField::Jwk { .. } = vm.public_key_field() {
+        return Ok(vm.public_key()?);
+    }
     let key_type = match vm.verification_method_type() {
         VerificationMethodType::Ed25519VerificationKey2018
         | VerificationMethodType::Ed25519VerificationKey2020 => KeyType::Ed25519,
@@ -276,5 +280,21 @@ mod tests {
                 key_2()
             );
         }
+
+        #[test]
+        fn test_get_key_by_verification_method_jwk() {
+            let vm = VerificationMethod::builder(
+                did().into(),
+                did(),
+                VerificationMethodType::JsonWebKey2020,
+            )
+            .add_public_key(&key_1())
+            .unwrap()
+            .build();
+            assert_eq!(
+                verification_method::get_key_by_verification_method(&vm).unwrap(),
+                key_1()
+            );
+        }
     }
 }
@@ -0,0 +1,71 @@
+use multibase::Base;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::DidPeerError;
+
+/// Multicodec code of JSON encoded data
+const MULTICODEC_JSON: u64 = 0x0200;
+/// Multihash code of SHA2-256 followed by the digest length
+const MULTIHASH_SHA2_256: [u8; 2] = [0x12, 0x20];
+
+pub(super) fn encode_document(document: &Value) -> Result<String, DidPeerError> {
+    let mut buffer = unsigned_varint::encode::u64_buffer();
+    let mut bytes = unsigned_varint::encode::u64(MULTICODEC_JSON, &mut buffer).to_vec();
+    bytes.extend(serde_json::to_vec(document)?);
+    Ok(multibase::encode(Base::Base58Btc, bytes))
+}
+
+pub(super) fn decode_document(encoded_document: &str) -> Result<Value, DidPeerError> {
+    let (_base, bytes) = multibase::decode(encoded_document)?;
+    let (code, json) = unsigned_varint::decode::u64(&bytes)?;
+    if code != MULTICODEC_JSON {
+        return Err(DidPeerError::DidValidationError(format!(
+            "Unexpected multicodec {code:#x} of did:peer:4 encoded document, expected JSON"
+        )));
+    }
+    Ok(serde_json::from_slice(json)?)
+}
+
+pub(super) fn hash_document(encoded_document: &str) -> String {
+    let mut multihash = MULTIHASH_SHA2_256.to_vec();
+    multihash.extend(Sha256::digest(encoded_document.as_bytes()));
+    multibase::encode(Base::Base58Btc, multihash)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_document() {
+        let document = json!({
+            "verificationMethod": [{
+                "id": "#key-1",
+                "type": "Ed25519VerificationKey2020",
+                "publicKeyMultibase": "z6MkrCD1csqtgdj8sjrsu8jxcbeyP6m7LiK87NzhfWqio5yr"
+            }]
+        });
+        let encoded = encode_document(&document).unwrap();
+        assert!(encoded.starts_with('z'));
+        assert_eq!(decode_document(&encoded).unwrap(), document);
+    }
+
+    #[test]
+    fn test_decode_document_not_json() {
+        let encoded = multibase::encode(Base::Base58Btc, [0xed, 0x01, 0x00]);
+        assert!(matches!(
+            decode_document(&encoded),
+            Err(DidPeerError::DidValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_document() {
+        let hash = hash_document("z2M1k7h4psgp4CmJcnQn2Ljp7Pz7ktsd7oBhMU3dWY5s4fhFNj17qcRT");
+        assert!(hash.starts_with("zQm"));
+        assert_eq!(hash.len(), 47);
+    }
+}
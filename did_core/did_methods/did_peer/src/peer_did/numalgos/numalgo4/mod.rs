@@ -0,0 +1,253 @@
+use did_doc::schema::did_doc::DidDocument;
+use did_doc_sov::extra_fields::ExtraFieldsSov;
+use did_parser::Did;
+use encoding::{encode_document, hash_document};
+use resolve::{resolve_numalgo4, VERIFICATION_RELATIONSHIPS};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::DidPeerError,
+    peer_did::{
+        numalgos::{Numalgo, ResolvableNumalgo},
+        parse::parse_numalgo,
+        validate::validate,
+        FromDidDoc, PeerDid,
+    },
+    resolver::options::PublicKeyEncoding,
+};
+
+mod encoding;
+pub mod resolve;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Numalgo4;
+
+impl Numalgo for Numalgo4 {
+    const NUMALGO_CHAR: char = '4';
+
+    fn parse<T>(did: T) -> Result<PeerDid<Self>, DidPeerError>
+    where
+        Did: TryFrom<T>,
+        <Did as TryFrom<T>>::Error: Into<DidPeerError>,
+    {
+        let did: Did = did.try_into().map_err(Into::into)?;
+        let numalgo_char = parse_numalgo(&did)?.to_char();
+        if numalgo_char != Self::NUMALGO_CHAR {
+            return Err(DidPeerError::InvalidNumalgoCharacter(numalgo_char));
+        }
+        validate(&did)?;
+        let peer_did = PeerDid::from_parts(did, Self);
+        if let Some(encoded_document) = peer_did.encoded_document() {
+            if hash_document(encoded_document) != peer_did.hash() {
+                return Err(DidPeerError::DidValidationError(format!(
+                    "Invalid did: {} because its hash does not match the encoded document",
+                    peer_did.did()
+                )));
+            }
+        }
+        Ok(peer_did)
+    }
+}
+
+impl FromDidDoc for Numalgo4 {
+    fn from_did_doc(
+        did_document: DidDocument<ExtraFieldsSov>,
+    ) -> Result<PeerDid<Numalgo4>, DidPeerError> {
+        let source_did = did_document.id().to_string();
+        let mut document = serde_json::to_value(did_document)?;
+        // The input document is identified by its hash, so it must not refer to the DID it was
+        // taken from, be it by its id, its other form or as the controller of its keys
+        if let Value::Object(document) = &mut document {
+            strip_source_did(document, &source_did);
+        }
+        let encoded_document = encode_document(&document)?;
+        let hash = hash_document(&encoded_document);
+        PeerDid::<Numalgo4>::parse(format!("did:peer:4{hash}:{encoded_document}"))
+    }
+}
+
+fn strip_source_did(document: &mut Map<String, Value>, source_did: &str) {
+    // A did:peer:4 source is known by its short and long form alike
+    let short_form = PeerDid::<Numalgo4>::parse(source_did.to_string())
+        .ok()
+        .map(|peer_did| format!("did:peer:4{}", peer_did.hash()));
+    let refers_to_source = |value: &Value| {
+        value.as_str().is_some_and(|value| {
+            value == source_did
+                || short_form.as_deref().is_some_and(|short_form| {
+                    value == short_form
+                        || value
+                            .strip_prefix(short_form)
+                            .is_some_and(|rest| rest.starts_with(':'))
+                })
+        })
+    };
+
+    document.remove("id");
+    if let Some(Value::Array(also_known_as)) = document.get_mut("alsoKnownAs") {
+        also_known_as.retain(|did| !refers_to_source(did));
+        if also_known_as.is_empty() {
+            document.remove("alsoKnownAs");
+        }
+    }
+    for relationship in VERIFICATION_RELATIONSHIPS {
+        let methods = document.get_mut(relationship).and_then(Value::as_array_mut);
+        for method in methods
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_object_mut)
+        {
+            if method.get("controller").is_some_and(refers_to_source) {
+                method.remove("controller");
+            }
+        }
+    }
+}
+
+impl PeerDid<Numalgo4> {
+    /// Multibase encoded hash of the input document, which alone makes up the short form
+    pub fn hash(&self) -> &str {
+        let id = &self.did().id()[1..];
+        id.split_once(':').map_or(id, |(hash, _)| hash)
+    }
+
+    /// Multibase encoded input document, present only in the long form
+    pub fn encoded_document(&self) -> Option<&str> {
+        self.did().id().split_once(':').map(|(_, encoded)| encoded)
+    }
+
+    pub fn is_long_form(&self) -> bool {
+        self.encoded_document().is_some()
+    }
+
+    pub fn short_form(&self) -> Result<PeerDid<Numalgo4>, DidPeerError> {
+        PeerDid::<Numalgo4>::parse(format!("did:peer:4{}", self.hash()))
+    }
+}
+
+impl ResolvableNumalgo for Numalgo4 {
+    fn resolve(
+        &self,
+        did: &Did,
+        _public_key_encoding: PublicKeyEncoding,
+    ) -> Result<DidDocument<ExtraFieldsSov>, DidPeerError> {
+        resolve_numalgo4(&PeerDid::<Numalgo4>::parse(did.to_owned())?, did)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use did_doc::schema::verification_method::VerificationMethodType;
+
+    use super::*;
+
+    // Test vector from https://identity.foundation/peer-did-method-spec/#method-4-short-form-and-long-form
+    const PEER_DID_NUMALGO4_SHORT: &str =
+        "did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd";
+    const PEER_DID_NUMALGO4_LONG: &str = "did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd\
+        :z2M1k7h4psgp4CmJcnQn2Ljp7Pz7ktsd7oBhMU3dWY5s4fhFNj17qcRTQ427C7QHNT6cQ7T3XfRh35Q2GhaNFZmW\
+         HVFq4vL7F8nm36PA9Y96DvdrUiRUaiCuXnBFrn1o7mxFZAx14JL4t8vUWpuDPwQuddVo1T8myRiVH7wdxuoYbsva\
+         5x6idEpCQydJdFjiHGCpNc2UtjzPQ8awSXkctGCnBmgkhrj5gto3D4i3EREXYq4Z8r2cWGBr2UzbSmnxW2BuYddF\
+         o9Yfm6mKjtJyLpF74ytqrF5xtf84MnGFg1hMBmh1xVx1JwjZ2BeMJs7mNS8DTZhKC7KH38EgqDtUZzfjhpjmmUfk\
+         Xg2KFEA3EGbbVm1DPqQXayPYKAsYPS9AyKkcQ3fzWafLPP93UfNhtUPL8JW5pMcSV3P8v6j3vPXqnnGknNyBprD6\
+         YGUVtgLiAqDBDUF3LSxFQJCVYYtghMTv8WuSw9h1a1SRFrDQLGHE4UrkgoRvwaGWr64aM87T1eVGkP5Dt4L1Abbo\
+         eK2ceLArPScrdYGTpi3BpTkLwZCdjdiFSfTy9okL1YNRARqUf2wm8DvkVGUU7u5nQA3ZMaXWJAewk6k1YUxKd7Lv\
+         ofGUK4YEDtoxN5vb6r1Q2godrGqaPkjfL3RoYPpDYymf9XhcgG8Kx3DZaA6cyTs24t45KxYAfeCw4wqUpCH9HbpD\
+         78TbEUr9PPAsJgXBvBj2VVsxnr7FKbK4KykGcg1W8M1JPz21Z4Y72LWgGQCmixovrkHktcTX1uNHjAvKBqVD5C7X\
+         mVfHgXCHj7djCh3vzLNuVLtEED8J1hhqsB1oCBGiuh3xXr7fZ9wUjJCQ1HYHqxLJKdYKtoCiPmgKM7etVftXkmTF\
+         ETZmpM19aRyih3bao76LdpQtbw636r7a3qt8v4WfxsXJetSL8c7t24SqQBcAY89FBsbEnFNrQCMK3JEseKHVaU38\
+         8ctvRD45uQfe5GndFxthj4iSDomk4uRFd1uRbywoP1tRuabHTDX42UxPjz";
+
+    fn peer_did_numalgo4_long() -> PeerDid<Numalgo4> {
+        PeerDid::<Numalgo4>::parse(PEER_DID_NUMALGO4_LONG.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_numalgo4_long_form() {
+        let peer_did = peer_did_numalgo4_long();
+        assert!(peer_did.is_long_form());
+        assert_eq!(
+            peer_did.hash(),
+            "zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd"
+        );
+    }
+
+    #[test]
+    fn test_parse_numalgo4_short_form() {
+        let peer_did = PeerDid::<Numalgo4>::parse(PEER_DID_NUMALGO4_SHORT.to_string()).unwrap();
+        assert!(!peer_did.is_long_form());
+        assert_eq!(peer_did.encoded_document(), None);
+    }
+
+    #[test]
+    fn test_parse_numalgo4_hash_mismatch() {
+        let tampered = PEER_DID_NUMALGO4_LONG.replace(":z2M1k7h4", ":z2M1k7h5");
+        assert!(matches!(
+            PeerDid::<Numalgo4>::parse(tampered),
+            Err(DidPeerError::DidValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_numalgo4_long_to_short_form() {
+        assert_eq!(
+            peer_did_numalgo4_long().short_form().unwrap(),
+            PeerDid::<Numalgo4>::parse(PEER_DID_NUMALGO4_SHORT.to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resolve_numalgo4_long_form() {
+        let peer_did = peer_did_numalgo4_long();
+        let did_document = resolve_numalgo4(&peer_did, peer_did.did()).unwrap();
+        assert_eq!(did_document.id(), peer_did.did());
+        assert_eq!(
+            did_document.also_known_as()[0].to_string(),
+            PEER_DID_NUMALGO4_SHORT
+        );
+        let verification_methods = did_document.verification_method();
+        assert_eq!(verification_methods.len(), 2);
+        assert_eq!(verification_methods[0].controller(), peer_did.did());
+        assert_eq!(
+            verification_methods[0].verification_method_type(),
+            &VerificationMethodType::X25519KeyAgreementKey2020
+        );
+        assert_eq!(did_document.key_agreement().len(), 1);
+        assert_eq!(did_document.authentication().len(), 1);
+        assert_eq!(did_document.service().len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_numalgo4_short_form() {
+        let peer_did = peer_did_numalgo4_long();
+        let short_form = peer_did.short_form().unwrap();
+        let did_document = resolve_numalgo4(&peer_did, short_form.did()).unwrap();
+        assert_eq!(did_document.id(), short_form.did());
+        assert_eq!(
+            did_document.also_known_as()[0].to_string(),
+            PEER_DID_NUMALGO4_LONG
+        );
+    }
+
+    #[test]
+    fn test_generate_numalgo4() {
+        let peer_did = peer_did_numalgo4_long();
+        let did_document = resolve_numalgo4(&peer_did, peer_did.did()).unwrap();
+        let generated = PeerDid::<Numalgo4>::from_did_doc(did_document.clone()).unwrap();
+        assert!(generated.is_long_form());
+        let regenerated = resolve_numalgo4(&generated, generated.did()).unwrap();
+
+        // Apart from the DID it is known by, the regenerated document is the original one
+        let expected = serde_json::to_string(&did_document)
+            .unwrap()
+            .replace(PEER_DID_NUMALGO4_LONG, &generated.to_string())
+            .replace(
+                PEER_DID_NUMALGO4_SHORT,
+                &generated.short_form().unwrap().to_string(),
+            );
+        assert_eq!(
+            serde_json::to_value(&regenerated).unwrap(),
+            serde_json::from_str::<Value>(&expected).unwrap()
+        );
+    }
+}
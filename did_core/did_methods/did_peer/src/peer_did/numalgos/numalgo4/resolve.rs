@@ -0,0 +1,84 @@
+use did_doc::schema::did_doc::DidDocument;
+use did_doc_sov::extra_fields::ExtraFieldsSov;
+use did_parser::Did;
+use serde_json::{json, Map, Value};
+
+use super::{encoding::decode_document, Numalgo4};
+use crate::{error::DidPeerError, peer_did::PeerDid};
+
+pub(super) const VERIFICATION_RELATIONSHIPS: [&str; 6] = [
+    "verificationMethod",
+    "authentication",
+    "assertionMethod",
+    "keyAgreement",
+    "capabilityInvocation",
+    "capabilityDelegation",
+];
+
+/// Resolves `did`, either the long or the short form of `long_form`, by contextualizing the
+/// input document encoded in the long form. The other form is listed in `alsoKnownAs`.
+pub fn resolve_numalgo4(
+    long_form: &PeerDid<Numalgo4>,
+    did: &Did,
+) -> Result<DidDocument<ExtraFieldsSov>, DidPeerError> {
+    let encoded_document = long_form
+        .encoded_document()
+        .ok_or_else(|| DidPeerError::UnknownShortFormDid(long_form.did().to_string()))?;
+    let short_form = long_form.short_form()?;
+    let also_known_as = if did == short_form.did() {
+        long_form.did()
+    } else {
+        short_form.did()
+    };
+
+    let Value::Object(mut document) = decode_document(encoded_document)? else {
+        return Err(DidPeerError::DidValidationError(format!(
+            "Encoded document of {} is not a JSON object",
+            long_form.did()
+        )));
+    };
+    document.insert("id".to_string(), json!(did.to_string()));
+    document.insert(
+        "alsoKnownAs".to_string(),
+        json!([also_known_as.to_string()]),
+    );
+    for relationship in VERIFICATION_RELATIONSHIPS {
+        let methods = document.get_mut(relationship).and_then(Value::as_array_mut);
+        for method in methods
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_object_mut)
+        {
+            method
+                .entry("controller")
+                .or_insert_with(|| json!(did.to_string()));
+        }
+    }
+    let services = document.get_mut("service").and_then(Value::as_array_mut);
+    for service in services
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+    {
+        flatten_service_endpoint(service);
+    }
+
+    Ok(serde_json::from_value(Value::Object(document))?)
+}
+
+/// DIDComm v2 services carry their `uri`, `accept` and `routingKeys` in a `serviceEndpoint`
+/// object, whereas [`DidDocument`] services hold them next to a plain endpoint URI.
+fn flatten_service_endpoint(service: &mut Map<String, Value>) {
+    if !service.get("serviceEndpoint").is_some_and(Value::is_object) {
+        return;
+    }
+    let Some(Value::Object(endpoint)) = service.remove("serviceEndpoint") else {
+        return;
+    };
+    for (key, value) in endpoint {
+        match key.as_str() {
+            "uri" => service.insert("serviceEndpoint".to_string(), value),
+            _ => service.insert(key, value),
+        };
+    }
+}
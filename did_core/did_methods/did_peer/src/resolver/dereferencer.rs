@@ -0,0 +1,59 @@
+use std::io::Cursor;
+
+use async_trait::async_trait;
+use did_doc_sov::extra_fields::ExtraFieldsSov;
+use did_parser::DidUrl;
+use did_resolver::{
+    error::GenericError,
+    traits::{
+        dereferenceable::{
+            dereferencing_options::DidDereferencingOptions,
+            dereferencing_output::DidDereferencingOutput, utils::dereference_did_document,
+            DidDereferenceable,
+        },
+        resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+    },
+};
+
+use crate::resolver::{options::ExtraFieldsOptions, CachingPeerDidResolver, PeerDidResolver};
+
+async fn dereference_peer_did<R>(
+    resolver: &R,
+    did_url: &DidUrl,
+) -> Result<DidDereferencingOutput<Cursor<Vec<u8>>>, GenericError>
+where
+    R: DidResolvable<ExtraFieldsService = ExtraFieldsSov, ExtraFieldsOptions = ExtraFieldsOptions>
+        + Sync,
+{
+    let resolution_output = resolver
+        .resolve(&did_url.try_into()?, &DidResolutionOptions::default())
+        .await?;
+
+    dereference_did_document(&resolution_output, did_url).map_err(|err| err.into())
+}
+
+#[async_trait]
+impl DidDereferenceable for PeerDidResolver {
+    type Output = Cursor<Vec<u8>>;
+
+    async fn dereference(
+        &self,
+        did_url: &DidUrl,
+        _options: &DidDereferencingOptions,
+    ) -> Result<DidDereferencingOutput<Self::Output>, GenericError> {
+        dereference_peer_did(self, did_url).await
+    }
+}
+
+#[async_trait]
+impl DidDereferenceable for CachingPeerDidResolver {
+    type Output = Cursor<Vec<u8>>;
+
+    async fn dereference(
+        &self,
+        did_url: &DidUrl,
+        _options: &DidDereferencingOptions,
+    ) -> Result<DidDereferencingOutput<Self::Output>, GenericError> {
+        dereference_peer_did(self, did_url).await
+    }
+}
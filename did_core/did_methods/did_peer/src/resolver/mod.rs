@@ -1,3 +1,8 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Mutex, PoisonError},
+};
+
 use async_trait::async_trait;
 use did_doc_sov::extra_fields::ExtraFieldsSov;
 use did_parser::Did;
@@ -8,15 +13,28 @@ use did_resolver::{
         resolution_output::DidResolutionOutput, DidResolvable,
     },
 };
+use lru::LruCache;
 
 use crate::{
     error::DidPeerError,
-    peer_did::{generic::AnyPeerDid, numalgos::numalgo2::resolve::resolve_numalgo2},
+    peer_did::{
+        generic::AnyPeerDid,
+        numalgos::{
+            numalgo2::resolve::resolve_numalgo2,
+            numalgo4::{resolve::resolve_numalgo4, Numalgo4},
+        },
+        PeerDid,
+    },
     resolver::options::ExtraFieldsOptions,
 };
 
+mod dereferencer;
 pub mod options;
 
+const DEFAULT_NUMALGO4_CAPACITY: usize = 1024;
+
+/// Resolves did:peer DIDs. Short form did:peer:4 DIDs cannot be resolved, as their DID documents
+/// are only encoded in the long form; use [`CachingPeerDidResolver`] for those.
 #[derive(Default)]
 pub struct PeerDidResolver;
 
@@ -26,6 +44,79 @@ impl PeerDidResolver {
     }
 }
 
+/// Resolves did:peer DIDs like [`PeerDidResolver`], remembering long form did:peer:4 DIDs once
+/// resolved. A short form did:peer:4 DID only resolves if this instance has already resolved its
+/// long form, and only the most recently used long forms are kept.
+pub struct CachingPeerDidResolver {
+    numalgo4_long_forms: Mutex<LruCache<String, PeerDid<Numalgo4>>>,
+}
+
+impl Default for CachingPeerDidResolver {
+    fn default() -> Self {
+        Self::with_numalgo4_capacity(
+            NonZeroUsize::new(DEFAULT_NUMALGO4_CAPACITY).expect("capacity is not zero"),
+        )
+    }
+}
+
+impl CachingPeerDidResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers up to `capacity` long form did:peer:4 DIDs
+    pub fn with_numalgo4_capacity(capacity: NonZeroUsize) -> Self {
+        Self {
+            numalgo4_long_forms: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn numalgo4_long_form(
+        &self,
+        peer_did: PeerDid<Numalgo4>,
+    ) -> Result<PeerDid<Numalgo4>, DidPeerError> {
+        let mut long_forms = self
+            .numalgo4_long_forms
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if peer_did.is_long_form() {
+            long_forms.put(peer_did.hash().to_string(), peer_did.clone());
+            return Ok(peer_did);
+        }
+        long_forms
+            .get(peer_did.hash())
+            .cloned()
+            .ok_or_else(|| DidPeerError::UnknownShortFormDid(peer_did.to_string()))
+    }
+}
+
+/// Resolves `did`, looking up the long form of did:peer:4 DIDs with `numalgo4_long_form`
+fn resolve_peer_did(
+    did: &Did,
+    options: &DidResolutionOptions<ExtraFieldsOptions>,
+    numalgo4_long_form: impl FnOnce(PeerDid<Numalgo4>) -> Result<PeerDid<Numalgo4>, DidPeerError>,
+) -> Result<DidResolutionOutput<ExtraFieldsSov>, GenericError> {
+    let peer_did = AnyPeerDid::parse(did.to_owned())?;
+    let did_doc = match peer_did {
+        AnyPeerDid::Numalgo2(peer_did) => {
+            resolve_numalgo2(peer_did.did(), options.extra().public_key_encoding())?
+                .add_also_known_as(peer_did.to_numalgo3()?.to_string().parse()?)
+                .build()
+        }
+        AnyPeerDid::Numalgo4(peer_did) => {
+            let long_form = numalgo4_long_form(peer_did.clone())?;
+            resolve_numalgo4(&long_form, peer_did.did())?
+        }
+        n => return Err(Box::new(DidPeerError::UnsupportedNumalgo(n.numalgo()))),
+    };
+    let resolution_metadata = DidResolutionMetadata::builder()
+        .content_type("application/did+json".to_string())
+        .build();
+    let builder =
+        DidResolutionOutput::builder(did_doc).did_resolution_metadata(resolution_metadata);
+    Ok(builder.build())
+}
+
 #[async_trait]
 impl DidResolvable for PeerDidResolver {
     type ExtraFieldsService = ExtraFieldsSov;
@@ -36,21 +127,26 @@ impl DidResolvable for PeerDidResolver {
         did: &Did,
         options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
     ) -> Result<DidResolutionOutput<Self::ExtraFieldsService>, GenericError> {
-        let peer_did = AnyPeerDid::parse(did.to_owned())?;
-        match peer_did {
-            AnyPeerDid::Numalgo2(peer_did) => {
-                let did_doc =
-                    resolve_numalgo2(peer_did.did(), options.extra().public_key_encoding())?
-                        .add_also_known_as(peer_did.to_numalgo3()?.to_string().parse()?)
-                        .build();
-                let resolution_metadata = DidResolutionMetadata::builder()
-                    .content_type("application/did+json".to_string())
-                    .build();
-                let builder = DidResolutionOutput::builder(did_doc)
-                    .did_resolution_metadata(resolution_metadata);
-                Ok(builder.build())
+        resolve_peer_did(did, options, |peer_did| {
+            if peer_did.is_long_form() {
+                Ok(peer_did)
+            } else {
+                Err(DidPeerError::UnknownShortFormDid(peer_did.to_string()))
             }
-            n => Err(Box::new(DidPeerError::UnsupportedNumalgo(n.numalgo()))),
-        }
+        })
+    }
+}
+
+#[async_trait]
+impl DidResolvable for CachingPeerDidResolver {
+    type ExtraFieldsService = ExtraFieldsSov;
+    type ExtraFieldsOptions = ExtraFieldsOptions;
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
+    ) -> Result<DidResolutionOutput<Self::ExtraFieldsService>, GenericError> {
+        resolve_peer_did(did, options, |peer_did| self.numalgo4_long_form(peer_did))
     }
 }
@@ -0,0 +1,81 @@
+mod fixtures;
+
+use std::io::Read;
+
+use did_parser::DidUrl;
+use did_peer::resolver::PeerDidResolver;
+use did_resolver::traits::dereferenceable::{
+    dereferencing_error::DidDereferencingError, dereferencing_options::DidDereferencingOptions,
+    DidDereferenceable,
+};
+use serde_json::Value;
+use tokio::test;
+
+use crate::fixtures::basic::PEER_DID_NUMALGO_2_BASIC;
+
+async fn dereference(did_url: &str) -> Result<String, DidDereferencingError> {
+    let output = PeerDidResolver::new()
+        .dereference(
+            &DidUrl::parse(did_url.to_string()).unwrap(),
+            &DidDereferencingOptions::default(),
+        )
+        .await
+        .map_err(|err| err.downcast_ref::<DidDereferencingError>().unwrap().clone())?;
+    let mut content = String::new();
+    output
+        .content_stream()
+        .clone()
+        .read_to_string(&mut content)
+        .unwrap();
+    Ok(content)
+}
+
+#[test]
+async fn test_dereference_key_agreement() {
+    let content = dereference(&format!("{}#6LSbysY2", PEER_DID_NUMALGO_2_BASIC))
+        .await
+        .unwrap();
+    let verification_method: Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(verification_method["type"], "X25519KeyAgreementKey2020");
+    assert_eq!(
+        verification_method["publicKeyBase58"],
+        "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr"
+    );
+}
+
+#[test]
+async fn test_dereference_service() {
+    let content = dereference(&format!("{}#didcommmessaging-0", PEER_DID_NUMALGO_2_BASIC))
+        .await
+        .unwrap();
+    let service: Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(service["serviceEndpoint"], "https://example.com/endpoint");
+}
+
+#[test]
+async fn test_dereference_service_endpoint() {
+    let content = dereference(&format!(
+        "{}?service=didcommmessaging-0&relativeRef=%2Finbox",
+        PEER_DID_NUMALGO_2_BASIC
+    ))
+    .await
+    .unwrap();
+    // The relative reference is resolved as per RFC 3986, its absolute path replaces the endpoint's
+    assert_eq!(content, "https://example.com/inbox");
+}
+
+#[test]
+async fn test_dereference_errors() {
+    assert_eq!(
+        dereference(&format!("{}#unknown", PEER_DID_NUMALGO_2_BASIC)).await,
+        Err(DidDereferencingError::NotFound)
+    );
+    assert_eq!(
+        dereference(&format!(
+            "{}?versionTime=2021-05-10T17:00:00Z#6LSbysY2",
+            PEER_DID_NUMALGO_2_BASIC
+        ))
+        .await,
+        Err(DidDereferencingError::VersionNotSupported)
+    );
+}
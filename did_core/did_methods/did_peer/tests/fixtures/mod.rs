@@ -4,3 +4,4 @@ pub mod basic;
 pub mod multiple_services;
 pub mod no_routing_keys;
 pub mod no_services;
+pub mod numalgo4;
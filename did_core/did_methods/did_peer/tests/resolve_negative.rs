@@ -119,3 +119,12 @@ async fn test_resolve_numalgo_2_invalid_7() {
         DidPeerError::PublicKeyError(_)
     ));
 }
+
+#[test]
+async fn test_resolve_numalgo_4_unknown_short_form() {
+    let peer_did = "did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd";
+    assert!(matches!(
+        resolve_error(peer_did).await,
+        DidPeerError::UnknownShortFormDid(_)
+    ));
+}
@@ -1,10 +1,16 @@
 mod fixtures;
 
+use std::num::NonZeroUsize;
+
 use did_doc::schema::did_doc::DidDocument;
 use did_doc_sov::extra_fields::ExtraFieldsSov;
-use did_peer::resolver::{
-    options::{ExtraFieldsOptions, PublicKeyEncoding},
-    PeerDidResolver,
+use did_peer::{
+    error::DidPeerError,
+    peer_did::{numalgos::numalgo4::Numalgo4, PeerDid},
+    resolver::{
+        options::{ExtraFieldsOptions, PublicKeyEncoding},
+        CachingPeerDidResolver, PeerDidResolver,
+    },
 };
 use did_resolver::traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable};
 use tokio::test;
@@ -14,6 +20,7 @@ use crate::fixtures::{
     multiple_services::{DID_DOC_MULTIPLE_SERVICES, PEER_DID_NUMALGO_2_MULTIPLE_SERVICES},
     no_routing_keys::{DID_DOC_NO_ROUTING_KEYS, PEER_DID_NUMALGO_2_NO_ROUTING_KEYS},
     no_services::{DID_DOC_NO_SERVICES, PEER_DID_NUMALGO_2_NO_SERVICES},
+    numalgo4::{DID_DOC_NUMALGO_4, PEER_DID_NUMALGO_4_LONG, PEER_DID_NUMALGO_4_SHORT},
 };
 
 macro_rules! resolve_positive_test {
@@ -62,3 +69,86 @@ resolve_positive_test!(
     PEER_DID_NUMALGO_2_NO_SERVICES,
     PublicKeyEncoding::Multibase
 );
+
+#[test]
+async fn test_resolve_numalgo4_long_and_short_form() {
+    let resolver = CachingPeerDidResolver::new();
+    let options = DidResolutionOptions::new(ExtraFieldsOptions::new());
+    let did_document_expected =
+        serde_json::from_str::<DidDocument<ExtraFieldsSov>>(DID_DOC_NUMALGO_4).unwrap();
+
+    let ddo_long = resolver
+        .resolve(&PEER_DID_NUMALGO_4_LONG.parse().unwrap(), &options)
+        .await
+        .unwrap();
+    assert_eq!(ddo_long.did_document(), &did_document_expected);
+
+    // The short form is resolvable only after the long form has been seen
+    let ddo_short = resolver
+        .resolve(&PEER_DID_NUMALGO_4_SHORT.parse().unwrap(), &options)
+        .await
+        .unwrap();
+    let did_document_short = ddo_short.did_document();
+    assert_eq!(did_document_short.id().did(), PEER_DID_NUMALGO_4_SHORT);
+    assert_eq!(
+        did_document_short.also_known_as()[0].to_string(),
+        PEER_DID_NUMALGO_4_LONG
+    );
+    assert_eq!(
+        did_document_short.verification_method().len(),
+        did_document_expected.verification_method().len()
+    );
+}
+
+#[test]
+async fn test_resolve_numalgo4_long_form_without_cache() {
+    let options = DidResolutionOptions::new(ExtraFieldsOptions::new());
+    let did_document_expected =
+        serde_json::from_str::<DidDocument<ExtraFieldsSov>>(DID_DOC_NUMALGO_4).unwrap();
+
+    let ddo_long = PeerDidResolver
+        .resolve(&PEER_DID_NUMALGO_4_LONG.parse().unwrap(), &options)
+        .await
+        .unwrap();
+    assert_eq!(ddo_long.did_document(), &did_document_expected);
+
+    let err = PeerDidResolver
+        .resolve(&PEER_DID_NUMALGO_4_SHORT.parse().unwrap(), &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *err.downcast::<DidPeerError>().unwrap(),
+        DidPeerError::UnknownShortFormDid(_)
+    ));
+}
+
+#[test]
+async fn test_resolve_numalgo4_short_form_of_evicted_long_form() {
+    let resolver = CachingPeerDidResolver::with_numalgo4_capacity(NonZeroUsize::new(1).unwrap());
+    let options = DidResolutionOptions::new(ExtraFieldsOptions::new());
+    let did_document =
+        serde_json::from_str::<DidDocument<ExtraFieldsSov>>(DID_DOC_NUMALGO_4).unwrap();
+    let other_long_form = PeerDid::<Numalgo4>::from_did_doc(did_document).unwrap();
+
+    resolver
+        .resolve(&PEER_DID_NUMALGO_4_LONG.parse().unwrap(), &options)
+        .await
+        .unwrap();
+    resolver
+        .resolve(other_long_form.did(), &options)
+        .await
+        .unwrap();
+
+    let err = resolver
+        .resolve(&PEER_DID_NUMALGO_4_SHORT.parse().unwrap(), &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *err.downcast::<DidPeerError>().unwrap(),
+        DidPeerError::UnknownShortFormDid(_)
+    ));
+    resolver
+        .resolve(other_long_form.short_form().unwrap().did(), &options)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use hyper::{http::uri::InvalidUri, StatusCode};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum DidUniversalError {
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+    #[error("Universal resolver returned error: {0}")]
+    ResolutionError(String),
+    #[error("Invalid resolution result: {0}")]
+    InvalidResolutionResult(String),
+    #[error("Invalid resolver endpoint: {0}")]
+    InvalidEndpoint(#[from] InvalidUri),
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] hyper::Error),
+    #[error("HTTP request error: {0}")]
+    HttpRequestError(#[from] hyper::http::Error),
+    #[error("Non-success server response: {0}")]
+    NonSuccessResponse(StatusCode),
+    #[error("Universal resolver did not respond within {0:?}")]
+    Timeout(Duration),
+    #[error("Universal resolver response exceeds {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("JSON parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
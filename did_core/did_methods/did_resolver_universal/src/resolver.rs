@@ -0,0 +1,240 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use did_resolver::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser::Did,
+    error::GenericError,
+    shared_types::did_document_metadata::DidDocumentMetadata,
+    traits::resolvable::{
+        resolution_metadata::DidResolutionMetadata, resolution_options::DidResolutionOptions,
+        resolution_output::DidResolutionOutput, DidResolvable,
+    },
+};
+use hyper::{
+    body::HttpBody,
+    client::{connect::Connect, HttpConnector},
+    header::ACCEPT,
+    Body, Client, Request, Uri,
+};
+use hyper_tls::HttpsConnector;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::DidUniversalError;
+
+// Asks the resolver for the full resolution result rather than just the DID document
+const ACCEPT_RESOLUTION_RESULT: &str =
+    r#"application/ld+json;profile="https://w3id.org/did-resolution""#;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Resolution results are small JSON documents, anything much larger is not read into memory
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+// Characters which can't appear unencoded within a single path segment. DIDs may contain `%`
+// themselves, e.g. did:web DIDs with a port.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Resolves DIDs of any method by calling an HTTP endpoint compatible with the DIF Universal
+/// Resolver, e.g. `https://dev.uniresolver.io`. Mainly meant as a fallback for methods which
+/// have no resolver available in-process.
+pub struct UniversalResolver<C>
+where
+    C: Connect + Send + Sync + Clone + 'static,
+{
+    client: Client<C>,
+    endpoint: String,
+    timeout: Duration,
+    max_response_size: usize,
+}
+
+impl UniversalResolver<HttpConnector> {
+    pub fn http(endpoint: &str) -> Result<UniversalResolver<HttpConnector>, DidUniversalError> {
+        Ok(UniversalResolver {
+            client: Client::builder().build::<_, Body>(HttpConnector::new()),
+            endpoint: parse_endpoint(endpoint)?,
+            timeout: DEFAULT_TIMEOUT,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+        })
+    }
+}
+
+impl UniversalResolver<HttpsConnector<HttpConnector>> {
+    pub fn https(
+        endpoint: &str,
+    ) -> Result<UniversalResolver<HttpsConnector<HttpConnector>>, DidUniversalError> {
+        Ok(UniversalResolver {
+            client: Client::builder().build::<_, Body>(HttpsConnector::new()),
+            endpoint: parse_endpoint(endpoint)?,
+            timeout: DEFAULT_TIMEOUT,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+        })
+    }
+}
+
+fn parse_endpoint(endpoint: &str) -> Result<String, DidUniversalError> {
+    endpoint.parse::<Uri>()?;
+    Ok(endpoint.trim_end_matches('/').to_string())
+}
+
+async fn read_body(mut body: Body, max_size: usize) -> Result<Vec<u8>, DidUniversalError> {
+    if body.size_hint().lower() > max_size as u64 {
+        return Err(DidUniversalError::ResponseTooLarge(max_size));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > max_size {
+            return Err(DidUniversalError::ResponseTooLarge(max_size));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolutionResult {
+    did_document: Option<DidDocument<HashMap<String, Value>>>,
+    #[serde(default)]
+    did_resolution_metadata: ResolutionResultMetadata,
+    #[serde(default)]
+    did_document_metadata: DidDocumentMetadata,
+}
+
+// Universal resolver drivers may report errors which are not defined by DID Core, so the error
+// is kept as a plain string rather than parsed into `DidResolutionError`
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ResolutionResultMetadata {
+    content_type: Option<String>,
+    error: Option<String>,
+    error_message: Option<String>,
+}
+
+impl<C> UniversalResolver<C>
+where
+    C: Connect + Send + Sync + Clone + 'static,
+{
+    /// Sets how long to wait for the universal resolver to respond, 10 seconds by default
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the maximal size of the universal resolver response in bytes, 1 MiB by default
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    async fn fetch_resolution_result(
+        &self,
+        did: &Did,
+    ) -> Result<ResolutionResult, DidUniversalError> {
+        let url: Uri = format!(
+            "{}/1.0/identifiers/{}",
+            self.endpoint,
+            utf8_percent_encode(did.did(), PATH_SEGMENT)
+        )
+        .parse()?;
+        let req = Request::get(url)
+            .header(ACCEPT, ACCEPT_RESOLUTION_RESULT)
+            .body(Body::empty())?;
+        let (status, body) = tokio::time::timeout(self.timeout, async {
+            let res = self.client.request(req).await?;
+            let status = res.status();
+            let body = read_body(res.into_body(), self.max_response_size).await?;
+            Ok::<_, DidUniversalError>((status, body))
+        })
+        .await
+        .map_err(|_| DidUniversalError::Timeout(self.timeout))??;
+
+        // Failed resolutions are reported with an error status code, but usually still carry
+        // a resolution result describing the error
+        match serde_json::from_slice::<ResolutionResult>(&body) {
+            Ok(result) => {
+                if let Some(error) = result.did_resolution_metadata.error {
+                    return Err(DidUniversalError::ResolutionError(
+                        match result.did_resolution_metadata.error_message {
+                            Some(message) => format!("{}: {}", error, message),
+                            None => error,
+                        },
+                    ));
+                }
+                if !status.is_success() {
+                    return Err(DidUniversalError::NonSuccessResponse(status));
+                }
+                Ok(result)
+            }
+            Err(_) if !status.is_success() => Err(DidUniversalError::NonSuccessResponse(status)),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl<C> DidResolvable for UniversalResolver<C>
+where
+    C: Connect + Send + Sync + Clone + 'static,
+{
+    type ExtraFieldsService = HashMap<String, Value>;
+    type ExtraFieldsOptions = ();
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        _options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
+    ) -> Result<DidResolutionOutput<Self::ExtraFieldsService>, GenericError> {
+        if did.method().is_none() {
+            return Err(Box::new(DidUniversalError::InvalidDid(
+                "Attempted to resolve unqualified did".to_string(),
+            )));
+        }
+
+        let result = self.fetch_resolution_result(did).await?;
+        let did_document = result.did_document.ok_or_else(|| {
+            DidUniversalError::InvalidResolutionResult(format!(
+                "Resolution result for {} contains no DID document",
+                did
+            ))
+        })?;
+        if did_document.id().did() != did.did() {
+            return Err(Box::new(DidUniversalError::InvalidResolutionResult(
+                format!(
+                    "Resolution result for {} contains the DID document of {}",
+                    did,
+                    did_document.id()
+                ),
+            )));
+        }
+
+        let mut did_resolution_metadata = DidResolutionMetadata::builder();
+        if let Some(content_type) = result.did_resolution_metadata.content_type {
+            did_resolution_metadata = did_resolution_metadata.content_type(content_type);
+        }
+
+        Ok(DidResolutionOutput::builder(did_document)
+            .did_resolution_metadata(did_resolution_metadata.build())
+            .did_document_metadata(result.did_document_metadata)
+            .build())
+    }
+}
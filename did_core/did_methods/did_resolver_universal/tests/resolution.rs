@@ -0,0 +1,227 @@
+use std::{convert::Infallible, net::SocketAddr, time::Duration};
+
+use did_resolver::{
+    did_parser::Did,
+    traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+};
+use did_resolver_universal::{error::DidUniversalError, resolver::UniversalResolver};
+use hyper::{
+    header::ACCEPT,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+
+const RESOLUTION_RESULT: &str = r#"
+{
+  "didDocument": {
+    "@context": "https://www.w3.org/ns/did/v1",
+    "id": "did:example:123456789abcdefghi",
+    "verificationMethod": [
+      {
+        "id": "did:example:123456789abcdefghi#keys-1",
+        "type": "Ed25519VerificationKey2018",
+        "controller": "did:example:123456789abcdefghi",
+        "publicKeyBase58": "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV"
+      }
+    ],
+    "authentication": ["did:example:123456789abcdefghi#keys-1"]
+  },
+  "didResolutionMetadata": {
+    "contentType": "application/did+ld+json",
+    "pattern": "^(did:example:.+)$",
+    "driverUrl": "http://driver-example:8080/1.0/identifiers/"
+  },
+  "didDocumentMetadata": {
+    "versionId": "2",
+    "updated": "2023-03-01T12:00:00Z"
+  }
+}"#;
+
+const NOT_FOUND_RESULT: &str = r#"
+{
+  "didDocument": null,
+  "didResolutionMetadata": {
+    "error": "notFound",
+    "errorMessage": "DID not found on the ledger"
+  },
+  "didDocumentMetadata": {}
+}"#;
+
+const DID_WEB_RESOLUTION_RESULT: &str = r#"
+{
+  "didDocument": {
+    "@context": "https://www.w3.org/ns/did/v1",
+    "id": "did:web:localhost%3A8080"
+  },
+  "didResolutionMetadata": {},
+  "didDocumentMetadata": {}
+}"#;
+
+async fn mock_server_handler(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let requests_resolution_result = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("did-resolution"));
+    let response = match req.uri().path() {
+        "/1.0/identifiers/did:example:123456789abcdefghi" if requests_resolution_result => {
+            Response::new(Body::from(RESOLUTION_RESULT))
+        }
+        // The DID is percent encoded within the path
+        "/1.0/identifiers/did:web:localhost%253A8080" => {
+            Response::new(Body::from(DID_WEB_RESOLUTION_RESULT))
+        }
+        "/1.0/identifiers/did:example:impostor" => Response::new(Body::from(RESOLUTION_RESULT)),
+        "/1.0/identifiers/did:example:slow" => {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Response::new(Body::from(RESOLUTION_RESULT))
+        }
+        "/1.0/identifiers/did:example:huge" => {
+            Response::new(Body::from(" ".repeat(2 * 1024 * 1024) + RESOLUTION_RESULT))
+        }
+        // Streamed without a content length
+        "/1.0/identifiers/did:example:huge_stream" => {
+            let (mut sender, body) = Body::channel();
+            tokio::spawn(async move {
+                for _ in 0..64 {
+                    if sender
+                        .send_data(" ".repeat(64 * 1024).into())
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+            Response::new(body)
+        }
+        "/1.0/identifiers/did:example:unknown" => Response::builder()
+            .status(404)
+            .body(Body::from(NOT_FOUND_RESULT))
+            .unwrap(),
+        _ => Response::builder()
+            .status(501)
+            .body(Body::from("Not Implemented"))
+            .unwrap(),
+    };
+
+    Ok(response)
+}
+
+async fn create_mock_server(port: u16) -> String {
+    let make_svc =
+        make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(mock_server_handler)) });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::bind(&addr).serve(make_svc);
+
+    tokio::spawn(async move {
+        server.await.unwrap();
+    });
+
+    format!("http://localhost:{}/", port)
+}
+
+#[tokio::test]
+async fn test_universal_resolver() {
+    let endpoint = create_mock_server(3010).await;
+    let resolver = UniversalResolver::http(&endpoint).unwrap();
+
+    let did = Did::parse("did:example:123456789abcdefghi".to_string()).unwrap();
+    let output = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(output.did_document().id(), &did);
+    assert_eq!(output.did_document().verification_method().len(), 1);
+    assert_eq!(output.did_document().authentication().len(), 1);
+    assert_eq!(
+        output.did_resolution_metadata().content_type(),
+        Some(&"application/did+ld+json".to_string())
+    );
+    assert_eq!(
+        output.did_document_metadata().version_id(),
+        Some(&"2".to_string())
+    );
+
+    let did = Did::parse("did:web:localhost%3A8080".to_string()).unwrap();
+    let output = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().id(), &did);
+}
+
+#[tokio::test]
+async fn test_universal_resolver_errors() {
+    let endpoint = create_mock_server(3011).await;
+    let resolver = UniversalResolver::http(&endpoint).unwrap();
+
+    let did = Did::parse("did:example:unknown".to_string()).unwrap();
+    let err = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DidUniversalError>(),
+        Some(DidUniversalError::ResolutionError(error)) if error.starts_with("notFound")
+    ));
+
+    let did = Did::parse("did:unsupported:123".to_string()).unwrap();
+    let err = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DidUniversalError>(),
+        Some(DidUniversalError::NonSuccessResponse(status)) if status.as_u16() == 501
+    ));
+
+    // The returned DID document belongs to another DID
+    let did = Did::parse("did:example:impostor".to_string()).unwrap();
+    let err = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DidUniversalError>(),
+        Some(DidUniversalError::InvalidResolutionResult(_))
+    ));
+
+    let resolver = resolver.with_timeout(Duration::from_millis(100));
+    let did = Did::parse("did:example:slow".to_string()).unwrap();
+    let err = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DidUniversalError>(),
+        Some(DidUniversalError::Timeout(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_universal_resolver_limits_response_size() {
+    let endpoint = create_mock_server(3012).await;
+    let resolver = UniversalResolver::http(&endpoint).unwrap();
+
+    for did in ["did:example:huge", "did:example:huge_stream"] {
+        let did = Did::parse(did.to_string()).unwrap();
+        let err = resolver
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DidUniversalError>(),
+            Some(DidUniversalError::ResponseTooLarge(_))
+        ));
+    }
+
+    let resolver = resolver.with_max_response_size(RESOLUTION_RESULT.len());
+    let did = Did::parse("did:example:123456789abcdefghi".to_string()).unwrap();
+    resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+}
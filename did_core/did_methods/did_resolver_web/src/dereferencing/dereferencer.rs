@@ -0,0 +1,38 @@
+use std::io::Cursor;
+
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::DidUrl,
+    error::GenericError,
+    traits::{
+        dereferenceable::{
+            dereferencing_options::DidDereferencingOptions,
+            dereferencing_output::DidDereferencingOutput, utils::dereference_did_document,
+            DidDereferenceable,
+        },
+        resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+    },
+};
+use hyper::client::connect::Connect;
+
+use crate::resolution::resolver::DidWebResolver;
+
+#[async_trait]
+impl<C> DidDereferenceable for DidWebResolver<C>
+where
+    C: Connect + Send + Sync + Clone + 'static,
+{
+    type Output = Cursor<Vec<u8>>;
+
+    async fn dereference(
+        &self,
+        did_url: &DidUrl,
+        _options: &DidDereferencingOptions,
+    ) -> Result<DidDereferencingOutput<Self::Output>, GenericError> {
+        let resolution_output = self
+            .resolve(&did_url.try_into()?, &DidResolutionOptions::default())
+            .await?;
+
+        dereference_did_document(&resolution_output, did_url).map_err(|err| err.into())
+    }
+}
@@ -1,6 +1,8 @@
 pub mod parsing;
 
+use did_resolver::{did_doc::error::DidDocumentBuilderError, did_parser::ParseError};
 use hyper::StatusCode;
+use public_key::{KeyType, PublicKeyError};
 use thiserror::Error;
 
 use self::parsing::ParsingErrorSource;
@@ -20,6 +22,14 @@ pub enum DidWebError {
     HttpError(#[from] hyper::Error),
     #[error("Non-success server response: {0}")]
     NonSuccessResponse(StatusCode),
+    #[error("Unsupported key type: {0:?}")]
+    UnsupportedKeyType(KeyType),
+    #[error("Public key error: {0}")]
+    PublicKeyError(#[from] PublicKeyError),
+    #[error("DID parsing error: {0}")]
+    DidParserError(#[from] ParseError),
+    #[error("DID document error: {0}")]
+    DidDocumentError(#[from] DidDocumentBuilderError),
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
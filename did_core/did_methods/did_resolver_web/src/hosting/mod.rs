@@ -0,0 +1,243 @@
+#[cfg(feature = "axum")]
+pub mod router;
+
+use std::time::{Duration, SystemTime};
+
+use did_doc_sov::extra_fields::{didcommv1::ExtraFieldsDidCommV1, ExtraFieldsSov, KeyKind};
+use did_resolver::{
+    did_doc::schema::{
+        did_doc::{DidDocument, DidDocumentBuilder},
+        service::Service,
+        types::{uri::Uri, url::Url},
+        verification_method::{VerificationMethod, VerificationMethodType},
+    },
+    did_parser::{Did, DidUrl},
+};
+use public_key::{Key, KeyType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{error::DidWebError, location::did_document_path};
+
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct RetiredKey {
+    key: Key,
+    retired_until: SystemTime,
+}
+
+/// Keys of a [`DidWebHost`], to be persisted after every rotation and restored with
+/// [`DidWebHost::restore_state`] on restart, so that retired keys outlive the process for the
+/// rest of their grace period.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DidWebHostState {
+    current_key: Key,
+    retired_keys: Vec<RetiredKey>,
+}
+
+#[derive(Clone, Debug)]
+struct DidCommService {
+    service_endpoint: Url,
+    routing_keys: Vec<KeyKind>,
+}
+
+/// Produces the DID document of a did:web DID controlled by an ed25519 key, to be served at
+/// the URL given by [`crate::location::did_document_url`].
+///
+/// The document lists the ed25519 key for authentication and assertion, the X25519 key derived
+/// from it for key agreement and optionally a DIDComm service. When the key is rotated, the
+/// previous keys stay in the document for a grace period, so that messages and signatures
+/// created with them can still be processed.
+#[derive(Clone, Debug)]
+pub struct DidWebHost {
+    did: Did,
+    current_key: Key,
+    retired_keys: Vec<RetiredKey>,
+    grace_period: Duration,
+    service: Option<DidCommService>,
+}
+
+impl DidWebHost {
+    pub fn new(did: Did, key: Key) -> Result<Self, DidWebError> {
+        did_document_path(&did)?;
+        validate_key(&key)?;
+        Ok(Self {
+            did,
+            current_key: key,
+            retired_keys: Vec::new(),
+            grace_period: DEFAULT_GRACE_PERIOD,
+            service: None,
+        })
+    }
+
+    /// Same as [`DidWebHost::new`], taking the base58 encoded verkey as stored in the wallet.
+    pub fn from_verkey(did: Did, verkey: &str) -> Result<Self, DidWebError> {
+        Self::new(did, Key::from_base58(verkey, KeyType::Ed25519)?)
+    }
+
+    pub fn set_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    pub fn set_didcomm_service(
+        mut self,
+        service_endpoint: Url,
+        routing_keys: Vec<KeyKind>,
+    ) -> Self {
+        self.service = Some(DidCommService {
+            service_endpoint,
+            routing_keys,
+        });
+        self
+    }
+
+    pub fn did(&self) -> &Did {
+        &self.did
+    }
+
+    pub fn current_key(&self) -> &Key {
+        &self.current_key
+    }
+
+    /// Makes `new_key` the current key. The replaced key stays in the document until the grace
+    /// period elapses.
+    pub fn rotate_key(&mut self, new_key: Key) -> Result<(), DidWebError> {
+        validate_key(&new_key)?;
+        if new_key == self.current_key {
+            return Ok(());
+        }
+        let now = SystemTime::now();
+        self.retired_keys
+            .retain(|retired| retired.retired_until > now && retired.key != new_key);
+        let retired_key = std::mem::replace(&mut self.current_key, new_key);
+        self.retired_keys.push(RetiredKey {
+            key: retired_key,
+            retired_until: now + self.grace_period,
+        });
+        Ok(())
+    }
+
+    pub fn state(&self) -> DidWebHostState {
+        DidWebHostState {
+            current_key: self.current_key.clone(),
+            retired_keys: self.retired_keys.clone(),
+        }
+    }
+
+    /// Restores the retired keys of a previous run whose grace period has not elapsed yet. If
+    /// the current key of that run differs from ours, it is retired as by [`Self::rotate_key`].
+    pub fn restore_state(&mut self, state: DidWebHostState) -> Result<(), DidWebError> {
+        validate_key(&state.current_key)?;
+        for retired in &state.retired_keys {
+            validate_key(&retired.key)?;
+        }
+        let now = SystemTime::now();
+        let current_key = std::mem::replace(&mut self.current_key, state.current_key);
+        self.retired_keys = state
+            .retired_keys
+            .into_iter()
+            .filter(|retired| retired.retired_until > now && retired.key != current_key)
+            .collect();
+        self.rotate_key(current_key)
+    }
+
+    pub fn did_document(&self) -> Result<DidDocument<ExtraFieldsSov>, DidWebError> {
+        let now = SystemTime::now();
+        let keys = std::iter::once(&self.current_key).chain(
+            self.retired_keys
+                .iter()
+                .filter(|retired| retired.retired_until > now)
+                .map(|retired| &retired.key),
+        );
+
+        let mut builder = DidDocument::builder(self.did.to_owned());
+        let mut current_key_agreement_id = None;
+        for key in keys {
+            let (new_builder, key_agreement_id) = self.add_key(builder, key)?;
+            builder = new_builder;
+            current_key_agreement_id.get_or_insert(key_agreement_id);
+        }
+
+        if let (Some(service), Some(key_agreement_id)) = (&self.service, current_key_agreement_id) {
+            builder = builder.add_service(self.build_service(service, key_agreement_id)?);
+        }
+
+        Ok(builder
+            .add_extra_field(
+                "@context".to_string(),
+                json!([
+                    "https://www.w3.org/ns/did/v1",
+                    "https://w3id.org/security/suites/ed25519-2020/v1",
+                    "https://w3id.org/security/suites/x25519-2020/v1"
+                ]),
+            )
+            .build())
+    }
+
+    fn add_key(
+        &self,
+        builder: DidDocumentBuilder<ExtraFieldsSov>,
+        key: &Key,
+    ) -> Result<(DidDocumentBuilder<ExtraFieldsSov>, DidUrl), DidWebError> {
+        let signing_vm = self
+            .build_verification_method(key, VerificationMethodType::Ed25519VerificationKey2020)?;
+        let key_agreement_vm = self.build_verification_method(
+            &key.ed25519_to_x25519()?,
+            VerificationMethodType::X25519KeyAgreementKey2020,
+        )?;
+        let signing_id = signing_vm.id().to_owned();
+        let key_agreement_id = key_agreement_vm.id().to_owned();
+
+        let builder = builder
+            .add_verification_method(signing_vm)
+            .add_verification_method(key_agreement_vm)
+            .add_authentication_reference(signing_id.clone())
+            .add_assertion_method_reference(signing_id)
+            .add_key_agreement_reference(key_agreement_id.clone());
+        Ok((builder, key_agreement_id))
+    }
+
+    fn build_verification_method(
+        &self,
+        key: &Key,
+        vm_type: VerificationMethodType,
+    ) -> Result<VerificationMethod, DidWebError> {
+        let id = DidUrl::parse(format!("{}#{}", self.did, key.fingerprint()))?;
+        Ok(
+            VerificationMethod::builder(id, self.did.to_owned(), vm_type)
+                .add_public_key_multibase(key.fingerprint())
+                .build(),
+        )
+    }
+
+    // Only the current key is advertised as recipient key, retired keys are kept for
+    // verification and decryption of messages sent before the rotation
+    fn build_service(
+        &self,
+        service: &DidCommService,
+        recipient_key: DidUrl,
+    ) -> Result<Service<ExtraFieldsSov>, DidWebError> {
+        let extra = ExtraFieldsSov::DIDCommV1(
+            ExtraFieldsDidCommV1::builder()
+                .set_recipient_keys(vec![KeyKind::Reference(recipient_key)])
+                .set_routing_keys(service.routing_keys.clone())
+                .build(),
+        );
+        Ok(Service::builder(
+            Uri::new(&format!("{}#did-communication", self.did))?,
+            service.service_endpoint.clone(),
+            extra,
+        )
+        .add_service_type("did-communication".to_string())?
+        .build())
+    }
+}
+
+fn validate_key(key: &Key) -> Result<(), DidWebError> {
+    match key.key_type() {
+        KeyType::Ed25519 => Ok(()),
+        key_type => Err(DidWebError::UnsupportedKeyType(*key_type)),
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    extract::State,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use crate::{error::DidWebError, hosting::DidWebHost, location::did_document_path};
+
+pub type SharedDidWebHost = Arc<RwLock<DidWebHost>>;
+
+/// Router serving the DID document of `host` at the did:web path of its DID, to be merged into
+/// the router of the server the DID's domain points to. Keys rotated through the shared host
+/// are reflected in the served document right away.
+pub fn did_web_router<S>(host: SharedDidWebHost) -> Result<Router<S>, DidWebError>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let path = did_document_path(host.read().unwrap().did())?;
+    Ok(Router::new()
+        .route(&path, get(serve_did_document))
+        .with_state(host))
+}
+
+async fn serve_did_document(State(host): State<SharedDidWebHost>) -> Response {
+    let did_document = host
+        .read()
+        .unwrap()
+        .did_document()
+        .and_then(|did_document| serde_json::to_string(&did_document).map_err(Into::into));
+    match did_document {
+        Ok(did_document) => {
+            ([(CONTENT_TYPE, "application/did+json")], did_document).into_response()
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
@@ -1,2 +1,5 @@
+pub mod dereferencing;
 pub mod error;
+pub mod hosting;
+pub mod location;
 pub mod resolution;
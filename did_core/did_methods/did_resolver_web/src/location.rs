@@ -0,0 +1,47 @@
+use did_resolver::did_parser::Did;
+use hyper::{
+    http::uri::{self, Scheme},
+    Uri,
+};
+
+use crate::error::DidWebError;
+
+/// Path of the DID document of a did:web DID, i.e. `/.well-known/did.json` for a bare domain or
+/// `/{path}/did.json` for a path based DID, following
+/// https://w3c-ccg.github.io/did-method-web/#read-resolve
+pub fn did_document_path(did: &Did) -> Result<String, DidWebError> {
+    let (_, path_parts) = split_did_web(did)?;
+    if path_parts.is_empty() {
+        Ok("/.well-known/did.json".to_string())
+    } else {
+        Ok(format!("/{}/did.json", path_parts.join("/")))
+    }
+}
+
+/// URL the DID document of a did:web DID is served at.
+pub fn did_document_url(did: &Did, scheme: Scheme) -> Result<Uri, DidWebError> {
+    let (domain, _) = split_did_web(did)?;
+    let path_and_query = did_document_path(did)?;
+    uri::Builder::new()
+        .scheme(scheme)
+        .authority(domain.as_str())
+        .path_and_query(path_and_query.as_str())
+        .build()
+        .map_err(|err| DidWebError::InvalidDid(format!("{}: {}", did, err)))
+}
+
+fn split_did_web(did: &Did) -> Result<(String, Vec<&str>), DidWebError> {
+    let method = did.method().ok_or_else(|| {
+        DidWebError::InvalidDid("Attempted to resolve unqualified did".to_string())
+    })?;
+    if method != "web" {
+        return Err(DidWebError::MethodNotSupported(method.to_string()));
+    }
+
+    let mut did_parts = did.id().split(':');
+    let domain = match did_parts.next() {
+        Some(domain) if !domain.is_empty() => domain.replace("%3A", ":"),
+        _ => return Err(DidWebError::InvalidDid(did.id().to_string())),
+    };
+    Ok((domain, did_parts.collect()))
+}
@@ -10,12 +10,12 @@ use did_resolver::{
 };
 use hyper::{
     client::{connect::Connect, HttpConnector},
-    http::uri::{self, Scheme},
+    http::uri::Scheme,
     Body, Client, Uri,
 };
 use hyper_tls::HttpsConnector;
 
-use crate::error::DidWebError;
+use crate::{error::DidWebError, location::did_document_url};
 
 pub struct DidWebResolver<C>
 where
@@ -73,14 +73,7 @@ where
         did: &Did,
         options: &DidResolutionOptions<Self::ExtraFieldsOptions>,
     ) -> Result<DidResolutionOutput<()>, GenericError> {
-        let method = did.method().ok_or_else(|| {
-            DidWebError::InvalidDid("Attempted to resolve unqualified did".to_string())
-        })?;
-        if method != "web" {
-            return Err(Box::new(DidWebError::MethodNotSupported(
-                method.to_string(),
-            )));
-        }
+        let url = did_document_url(did, self.scheme.clone())?;
 
         if let Some(accept) = options.accept() {
             if accept != &MediaType::DidJson {
@@ -90,27 +83,6 @@ where
             }
         }
 
-        let did_parts: Vec<&str> = did.id().split(':').collect();
-
-        if did_parts.is_empty() {
-            return Err(Box::new(DidWebError::InvalidDid(did.id().to_string())));
-        }
-
-        let domain = did_parts[0].replace("%3A", ":");
-
-        let path_parts = &did_parts[1..];
-        let path_and_query = if path_parts.is_empty() {
-            "/.well-known/did.json".to_string()
-        } else {
-            let path = path_parts.join("/");
-            format!("/{}/did.json", path)
-        };
-        let url = uri::Builder::new()
-            .scheme(self.scheme.clone())
-            .authority(domain.as_str())
-            .path_and_query(path_and_query.as_str())
-            .build()?;
-
         let did_document = serde_json::from_str(&self.fetch_did_document(url).await?)?;
 
         let did_resolution_output = DidResolutionOutput::builder(did_document)
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use did_doc_sov::extra_fields::{ExtraFieldsSov, KeyKind};
+use did_resolver::{
+    did_doc::schema::{did_doc::DidDocument, types::url::Url},
+    did_parser::Did,
+};
+use did_resolver_web::{
+    error::DidWebError,
+    hosting::{DidWebHost, DidWebHostState},
+    location::{did_document_path, did_document_url},
+};
+use hyper::http::uri::Scheme;
+use public_key::Key;
+
+const ED25519_FINGERPRINT_1: &str = "z6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V";
+const ED25519_FINGERPRINT_2: &str = "z6MkgoLTnTypo3tDRwCkZXSccTPHRLhF4ZnjhueYAFpEX6vg";
+const ED25519_FINGERPRINT_3: &str = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+fn key(fingerprint: &str) -> Key {
+    Key::from_fingerprint(fingerprint).unwrap()
+}
+
+fn verification_method_ids(did_document: &DidDocument<ExtraFieldsSov>) -> Vec<String> {
+    did_document
+        .verification_method()
+        .iter()
+        .map(|vm| vm.id().to_string())
+        .collect()
+}
+
+#[test]
+fn test_did_document_url() {
+    let url = |did: &str| {
+        did_document_url(&Did::parse(did.to_string()).unwrap(), Scheme::HTTPS)
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(
+        url("did:web:w3c-ccg.github.io"),
+        "https://w3c-ccg.github.io/.well-known/did.json"
+    );
+    assert_eq!(
+        url("did:web:w3c-ccg.github.io:user:alice"),
+        "https://w3c-ccg.github.io/user/alice/did.json"
+    );
+    assert_eq!(
+        url("did:web:example.com%3A3000:user:alice"),
+        "https://example.com:3000/user/alice/did.json"
+    );
+
+    let did = Did::parse(format!("did:key:{}", ED25519_FINGERPRINT_1)).unwrap();
+    assert!(matches!(
+        did_document_path(&did),
+        Err(DidWebError::MethodNotSupported(method)) if method == "key"
+    ));
+}
+
+#[test]
+fn test_did_web_host_did_document() {
+    let did = Did::parse("did:web:example.com".to_string()).unwrap();
+    let host = DidWebHost::new(did.clone(), key(ED25519_FINGERPRINT_1))
+        .unwrap()
+        .set_didcomm_service(Url::new("https://example.com/didcomm").unwrap(), vec![]);
+
+    let did_document = host.did_document().unwrap();
+    let key_agreement_key = key(ED25519_FINGERPRINT_1).ed25519_to_x25519().unwrap();
+    assert_eq!(did_document.id(), &did);
+    assert_eq!(
+        verification_method_ids(&did_document),
+        vec![
+            format!("{}#{}", did, ED25519_FINGERPRINT_1),
+            format!("{}#{}", did, key_agreement_key.fingerprint())
+        ]
+    );
+    assert_eq!(did_document.authentication().len(), 1);
+    assert_eq!(did_document.assertion_method().len(), 1);
+    assert_eq!(did_document.key_agreement().len(), 1);
+
+    let service = &did_document.service()[0];
+    assert_eq!(
+        service.service_endpoint().to_string(),
+        "https://example.com/didcomm"
+    );
+    assert_eq!(
+        service.extra().recipient_keys().unwrap()[0].to_string(),
+        format!("{}#{}", did, key_agreement_key.fingerprint())
+    );
+    assert!(matches!(
+        service.extra().first_recipient_key().unwrap(),
+        KeyKind::Reference(_)
+    ));
+}
+
+#[test]
+fn test_did_web_host_rotate_key() {
+    let did = Did::parse("did:web:example.com:user:alice".to_string()).unwrap();
+    let mut host = DidWebHost::new(did.clone(), key(ED25519_FINGERPRINT_1)).unwrap();
+
+    host.rotate_key(key(ED25519_FINGERPRINT_2)).unwrap();
+    assert_eq!(host.current_key(), &key(ED25519_FINGERPRINT_2));
+
+    // the previous key stays in the document during the grace period, after the current key
+    let did_document = host.did_document().unwrap();
+    let ids = verification_method_ids(&did_document);
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids[0], format!("{}#{}", did, ED25519_FINGERPRINT_2));
+    assert_eq!(ids[2], format!("{}#{}", did, ED25519_FINGERPRINT_1));
+    assert_eq!(did_document.authentication().len(), 2);
+
+    let mut host = host.set_grace_period(Duration::ZERO);
+    host.rotate_key(key(ED25519_FINGERPRINT_1)).unwrap();
+    let did_document = host.did_document().unwrap();
+    assert_eq!(
+        verification_method_ids(&did_document)[0],
+        format!("{}#{}", did, ED25519_FINGERPRINT_1)
+    );
+    // the reinstated key is not listed twice and the key retired without a grace period is gone
+    assert_eq!(did_document.verification_method().len(), 2);
+}
+
+#[test]
+fn test_did_web_host_restore_state() {
+    let did = Did::parse("did:web:example.com".to_string()).unwrap();
+    let mut host = DidWebHost::new(did.clone(), key(ED25519_FINGERPRINT_1)).unwrap();
+    host.rotate_key(key(ED25519_FINGERPRINT_2)).unwrap();
+    let state: DidWebHostState =
+        serde_json::from_str(&serde_json::to_string(&host.state()).unwrap()).unwrap();
+
+    // restarted with the key in use before the restart
+    let mut restarted = DidWebHost::new(did.clone(), key(ED25519_FINGERPRINT_2)).unwrap();
+    restarted.restore_state(state.clone()).unwrap();
+    assert_eq!(restarted.state(), state);
+
+    // restarted with a new key, the key in use before the restart is retired as well
+    let new_key = key(ED25519_FINGERPRINT_3);
+    let mut restarted = DidWebHost::new(did.clone(), new_key.clone()).unwrap();
+    restarted.restore_state(state).unwrap();
+    assert_eq!(restarted.current_key(), &new_key);
+    let ids = verification_method_ids(&restarted.did_document().unwrap());
+    assert_eq!(ids.len(), 6);
+    assert_eq!(ids[0], format!("{}#{}", did, new_key.fingerprint()));
+}
+
+#[test]
+fn test_did_web_host_rejects_invalid_input() {
+    let did = Did::parse("did:web:example.com".to_string()).unwrap();
+    let x25519_key = key(ED25519_FINGERPRINT_1).ed25519_to_x25519().unwrap();
+    assert!(matches!(
+        DidWebHost::new(did, x25519_key),
+        Err(DidWebError::UnsupportedKeyType(_))
+    ));
+
+    let did = Did::parse(format!("did:key:{}", ED25519_FINGERPRINT_1)).unwrap();
+    assert!(matches!(
+        DidWebHost::new(did, key(ED25519_FINGERPRINT_1)),
+        Err(DidWebError::MethodNotSupported(_))
+    ));
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn test_did_web_router() {
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, RwLock},
+    };
+
+    use did_resolver::traits::resolvable::{
+        resolution_options::DidResolutionOptions, DidResolvable,
+    };
+    use did_resolver_web::{hosting::router::did_web_router, resolution::resolver::DidWebResolver};
+
+    let port = 3002;
+    let did = Did::parse(format!("did:web:localhost%3A{}:user:alice", port)).unwrap();
+    let host = Arc::new(RwLock::new(
+        DidWebHost::new(did.clone(), key(ED25519_FINGERPRINT_1)).unwrap(),
+    ));
+    let router = did_web_router::<()>(host.clone()).unwrap();
+    let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], port)))
+        .serve(router.into_make_service());
+    tokio::spawn(async move {
+        server.await.unwrap();
+    });
+
+    let resolver = DidWebResolver::http();
+    let output = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().id(), &did);
+    assert_eq!(output.did_document().verification_method().len(), 2);
+
+    host.write()
+        .unwrap()
+        .rotate_key(key(ED25519_FINGERPRINT_2))
+        .unwrap();
+    let output = resolver
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().verification_method().len(), 4);
+}
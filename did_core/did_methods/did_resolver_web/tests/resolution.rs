@@ -1,9 +1,15 @@
-use std::{convert::Infallible, net::SocketAddr};
+use std::{convert::Infallible, io::Read, net::SocketAddr};
 
 use did_resolver::{
     did_doc::schema::did_doc::DidDocument,
-    did_parser::Did,
-    traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+    did_parser::{Did, DidUrl},
+    traits::{
+        dereferenceable::{
+            dereferencing_error::DidDereferencingError,
+            dereferencing_options::DidDereferencingOptions, DidDereferenceable,
+        },
+        resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+    },
 };
 use did_resolver_web::resolution::resolver::DidWebResolver;
 use hyper::{
@@ -66,9 +72,35 @@ const DID_DOCUMENT: &str = r#"
   ]
 }"#;
 
+// Document listing its verification methods with ids relative to the DID it was resolved for
+const DID_DOCUMENT_RELATIVE_IDS: &str = r##"
+{
+  "@context": [
+    "https://www.w3.org/ns/did/v1",
+    "https://w3id.org/security/suites/jws-2020/v1"
+  ],
+  "id": "did:web:localhost%3A3001:user:bob",
+  "verificationMethod": [
+    {
+      "id": "#key-1",
+      "type": "JsonWebKey2020",
+      "controller": "did:web:localhost%3A3001:user:bob",
+      "publicKeyJwk": {
+        "kty": "OKP",
+        "crv": "X25519",
+        "x": "9GXjPGGvmRq9F6Ng5dQQ_s31mfhxrcNZxRGONrmH30k"
+      }
+    }
+  ],
+  "keyAgreement": [
+    "#key-1"
+  ]
+}"##;
+
 async fn mock_server_handler(req: Request<Body>) -> Result<Response<Body>, Infallible> {
     let response = match req.uri().path() {
         "/.well-known/did.json" | "/user/alice/did.json" => Response::new(Body::from(DID_DOCUMENT)),
+        "/user/bob/did.json" => Response::new(Body::from(DID_DOCUMENT_RELATIVE_IDS)),
         _ => Response::builder()
             .status(404)
             .body(Body::from("Not Found"))
@@ -127,3 +159,45 @@ async fn test_did_web_resolver() {
     );
     verify_did_document(result_2.did_document());
 }
+
+#[tokio::test]
+async fn test_did_web_dereferencer() {
+    let port = 3001;
+    let host = create_mock_server(port).await;
+
+    let did_web_resolver = DidWebResolver::http();
+
+    let did_url = DidUrl::parse(format!("did:web:{}%3A{}:user:bob#key-1", host, port)).unwrap();
+    let output = assert_ok!(
+        did_web_resolver
+            .dereference(&did_url, &DidDereferencingOptions::default())
+            .await
+    );
+    let mut content = String::new();
+    output
+        .content_stream()
+        .clone()
+        .read_to_string(&mut content)
+        .unwrap();
+    let verification_method: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(verification_method["id"], "#key-1");
+    assert_eq!(verification_method["publicKeyJwk"]["crv"], "X25519");
+
+    // The fragment is unknown, or only known within another DID (did:web:example.com)
+    for did_url in [
+        format!("did:web:{}%3A{}:user:bob#key-9", host, port),
+        format!("did:web:{}%3A{}#key-1", host, port),
+    ] {
+        let did_url = DidUrl::parse(did_url).unwrap();
+        let Err(err) = did_web_resolver
+            .dereference(&did_url, &DidDereferencingOptions::default())
+            .await
+        else {
+            panic!("Expected dereferencing of {} to fail", did_url);
+        };
+        assert_eq!(
+            err.downcast_ref::<DidDereferencingError>(),
+            Some(&DidDereferencingError::NotFound)
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use std::io::Cursor;
+
+use did_doc::schema::{
+    did_doc::DidDocument,
+    service::Service,
+    verification_method::{VerificationMethod, VerificationMethodKind},
+};
+use did_parser::DidUrl;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use url::Url;
+
+use super::{
+    dereferencing_error::DidDereferencingError, dereferencing_metadata::DidDereferencingMetadata,
+    dereferencing_output::DidDereferencingOutput,
+};
+use crate::traits::resolvable::resolution_output::DidResolutionOutput;
+
+const QUERY_SERVICE: &str = "service";
+const QUERY_RELATIVE_REF: &str = "relativeRef";
+const QUERY_VERSION_ID: &str = "versionId";
+const QUERY_VERSION_TIME: &str = "versionTime";
+
+const CONTENT_TYPE_DID_JSON: &str = "application/did+json";
+const CONTENT_TYPE_URI_LIST: &str = "text/uri-list";
+
+/// Dereferences a DID URL against an already resolved DID document, following
+/// https://w3c-ccg.github.io/did-resolution/#dereferencing-algorithm
+///
+/// * `?service=<id>[&relativeRef=<ref>]` dereferences to the service endpoint URL, with the
+///   relative reference resolved against it as per RFC 3986
+/// * `#<fragment>` dereferences to the matching verification method or service
+/// * a plain DID dereferences to the whole DID document
+///
+/// Versioning is not supported: `versionTime` is rejected and `versionId` must match the version
+/// of the resolved document, if any.
+pub fn dereference_did_document<E: Default + Serialize>(
+    resolution_output: &DidResolutionOutput<E>,
+    did_url: &DidUrl,
+) -> Result<DidDereferencingOutput<Cursor<Vec<u8>>>, DidDereferencingError> {
+    let did_document = resolution_output.did_document();
+    let content_metadata = resolution_output.did_document_metadata();
+    let queries = did_url.queries();
+
+    if queries.contains_key(QUERY_VERSION_TIME) {
+        return Err(DidDereferencingError::VersionNotSupported);
+    }
+    if let Some(version_id) = queries.get(QUERY_VERSION_ID) {
+        if content_metadata.version_id() != Some(version_id) {
+            return Err(DidDereferencingError::VersionNotSupported);
+        }
+    }
+    if did_url.path().is_some() {
+        return Err(DidDereferencingError::NotFound);
+    }
+
+    let (content, content_type) = if let Some(service_id) = queries.get(QUERY_SERVICE) {
+        let relative_ref = queries
+            .get(QUERY_RELATIVE_REF)
+            .map(|relative_ref| percent_decode_str(relative_ref).decode_utf8())
+            .transpose()
+            .map_err(|_| DidDereferencingError::InvalidDidUrl)?;
+        let service = did_document
+            .service()
+            .iter()
+            .find(|service| matches_fragment(&service.id().to_string(), did_url, service_id))
+            .ok_or(DidDereferencingError::NotFound)?;
+        let endpoint = service_endpoint_url(service, relative_ref.as_deref())?;
+        (endpoint.into_bytes(), CONTENT_TYPE_URI_LIST)
+    } else if let Some(fragment) = did_url.fragment() {
+        (
+            dereference_fragment(did_document, did_url, fragment)?,
+            CONTENT_TYPE_DID_JSON,
+        )
+    } else {
+        (
+            serde_json::to_vec(did_document).map_err(|_| DidDereferencingError::InvalidDid)?,
+            CONTENT_TYPE_DID_JSON,
+        )
+    };
+
+    let dereferencing_metadata = DidDereferencingMetadata::builder()
+        .content_type(content_type.to_string())
+        .build();
+
+    Ok(DidDereferencingOutput::builder(Cursor::new(content))
+        .content_metadata(content_metadata.clone())
+        .dereferencing_metadata(dereferencing_metadata)
+        .build())
+}
+
+fn dereference_fragment<E: Serialize>(
+    did_document: &DidDocument<E>,
+    did_url: &DidUrl,
+    fragment: &str,
+) -> Result<Vec<u8>, DidDereferencingError> {
+    let id_matcher = |id: &str| matches_fragment(id, did_url, fragment);
+
+    let service = did_document
+        .service()
+        .iter()
+        .find(|service| id_matcher(&service.id().to_string()));
+    let verification_method =
+        verification_methods(did_document).find(|vm| id_matcher(vm.id().did_url()));
+
+    let value = match (service, verification_method) {
+        (Some(service), None) => serde_json::to_vec(service),
+        (None, Some(verification_method)) => serde_json::to_vec(verification_method),
+        (None, None) => return Err(DidDereferencingError::NotFound),
+        (Some(_), Some(_)) => return Err(DidDereferencingError::InvalidDidUrl),
+    };
+    value.map_err(|_| DidDereferencingError::InvalidDid)
+}
+
+// Verification methods may be listed in `verificationMethod` or embedded in a relationship.
+fn verification_methods<E>(
+    did_document: &DidDocument<E>,
+) -> impl Iterator<Item = &VerificationMethod> {
+    let embedded = [
+        did_document.authentication(),
+        did_document.assertion_method(),
+        did_document.key_agreement(),
+        did_document.capability_invocation(),
+        did_document.capability_delegation(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|vm| match vm {
+        VerificationMethodKind::Resolved(vm) => Some(vm),
+        VerificationMethodKind::Resolvable(_) => None,
+    });
+    did_document.verification_method().iter().chain(embedded)
+}
+
+// An id matches the fragment if it is the full DID URL of the fragment within the requested DID,
+// or the relative id `#<fragment>`, which resolves against the requested DID.
+fn matches_fragment(id: &str, did_url: &DidUrl, fragment: &str) -> bool {
+    match id.strip_prefix('#') {
+        Some(relative_fragment) => relative_fragment == fragment,
+        None => did_url
+            .did()
+            .is_some_and(|did| id == format!("{}#{}", did, fragment)),
+    }
+}
+
+fn service_endpoint_url<E>(
+    service: &Service<E>,
+    relative_ref: Option<&str>,
+) -> Result<String, DidDereferencingError> {
+    let endpoint: Url = service.service_endpoint().clone().into();
+    match relative_ref {
+        Some(relative_ref) => endpoint
+            .join(relative_ref)
+            .map(String::from)
+            .map_err(|_| DidDereferencingError::InvalidDidUrl),
+        None => Ok(endpoint.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use did_doc::schema::verification_method::VerificationMethodType;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::shared_types::did_document_metadata::DidDocumentMetadata;
+
+    const DID: &str = "did:example:123456789abcdefghi";
+
+    fn example_resolution_output() -> DidResolutionOutput<()> {
+        let verification_method = VerificationMethod::builder(
+            DidUrl::parse(format!("{}#keys-1", DID)).unwrap(),
+            DID.to_string().try_into().unwrap(),
+            VerificationMethodType::Ed25519VerificationKey2018,
+        )
+        .add_public_key_base58("H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV".to_string())
+        .build();
+        let key_agreement = VerificationMethod::builder(
+            DidUrl::parse(format!("{}#keys-2", DID)).unwrap(),
+            DID.to_string().try_into().unwrap(),
+            VerificationMethodType::X25519KeyAgreementKey2019,
+        )
+        .add_public_key_base58("FxfdY3DCQxVZddKGAtSjZdFW9bCCW7oRwZn1NFJ2Tbg2".to_string())
+        .build();
+        let service = Service::builder(
+            format!("{}#files", DID).parse().unwrap(),
+            "https://example.com/files/".try_into().unwrap(),
+            (),
+        )
+        .add_service_type("LinkedDomains".to_string())
+        .unwrap()
+        .build();
+
+        let did_document = DidDocument::builder(DID.to_string().try_into().unwrap())
+            .add_verification_method(verification_method)
+            .add_key_agreement(key_agreement)
+            .add_service(service)
+            .build();
+        let metadata = DidDocumentMetadata::builder()
+            .version_id("1".to_string())
+            .build();
+        DidResolutionOutput::builder(did_document)
+            .did_document_metadata(metadata)
+            .build()
+    }
+
+    fn dereference(did_url: &str) -> Result<(String, String), DidDereferencingError> {
+        let output = dereference_did_document(
+            &example_resolution_output(),
+            &DidUrl::parse(did_url.to_string()).unwrap(),
+        )?;
+        let content_type = output
+            .dereferencing_metadata()
+            .content_type()
+            .unwrap()
+            .to_owned();
+        let mut content = String::new();
+        output
+            .content_stream()
+            .clone()
+            .read_to_string(&mut content)
+            .unwrap();
+        Ok((content, content_type))
+    }
+
+    #[test]
+    fn test_dereference_verification_method() {
+        let (content, content_type) = dereference(&format!("{}#keys-1", DID)).unwrap();
+        assert_eq!(content_type, CONTENT_TYPE_DID_JSON);
+        assert_eq!(
+            serde_json::from_str::<Value>(&content).unwrap(),
+            json!({
+                "id": format!("{}#keys-1", DID),
+                "type": "Ed25519VerificationKey2018",
+                "controller": DID,
+                "publicKeyBase58": "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV"
+            })
+        );
+    }
+
+    #[test]
+    fn test_dereference_embedded_verification_method() {
+        let (content, _) = dereference(&format!("{}#keys-2", DID)).unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["type"], "X25519KeyAgreementKey2019");
+    }
+
+    #[test]
+    fn test_dereference_service_fragment() {
+        let (content, _) = dereference(&format!("{}#files", DID)).unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["serviceEndpoint"], "https://example.com/files/");
+    }
+
+    #[test]
+    fn test_dereference_service_endpoint() {
+        let (content, content_type) = dereference(&format!("{}?service=files", DID)).unwrap();
+        assert_eq!(content_type, CONTENT_TYPE_URI_LIST);
+        assert_eq!(content, "https://example.com/files/");
+    }
+
+    #[test]
+    fn test_dereference_service_endpoint_with_relative_ref() {
+        let (content, _) =
+            dereference(&format!("{}?service=files&relativeRef=resume.pdf", DID)).unwrap();
+        assert_eq!(content, "https://example.com/files/resume.pdf");
+
+        // An absolute path replaces the path of the endpoint, as per RFC 3986
+        let (content, _) = dereference(&format!(
+            "{}?service=files&relativeRef=%2Fsome%2Fpath%3Fquery%23fragment",
+            DID
+        ))
+        .unwrap();
+        assert_eq!(content, "https://example.com/some/path?query#fragment");
+
+        let (content, _) =
+            dereference(&format!("{}?service=files&relativeRef=..%2Fimages%2F", DID)).unwrap();
+        assert_eq!(content, "https://example.com/images/");
+    }
+
+    #[test]
+    fn test_dereference_relative_ids() {
+        let service = Service::builder(
+            "#files".parse().unwrap(),
+            "https://example.com/files/".try_into().unwrap(),
+            (),
+        )
+        .add_service_type("LinkedDomains".to_string())
+        .unwrap()
+        .build();
+        let verification_method = VerificationMethod::builder(
+            DidUrl::parse("#keys-1".to_string()).unwrap(),
+            DID.to_string().try_into().unwrap(),
+            VerificationMethodType::Ed25519VerificationKey2018,
+        )
+        .add_public_key_base58("H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV".to_string())
+        .build();
+        let did_document = DidDocument::builder(DID.to_string().try_into().unwrap())
+            .add_verification_method(verification_method)
+            .add_service(service)
+            .build();
+        let resolution_output = DidResolutionOutput::<()>::builder(did_document).build();
+
+        for did_url in [format!("{}#keys-1", DID), format!("{}?service=files", DID)] {
+            assert!(
+                dereference_did_document(&resolution_output, &DidUrl::parse(did_url).unwrap())
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dereference_did_document() {
+        let (content, content_type) = dereference(DID).unwrap();
+        assert_eq!(content_type, CONTENT_TYPE_DID_JSON);
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["id"], DID);
+    }
+
+    #[test]
+    fn test_dereference_not_found() {
+        assert_eq!(
+            dereference(&format!("{}#non-existent", DID)),
+            Err(DidDereferencingError::NotFound)
+        );
+        assert_eq!(
+            dereference(&format!("{}?service=non-existent", DID)),
+            Err(DidDereferencingError::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_dereference_ignores_ids_of_other_dids() {
+        // The example document only lists ids within DID
+        for did_url in [
+            "did:example:other#keys-1",
+            "did:example:other#files",
+            "did:example:other?service=files",
+            "did:example:123#keys-1",
+        ] {
+            assert_eq!(dereference(did_url), Err(DidDereferencingError::NotFound));
+        }
+    }
+
+    #[test]
+    fn test_dereference_version_queries() {
+        assert!(dereference(&format!("{}?versionId=1#keys-1", DID)).is_ok());
+        assert_eq!(
+            dereference(&format!("{}?versionId=2#keys-1", DID)),
+            Err(DidDereferencingError::VersionNotSupported)
+        );
+        assert_eq!(
+            dereference(&format!("{}?versionTime=2021-05-10T17:00:00Z", DID)),
+            Err(DidDereferencingError::VersionNotSupported)
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use did_resolver::{
+    did_parser::Did,
+    traits::resolvable::{
+        resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
+    },
+};
+use lru::LruCache;
+
+use crate::GenericMap;
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+struct CacheEntry {
+    output: DidResolutionOutput<GenericMap>,
+    expires_at: Instant,
+}
+
+/// Caches successful resolutions for a configurable time to live, which can be set for all
+/// methods or per method. Nothing is cached unless a TTL applies to the method. Expired entries
+/// are swept on every insert, and the least recently used entry is evicted once the cache is
+/// full.
+pub(crate) struct ResolverCache {
+    default_ttl: Option<Duration>,
+    method_ttls: HashMap<String, Duration>,
+    entries: Mutex<LruCache<String, CacheEntry>>,
+}
+
+impl Default for ResolverCache {
+    fn default() -> Self {
+        Self {
+            default_ttl: None,
+            method_ttls: HashMap::new(),
+            entries: Mutex::new(LruCache::new(NonZeroUsize::new(DEFAULT_CAPACITY).unwrap())),
+        }
+    }
+}
+
+impl ResolverCache {
+    pub fn set_capacity(&mut self, capacity: NonZeroUsize) {
+        self.entries.get_mut().unwrap().resize(capacity);
+    }
+
+    pub fn set_default_ttl(&mut self, ttl: Duration) {
+        self.default_ttl = Some(ttl);
+    }
+
+    pub fn set_method_ttl(&mut self, method: String, ttl: Duration) {
+        self.method_ttls.insert(method, ttl);
+    }
+
+    pub fn get(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions<GenericMap>,
+    ) -> Option<DidResolutionOutput<GenericMap>> {
+        let key = cache_key(did, options);
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.output.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions<GenericMap>,
+        output: &DidResolutionOutput<GenericMap>,
+    ) {
+        if let Some(ttl) = self.entry_ttl(did, output) {
+            let now = Instant::now();
+            let mut entries = self.entries.lock().unwrap();
+            let expired: Vec<String> = entries
+                .iter()
+                .filter(|(_, entry)| entry.expires_at <= now)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in expired {
+                entries.pop(&key);
+            }
+            entries.put(
+                cache_key(did, options),
+                CacheEntry {
+                    output: output.clone(),
+                    expires_at: now + ttl,
+                },
+            );
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    // Deactivated documents are never cached, so that the deactivation is always reported by
+    // the resolver, and an entry never outlives the `nextUpdate` announced in the metadata.
+    fn entry_ttl(&self, did: &Did, output: &DidResolutionOutput<GenericMap>) -> Option<Duration> {
+        let ttl = did
+            .method()
+            .and_then(|method| self.method_ttls.get(method))
+            .or(self.default_ttl.as_ref())
+            .copied()?;
+        let metadata = output.did_document_metadata();
+        if metadata.deactivated() == Some(true) {
+            return None;
+        }
+        let ttl = match metadata.next_update() {
+            Some(next_update) => ttl.min((next_update - Utc::now()).to_std().ok()?),
+            None => ttl,
+        };
+        (!ttl.is_zero()).then_some(ttl)
+    }
+}
+
+// Options may change the resolution output, so they are part of the key. Extra options are
+// sorted to keep the key stable.
+fn cache_key(did: &Did, options: &DidResolutionOptions<GenericMap>) -> String {
+    let extra: BTreeMap<_, _> = options.extra().iter().collect();
+    format!(
+        "{}|{:?}|{}",
+        did.did(),
+        options.accept(),
+        serde_json::to_string(&extra).unwrap_or_default()
+    )
+}
@@ -1,8 +1,10 @@
+mod cache;
 pub mod error;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 use async_trait::async_trait;
+use cache::ResolverCache;
 use did_resolver::{
     did_doc::schema::did_doc::DidDocument,
     did_parser::Did,
@@ -23,6 +25,8 @@ pub type GenericResolver = dyn DidResolvableAdaptorTrait + Send + Sync;
 #[derive(Default)]
 pub struct ResolverRegistry {
     resolvers: HashMap<String, Box<GenericResolver>>,
+    fallback_resolver: Option<Box<GenericResolver>>,
+    cache: ResolverCache,
 }
 
 pub struct DidResolvableAdaptor<T: DidResolvable> {
@@ -98,6 +102,50 @@ impl ResolverRegistry {
         self
     }
 
+    /// Registers a resolver used for DIDs of methods which have no resolver registered, such as
+    /// `did_resolver_universal::resolver::UniversalResolver`.
+    pub fn register_fallback_resolver<T>(mut self, resolver: T) -> Self
+    where
+        T: DidResolvable + 'static + Send + Sync,
+        for<'de> <T as DidResolvable>::ExtraFieldsService:
+            Send + Sync + Serialize + Deserialize<'de>,
+        for<'de> <T as DidResolvable>::ExtraFieldsOptions:
+            Send + Sync + Serialize + Deserialize<'de>,
+    {
+        self.fallback_resolver = Some(Box::new(DidResolvableAdaptor { inner: resolver }));
+        self
+    }
+
+    pub fn unregister_fallback_resolver(mut self) -> Self {
+        self.fallback_resolver = None;
+        self
+    }
+
+    /// Enables caching of successful resolutions of all methods for `ttl`. Entries expire
+    /// earlier if the DID document metadata announces a `nextUpdate`, and deactivated DID
+    /// documents are never cached. A zero `ttl` disables caching.
+    pub fn set_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache.set_default_ttl(ttl);
+        self
+    }
+
+    /// Same as [`ResolverRegistry::set_cache_ttl`], overriding the TTL for a single method.
+    pub fn set_method_cache_ttl(mut self, method: String, ttl: Duration) -> Self {
+        self.cache.set_method_ttl(method, ttl);
+        self
+    }
+
+    /// Limits the number of cached resolutions, 1024 by default. The least recently used
+    /// resolution is evicted once the limit is reached.
+    pub fn set_cache_capacity(mut self, capacity: NonZeroUsize) -> Self {
+        self.cache.set_capacity(capacity);
+        self
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
     pub async fn resolve(
         &self,
         did: &Did,
@@ -106,10 +154,17 @@ impl ResolverRegistry {
         let method = did
             .method()
             .ok_or(DidResolverRegistryError::UnsupportedMethod)?;
-        match self.resolvers.get(method) {
-            Some(resolver) => resolver.resolve(did, options).await,
-            None => Err(Box::new(DidResolverRegistryError::UnsupportedMethod)),
+        if let Some(output) = self.cache.get(did, options) {
+            return Ok(output);
         }
+        let resolver = self
+            .resolvers
+            .get(method)
+            .or(self.fallback_resolver.as_ref())
+            .ok_or(DidResolverRegistryError::UnsupportedMethod)?;
+        let output = resolver.resolve(did, options).await?;
+        self.cache.insert(did, options, &output);
+        Ok(output)
     }
 }
 
@@ -118,7 +173,11 @@ mod tests {
     use std::{error::Error, pin::Pin};
 
     use async_trait::async_trait;
-    use did_resolver::did_doc::schema::did_doc::DidDocumentBuilder;
+    use chrono::Utc;
+    use did_resolver::{
+        did_doc::schema::did_doc::DidDocumentBuilder,
+        shared_types::did_document_metadata::DidDocumentMetadata,
+    };
     use mockall::{automock, predicate::eq};
 
     use super::*;
@@ -301,4 +360,224 @@ mod tests {
             .await;
         assert!(result_after.is_ok());
     }
+
+    fn mock_resolver(
+        did: &'static str,
+        times: usize,
+        metadata: DidDocumentMetadata,
+    ) -> MockDummyDidResolver {
+        let mut mock_resolver = MockDummyDidResolver::new();
+        mock_resolver
+            .expect_resolve()
+            .times(times)
+            .returning(move |_, _| {
+                let metadata = metadata.clone();
+                let future = async move {
+                    Ok::<DidResolutionOutput<()>, GenericError>(
+                        DidResolutionOutput::builder(
+                            DidDocumentBuilder::new(Did::parse(did.to_string()).unwrap()).build(),
+                        )
+                        .did_document_metadata(metadata)
+                        .build(),
+                    )
+                };
+                Pin::from(Box::new(future))
+            });
+        mock_resolver
+    }
+
+    async fn resolve_twice(registry: &ResolverRegistry, did: &str) {
+        let did = Did::parse(did.to_string()).unwrap();
+        for _ in 0..2 {
+            registry
+                .resolve(&did, &DidResolutionOptions::default())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_with_fallback_resolver() {
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 1, DidDocumentMetadata::default()),
+            )
+            .register_fallback_resolver::<MockDummyDidResolver>(mock_resolver(
+                "did:other:1234",
+                1,
+                DidDocumentMetadata::default(),
+            ));
+
+        // registered resolvers take precedence over the fallback resolver
+        let output = registry
+            .resolve(
+                &Did::parse("did:example:1234".to_string()).unwrap(),
+                &DidResolutionOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.did_document().id().to_string(), "did:example:1234");
+
+        let output = registry
+            .resolve(
+                &Did::parse("did:other:1234".to_string()).unwrap(),
+                &DidResolutionOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.did_document().id().to_string(), "did:other:1234");
+
+        let registry = registry.unregister_fallback_resolver();
+        let result = registry
+            .resolve(
+                &Did::parse("did:other:1234".to_string()).unwrap(),
+                &DidResolutionOptions::default(),
+            )
+            .await;
+        assert!(matches!(
+            result
+                .unwrap_err()
+                .downcast_ref::<DidResolverRegistryError>(),
+            Some(DidResolverRegistryError::UnsupportedMethod)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cached() {
+        let registry = ResolverRegistry::new()
+            .register_fallback_resolver::<MockDummyDidResolver>(mock_resolver(
+                "did:example:1234",
+                1,
+                DidDocumentMetadata::default(),
+            ))
+            .set_cache_ttl(Duration::from_secs(60));
+
+        resolve_twice(&registry, "did:example:1234").await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cache_expired() {
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 2, DidDocumentMetadata::default()),
+            )
+            .set_cache_ttl(Duration::from_secs(60))
+            .set_method_cache_ttl("example".to_string(), Duration::from_millis(10));
+
+        let did = Did::parse("did:example:1234".to_string()).unwrap();
+        registry
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        registry
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cache_evicts_least_recently_used() {
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 3, DidDocumentMetadata::default()),
+            )
+            .set_cache_ttl(Duration::from_secs(60))
+            .set_cache_capacity(NonZeroUsize::new(1).unwrap());
+
+        for did in ["did:example:1", "did:example:2", "did:example:1"] {
+            registry
+                .resolve(
+                    &Did::parse(did.to_string()).unwrap(),
+                    &DidResolutionOptions::default(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cache_sweeps_expired_entries() {
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 2, DidDocumentMetadata::default()),
+            )
+            .register_resolver::<MockDummyDidResolver>(
+                "short".to_string(),
+                mock_resolver("did:short:1234", 1, DidDocumentMetadata::default()),
+            )
+            .set_cache_ttl(Duration::from_secs(60))
+            .set_method_cache_ttl("short".to_string(), Duration::from_millis(10))
+            .set_cache_capacity(NonZeroUsize::new(2).unwrap());
+
+        let resolve = |did: &'static str| {
+            let registry = &registry;
+            async move {
+                registry
+                    .resolve(
+                        &Did::parse(did.to_string()).unwrap(),
+                        &DidResolutionOptions::default(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        };
+        resolve("did:example:1").await;
+        resolve("did:short:1").await;
+        std::thread::sleep(Duration::from_millis(20));
+        // The expired entry is swept instead of evicting the least recently used did:example:1
+        resolve("did:example:2").await;
+        resolve("did:example:1").await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cache_disabled_for_method() {
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 2, DidDocumentMetadata::default()),
+            )
+            .set_cache_ttl(Duration::from_secs(60))
+            .set_method_cache_ttl("example".to_string(), Duration::ZERO);
+
+        resolve_twice(&registry, "did:example:1234").await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cache_respects_document_metadata() {
+        let deactivated = DidDocumentMetadata::builder().deactivated(true).build();
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 2, deactivated),
+            )
+            .set_cache_ttl(Duration::from_secs(60));
+        resolve_twice(&registry, "did:example:1234").await;
+
+        let next_update_passed = DidDocumentMetadata::builder()
+            .next_update(Utc::now() - chrono::Duration::seconds(1))
+            .build();
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 2, next_update_passed),
+            )
+            .set_cache_ttl(Duration::from_secs(60));
+        resolve_twice(&registry, "did:example:1234").await;
+
+        let next_update_pending = DidDocumentMetadata::builder()
+            .next_update(Utc::now() + chrono::Duration::seconds(60))
+            .build();
+        let registry = ResolverRegistry::new()
+            .register_resolver::<MockDummyDidResolver>(
+                "example".to_string(),
+                mock_resolver("did:example:1234", 1, next_update_pending),
+            )
+            .set_cache_ttl(Duration::from_secs(60));
+        resolve_twice(&registry, "did:example:1234").await;
+    }
 }
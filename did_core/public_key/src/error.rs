@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::KeyType;
+
 #[derive(Debug, Error)]
 pub enum PublicKeyError {
     #[error("Base 64 decoding error")]
@@ -12,6 +14,14 @@ pub enum PublicKeyError {
     VarintDecodingError(#[from] VarintDecodingError),
     #[error("Unsupported multicodec descriptor: {0}")]
     UnsupportedMulticodecDescriptor(u64),
+    #[error("Unsupported key conversion from {0:?} to {1:?}")]
+    UnsupportedKeyConversion(KeyType, KeyType),
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+    #[error("JWK (de)serialization error")]
+    JwkSerializationError(#[from] serde_json::Error),
+    #[error("Unsupported JWK: {0}")]
+    UnsupportedJwk(String),
 }
 
 #[derive(Debug, Error)]
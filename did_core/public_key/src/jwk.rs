@@ -0,0 +1,342 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use k256::Secp256k1;
+use p256::{
+    elliptic_curve::{
+        sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint},
+        AffinePoint, CurveArithmetic, FieldBytesSize, PublicKey,
+    },
+    NistP256,
+};
+use p384::NistP384;
+use p521::NistP521;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{error::PublicKeyError, Key, KeyType};
+
+const KTY_OKP: &str = "OKP";
+const KTY_EC: &str = "EC";
+
+// Key types with a JWK representation along with their `kty` and `crv` parameters. BLS12-381
+// keys follow https://datatracker.ietf.org/doc/draft-ietf-cose-bls-key-representations/, the
+// trailing entries are the representation used by earlier did:key drafts, only accepted when
+// parsing.
+const JWK_CURVES: [(KeyType, &str, &str); 10] = [
+    (KeyType::Ed25519, KTY_OKP, "Ed25519"),
+    (KeyType::X25519, KTY_OKP, "X25519"),
+    (KeyType::Bls12381g1, KTY_OKP, "Bls12381G1"),
+    (KeyType::Bls12381g2, KTY_OKP, "Bls12381G2"),
+    (KeyType::P256, KTY_EC, "P-256"),
+    (KeyType::P384, KTY_EC, "P-384"),
+    (KeyType::P521, KTY_EC, "P-521"),
+    (KeyType::Secp256k1, KTY_EC, "secp256k1"),
+    (KeyType::Bls12381g1, KTY_EC, "BLS12381_G1"),
+    (KeyType::Bls12381g2, KTY_EC, "BLS12381_G2"),
+];
+
+// Only the public key members are kept, anything else (e.g. `kid`, `use` or private key
+// material) is dropped. Members are declared in lexicographic order, so that the serialized key
+// is the canonical form hashed by RFC 7638 thumbprints.
+#[derive(Serialize, Deserialize)]
+struct Jwk {
+    crv: String,
+    kty: String,
+    x: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y: Option<String>,
+}
+
+impl Key {
+    /// Parses a public key from an RFC 7517 JWK. Ed25519, X25519 and BLS12-381 keys are taken
+    /// as is, NIST and secp256k1 keys are validated and stored as compressed SEC1 points, as in
+    /// their multicodec representation.
+    pub fn from_jwk(jwk: &str) -> Result<Self, PublicKeyError> {
+        let jwk: Jwk = serde_json::from_str(jwk)?;
+        let key_type = JWK_CURVES
+            .iter()
+            .find(|(_, kty, crv)| *kty == jwk.kty && *crv == jwk.crv)
+            .map(|(key_type, _, _)| *key_type)
+            .ok_or_else(|| {
+                PublicKeyError::UnsupportedJwk(format!(
+                    "kty \"{}\" with crv \"{}\"",
+                    jwk.kty, jwk.crv
+                ))
+            })?;
+        let x = URL_SAFE_NO_PAD.decode(&jwk.x)?;
+        let y = || -> Result<Vec<u8>, PublicKeyError> {
+            let y = jwk.y.as_ref().ok_or_else(|| {
+                PublicKeyError::InvalidKey("EC JWK is missing the y coordinate".to_string())
+            })?;
+            Ok(URL_SAFE_NO_PAD.decode(y)?)
+        };
+        let key = match key_type {
+            KeyType::P256 => compress_ec_point::<NistP256>(&x, &y()?)?,
+            KeyType::P384 => compress_ec_point::<NistP384>(&x, &y()?)?,
+            KeyType::P521 => compress_ec_point::<NistP521>(&x, &y()?)?,
+            KeyType::Secp256k1 => compress_ec_point::<Secp256k1>(&x, &y()?)?,
+            KeyType::Ed25519 | KeyType::X25519 if x.len() != 32 => {
+                return Err(PublicKeyError::InvalidKey(format!(
+                    "{:?} JWK must contain 32 bytes, got {}",
+                    key_type,
+                    x.len()
+                )))
+            }
+            _ => x,
+        };
+        Ok(Self { key_type, key })
+    }
+
+    /// Serializes the key as an RFC 7517 JWK containing only the `kty`, `crv`, `x` and, for EC
+    /// keys, `y` members.
+    pub fn to_jwk(&self) -> Result<String, PublicKeyError> {
+        Ok(serde_json::to_string(&self.jwk()?)?)
+    }
+
+    /// Computes the RFC 7638 thumbprint of the key's JWK, base64url encoded SHA-256 hash.
+    pub fn jwk_thumbprint(&self) -> Result<String, PublicKeyError> {
+        let canonical_jwk = serde_json::to_vec(&self.jwk()?)?;
+        Ok(URL_SAFE_NO_PAD.encode(Sha256::digest(canonical_jwk)))
+    }
+
+    fn jwk(&self) -> Result<Jwk, PublicKeyError> {
+        let (_, kty, crv) = JWK_CURVES
+            .iter()
+            .find(|(key_type, _, _)| *key_type == self.key_type)
+            .ok_or_else(|| {
+                PublicKeyError::UnsupportedJwk(format!(
+                    "{:?} keys have no JWK representation",
+                    self.key_type
+                ))
+            })?;
+        let (x, y) = match self.key_type {
+            KeyType::P256 => ec_point_coordinates::<NistP256>(&self.key)?,
+            KeyType::P384 => ec_point_coordinates::<NistP384>(&self.key)?,
+            KeyType::P521 => ec_point_coordinates::<NistP521>(&self.key)?,
+            KeyType::Secp256k1 => ec_point_coordinates::<Secp256k1>(&self.key)?,
+            _ => (self.key.clone(), None),
+        };
+        Ok(Jwk {
+            crv: crv.to_string(),
+            kty: kty.to_string(),
+            x: URL_SAFE_NO_PAD.encode(x),
+            y: y.map(|y| URL_SAFE_NO_PAD.encode(y)),
+        })
+    }
+}
+
+fn compress_ec_point<C>(x: &[u8], y: &[u8]) -> Result<Vec<u8>, PublicKeyError>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let uncompressed_point = [&[0x04], x, y].concat();
+    let public_key = PublicKey::<C>::from_sec1_bytes(&uncompressed_point).map_err(|_| {
+        PublicKeyError::InvalidKey("JWK coordinates are not a valid curve point".to_string())
+    })?;
+    Ok(public_key.to_encoded_point(true).as_bytes().to_vec())
+}
+
+fn ec_point_coordinates<C>(key: &[u8]) -> Result<(Vec<u8>, Option<Vec<u8>>), PublicKeyError>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let public_key = PublicKey::<C>::from_sec1_bytes(key)
+        .map_err(|_| PublicKeyError::InvalidKey("Key is not a valid curve point".to_string()))?;
+    let point = public_key.to_encoded_point(false);
+    match (point.x(), point.y()) {
+        (Some(x), Some(y)) => Ok((x.to_vec(), Some(y.to_vec()))),
+        _ => Err(PublicKeyError::InvalidKey(
+            "Key is the point at infinity".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwk_roundtrip_test(fingerprint: &str, expected_jwk: &str) {
+        let key = Key::from_fingerprint(fingerprint).unwrap();
+        let jwk = key.to_jwk().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&jwk).unwrap(),
+            serde_json::from_str::<serde_json::Value>(expected_jwk).unwrap()
+        );
+        let key_from_jwk = Key::from_jwk(expected_jwk).unwrap();
+        assert_eq!(key_from_jwk, key);
+        assert_eq!(key_from_jwk.fingerprint(), fingerprint);
+    }
+
+    // Test vector from https://www.rfc-editor.org/rfc/rfc8037#appendix-A.3
+    #[test]
+    fn ed25519_jwk_thumbprint_test() {
+        let key = Key::from_jwk(
+            r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#,
+        )
+        .unwrap();
+        assert_eq!(key.key_type(), &KeyType::Ed25519);
+        assert_eq!(
+            key.jwk_thumbprint().unwrap(),
+            "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+        );
+    }
+
+    #[test]
+    fn ed25519_jwk_test() {
+        jwk_roundtrip_test(
+            "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+            r#"{"kty":"OKP","crv":"Ed25519","x":"Lm_M42cB3HkUiODQsXRcweM6TByfzEHGO9ND274JcOY"}"#,
+        );
+    }
+
+    #[test]
+    fn x25519_jwk_test() {
+        jwk_roundtrip_test(
+            "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p",
+            r#"{"kty":"OKP","crv":"X25519","x":"bl_3kgKpz9jgsg350CNuHa_kQL3B60Gi-98WmdQW2h8"}"#,
+        );
+    }
+
+    // Test vectors from https://w3c-ccg.github.io/did-method-key/#test-vectors
+    #[test]
+    fn p256_jwk_test() {
+        jwk_roundtrip_test(
+            "zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169",
+            r#"{
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI",
+                "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"
+            }"#,
+        );
+    }
+
+    #[test]
+    fn p384_jwk_test() {
+        jwk_roundtrip_test(
+            "z82Lm1MpAkeJcix9K8TMiLd5NMAhnwkjjCBeWHXyu3U4oT2MVJJKXkcVBgjGhnLBn2Kaau9",
+            r#"{
+                "kty": "EC",
+                "crv": "P-384",
+                "x": "lInTxl8fjLKp_UCrxI0WDklahi-7-_6JbtiHjiRvMvhedhKVdHBfi2HCY8t_QJyc",
+                "y": "y6N1IC-2mXxHreETBW7K3mBcw0qGr3CWHCs-yl09yCQRLcyfGv7XhqAngHOu51Zv"
+            }"#,
+        );
+    }
+
+    #[test]
+    fn secp256k1_jwk_test() {
+        jwk_roundtrip_test(
+            "zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme",
+            r#"{
+                "kty": "EC",
+                "crv": "secp256k1",
+                "x": "h0wVx_2iDlOcblulc8E5iEw1EYh5n1RYtLQfeSTyNc0",
+                "y": "O2EATIGbu6DezKFptj5scAIRntgfecanVNXxat1rnwE"
+            }"#,
+        );
+    }
+
+    #[test]
+    fn okp_jwk_length_test() {
+        let x = URL_SAFE_NO_PAD.encode([7u8; 31]);
+        for crv in ["Ed25519", "X25519"] {
+            let jwk = format!(r#"{{"kty":"OKP","crv":"{crv}","x":"{x}"}}"#);
+            assert!(matches!(
+                Key::from_jwk(&jwk),
+                Err(PublicKeyError::InvalidKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn p521_jwk_test() {
+        let generator = PublicKey::<NistP521>::from_affine(p521::AffinePoint::GENERATOR).unwrap();
+        let key = Key::new(
+            generator.to_encoded_point(true).as_bytes().to_vec(),
+            KeyType::P521,
+        )
+        .unwrap();
+        let jwk: serde_json::Value = serde_json::from_str(&key.to_jwk().unwrap()).unwrap();
+        assert_eq!(jwk["crv"], "P-521");
+        // coordinates are padded to the full 66 byte field size
+        assert_eq!(jwk["x"].as_str().unwrap().len(), 88);
+        assert_eq!(jwk["y"].as_str().unwrap().len(), 88);
+        assert_eq!(Key::from_jwk(&jwk.to_string()).unwrap(), key);
+    }
+
+    #[test]
+    fn bls12381_jwk_test() {
+        let g1_key = Key::new(vec![1; 48], KeyType::Bls12381g1).unwrap();
+        let g2_key = Key::new(vec![2; 96], KeyType::Bls12381g2).unwrap();
+        for key in [g1_key, g2_key] {
+            assert_eq!(Key::from_jwk(&key.to_jwk().unwrap()).unwrap(), key);
+        }
+
+        let legacy_g1_jwk = format!(
+            r#"{{"kty":"EC","crv":"BLS12381_G1","x":"{}"}}"#,
+            URL_SAFE_NO_PAD.encode([1; 48])
+        );
+        assert_eq!(
+            Key::from_jwk(&legacy_g1_jwk).unwrap(),
+            Key::new(vec![1; 48], KeyType::Bls12381g1).unwrap()
+        );
+
+        let g1g2_key = Key::new(vec![3; 144], KeyType::Bls12381g1g2).unwrap();
+        assert!(matches!(
+            g1g2_key.to_jwk(),
+            Err(PublicKeyError::UnsupportedJwk(_))
+        ));
+    }
+
+    #[test]
+    fn from_jwk_ignores_non_public_members_test() {
+        let key = Key::from_jwk(
+            r#"{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "kid": "key-1",
+                "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+                "d": "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            key.to_jwk().unwrap(),
+            r#"{"crv":"Ed25519","kty":"OKP","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#
+        );
+    }
+
+    #[test]
+    fn from_jwk_error_test() {
+        assert!(matches!(
+            Key::from_jwk(r#"{"kty":"RSA","crv":"","x":""}"#),
+            Err(PublicKeyError::UnsupportedJwk(_))
+        ));
+        assert!(matches!(
+            Key::from_jwk(
+                r#"{"kty":"EC","crv":"P-256","x":"fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI"}"#
+            ),
+            Err(PublicKeyError::InvalidKey(_))
+        ));
+        // y coordinate of the test vector with the last byte changed
+        assert!(matches!(
+            Key::from_jwk(
+                r#"{
+                    "kty": "EC",
+                    "crv": "P-256",
+                    "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI",
+                    "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTQ"
+                }"#
+            ),
+            Err(PublicKeyError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Key::from_jwk("not a jwk"),
+            Err(PublicKeyError::JwkSerializationError(_))
+        ));
+    }
+}
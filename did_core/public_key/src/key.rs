@@ -1,3 +1,5 @@
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+use curve25519_dalek::edwards::CompressedEdwardsY;
 use serde::{Deserialize, Serialize};
 
 use super::KeyType;
@@ -5,8 +7,8 @@ use crate::error::PublicKeyError;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Key {
-    key_type: KeyType,
-    key: Vec<u8>,
+    pub(crate) key_type: KeyType,
+    pub(crate) key: Vec<u8>,
 }
 
 impl Key {
@@ -46,7 +48,9 @@ impl Key {
         bs58::encode(&self.key).into_string()
     }
 
-    // TODO: base64, ...
+    pub fn base64(&self) -> String {
+        STANDARD_NO_PAD.encode(&self.key)
+    }
 
     pub fn multibase58(&self) -> String {
         multibase::encode(multibase::Base::Base58Btc, &self.key)
@@ -69,6 +73,38 @@ impl Key {
         })
     }
 
+    pub fn from_base64(base64: &str, key_type: KeyType) -> Result<Self, PublicKeyError> {
+        let decoded_bytes = STANDARD_NO_PAD.decode(base64)?;
+        Ok(Self {
+            key_type,
+            key: decoded_bytes,
+        })
+    }
+
+    /// Derives the X25519 key agreement key from an Ed25519 key by mapping the Edwards point to
+    /// its birationally equivalent Montgomery point.
+    pub fn ed25519_to_x25519(&self) -> Result<Self, PublicKeyError> {
+        if self.key_type != KeyType::Ed25519 {
+            return Err(PublicKeyError::UnsupportedKeyConversion(
+                self.key_type,
+                KeyType::X25519,
+            ));
+        }
+        let key_bytes: [u8; 32] = self.key.as_slice().try_into().map_err(|_| {
+            PublicKeyError::InvalidKey(format!(
+                "Ed25519 key must be 32 bytes long, got {} bytes",
+                self.key.len()
+            ))
+        })?;
+        let point = CompressedEdwardsY(key_bytes).decompress().ok_or_else(|| {
+            PublicKeyError::InvalidKey("Ed25519 key is not a valid curve point".to_string())
+        })?;
+        Ok(Self {
+            key_type: KeyType::X25519,
+            key: point.to_montgomery().to_bytes().to_vec(),
+        })
+    }
+
     // TODO: A better name?
     pub fn short_prefixless_fingerprint(&self) -> String {
         self.prefixless_fingerprint()
@@ -204,6 +240,15 @@ mod tests {
             super::base58_test(key_bytes(), KeyType::Ed25519, TEST_KEY_BASE58);
         }
 
+        #[test]
+        fn base64_test() {
+            let key = Key::new(key_bytes(), KeyType::Ed25519).unwrap();
+            assert_eq!(
+                Key::from_base64(&key.base64(), KeyType::Ed25519).unwrap(),
+                key
+            );
+        }
+
         #[test]
         fn from_fingerprint_test() {
             super::from_fingerprint_test(key_bytes(), KeyType::Ed25519, TEST_FINGERPRINT);
@@ -213,6 +258,28 @@ mod tests {
         fn strip_multicodec_prefix_if_present_test() {
             super::strip_multicodec_prefix_if_present_test(key_bytes(), &KeyType::Ed25519);
         }
+
+        // Test vector from https://w3c-ccg.github.io/did-method-key/#example-a-simple-ed25519-did-key-value
+        #[test]
+        fn ed25519_to_x25519_test() {
+            let key =
+                Key::from_fingerprint("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").unwrap();
+            let x25519_key = key.ed25519_to_x25519().unwrap();
+            assert_eq!(x25519_key.key_type(), &KeyType::X25519);
+            assert_eq!(
+                x25519_key.fingerprint(),
+                "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p"
+            );
+        }
+
+        #[test]
+        fn ed25519_to_x25519_invalid_length_test() {
+            let key = Key::new(key_bytes()[..31].to_vec(), KeyType::Ed25519).unwrap();
+            assert!(matches!(
+                key.ed25519_to_x25519(),
+                Err(PublicKeyError::InvalidKey(_))
+            ));
+        }
     }
 
     mod x25519 {
@@ -263,5 +330,17 @@ mod tests {
         fn strip_multicodec_prefix_if_present_test() {
             super::strip_multicodec_prefix_if_present_test(key_bytes(), &KeyType::X25519);
         }
+
+        #[test]
+        fn ed25519_to_x25519_unsupported_key_type_test() {
+            let key = Key::new(key_bytes(), KeyType::X25519).unwrap();
+            assert!(matches!(
+                key.ed25519_to_x25519(),
+                Err(PublicKeyError::UnsupportedKeyConversion(
+                    KeyType::X25519,
+                    KeyType::X25519
+                ))
+            ));
+        }
     }
 }
@@ -1,4 +1,5 @@
 mod error;
+mod jwk;
 mod key;
 mod key_type;
 
@@ -0,0 +1,491 @@
+use std::{
+    env,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use axum::{
+    body::StreamBody,
+    extract::{BodyStream, DefaultBodyLimit, Path as UrlPath, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::Mutex,
+};
+use tokio_util::io::ReaderStream;
+
+const DEFAULT_PORT: u16 = 8430;
+const DEFAULT_STORAGE_DIR: &str = "tails_files";
+const MAX_TAILS_FILE_SIZE: u64 = 512 * 1024 * 1024;
+
+struct TailsStorage {
+    dir: PathBuf,
+    upload_token: String,
+    max_file_size: u64,
+    // serializes storing of uploaded files, so concurrent uploads for the same rev reg id can't
+    // both be stored
+    write_lock: Mutex<()>,
+    upload_counter: AtomicU64,
+}
+
+impl TailsStorage {
+    async fn new(dir: impl Into<PathBuf>, upload_token: String) -> std::io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).await?;
+        Ok(Self {
+            dir,
+            upload_token,
+            max_file_size: MAX_TAILS_FILE_SIZE,
+            write_lock: Mutex::new(()),
+            upload_counter: AtomicU64::new(0),
+        })
+    }
+
+    // rev reg ids contain characters which are not safe in file names on every platform
+    fn tails_file_path(&self, rev_reg_id: &str) -> PathBuf {
+        self.dir.join(bs58::encode(rev_reg_id).into_string())
+    }
+
+    fn upload_file_path(&self) -> PathBuf {
+        let upload_id = self.upload_counter.fetch_add(1, Ordering::Relaxed);
+        self.dir.join(format!("upload_{upload_id}.tmp"))
+    }
+
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.upload_token.as_bytes()))
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadParams {
+    tails_hash: String,
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn encode_hash(hasher: Sha256) -> String {
+    bs58::encode(hasher.finalize()).into_string()
+}
+
+async fn file_hash(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(encode_hash(hasher));
+        }
+        hasher.update(&buf[..read]);
+    }
+}
+
+fn internal_error(err: impl std::fmt::Display) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Failed to store tails file: {err}"),
+    )
+        .into_response()
+}
+
+/// Streams the request body into `upload_path`, returning the tails hash of the written file, or
+/// the response to reject the upload with.
+async fn receive_tails_file(
+    storage: &TailsStorage,
+    upload_path: &Path,
+    mut body: BodyStream,
+) -> Result<String, Response> {
+    let mut file = File::create(upload_path).await.map_err(internal_error)?;
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read tails file: {err}"),
+            )
+                .into_response()
+        })?;
+        size += chunk.len() as u64;
+        if size > storage.max_file_size {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Tails file exceeds the maximal size of {} bytes",
+                    storage.max_file_size
+                ),
+            )
+                .into_response());
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await.map_err(internal_error)?;
+    }
+    file.flush().await.map_err(internal_error)?;
+    Ok(encode_hash(hasher))
+}
+
+async fn store_tails_file(
+    storage: &TailsStorage,
+    rev_reg_id: &str,
+    upload_path: &Path,
+    actual_hash: String,
+) -> Response {
+    let _guard = storage.write_lock.lock().await;
+    let file_path = storage.tails_file_path(rev_reg_id);
+
+    if fs::try_exists(&file_path).await.unwrap_or(false) {
+        return match file_hash(&file_path).await {
+            Ok(existing_hash) if existing_hash == actual_hash => {
+                (StatusCode::OK, actual_hash).into_response()
+            }
+            Ok(_) => (
+                StatusCode::CONFLICT,
+                format!("A different tails file is already stored for {rev_reg_id}"),
+            )
+                .into_response(),
+            Err(err) => internal_error(err),
+        };
+    }
+
+    match fs::rename(upload_path, &file_path).await {
+        Ok(()) => (StatusCode::CREATED, actual_hash).into_response(),
+        Err(err) => internal_error(err),
+    }
+}
+
+async fn upload_tails_file(
+    State(storage): State<Arc<TailsStorage>>,
+    UrlPath(rev_reg_id): UrlPath<String>,
+    Query(params): Query<UploadParams>,
+    headers: HeaderMap,
+    body: BodyStream,
+) -> Response {
+    if !storage.is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let upload_path = storage.upload_file_path();
+    let response = match receive_tails_file(&storage, &upload_path, body).await {
+        Ok(actual_hash) if actual_hash != params.tails_hash => (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Tails hash mismatch, expected: {}, actual: {}",
+                params.tails_hash, actual_hash
+            ),
+        )
+            .into_response(),
+        Ok(actual_hash) => store_tails_file(&storage, &rev_reg_id, &upload_path, actual_hash).await,
+        Err(response) => response,
+    };
+    // the upload is left over unless it was stored
+    let _ = fs::remove_file(&upload_path).await;
+    response
+}
+
+async fn download_tails_file(
+    State(storage): State<Arc<TailsStorage>>,
+    UrlPath(rev_reg_id): UrlPath<String>,
+) -> Response {
+    let file = match File::open(storage.tails_file_path(&rev_reg_id)).await {
+        Ok(file) => file,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let content_length = match file.metadata().await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_owned()),
+            (header::CONTENT_LENGTH, content_length.to_string()),
+        ],
+        StreamBody::new(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+async fn status() -> StatusCode {
+    StatusCode::OK
+}
+
+fn build_router(storage: Arc<TailsStorage>) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        // a wildcard, as qualified rev reg ids (e.g. did:indy) contain `/`; ids can also be sent
+        // percent-encoded, they are decoded by the path extractor
+        .route(
+            "/*rev_reg_id",
+            get(download_tails_file).put(upload_tails_file),
+        )
+        // the size of uploads is limited while they are streamed to disk
+        .layer(DefaultBodyLimit::disable())
+        .with_state(storage)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let port = env::var("TAILS_SERVER_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let storage_dir =
+        env::var("TAILS_SERVER_STORAGE_DIR").unwrap_or_else(|_| DEFAULT_STORAGE_DIR.to_owned());
+    let upload_token = env::var("TAILS_SERVER_UPLOAD_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+        .expect("TAILS_SERVER_UPLOAD_TOKEN must be set to the token authorizing uploads");
+    let storage = Arc::new(TailsStorage::new(storage_dir, upload_token).await?);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    axum::Server::bind(&addr)
+        .serve(build_router(storage).into_make_service())
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        Router,
+    };
+    use sha2::{Digest, Sha256};
+    use tokio::fs;
+    use tower::ServiceExt;
+
+    use crate::{build_router, TailsStorage};
+
+    const REV_REG_ID: &str =
+        "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1:CL_ACCUM:tag1";
+    const QUALIFIED_REV_REG_ID: &str =
+        "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/REV_REG_DEF/1281/tag1/tag1";
+    const UPLOAD_TOKEN: &str = "upload-token";
+
+    async fn temp_storage(name: &str) -> Arc<TailsStorage> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = env::temp_dir().join(format!("tails_server_{name}_{nanos}"));
+        Arc::new(
+            TailsStorage::new(dir, UPLOAD_TOKEN.to_owned())
+                .await
+                .unwrap(),
+        )
+    }
+
+    fn tails_hash(tails_file: &[u8]) -> String {
+        bs58::encode(Sha256::digest(tails_file)).into_string()
+    }
+
+    fn upload_request(hash: &str, token: &str, tails_file: &'static [u8]) -> Request<Body> {
+        upload_request_to(REV_REG_ID, hash, token, tails_file)
+    }
+
+    fn upload_request_to(
+        path: &str,
+        hash: &str,
+        token: &str,
+        tails_file: &'static [u8],
+    ) -> Request<Body> {
+        Request::put(format!("/{path}?tails_hash={hash}"))
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::from(tails_file))
+            .unwrap()
+    }
+
+    fn download_request() -> Request<Body> {
+        download_request_from(REV_REG_ID)
+    }
+
+    fn download_request_from(path: &str) -> Request<Body> {
+        Request::get(format!("/{path}"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn call(app: &Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+        let res = app.clone().oneshot(request).await.unwrap();
+        let status = res.status();
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_download() {
+        let storage = temp_storage("upload_download").await;
+        let app = build_router(storage.clone());
+
+        let tails_file = b"tails file content";
+        let hash = tails_hash(tails_file);
+
+        // unknown rev reg id == NOT FOUND
+        let (status, _) = call(&app, download_request()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = call(&app, upload_request(&hash, UPLOAD_TOKEN, tails_file)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = call(&app, download_request()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, tails_file);
+
+        // uploading the same file again is idempotent
+        let (status, _) = call(&app, upload_request(&hash, UPLOAD_TOKEN, tails_file)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // status route is not shadowed by the download route
+        let (status, _) = call(&app, Request::get("/status").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_requires_token() {
+        let storage = temp_storage("unauthorized").await;
+        let app = build_router(storage.clone());
+
+        let tails_file = b"tails file content";
+        let hash = tails_hash(tails_file);
+
+        let (status, _) = call(&app, upload_request(&hash, "wrong-token", tails_file)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let request = Request::put(format!("/{REV_REG_ID}?tails_hash={hash}"))
+            .body(Body::from(&tails_file[..]))
+            .unwrap();
+        let (status, _) = call(&app, request).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(&app, download_request()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_hash_mismatch() {
+        let storage = temp_storage("hash_mismatch").await;
+        let app = build_router(storage.clone());
+
+        let hash = tails_hash(b"other content");
+        let (status, _) = call(
+            &app,
+            upload_request(&hash, UPLOAD_TOKEN, b"tails file content"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = call(&app, download_request()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_too_large_file() {
+        let mut storage = temp_storage("too_large").await;
+        Arc::get_mut(&mut storage).unwrap().max_file_size = 4;
+        let app = build_router(storage.clone());
+
+        let tails_file = b"tails file content";
+        let (status, _) = call(
+            &app,
+            upload_request(&tails_hash(tails_file), UPLOAD_TOKEN, tails_file),
+        )
+        .await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let (status, _) = call(&app, download_request()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_different_file_for_same_rev_reg() {
+        let storage = temp_storage("conflict").await;
+        let app = build_router(storage.clone());
+
+        let first = b"first tails file";
+        let second = b"second tails file";
+
+        let (status, _) = call(
+            &app,
+            upload_request(&tails_hash(first), UPLOAD_TOKEN, first),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = call(
+            &app,
+            upload_request(&tails_hash(second), UPLOAD_TOKEN, second),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (_, body) = call(&app, download_request()).await;
+        assert_eq!(body, first);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_qualified_rev_reg_id_with_slashes() {
+        let storage = temp_storage("qualified").await;
+        let app = build_router(storage.clone());
+
+        let tails_file = b"tails file content";
+        let hash = tails_hash(tails_file);
+        let encoded_id = QUALIFIED_REV_REG_ID.replace('/', "%2F");
+
+        let (status, _) = call(
+            &app,
+            upload_request_to(QUALIFIED_REV_REG_ID, &hash, UPLOAD_TOKEN, tails_file),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // the raw and the percent-encoded id address the same file
+        for path in [QUALIFIED_REV_REG_ID, &encoded_id] {
+            let (status, body) = call(&app, download_request_from(path)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body, tails_file);
+        }
+        let (status, _) = call(
+            &app,
+            upload_request_to(&encoded_id, &hash, UPLOAD_TOKEN, tails_file),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+}